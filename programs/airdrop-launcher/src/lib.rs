@@ -0,0 +1,179 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token::AssociatedToken, token_interface::TokenInterface};
+use merkle_tree_token_claimer::{
+    cpi::accounts::{Claim, Initialize},
+    program::MerkleTreeTokenClaimer,
+    AirdropParams, MintAuthorityMode, PayoutParams, HASHING_ALGORITHM_KECCAK, LEAF_HASHING_SINGLE, LEAF_VERSION_CLAIMANT_AMOUNT,
+    PAIR_ORDERING_INDEXED,
+};
+
+declare_id!("J6doNkhvx6fm7YEsg6jjnyNDuB7VbpsnvUkNW1x48YHU");
+
+/// Starts airdrops and claims from them through the `cpi` feature of the claimer crate, the way
+/// an integrating program depends on it.
+#[program]
+pub mod airdrop_launcher {
+    use super::*;
+
+    /// Starts an airdrop of `amount` tokens of a fresh mint with default settings.
+    pub fn launch(
+        ctx: Context<Launch>,
+        merkle_root: [u8; 32],
+        amount: u64,
+        num_leaves: u64,
+    ) -> Result<()> {
+
+        merkle_tree_token_claimer::cpi::initialize_airdrop_data(
+            CpiContext::new(
+                ctx.accounts.claimer_program.to_account_info(),
+                Initialize {
+                    airdrop_state: ctx.accounts.airdrop_state.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    vault: ctx.accounts.vault.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                    payer: ctx.accounts.authority.to_account_info(),
+                    metadata: None,
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                    associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+                    token_metadata_program: None,
+                    rent: None,
+                    event_authority: ctx.accounts.event_authority.to_account_info(),
+                    program: ctx.accounts.claimer_program.to_account_info(),
+                },
+            ),
+            AirdropParams {
+                airdrop_id: 0,
+                label: String::new(),
+                metadata_uri: String::new(),
+                merkle_root,
+                num_leaves,
+                start_time: 0,
+                end_time: 0,
+                root_timelock: 0,
+                expected_recipients: 0,
+                hashing_algorithm: HASHING_ALGORITHM_KECCAK,
+                leaf_hashing: LEAF_HASHING_SINGLE,
+                pair_ordering: PAIR_ORDERING_INDEXED,
+            },
+            PayoutParams {
+                amount,
+                leaf_version: LEAF_VERSION_CLAIMANT_AMOUNT,
+                ..Default::default()
+            },
+            6,
+            MintAuthorityMode::Revoke,
+            false,
+            None,
+        )
+    }
+
+    /// Claims the allocation of the signing claimant.
+    pub fn claim<'info>(
+        ctx: Context<'_, '_, '_, 'info, LaunchedClaim<'info>>,
+        amount: u64,
+        hashes: Vec<u8>,
+        index: u64,
+        expected_root_version: u64,
+    ) -> Result<()> {
+
+        merkle_tree_token_claimer::cpi::claim_airdrop(
+            CpiContext::new(
+                ctx.accounts.claimer_program.to_account_info(),
+                Claim {
+                    airdrop_state: ctx.accounts.airdrop_state.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    vault: ctx.accounts.vault.to_account_info(),
+                    signer_ata: ctx.accounts.claimant_ata.to_account_info(),
+                    claim_bitmap: ctx.accounts.claim_bitmap.to_account_info(),
+                    claim_progress: None,
+                    vesting_config: None,
+                    vesting_account: None,
+                    decay_config: None,
+                    bonus_config: None,
+                    fee_ata: None,
+                    fee_treasury: None,
+                    fee_mint: None,
+                    payer_fee_ata: None,
+                    treasury_fee_ata: None,
+                    fee_token_program: None,
+                    ticket_mint: None,
+                    ticket_ata: None,
+                    ticket_token_program: None,
+                    gate_nft_account: None,
+                    gate_nft_metadata: None,
+                    cosigner: None,
+                    instructions_sysvar: None,
+                    revoked_leaf: None,
+                    referral_stats: None,
+                    hook_program: None,
+                    stake_config: None,
+                    stake_program: None,
+                    unwrap_destination: None,
+                    basket: None,
+                    canopy: None,
+                    claimant: ctx.accounts.claimant.to_account_info(),
+                    payer: ctx.accounts.claimant.to_account_info(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                    associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+                    event_authority: ctx.accounts.event_authority.to_account_info(),
+                    program: ctx.accounts.claimer_program.to_account_info(),
+                },
+            ).with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+            amount,
+            hashes,
+            index,
+            expected_root_version,
+            None,
+            false,
+        )
+    }
+}
+
+#[derive(Accounts)]
+pub struct Launch<'info> {
+    /// CHECK: Created by the claimer program
+    #[account(mut)]
+    pub airdrop_state: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub mint: Signer<'info>,
+    /// CHECK: Created by the claimer program
+    #[account(mut)]
+    pub vault: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: Event authority of the claimer program, checked by the initialization
+    pub event_authority: UncheckedAccount<'info>,
+    pub claimer_program: Program<'info, MerkleTreeTokenClaimer>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[derive(Accounts)]
+pub struct LaunchedClaim<'info> {
+    /// CHECK: Validated by the claim
+    #[account(mut)]
+    pub airdrop_state: UncheckedAccount<'info>,
+    /// CHECK: Validated by the claim
+    #[account(mut)]
+    pub mint: UncheckedAccount<'info>,
+    /// CHECK: Validated by the claim
+    #[account(mut)]
+    pub vault: UncheckedAccount<'info>,
+    /// CHECK: Created if needed and validated by the claim
+    #[account(mut)]
+    pub claimant_ata: UncheckedAccount<'info>,
+    /// CHECK: Validated by the claim
+    #[account(mut)]
+    pub claim_bitmap: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub claimant: Signer<'info>,
+    /// CHECK: Event authority of the claimer program, checked by the claim
+    pub event_authority: UncheckedAccount<'info>,
+    pub claimer_program: Program<'info, MerkleTreeTokenClaimer>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+
+declare_id!("3PpMCdPhuAQZ622gGsVp1kSUDPTrZaX7kQFxPZPvp8Rb");
+
+/// Post-claim hook awarding a loyalty point for every token claimed, used to check that claims
+/// notify the hook program of their airdrop and fail with it.
+#[program]
+pub mod claim_points_hook {
+    use super::*;
+
+    /// Called by the claimer once a claim is paid, with the layout of `call_claim_hook`.
+    pub fn on_claim(
+        ctx: Context<OnClaim>,
+        claimant: Pubkey,
+        amount: u64,
+        _index: u64,
+        airdrop_state: Pubkey,
+    ) -> Result<()> {
+
+        require_keys_eq!(claimant, ctx.accounts.claimant.key());
+        require_keys_eq!(airdrop_state, ctx.accounts.airdrop_state.key());
+
+        let points = &mut ctx.accounts.points;
+        points.claimant = claimant;
+        points.points = points
+            .points
+            .checked_add(amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct OnClaim<'info> {
+    /// CHECK: Airdrop of the claim, tied to the hook authority by its seeds
+    #[account(owner = merkle_tree_token_claimer::ID)]
+    pub airdrop_state: UncheckedAccount<'info>,
+    /// Only the claimer can sign for the hook authority of an airdrop, so no one else awards points
+    #[account(
+        seeds = [b"hook_authority".as_ref(), airdrop_state.key().as_ref()],
+        bump,
+        seeds::program = merkle_tree_token_claimer::ID
+    )]
+    pub hook_authority: Signer<'info>,
+    pub claimant: Signer<'info>,
+    #[account(
+        init_if_needed,
+        seeds = [b"points".as_ref(), airdrop_state.key().as_ref(), claimant.key().as_ref()],
+        bump,
+        payer = payer,
+        space = 8 + Points::INIT_SPACE
+    )]
+    pub points: Account<'info, Points>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Points {
+    pub claimant: Pubkey,
+    pub points: u64,
+}
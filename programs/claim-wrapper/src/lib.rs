@@ -0,0 +1,127 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::get_return_data;
+use anchor_spl::{associated_token::AssociatedToken, token_interface::{Mint, TokenAccount, TokenInterface}};
+use merkle_tree_token_claimer::{cpi::accounts::Claim, program::MerkleTreeTokenClaimer, AirdropState, ClaimBitmap, ClaimResult};
+
+declare_id!("HMSXBjszu7mn5W1do55Cdv65h2Q7r1FuAqpEcBkXomDP");
+
+/// Claims on behalf of its signer through CPI and records the claim's return data, used to
+/// check what `claim_airdrop` hands back to calling programs.
+#[program]
+pub mod claim_wrapper {
+    use super::*;
+
+    pub fn claim<'info>(
+        ctx: Context<'_, '_, '_, 'info, WrappedClaim<'info>>,
+        amount: u64,
+        hashes: Vec<u8>,
+        index: u64,
+        expected_root_version: u64,
+    ) -> Result<()> {
+
+        merkle_tree_token_claimer::cpi::claim_airdrop(
+            CpiContext::new(
+                ctx.accounts.claimer_program.to_account_info(),
+                Claim {
+                    airdrop_state: ctx.accounts.airdrop_state.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    vault: ctx.accounts.vault.to_account_info(),
+                    signer_ata: ctx.accounts.signer_ata.to_account_info(),
+                    claim_bitmap: ctx.accounts.claim_bitmap.to_account_info(),
+                    claim_progress: None,
+                    vesting_config: None,
+                    vesting_account: None,
+                    decay_config: None,
+                    bonus_config: None,
+                    fee_ata: None,
+                    fee_treasury: None,
+                    fee_mint: None,
+                    payer_fee_ata: None,
+                    treasury_fee_ata: None,
+                    fee_token_program: None,
+                    ticket_mint: None,
+                    ticket_ata: None,
+                    ticket_token_program: None,
+                    gate_nft_account: None,
+                    gate_nft_metadata: None,
+                    cosigner: None,
+                    instructions_sysvar: None,
+                    revoked_leaf: None,
+                    referral_stats: None,
+                    hook_program: None,
+                    stake_config: None,
+                    stake_program: None,
+                    unwrap_destination: None,
+                    basket: None,
+                    canopy: None,
+                    claimant: ctx.accounts.signer.to_account_info(),
+                    payer: ctx.accounts.signer.to_account_info(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                    associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+                    event_authority: ctx.accounts.event_authority.to_account_info(),
+                    program: ctx.accounts.claimer_program.to_account_info(),
+                }
+            ).with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+            amount,
+            hashes,
+            index,
+            expected_root_version,
+            None,
+            false,
+        )?;
+
+        let (program_id, data) = get_return_data().ok_or(ProgramError::InvalidAccountData)?;
+        require_keys_eq!(program_id, merkle_tree_token_claimer::ID);
+        let result = ClaimResult::try_from_slice(&data)?;
+
+        ctx.accounts.record.set_inner(ClaimRecord {
+            amount_transferred: result.amount_transferred,
+            claimant: result.claimant,
+            amount_claimed: result.amount_claimed,
+            merkle_root: result.merkle_root,
+        });
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct WrappedClaim<'info> {
+    #[account(mut)]
+    pub airdrop_state: Account<'info, AirdropState>,
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: Created if needed and validated by the claim
+    #[account(mut)]
+    pub signer_ata: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub claim_bitmap: AccountLoader<'info, ClaimBitmap>,
+    #[account(
+        init,
+        payer = signer,
+        seeds = [b"record".as_ref(), signer.key().as_ref()],
+        bump,
+        space = 8 + ClaimRecord::INIT_SPACE
+    )]
+    pub record: Account<'info, ClaimRecord>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    /// CHECK: Event authority of the claimer program, checked by the claim
+    pub event_authority: UncheckedAccount<'info>,
+    pub claimer_program: Program<'info, MerkleTreeTokenClaimer>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ClaimRecord {
+    pub amount_transferred: u64,
+    pub claimant: Pubkey,
+    pub amount_claimed: u64,
+    pub merkle_root: [u8; 32],
+}
@@ -0,0 +1,95 @@
+//! Calls into spl-account-compression for airdrops in `TREE_MODE_CONCURRENT`, whose tree is a
+//! `ConcurrentMerkleTree` account owned by the compression program with the airdrop state as
+//! its authority. Instructions are built by hand like the other CPIs of the program.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::solana_program::pubkey;
+
+/// spl-account-compression, the owner of every concurrent tree.
+pub const SPL_ACCOUNT_COMPRESSION_ID: Pubkey = pubkey!("cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK");
+
+/// spl-noop, the compression program logs every change of a tree through for indexers.
+pub const SPL_NOOP_ID: Pubkey = pubkey!("noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV");
+
+/// Node a claim replaces its leaf with, the empty node of the compression program, which no
+/// allocation hashes to.
+pub const CLAIMED_LEAF: [u8; 32] = [0; 32];
+
+/// Anchor discriminators of the compression instructions, the first 8 bytes of
+/// `sha256("global:<name>")`.
+const INIT_EMPTY_MERKLE_TREE_DISCRIMINATOR: [u8; 8] = [191, 11, 119, 7, 180, 107, 220, 110];
+const APPEND_DISCRIMINATOR: [u8; 8] = [149, 120, 18, 222, 236, 225, 88, 203];
+const REPLACE_LEAF_DISCRIMINATOR: [u8; 8] = [204, 165, 76, 100, 73, 147, 0, 128];
+
+/// Accounts every call into the compression program goes through, `authority` being the
+/// airdrop state signing with the seeds passed along.
+pub struct CompressionAccounts<'a, 'info> {
+    pub merkle_tree: &'a AccountInfo<'info>,
+    pub authority: &'a AccountInfo<'info>,
+    pub noop_program: &'a AccountInfo<'info>,
+    pub compression_program: &'a AccountInfo<'info>,
+}
+
+impl<'a, 'info> CompressionAccounts<'a, 'info> {
+    /// Calls the compression program with `data`, the nodes of `proof` passed after the
+    /// accounts every instruction takes.
+    fn invoke(&self, data: Vec<u8>, proof: &[AccountInfo<'info>], signer_seeds: &[&[&[u8]]]) -> Result<()> {
+        let mut metas = vec![
+            AccountMeta::new(self.merkle_tree.key(), false),
+            AccountMeta::new_readonly(self.authority.key(), true),
+            AccountMeta::new_readonly(self.noop_program.key(), false),
+        ];
+        metas.extend(proof.iter().map(|node| AccountMeta::new_readonly(node.key(), false)));
+        let mut account_infos = vec![
+            self.merkle_tree.clone(),
+            self.authority.clone(),
+            self.noop_program.clone(),
+        ];
+        account_infos.extend_from_slice(proof);
+        account_infos.push(self.compression_program.clone());
+
+        invoke_signed(
+            &Instruction { program_id: SPL_ACCOUNT_COMPRESSION_ID, accounts: metas, data },
+            &account_infos,
+            signer_seeds,
+        )
+        .map_err(Into::into)
+    }
+
+    /// Initializes the tree account, allocated to the compression program beforehand, as an
+    /// empty tree of `max_depth` keeping the last `max_buffer_size` changes.
+    pub fn init_empty_merkle_tree(&self, max_depth: u32, max_buffer_size: u32, signer_seeds: &[&[&[u8]]]) -> Result<()> {
+        let mut data = INIT_EMPTY_MERKLE_TREE_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&max_depth.to_le_bytes());
+        data.extend_from_slice(&max_buffer_size.to_le_bytes());
+        self.invoke(data, &[], signer_seeds)
+    }
+
+    /// Appends `leaf` at the next free index of the tree.
+    pub fn append(&self, leaf: &[u8; 32], signer_seeds: &[&[&[u8]]]) -> Result<()> {
+        let mut data = APPEND_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(leaf);
+        self.invoke(data, &[], signer_seeds)
+    }
+
+    /// Replaces `previous_leaf` at `index` with `new_leaf`, `proof` leading to `root` or any
+    /// root still in the change log of the tree, so proofs outlive the changes made since.
+    pub fn replace_leaf(
+        &self,
+        root: &[u8; 32],
+        previous_leaf: &[u8; 32],
+        new_leaf: &[u8; 32],
+        index: u32,
+        proof: &[AccountInfo<'info>],
+        signer_seeds: &[&[&[u8]]],
+    ) -> Result<()> {
+        let mut data = REPLACE_LEAF_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(root);
+        data.extend_from_slice(previous_leaf);
+        data.extend_from_slice(new_leaf);
+        data.extend_from_slice(&index.to_le_bytes());
+        self.invoke(data, proof, signer_seeds)
+    }
+}
@@ -0,0 +1,396 @@
+//! Leaf construction and proof verification, the same functions the claim instructions run so
+//! offchain tooling rebuilding leaves and checking proofs cannot drift from them.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{hash, keccak};
+
+use crate::{
+    WhitelistError, HASHING_ALGORITHM_KECCAK, HASHING_ALGORITHM_SHA256, HASH_SIZE, LEAF_HASHING_DOUBLE,
+    LEAF_HASHING_SINGLE, LEAF_PREFIX, LEAF_VERSION_BASKET, LEAF_VERSION_CLAIMANT_AMOUNT,
+    LEAF_VERSION_COMMITTED, LEAF_VERSION_DEADLINE, LEAF_VERSION_INDEX_CLAIMANT_AMOUNT, LEAF_VERSION_NFT,
+    LEAF_VERSION_SPLIT, MAX_PROOF_DEPTH, NODE_PREFIX, PAIR_ORDERING_INDEXED, PAIR_ORDERING_INDEXED_PREFIXED,
+    PAIR_ORDERING_SORTED,
+};
+
+/// Leaf of `claimant`'s allocation at `index` as it is hashed into the tree, laid out as
+/// `leaf_version` says. Every claim builds its leaf here, unknown versions are rejected.
+pub fn encode_leaf(leaf_version: u8, index: u64, claimant: &Pubkey, amount: u64) -> Result<Vec<u8>> {
+    let mut leaf = Vec::with_capacity(8 + 32 + 8);
+    match leaf_version {
+        LEAF_VERSION_CLAIMANT_AMOUNT => {}
+        LEAF_VERSION_INDEX_CLAIMANT_AMOUNT => leaf.extend_from_slice(&index.to_le_bytes()),
+        LEAF_VERSION_COMMITTED => return err!(WhitelistError::LeafDataHashRequired),
+        LEAF_VERSION_SPLIT => return err!(WhitelistError::SplitAmountsRequired),
+        LEAF_VERSION_DEADLINE => return err!(WhitelistError::DeadlineRequired),
+        LEAF_VERSION_BASKET => return err!(WhitelistError::BasketAmountsRequired),
+        LEAF_VERSION_NFT => return err!(WhitelistError::NftMintRequired),
+        _ => return err!(WhitelistError::UnsupportedLeafVersion),
+    }
+    leaf.extend_from_slice(&claimant.to_bytes());
+    leaf.extend_from_slice(&amount.to_le_bytes());
+    Ok(leaf)
+}
+
+/// Rejects proofs deeper than `MAX_PROOF_DEPTH`, that cannot be split into whole hashes,
+/// or that are empty although the tree has more than one leaf, before they are verified.
+pub fn check_proof_shape(hashes: &[u8], num_leaves: u64) -> Result<()> {
+    require!(
+        hashes.len() <= MAX_PROOF_DEPTH * HASH_SIZE,
+        WhitelistError::ProofTooLong
+    );
+    require!(
+        hashes.len() % HASH_SIZE == 0 && (num_leaves <= 1 || !hashes.is_empty()),
+        WhitelistError::MalformedProof
+    );
+    Ok(())
+}
+
+/// Leaf index as proofs are verified at, failing instead of truncating indices past `u32::MAX`.
+pub fn proof_index(index: u64) -> Result<u32> {
+    u32::try_from(index).map_err(|_| error!(WhitelistError::IndexOutOfRange))
+}
+
+/// Rejects hashing algorithm discriminants the program has no hash syscall for.
+pub fn check_hashing_algorithm(discriminant: u8) -> Result<()> {
+    require!(
+        matches!(discriminant, HASHING_ALGORITHM_KECCAK | HASHING_ALGORITHM_SHA256),
+        WhitelistError::UnsupportedHashingAlgorithm
+    );
+    Ok(())
+}
+
+/// Leaf of a `LEAF_VERSION_COMMITTED` airdrop, `hash(hash(claimant ‖ amount) ‖ data_hash)`.
+/// Only the inner hash is reconstructed from what the program knows, so a leaf can never be
+/// claimed by another signer or for another amount whatever `data_hash` holds.
+pub fn committed_leaf(hashing_algorithm: u8, claimant: &Pubkey, amount: u64, data_hash: &[u8; 32]) -> Vec<u8> {
+    let allocation = hash_pair(hashing_algorithm, &claimant.to_bytes(), &amount.to_le_bytes());
+    hash_pair(hashing_algorithm, &allocation, data_hash).to_vec()
+}
+
+/// Leaf of a `LEAF_VERSION_SPLIT` airdrop, `claimant ‖ unlocked_amount ‖ locked_amount` with
+/// the amounts little-endian.
+pub fn split_leaf(claimant: &Pubkey, unlocked_amount: u64, locked_amount: u64) -> Vec<u8> {
+    let mut leaf = Vec::with_capacity(32 + 8 + 8);
+    leaf.extend_from_slice(&claimant.to_bytes());
+    leaf.extend_from_slice(&unlocked_amount.to_le_bytes());
+    leaf.extend_from_slice(&locked_amount.to_le_bytes());
+    leaf
+}
+
+/// Leaf of a `LEAF_VERSION_DEADLINE` airdrop, `claimant ‖ amount ‖ deadline` with the
+/// integers little-endian.
+pub fn deadline_leaf(claimant: &Pubkey, amount: u64, deadline: i64) -> Vec<u8> {
+    let mut leaf = Vec::with_capacity(32 + 8 + 8);
+    leaf.extend_from_slice(&claimant.to_bytes());
+    leaf.extend_from_slice(&amount.to_le_bytes());
+    leaf.extend_from_slice(&deadline.to_le_bytes());
+    leaf
+}
+
+/// Leaf of a `LEAF_VERSION_BASKET` airdrop, `claimant ‖ amount ‖ basket_amounts…` with the
+/// amounts little-endian, the airdrop mint's first.
+pub fn basket_leaf(claimant: &Pubkey, amount: u64, basket_amounts: &[u64]) -> Vec<u8> {
+    let mut leaf = Vec::with_capacity(32 + 8 * (1 + basket_amounts.len()));
+    leaf.extend_from_slice(&claimant.to_bytes());
+    leaf.extend_from_slice(&amount.to_le_bytes());
+    for basket_amount in basket_amounts {
+        leaf.extend_from_slice(&basket_amount.to_le_bytes());
+    }
+    leaf
+}
+
+/// Leaf of a `LEAF_VERSION_NFT` airdrop, `claimant ‖ nft_mint`.
+pub fn nft_leaf(claimant: &Pubkey, nft_mint: &Pubkey) -> Vec<u8> {
+    let mut leaf = Vec::with_capacity(64);
+    leaf.extend_from_slice(&claimant.to_bytes());
+    leaf.extend_from_slice(&nft_mint.to_bytes());
+    leaf
+}
+
+/// `leaf` as it is fed into the tree, hashed once more in double hashing mode.
+pub fn prepare_leaf(leaf_hashing: u8, hashing_algorithm: u8, leaf: Vec<u8>) -> Result<Vec<u8>> {
+    match leaf_hashing {
+        LEAF_HASHING_SINGLE => Ok(leaf),
+        LEAF_HASHING_DOUBLE => Ok(hash_leaf(hashing_algorithm, &leaf).to_vec()),
+        _ => err!(WhitelistError::UnsupportedLeafHashing),
+    }
+}
+
+/// Hash of `leaf` as it sits at the bottom of a tree built with `hashing_algorithm`.
+pub fn hash_leaf(hashing_algorithm: u8, leaf: &[u8]) -> [u8; 32] {
+    hash_parts(hashing_algorithm, &[leaf])
+}
+
+/// Bottom node of `leaf` in a tree built with `hashing_algorithm` and `pair_ordering`, what
+/// `verify_eligibility` reports as the leaf hash.
+pub fn leaf_hash(hashing_algorithm: u8, pair_ordering: u8, leaf: &[u8]) -> [u8; 32] {
+    match pair_ordering {
+        PAIR_ORDERING_INDEXED_PREFIXED => hash_parts(hashing_algorithm, &[&[LEAF_PREFIX], leaf]),
+        _ => hash_leaf(hashing_algorithm, leaf),
+    }
+}
+
+/// Hash of the concatenation of `parts`.
+fn hash_parts(hashing_algorithm: u8, parts: &[&[u8]]) -> [u8; 32] {
+    match hashing_algorithm {
+        HASHING_ALGORITHM_SHA256 => hash::hashv(parts).to_bytes(),
+        _ => keccak::hashv(parts).to_bytes(),
+    }
+}
+
+/// Hash of `left ‖ right`, sibling nodes or the parts of a committed leaf.
+fn hash_pair(hashing_algorithm: u8, left: &[u8], right: &[u8]) -> [u8; 32] {
+    hash_parts(hashing_algorithm, &[left, right])
+}
+
+/// Parent of `left` and `right`, prefixed trees marking it as a node.
+fn hash_node(hashing_algorithm: u8, pair_ordering: u8, left: &[u8], right: &[u8]) -> [u8; 32] {
+    match pair_ordering {
+        PAIR_ORDERING_INDEXED_PREFIXED => hash_parts(hashing_algorithm, &[&[NODE_PREFIX], left, right]),
+        _ => hash_pair(hashing_algorithm, left, right),
+    }
+}
+
+/// Node `hashes` leads to from `node` at `index`, one hash syscall per level over fixed
+/// nodes. Index-ordered pairs are placed by bit `depth` of the index, as `svm_merkle_tree`
+/// places them, sorted pairs put the lesser node on the left.
+fn fold_proof(hashing_algorithm: u8, pair_ordering: u8, mut node: [u8; 32], hashes: &[u8], index: u64) -> [u8; 32] {
+    for (depth, sibling) in hashes.chunks_exact(HASH_SIZE).enumerate() {
+        let is_left = match pair_ordering {
+            PAIR_ORDERING_SORTED => node.as_slice() <= sibling,
+            _ => index.checked_shr(depth as u32).unwrap_or(0) & 1 == 0,
+        };
+        node = if is_left {
+            hash_node(hashing_algorithm, pair_ordering, &node, sibling)
+        } else {
+            hash_node(hashing_algorithm, pair_ordering, sibling, &node)
+        };
+    }
+    node
+}
+
+/// Whether `hashes` proves that `leaf` sits at `index` of the tree committed to by `root`,
+/// built with `hashing_algorithm` and `pair_ordering`. Sorted pairs do not depend on `index`.
+pub fn verify_proof(
+    hashing_algorithm: u8,
+    pair_ordering: u8,
+    root: &[u8; 32],
+    leaf: &[u8],
+    hashes: &[u8],
+    index: u32,
+) -> Result<bool> {
+    check_hashing_algorithm(hashing_algorithm)?;
+    require!(
+        matches!(pair_ordering, PAIR_ORDERING_INDEXED | PAIR_ORDERING_SORTED | PAIR_ORDERING_INDEXED_PREFIXED),
+        WhitelistError::UnsupportedPairOrdering
+    );
+
+    let node = leaf_hash(hashing_algorithm, pair_ordering, leaf);
+    Ok(fold_proof(hashing_algorithm, pair_ordering, node, hashes, u64::from(index)) == *root)
+}
+
+/// Root the nodes of a canopy hash up to, a node left without a sibling being paired with
+/// itself. Sorted pairs skip those levels instead and are not supported.
+pub fn canopy_root(hashing_algorithm: u8, pair_ordering: u8, nodes: &[[u8; 32]]) -> Result<[u8; 32]> {
+    require!(pair_ordering != PAIR_ORDERING_SORTED, WhitelistError::UnsupportedPairOrdering);
+    require!(!nodes.is_empty(), WhitelistError::InvalidCanopy);
+
+    let mut level = nodes.to_vec();
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| hash_node(hashing_algorithm, pair_ordering, &pair[0], pair.last().unwrap_or(&pair[0])))
+            .collect();
+    }
+    Ok(level[0])
+}
+
+/// Checks that `hashes` proves the leaf built from `leaf_data` at `index` up to its node in
+/// the canopy `height` levels above the leaves, `canopy` holding the nodes of that level.
+pub fn verify_below_canopy(
+    layout: &TreeLayout,
+    height: u8,
+    canopy: &[[u8; 32]],
+    leaf_data: &[u8],
+    index: u64,
+    hashes: &[u8],
+) -> Result<()> {
+    require!(index < layout.num_leaves, WhitelistError::IndexOutOfRange);
+    require!(layout.pair_ordering != PAIR_ORDERING_SORTED, WhitelistError::UnsupportedPairOrdering);
+    let leaf = prepare_leaf(layout.leaf_hashing, layout.hashing_algorithm, leaf_data.to_vec())?;
+
+    let node = fold_proof(
+        layout.hashing_algorithm,
+        layout.pair_ordering,
+        leaf_hash(layout.hashing_algorithm, layout.pair_ordering, &leaf),
+        hashes,
+        index,
+    );
+    let position = index.checked_shr(u32::from(height)).unwrap_or(0);
+    require!(
+        usize::try_from(position).is_ok_and(|position| canopy.get(position) == Some(&node)),
+        WhitelistError::InvalidProof
+    );
+    Ok(())
+}
+
+/// How an airdrop's tree is built, what a claim is verified against besides the root.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TreeLayout {
+    pub hashing_algorithm: u8,
+    pub leaf_hashing: u8,
+    pub pair_ordering: u8,
+    pub num_leaves: u64,
+}
+
+/// Checks that `hashes` proves the leaf built from `leaf_data`, as `encode_leaf` or
+/// `committed_leaf` return it, at `index` of the tree committed to by `root`. Fails with the
+/// error the claim would, `InvalidProof` for any proof that does not lead to `root`.
+pub fn verify_claim(layout: &TreeLayout, root: &[u8; 32], leaf_data: &[u8], index: u64, hashes: &[u8]) -> Result<()> {
+    require!(index < layout.num_leaves, WhitelistError::IndexOutOfRange);
+    let proof_index = proof_index(index)?;
+    let leaf = prepare_leaf(layout.leaf_hashing, layout.hashing_algorithm, leaf_data.to_vec())?;
+
+    check_proof_shape(hashes, layout.num_leaves)?;
+    require!(
+        verify_proof(layout.hashing_algorithm, layout.pair_ordering, root, &leaf, hashes, proof_index)?,
+        WhitelistError::InvalidProof
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const ORDERINGS: [u8; 3] = [PAIR_ORDERING_INDEXED, PAIR_ORDERING_SORTED, PAIR_ORDERING_INDEXED_PREFIXED];
+
+    /// Random airdrop tree, `2^depth` leaves of random claimants and amounts.
+    #[derive(Debug)]
+    struct Tree {
+        layout: TreeLayout,
+        leaf_version: u8,
+        allocations: Vec<(Pubkey, u64)>,
+        levels: Vec<Vec<[u8; 32]>>,
+    }
+
+    impl Tree {
+        fn build(layout: TreeLayout, leaf_version: u8, allocations: Vec<(Pubkey, u64)>) -> Self {
+            let mut levels = vec![(0..allocations.len())
+                .map(|index| {
+                    let leaf = prepare_leaf(layout.leaf_hashing, layout.hashing_algorithm, Self::leaf_data(leaf_version, &allocations, index as u64)).unwrap();
+                    leaf_hash(layout.hashing_algorithm, layout.pair_ordering, &leaf)
+                })
+                .collect::<Vec<_>>()];
+            while levels.last().unwrap().len() > 1 {
+                let level = levels
+                    .last()
+                    .unwrap()
+                    .chunks(2)
+                    .map(|pair| match layout.pair_ordering {
+                        PAIR_ORDERING_SORTED => hash_node(layout.hashing_algorithm, layout.pair_ordering, pair[0].min(pair[1]).as_slice(), pair[0].max(pair[1]).as_slice()),
+                        _ => hash_node(layout.hashing_algorithm, layout.pair_ordering, &pair[0], &pair[1]),
+                    })
+                    .collect();
+                levels.push(level);
+            }
+            Self { layout, leaf_version, allocations, levels }
+        }
+
+        /// Leaf data of the allocation at `index`, as the claim of `index` encodes it.
+        fn leaf_data(leaf_version: u8, allocations: &[(Pubkey, u64)], index: u64) -> Vec<u8> {
+            let (claimant, amount) = allocations[index as usize];
+            encode_leaf(leaf_version, index, &claimant, amount).unwrap()
+        }
+
+        fn root(&self) -> [u8; 32] {
+            self.levels.last().unwrap()[0]
+        }
+
+        fn proof(&self, index: usize) -> Vec<u8> {
+            self.levels[..self.levels.len() - 1]
+                .iter()
+                .enumerate()
+                .flat_map(|(depth, level)| level[(index >> depth) ^ 1])
+                .collect()
+        }
+
+        fn verify(&self, leaf_data: &[u8], index: u64, hashes: &[u8]) -> Result<()> {
+            verify_claim(&self.layout, &self.root(), leaf_data, index, hashes)
+        }
+    }
+
+    fn trees(depths: std::ops::RangeInclusive<u32>) -> impl Strategy<Value = Tree> {
+        (depths, 0..2u8, 0..2u8, prop::sample::select(ORDERINGS.to_vec()), 0..2u8).prop_flat_map(
+            |(depth, hashing_algorithm, leaf_hashing, pair_ordering, leaf_version)| {
+                let num_leaves = 1usize << depth;
+                prop::collection::vec((any::<[u8; 32]>().prop_map(Pubkey::new_from_array), any::<u64>()), num_leaves).prop_map(
+                    move |allocations| {
+                        let layout = TreeLayout { hashing_algorithm, leaf_hashing, pair_ordering, num_leaves: num_leaves as u64 };
+                        Tree::build(layout, leaf_version, allocations)
+                    },
+                )
+            },
+        )
+    }
+
+    fn error_code(result: Result<()>) -> Option<u32> {
+        match result {
+            Err(Error::AnchorError(error)) => Some(error.error_code_number),
+            _ => None,
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn verifies_every_leaf(tree in trees(0..=6)) {
+            for index in 0..tree.allocations.len() {
+                let leaf_data = Tree::leaf_data(tree.leaf_version, &tree.allocations, index as u64);
+                prop_assert!(tree.verify(&leaf_data, index as u64, &tree.proof(index)).is_ok());
+            }
+        }
+
+        #[test]
+        fn rejects_a_wrong_amount(tree in trees(0..=6), index in any::<prop::sample::Index>(), delta in 1..=u64::MAX) {
+            let index = index.index(tree.allocations.len());
+            let (claimant, amount) = tree.allocations[index];
+            let leaf_data = encode_leaf(tree.leaf_version, index as u64, &claimant, amount.wrapping_add(delta)).unwrap();
+
+            prop_assert_eq!(error_code(tree.verify(&leaf_data, index as u64, &tree.proof(index))), Some(u32::from(WhitelistError::InvalidProof)));
+        }
+
+        #[test]
+        fn rejects_a_wrong_index(tree in trees(1..=6), index in any::<prop::sample::Index>(), offset in any::<prop::sample::Index>()) {
+            // A sorted tree does not bind a leaf without its index to a position, the bitmap does
+            prop_assume!(tree.layout.pair_ordering != PAIR_ORDERING_SORTED || tree.leaf_version == LEAF_VERSION_INDEX_CLAIMANT_AMOUNT);
+            let num_leaves = tree.allocations.len();
+            let index = index.index(num_leaves);
+            let wrong_index = (index + 1 + offset.index(num_leaves - 1)) % num_leaves;
+            let (claimant, amount) = tree.allocations[index];
+            let leaf_data = encode_leaf(tree.leaf_version, wrong_index as u64, &claimant, amount).unwrap();
+
+            prop_assert_eq!(error_code(tree.verify(&leaf_data, wrong_index as u64, &tree.proof(index))), Some(u32::from(WhitelistError::InvalidProof)));
+        }
+
+        #[test]
+        fn rejects_a_truncated_proof(tree in trees(1..=6), index in any::<prop::sample::Index>(), cut in any::<prop::sample::Index>()) {
+            let index = index.index(tree.allocations.len());
+            let leaf_data = Tree::leaf_data(tree.leaf_version, &tree.allocations, index as u64);
+            let proof = tree.proof(index);
+
+            prop_assert!(tree.verify(&leaf_data, index as u64, &proof[..cut.index(proof.len())]).is_err());
+        }
+
+        #[test]
+        fn rejects_a_bit_flipped_sibling(tree in trees(1..=6), index in any::<prop::sample::Index>(), byte in any::<prop::sample::Index>(), bit in 0..8u8) {
+            let index = index.index(tree.allocations.len());
+            let leaf_data = Tree::leaf_data(tree.leaf_version, &tree.allocations, index as u64);
+            let mut proof = tree.proof(index);
+            let byte = byte.index(proof.len());
+            proof[byte] ^= 1 << bit;
+
+            prop_assert_eq!(error_code(tree.verify(&leaf_data, index as u64, &proof)), Some(u32::from(WhitelistError::InvalidProof)));
+        }
+    }
+}
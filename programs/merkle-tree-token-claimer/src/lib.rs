@@ -1,227 +1,7708 @@
-use anchor_lang::prelude::*;
-use anchor_spl::{associated_token::AssociatedToken, token::{mint_to, set_authority, transfer, Mint, MintTo, SetAuthority, Token, TokenAccount, Transfer}};
-use svm_merkle_tree::{HashingAlgorithm, MerkleProof};
+//! Other programs depend on this crate with the `cpi` feature, which drops the entrypoint and
+//! exposes typed builders for every instruction under `cpi`, their accounts under `cpi::accounts`.
+
+use anchor_lang::{prelude::*, solana_program::program_option::COption};
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::{invoke, invoke_signed, set_return_data};
+use anchor_lang::solana_program::{ed25519_program, sysvar::instructions::{self as instructions_sysvar, load_current_index_checked, load_instruction_at_checked}};
+use anchor_spl::{associated_token::{create_idempotent, get_associated_token_address_with_program_id, AssociatedToken, Create}, metadata::{create_metadata_accounts_v3, mpl_token_metadata::types::DataV2, CreateMetadataAccountsV3, Metadata, MetadataAccount}, token_2022::{initialize_mint2, spl_token_2022::{self, extension::{transfer_fee::TransferFeeConfig, BaseStateWithExtensions, ExtensionType, StateWithExtensions}, instruction::AuthorityType}, InitializeMint2, Token2022}, token_2022_extensions::{non_transferable::{non_transferable_mint_initialize, NonTransferableMintInitialize}, token_metadata::{token_metadata_initialize, TokenMetadataInitialize}}, token::spl_token, token_interface::{burn, close_account, mint_to, set_authority, sync_native, Burn, CloseAccount, Mint, MintTo, SetAuthority, SyncNative, TokenAccount, TokenInterface}};
+use spl_pod::optional_keys::OptionalNonZeroPubkey;
 
 declare_id!("GTCPuHiGookQVSAgGc7CzBiFYPytjVAq6vdCV3NnZoHa");
 
+pub mod compression;
+pub mod leaf;
+pub mod migration;
+#[cfg(not(target_os = "solana"))]
+pub mod tree;
+
+use leaf::{basket_leaf, canopy_root, check_hashing_algorithm, committed_leaf, deadline_leaf, encode_leaf, leaf_hash, nft_leaf, prepare_leaf, proof_index, split_leaf, verify_below_canopy, verify_claim, TreeLayout};
+use compression::{CompressionAccounts, CLAIMED_LEAF, SPL_ACCOUNT_COMPRESSION_ID, SPL_NOOP_ID};
+use migration::{AirdropStateV1, AirdropStateV2};
+
+#[constant]
+pub const MAX_DECIMALS: u8 = 9;
+/// Layout version of `AirdropState`, accounts of older layouts go through `migrate_state`.
+#[constant]
+pub const STATE_VERSION: u8 = 3;
+/// Longest label an airdrop can be given, in bytes.
+#[constant]
+pub const MAX_LABEL_LEN: usize = 32;
+/// Longest allocation list URI an airdrop can point at, in bytes.
+#[constant]
+pub const MAX_METADATA_URI_LEN: usize = 200;
+
+pub const MAX_NAME_LENGTH: usize = 32;
+pub const MAX_SYMBOL_LENGTH: usize = 10;
+pub const MAX_URI_LENGTH: usize = 200;
+
+/// Size in bytes of every hash in a proof.
+pub const HASH_SIZE: usize = 32;
+
+/// Deepest proof accepted, enough for trees of up to 2^32 leaves.
+#[constant]
+pub const MAX_PROOF_DEPTH: usize = 32;
+
+/// Claims pay out exactly the amount of their leaf, once.
+#[constant]
+pub const CLAIM_MODE_EXACT: u8 = 0;
+/// The amount of a leaf is a cap claimed in as many parts as the claimant wants with
+/// `claim_partial`.
+#[constant]
+pub const CLAIM_MODE_PARTIAL: u8 = 1;
+/// Leaves hold the amount ever owed to their claimant and `claim_airdrop` pays what is due
+/// above what they were already paid, so roots can be republished with growing totals.
+#[constant]
+pub const CLAIM_MODE_CUMULATIVE: u8 = 2;
+/// `claim_airdrop` escrows the amount of a leaf in a `VestingAccount` instead of paying it, and
+/// `release` pays it out linearly over the schedule set with `configure_vesting`.
+#[constant]
+pub const CLAIM_MODE_VESTING: u8 = 3;
+/// Leaves decay linearly from the start of the airdrop over the schedule set with
+/// `configure_decay`, `claim_airdrop` pays what is left of them and forfeits the rest.
+#[constant]
+pub const CLAIM_MODE_DECAYING: u8 = 4;
+
+/// The tree is the root stored in the airdrop state, swapped as a whole by `update_tree`.
+#[constant]
+pub const TREE_MODE_ROOT: u8 = 0;
+/// The tree is a concurrent tree of spl-account-compression, see `enable_concurrent_tree`.
+/// Claims replace their leaf with `claim_concurrent`, the authority appends and replaces leaves.
+#[constant]
+pub const TREE_MODE_CONCURRENT: u8 = 1;
+
+/// Basis points a whole amount is made of.
+#[constant]
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Most claims `claim_many` verifies in one go, keeping a batch within the compute budget of
+/// a transaction.
+#[constant]
+pub const MAX_BATCH_CLAIMS: usize = 8;
+
+/// Most nodes a `Canopy` holds, the upper 8 levels of a tree.
+#[constant]
+pub const MAX_CANOPY_NODES: usize = 256;
+
+/// Roles an `AdminRegistry` grants, bits of `RoleGrant::roles`. The update authority holds all
+/// but `ROLE_WITHDRAWER` without a registry, the withdraw authority that and `ROLE_PAUSER`.
+/// `ROLE_PAUSER` pauses and resumes claims.
+#[constant]
+pub const ROLE_PAUSER: u8 = 1 << 0;
+/// Manages the root, canopy, rounds, revocations, funding and claim configs, and pushes claims.
+#[constant]
+pub const ROLE_UPDATER: u8 = 1 << 1;
+/// Withdraws, burns and closes like the withdraw authority.
+#[constant]
+pub const ROLE_WITHDRAWER: u8 = 1 << 2;
+/// Lowers the fees of the airdrop and funds its bonus reserve.
+#[constant]
+pub const ROLE_FEE_MANAGER: u8 = 1 << 3;
+/// Every role a grant can hold.
+pub const ALL_ROLES: u8 = ROLE_PAUSER | ROLE_UPDATER | ROLE_WITHDRAWER | ROLE_FEE_MANAGER;
+
+/// Most keys an `AdminRegistry` grants roles to.
+#[constant]
+pub const MAX_ROLE_MEMBERS: usize = 16;
+
+/// Most mints a `Basket` adds to the mint of its airdrop.
+#[constant]
+pub const MAX_BASKET_MINTS: usize = 4;
+
+/// Most accounts a `StakeConfig` passes to the deposit of its staking program.
+#[constant]
+pub const MAX_STAKE_ACCOUNTS: usize = 8;
+
+/// Discriminants of the hashing algorithms a tree can be built with.
+#[constant]
+pub const HASHING_ALGORITHM_KECCAK: u8 = 0;
+#[constant]
+pub const HASHING_ALGORITHM_SHA256: u8 = 1;
+
+/// Discriminants of how many times leaf data is hashed before it enters the tree.
+#[constant]
+pub const LEAF_HASHING_SINGLE: u8 = 0;
+#[constant]
+pub const LEAF_HASHING_DOUBLE: u8 = 1;
+
+/// Discriminants of how sibling hashes are ordered when combined: by the leaf index, sorted
+/// as `merkletreejs` does with `sortPairs`, or by the leaf index with leaves hashed as
+/// `hash(LEAF_PREFIX ‖ leaf)`, internal nodes as `hash(NODE_PREFIX ‖ left ‖ right)` and an
+/// odd node paired with itself.
+#[constant]
+pub const PAIR_ORDERING_INDEXED: u8 = 0;
+#[constant]
+pub const PAIR_ORDERING_SORTED: u8 = 1;
+#[constant]
+pub const PAIR_ORDERING_INDEXED_PREFIXED: u8 = 2;
+
+/// Domain separation bytes of `PAIR_ORDERING_INDEXED_PREFIXED`, an internal node can never be
+/// passed off as a leaf.
+#[constant]
+pub const LEAF_PREFIX: u8 = 0x00;
+#[constant]
+pub const NODE_PREFIX: u8 = 0x01;
+
+/// Leaf versions, the layouts a leaf can be encoded in: `claimant ‖ amount`, the merkle-distributor
+/// `index ‖ claimant ‖ amount`, integers little-endian, or a commitment to the claimant and
+/// amount along with data the program does not interpret, claimed with `claim_committed_leaf`,
+/// `claimant ‖ unlocked_amount ‖ locked_amount` claimed with `claim_split_leaf`,
+/// `claimant ‖ amount ‖ deadline` claimed with `claim_with_deadline` until the deadline,
+/// `claimant ‖ amount ‖ basket_amount…` claimed with `claim_basket`, an amount per mint, or
+/// `claimant ‖ nft_mint` claimed with `claim_nft`, only airdrops of `initialize_nft_airdrop`.
+#[constant]
+pub const LEAF_VERSION_CLAIMANT_AMOUNT: u8 = 0;
+#[constant]
+pub const LEAF_VERSION_INDEX_CLAIMANT_AMOUNT: u8 = 1;
+#[constant]
+pub const LEAF_VERSION_COMMITTED: u8 = 2;
+#[constant]
+pub const LEAF_VERSION_SPLIT: u8 = 3;
+#[constant]
+pub const LEAF_VERSION_DEADLINE: u8 = 4;
+#[constant]
+pub const LEAF_VERSION_BASKET: u8 = 5;
+#[constant]
+pub const LEAF_VERSION_NFT: u8 = 6;
+
+/// `mint` of airdrops paying native SOL out of their SOL vault, which have no mint. Their
+/// airdrop state is derived from it like from a mint.
+pub const NATIVE_SOL: Pubkey = Pubkey::new_from_array([0; 32]);
+
+/// `mint` of NFT airdrops, whose leaves each name the mint they pay, derived from like
+/// `NATIVE_SOL`.
+pub const NFT_AIRDROP: Pubkey = Pubkey::new_from_array([1; 32]);
+
 #[program]
 pub mod merkle_tree_token_claimer {
-    use anchor_spl::token::spl_token::instruction::AuthorityType;
-
     use super::*;
 
     pub fn initialize_airdrop_data(
         ctx: Context<Initialize>, 
-        merkle_root: [u8; 32],
-        amount: u64,
+        params: AirdropParams,
+        payout: PayoutParams,
+        decimals: u8,
+        authority_mode: MintAuthorityMode,
+        mint_on_claim: bool,
+        metaplex_metadata: Option<TokenMetadataArgs>,
     ) -> Result<()> {
 
-        ctx.accounts.airdrop_state.set_inner(
-            AirdropState {
-                merkle_root,
-                authority: ctx.accounts.authority.key(),
-                mint: ctx.accounts.mint.key(),
-                airdrop_amount: amount,
-                amount_claimed: 0,
-                bump: ctx.bumps.airdrop_state,
-            }
+        params.validate()?;
+        payout.validate()?;
+        require!(decimals <= MAX_DECIMALS, WhitelistError::InvalidDecimals);
+        require!(
+            !mint_on_claim || authority_mode == MintAuthorityMode::TransferToPda,
+            WhitelistError::InvalidAuthorityMode
         );
 
-        mint_to(
+        ctx.accounts.airdrop_state.set_inner(AirdropState::new(
+            &params,
+            &payout,
+            ctx.accounts.authority.key(),
+            ctx.accounts.mint.key(),
+            ctx.accounts.mint.decimals,
+            mint_on_claim,
+            ctx.bumps.airdrop_state,
+        ));
+
+        // Metadata creation needs the mint authority signature, so it runs before it is released
+        if let Some(metadata) = metaplex_metadata {
+            require!(
+                metadata.name.len() <= MAX_NAME_LENGTH
+                    && metadata.symbol.len() <= MAX_SYMBOL_LENGTH
+                    && metadata.uri.len() <= MAX_URI_LENGTH,
+                WhitelistError::MetadataTooLong
+            );
+
+            let (Some(metadata_account), Some(token_metadata_program), Some(rent)) = (
+                &ctx.accounts.metadata,
+                &ctx.accounts.token_metadata_program,
+                &ctx.accounts.rent,
+            ) else {
+                return err!(WhitelistError::MissingMetadataAccounts);
+            };
+
+            create_metadata_accounts_v3(
+                CpiContext::new(
+                    token_metadata_program.to_account_info(),
+                    CreateMetadataAccountsV3 {
+                        metadata: metadata_account.to_account_info(),
+                        mint: ctx.accounts.mint.to_account_info(),
+                        mint_authority: ctx.accounts.authority.to_account_info(),
+                        payer: ctx.accounts.payer.to_account_info(),
+                        update_authority: ctx.accounts.authority.to_account_info(),
+                        system_program: ctx.accounts.system_program.to_account_info(),
+                        rent: rent.to_account_info(),
+                    }
+                ),
+                DataV2 {
+                    name: metadata.name,
+                    symbol: metadata.symbol,
+                    uri: metadata.uri,
+                    seller_fee_basis_points: 0,
+                    creators: None,
+                    collection: None,
+                    uses: None,
+                },
+                metadata.keep_update_authority,
+                true,
+                None
+            )?;
+        }
+
+        fund_new_mint(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.mint.to_account_info(),
+            ctx.accounts.vault.to_account_info(),
+            ctx.accounts.authority.to_account_info(),
+            ctx.accounts.airdrop_state.key(),
+            payout.amount,
+            authority_mode,
+            mint_on_claim,
+        )?;
+
+        emit_cpi!(AirdropInitialized {
+            airdrop_state: ctx.accounts.airdrop_state.key(),
+            airdrop_id: params.airdrop_id,
+            label: params.label,
+            metadata_uri: params.metadata_uri,
+            authority: ctx.accounts.authority.key(),
+            mint: ctx.accounts.mint.key(),
+            merkle_root: params.merkle_root,
+            airdrop_amount: payout.amount,
+            num_leaves: params.num_leaves,
+        });
+
+        Ok(())
+    }
+
+    pub fn initialize_with_metadata(
+        ctx: Context<InitializeWithMetadata>, 
+        params: AirdropParams,
+        payout: PayoutParams,
+        decimals: u8,
+        authority_mode: MintAuthorityMode,
+        mint_on_claim: bool,
+        metadata: TokenMetadataArgs,
+    ) -> Result<()> {
+
+        params.validate()?;
+        payout.validate()?;
+        require!(decimals <= MAX_DECIMALS, WhitelistError::InvalidDecimals);
+        require!(
+            !mint_on_claim || authority_mode == MintAuthorityMode::TransferToPda,
+            WhitelistError::InvalidAuthorityMode
+        );
+        require!(
+            metadata.name.len() <= MAX_NAME_LENGTH
+                && metadata.symbol.len() <= MAX_SYMBOL_LENGTH
+                && metadata.uri.len() <= MAX_URI_LENGTH,
+            WhitelistError::MetadataTooLong
+        );
+
+        ctx.accounts.airdrop_state.set_inner(AirdropState::new(
+            &params,
+            &payout,
+            ctx.accounts.authority.key(),
+            ctx.accounts.mint.key(),
+            ctx.accounts.mint.decimals,
+            mint_on_claim,
+            ctx.bumps.airdrop_state,
+        ));
+
+        // The metadata extension is variable length, the mint needs the rent for it up front
+        let mint_info = ctx.accounts.mint.to_account_info();
+        let required_lamports = Rent::get()?
+            .minimum_balance(mint_info.data_len() + metadata.tlv_space())
+            .saturating_sub(mint_info.lamports());
+        if required_lamports > 0 {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.payer.to_account_info(),
+                        to: mint_info.clone(),
+                    }
+                ),
+                required_lamports
+            )?;
+        }
+
+        token_metadata_initialize(
             CpiContext::new(
                 ctx.accounts.token_program.to_account_info(), 
-                MintTo {
-                    mint: ctx.accounts.mint.to_account_info(),
-                    to: ctx.accounts.vault.to_account_info(),
-                    authority: ctx.accounts.authority.to_account_info(),
+                TokenMetadataInitialize {
+                    token_program_id: ctx.accounts.token_program.to_account_info(),
+                    mint: mint_info.clone(),
+                    metadata: mint_info.clone(),
+                    mint_authority: ctx.accounts.authority.to_account_info(),
+                    update_authority: ctx.accounts.authority.to_account_info(),
                 }
             ),
-            amount
+            metadata.name,
+            metadata.symbol,
+            metadata.uri
         )?;
 
-        set_authority(
+        if !metadata.keep_update_authority {
+            invoke(
+                &spl_token_metadata_interface::instruction::update_authority(
+                    &ctx.accounts.token_program.key(),
+                    &ctx.accounts.mint.key(),
+                    &ctx.accounts.authority.key(),
+                    OptionalNonZeroPubkey::default(),
+                ),
+                &[mint_info, ctx.accounts.authority.to_account_info()],
+            )?;
+        }
+
+        fund_new_mint(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.mint.to_account_info(),
+            ctx.accounts.vault.to_account_info(),
+            ctx.accounts.authority.to_account_info(),
+            ctx.accounts.airdrop_state.key(),
+            payout.amount,
+            authority_mode,
+            mint_on_claim,
+        )?;
+
+        emit_cpi!(AirdropInitialized {
+            airdrop_state: ctx.accounts.airdrop_state.key(),
+            airdrop_id: params.airdrop_id,
+            label: params.label,
+            metadata_uri: params.metadata_uri,
+            authority: ctx.accounts.authority.key(),
+            mint: ctx.accounts.mint.key(),
+            merkle_root: params.merkle_root,
+            airdrop_amount: payout.amount,
+            num_leaves: params.num_leaves,
+        });
+
+        Ok(())
+    }
+
+    /// Initializes an airdrop of a soulbound token, creating its Token-2022 mint with the
+    /// `NonTransferable` extension. Tokens of the mint never leave the account they are minted
+    /// into, vault included, so the airdrop mints on claim with the airdrop state as the mint
+    /// authority and the payout amount as the cap.
+    pub fn initialize_non_transferable(
+        ctx: Context<InitializeNonTransferable>,
+        params: AirdropParams,
+        payout: PayoutParams,
+        decimals: u8,
+    ) -> Result<()> {
+
+        params.validate()?;
+        payout.validate()?;
+        require!(decimals <= MAX_DECIMALS, WhitelistError::InvalidDecimals);
+
+        ctx.accounts.airdrop_state.set_inner(AirdropState::new(
+            &params,
+            &payout,
+            ctx.accounts.authority.key(),
+            ctx.accounts.mint.key(),
+            decimals,
+            true,
+            ctx.bumps.airdrop_state,
+        ));
+
+        // Anchor has no constraint for the extension, so the mint is created by hand: the
+        // extension goes in before the mint itself is initialized
+        let mint_info = ctx.accounts.mint.to_account_info();
+        let space = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[
+            ExtensionType::NonTransferable,
+        ])?;
+        anchor_lang::system_program::create_account(
             CpiContext::new(
-                ctx.accounts.token_program.to_account_info(), 
-                SetAuthority {
-                    current_authority: ctx.accounts.authority.to_account_info(),
-                    account_or_mint: ctx.accounts.mint.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::CreateAccount {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: mint_info.clone(),
                 }
-            ), 
-            AuthorityType::MintTokens,
-            None
+            ),
+            Rent::get()?.minimum_balance(space),
+            space as u64,
+            &ctx.accounts.token_program.key(),
+        )?;
+        non_transferable_mint_initialize(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                NonTransferableMintInitialize {
+                    token_program_id: ctx.accounts.token_program.to_account_info(),
+                    mint: mint_info.clone(),
+                }
+            )
+        )?;
+        initialize_mint2(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                InitializeMint2 { mint: mint_info.clone() }
+            ),
+            decimals,
+            &ctx.accounts.airdrop_state.key(),
+            None,
+        )?;
+
+        // Nothing is ever paid out of the vault, it only exists for the accounts of the claims
+        create_idempotent(
+            CpiContext::new(
+                ctx.accounts.associated_token_program.to_account_info(),
+                Create {
+                    payer: ctx.accounts.payer.to_account_info(),
+                    associated_token: ctx.accounts.vault.to_account_info(),
+                    authority: ctx.accounts.airdrop_state.to_account_info(),
+                    mint: mint_info,
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                }
+            )
+        )?;
+
+        emit_cpi!(AirdropInitialized {
+            airdrop_state: ctx.accounts.airdrop_state.key(),
+            airdrop_id: params.airdrop_id,
+            label: params.label,
+            metadata_uri: params.metadata_uri,
+            authority: ctx.accounts.authority.key(),
+            mint: ctx.accounts.mint.key(),
+            merkle_root: params.merkle_root,
+            airdrop_amount: payout.amount,
+            num_leaves: params.num_leaves,
+        });
+
+        Ok(())
+    }
+
+    /// Initializes an airdrop of native SOL, funding its SOL vault with `amount` lamports on
+    /// top of the vault's own rent exemption. The airdrop has no mint, `mint` is `NATIVE_SOL`,
+    /// and is claimed with `claim_sol`.
+    pub fn initialize_sol_airdrop(
+        ctx: Context<InitializeSolAirdrop>,
+        params: AirdropParams,
+        amount: u64,
+        leaf_version: u8,
+    ) -> Result<()> {
+
+        // SOL airdrops take no fees and no cosigner
+        let payout = PayoutParams { amount, leaf_version, ..Default::default() };
+        params.validate()?;
+        payout.validate()?;
+
+        ctx.accounts.airdrop_state.set_inner(AirdropState::new(
+            &params,
+            &payout,
+            ctx.accounts.authority.key(),
+            NATIVE_SOL,
+            9,
+            false,
+            ctx.bumps.airdrop_state,
+        ));
+
+        // The vault keeps its rent exemption for good, claims only ever pay out above it. The
+        // payer covers the exemption, the authority the lamports paid out
+        let rent_lamports = Rent::get()?
+            .minimum_balance(0)
+            .saturating_sub(ctx.accounts.sol_vault.lamports());
+        if rent_lamports > 0 {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.payer.to_account_info(),
+                        to: ctx.accounts.sol_vault.to_account_info(),
+                    }
+                ),
+                rent_lamports
+            )?;
+        }
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: ctx.accounts.sol_vault.to_account_info(),
+                }
+            ),
+            amount
         )?;
 
+        emit_cpi!(AirdropInitialized {
+            airdrop_state: ctx.accounts.airdrop_state.key(),
+            airdrop_id: params.airdrop_id,
+            label: params.label,
+            metadata_uri: params.metadata_uri,
+            authority: ctx.accounts.authority.key(),
+            mint: NATIVE_SOL,
+            merkle_root: params.merkle_root,
+            airdrop_amount: payout.amount,
+            num_leaves: params.num_leaves,
+        });
+
+        Ok(())
+    }
+
+    /// Initializes an airdrop of NFTs, every leaf `claimant ‖ nft_mint` paying the one token of
+    /// its mint with `claim_nft`. `mint` is `NFT_AIRDROP`, the NFTs are deposited into vaults of
+    /// the airdrop state with `deposit_nft` and every leaf counts as one towards its amounts.
+    pub fn initialize_nft_airdrop(
+        ctx: Context<InitializeNftAirdrop>,
+        params: AirdropParams,
+    ) -> Result<()> {
+
+        require!(params.num_leaves > 0, WhitelistError::InvalidAmount);
+        params.validate()?;
+
+        ctx.accounts.airdrop_state.set_inner(AirdropState::new(
+            &params,
+            &PayoutParams { amount: params.num_leaves, leaf_version: LEAF_VERSION_NFT, ..Default::default() },
+            ctx.accounts.authority.key(),
+            NFT_AIRDROP,
+            0,
+            false,
+            ctx.bumps.airdrop_state,
+        ));
+
+        emit_cpi!(AirdropInitialized {
+            airdrop_state: ctx.accounts.airdrop_state.key(),
+            airdrop_id: params.airdrop_id,
+            label: params.label,
+            metadata_uri: params.metadata_uri,
+            authority: ctx.accounts.authority.key(),
+            mint: NFT_AIRDROP,
+            merkle_root: params.merkle_root,
+            airdrop_amount: params.num_leaves,
+            num_leaves: params.num_leaves,
+        });
+
+        Ok(())
+    }
+
+    /// Deposits the NFT of `nft_mint` into its vault, the ATA of the airdrop state, for the
+    /// leaf naming it to claim.
+    pub fn deposit_nft<'info>(ctx: Context<'_, '_, '_, 'info, DepositNft<'info>>) -> Result<()> {
+        transfer_checked_with_hook(
+            &ctx.accounts.token_program,
+            ctx.accounts.authority_ata.to_account_info(),
+            &ctx.accounts.nft_mint,
+            ctx.accounts.vault.to_account_info(),
+            ctx.accounts.update_authority.to_account_info(),
+            ctx.remaining_accounts,
+            1,
+            &[],
+        )
+    }
+
+    pub fn initialize_with_existing_mint<'info>(
+        ctx: Context<'_, '_, '_, 'info, InitializeWithExistingMint<'info>>, 
+        params: AirdropParams,
+        payout: PayoutParams,
+    ) -> Result<()> {
+
+        params.validate()?;
+        payout.validate()?;
+        ctx.accounts.airdrop_state.set_inner(AirdropState::new(
+            &params,
+            &payout,
+            ctx.accounts.authority.key(),
+            ctx.accounts.mint.key(),
+            ctx.accounts.mint.decimals,
+            false,
+            ctx.bumps.airdrop_state,
+        ));
+
+        // Wrapped SOL is funded in lamports straight into the vault, the authority needs no
+        // wrapped SOL of its own
+        if is_native_mint(&ctx.accounts.mint.key()) {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.authority.to_account_info(),
+                        to: ctx.accounts.vault.to_account_info(),
+                    }
+                ),
+                payout.amount
+            )?;
+            sync_native(CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                SyncNative { account: ctx.accounts.vault.to_account_info() },
+            ))?;
+        } else {
+            let authority_ata = ctx.accounts.authority_ata
+                .as_ref()
+                .ok_or(WhitelistError::MissingAuthorityTokenAccount)?;
+            require!(authority_ata.amount >= payout.amount, WhitelistError::InsufficientFunds);
+
+            transfer_checked_with_hook(
+                &ctx.accounts.token_program,
+                authority_ata.to_account_info(),
+                &ctx.accounts.mint,
+                ctx.accounts.vault.to_account_info(),
+                ctx.accounts.authority.to_account_info(),
+                ctx.remaining_accounts,
+                payout.amount,
+                &[],
+            )?;
+        }
+
+        emit_cpi!(AirdropInitialized {
+            airdrop_state: ctx.accounts.airdrop_state.key(),
+            airdrop_id: params.airdrop_id,
+            label: params.label,
+            metadata_uri: params.metadata_uri,
+            authority: ctx.accounts.authority.key(),
+            mint: ctx.accounts.mint.key(),
+            merkle_root: params.merkle_root,
+            airdrop_amount: payout.amount,
+            num_leaves: params.num_leaves,
+        });
+
         Ok(())
     }
 
     pub fn update_tree(
         ctx: Context<Update>, 
-        new_root: [u8; 32]
+        new_root: [u8; 32],
+        num_leaves: u64,
+    ) -> Result<()> {
+
+        // A concurrent tree keeps its root in the tree account, it is never replaced from here
+        require!(
+            ctx.accounts.airdrop_state.tree_mode != TREE_MODE_CONCURRENT,
+            WhitelistError::TreeModeMismatch
+        );
+        require!(
+            !ctx.accounts.airdrop_state.is_finalized,
+            WhitelistError::TreeFinalized
+        );
+        require!(
+            ctx.accounts.airdrop_state.root_timelock == 0,
+            WhitelistError::RootTimelocked
+        );
+        // Outstanding proofs die with the old root, changing it mid-airdrop has to be deliberate.
+        // Cumulative leaves only ever grow, claims against the old root pay out nothing twice.
+        require!(
+            ctx.accounts.airdrop_state.amount_claimed == 0
+                || ctx.accounts.airdrop_state.claim_mode == CLAIM_MODE_CUMULATIVE,
+            WhitelistError::ClaimsAlreadyStarted
+        );
+
+        let clock = Clock::get()?;
+        let airdrop_state = &mut ctx.accounts.airdrop_state;
+        let old_root = airdrop_state.merkle_root;
+        airdrop_state.rotate_root(new_root, num_leaves, clock.slot)?;
+        emit_cpi!(root_updated_event(airdrop_state, old_root, &clock));
+
+        Ok(())
+    }
+
+    pub fn force_update_tree(
+        ctx: Context<Update>, 
+        new_root: [u8; 32],
+        num_leaves: u64,
     ) -> Result<()> {
+        let airdrop_state = &mut ctx.accounts.airdrop_state;
+
+        require!(airdrop_state.tree_mode != TREE_MODE_CONCURRENT, WhitelistError::TreeModeMismatch);
+        require!(!airdrop_state.is_finalized, WhitelistError::TreeFinalized);
+        require!(airdrop_state.root_timelock == 0, WhitelistError::RootTimelocked);
 
-        ctx.accounts.airdrop_state.merkle_root = new_root;
+        let clock = Clock::get()?;
+        let old_root = airdrop_state.merkle_root;
+        airdrop_state.rotate_root(new_root, num_leaves, clock.slot)?;
+        emit_cpi!(root_updated_event(airdrop_state, old_root, &clock));
+
+        emit_cpi!(RootForceUpdated {
+            airdrop_state: airdrop_state.key(),
+            old_root,
+            new_root,
+            root_version: airdrop_state.root_version,
+            amount_claimed: airdrop_state.amount_claimed,
+        });
 
         Ok(())
     }
 
-    pub fn claim_airdrop(
-        ctx: Context<Claim>,
-        amount: u64,
-        hashes: Vec<u8>,
-        index: u64,
-    ) -> Result<()> {    
+    pub fn propose_root(
+        ctx: Context<Update>, 
+        new_root: [u8; 32],
+        num_leaves: u64,
+    ) -> Result<()> {
         let airdrop_state = &mut ctx.accounts.airdrop_state;
-    
-        // Step 1: Verify that the Signer and Amount are right by computing the original leaf
-        let mut original_leaf = Vec::new();
-        original_leaf.extend_from_slice(&ctx.accounts.signer.key().to_bytes());
-        original_leaf.extend_from_slice(&amount.to_le_bytes());
-        original_leaf.push(0u8); // isClaimed = false
-    
-        // Step 2: Verify the Merkle proof against the on-chain root
-        let merkle_proof = MerkleProof::new(
-            HashingAlgorithm::Keccak,
-            32,
-            index as u32,
-            hashes.clone(),
+
+        require!(airdrop_state.tree_mode != TREE_MODE_CONCURRENT, WhitelistError::TreeModeMismatch);
+        require!(!airdrop_state.is_finalized, WhitelistError::TreeFinalized);
+        // Without a timelock the root is changed through `update_tree` alone
+        require!(airdrop_state.root_timelock > 0, WhitelistError::RootTimelocked);
+        require!(
+            airdrop_state.amount_claimed == 0 || airdrop_state.claim_mode == CLAIM_MODE_CUMULATIVE,
+            WhitelistError::ClaimsAlreadyStarted
         );
-    
-        let computed_root = merkle_proof
-            .merklize(&original_leaf)
-            .map_err(|_| WhitelistError::InvalidProof)?;
-    
+
+        airdrop_state.pending_root = Some(new_root);
+        airdrop_state.pending_num_leaves = num_leaves;
+        airdrop_state.pending_root_activates_at = Clock::get()?
+            .unix_timestamp
+            .checked_add(airdrop_state.root_timelock)
+            .ok_or(WhitelistError::OverFlow)?;
+
+        Ok(())
+    }
+
+    pub fn cancel_pending_root(
+        ctx: Context<Update>
+    ) -> Result<()> {
+        let airdrop_state = &mut ctx.accounts.airdrop_state;
+
+        require!(airdrop_state.pending_root.is_some(), WhitelistError::NoPendingRoot);
+
+        airdrop_state.pending_root = None;
+        airdrop_state.pending_num_leaves = 0;
+        airdrop_state.pending_root_activates_at = 0;
+
+        Ok(())
+    }
+
+    pub fn activate_root(
+        ctx: Context<ActivateRoot>
+    ) -> Result<()> {
+        let airdrop_state = &mut ctx.accounts.airdrop_state;
+        let clock = Clock::get()?;
+
+        require!(airdrop_state.tree_mode != TREE_MODE_CONCURRENT, WhitelistError::TreeModeMismatch);
+        require!(!airdrop_state.is_finalized, WhitelistError::TreeFinalized);
+        let new_root = airdrop_state.pending_root.ok_or(WhitelistError::NoPendingRoot)?;
         require!(
-            computed_root.eq(&airdrop_state.merkle_root),
-            WhitelistError::InvalidProof
+            clock.unix_timestamp >= airdrop_state.pending_root_activates_at,
+            WhitelistError::TimelockNotElapsed
         );
-    
-        // Step 3: Execute the transfer
-        let mint_key = ctx.accounts.mint.key().to_bytes();
+
+        let old_root = airdrop_state.merkle_root;
+        let num_leaves = airdrop_state.pending_num_leaves;
+        airdrop_state.rotate_root(new_root, num_leaves, clock.slot)?;
+        emit_cpi!(root_updated_event(airdrop_state, old_root, &clock));
+        airdrop_state.pending_root = None;
+        airdrop_state.pending_num_leaves = 0;
+        airdrop_state.pending_root_activates_at = 0;
+
+        Ok(())
+    }
+
+    pub fn set_root_grace_period(
+        ctx: Context<Update>, 
+        grace_slots: u64
+    ) -> Result<()> {
+
+        ctx.accounts.airdrop_state.root_grace_slots = grace_slots;
+
+        Ok(())
+    }
+
+    pub fn finalize_tree(
+        ctx: Context<Update>
+    ) -> Result<()> {
+        let airdrop_state = &mut ctx.accounts.airdrop_state;
+
+        airdrop_state.is_finalized = true;
+
+        emit_cpi!(RootFinalized {
+            airdrop_state: airdrop_state.key(),
+            merkle_root: airdrop_state.merkle_root,
+        });
+
+        Ok(())
+    }
+
+    /// Moves the airdrop onto a concurrent tree of spl-account-compression before its first
+    /// claim. `merkle_tree` is allocated to the compression program beforehand for `max_depth`
+    /// and `max_buffer_size` and starts out empty, the authority appends the leaves with
+    /// `append_concurrent_leaves`. Leaves are the bottom nodes `leaf_hash` returns, hashed
+    /// with Keccak in index order as the compression program does.
+    pub fn enable_concurrent_tree(
+        ctx: Context<EnableConcurrentTree>,
+        max_depth: u32,
+        max_buffer_size: u32,
+    ) -> Result<()> {
+        let airdrop_state = &mut ctx.accounts.airdrop_state;
+
+        require!(!airdrop_state.is_finalized, WhitelistError::TreeFinalized);
+        require!(airdrop_state.num_claims == 0, WhitelistError::ClaimsAlreadyStarted);
+        require!(airdrop_state.tree_mode == TREE_MODE_ROOT, WhitelistError::TreeModeMismatch);
+        require!(airdrop_state.hook_program.is_none(), WhitelistError::ClaimHookUnsupported);
+        require!(
+            airdrop_state.hashing_algorithm == HASHING_ALGORITHM_KECCAK,
+            WhitelistError::UnsupportedHashingAlgorithm
+        );
+        require!(
+            airdrop_state.pair_ordering == PAIR_ORDERING_INDEXED,
+            WhitelistError::UnsupportedPairOrdering
+        );
+
+        let mint_key = airdrop_state.mint.to_bytes();
+        let id_seed = AirdropState::id_seed(airdrop_state.airdrop_id);
         let signer_seeds = &[
             b"merkle_tree".as_ref(),
             mint_key.as_ref(),
+            id_seed.as_slice(),
             &[airdrop_state.bump],
         ];
-        transfer(
-            CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                Transfer {
-                    from: ctx.accounts.vault.to_account_info(),
-                    to: ctx.accounts.signer_ata.to_account_info(),
-                    authority: airdrop_state.to_account_info(),
-                },
-                &[signer_seeds],
-            ),
-            amount,
-        )?;
-    
-        // Step 4: Update the `is_claimed` flag in the leaf
-        let mut updated_leaf = Vec::new();
-        updated_leaf.extend_from_slice(&ctx.accounts.signer.key().to_bytes());
-        updated_leaf.extend_from_slice(&amount.to_le_bytes());
-        updated_leaf.push(1u8); // isClaimed = true
-    
-        let updated_root: [u8; 32] = merkle_proof
-            .merklize(&updated_leaf)
-            .map_err(|_| WhitelistError::InvalidProof)?
-            .try_into()
-            .map_err(|_| WhitelistError::InvalidProof)?;
-    
-        // Step 5: Update the Merkle root in the airdrop state
-        airdrop_state.merkle_root = updated_root;
-    
-        // Step 6: Update the airdrop state
-        airdrop_state.amount_claimed = airdrop_state
-            .amount_claimed
-            .checked_add(amount)
-            .ok_or(WhitelistError::OverFlow)?;
-    
+        CompressionAccounts {
+            merkle_tree: &ctx.accounts.merkle_tree,
+            authority: &airdrop_state.to_account_info(),
+            noop_program: &ctx.accounts.noop_program,
+            compression_program: &ctx.accounts.compression_program,
+        }
+        .init_empty_merkle_tree(max_depth, max_buffer_size, &[signer_seeds])?;
+
+        airdrop_state.tree_mode = TREE_MODE_CONCURRENT;
+        airdrop_state.concurrent_tree = ctx.accounts.merkle_tree.key();
+        airdrop_state.merkle_root = [0; 32];
+        airdrop_state.num_leaves = 0;
+
         Ok(())
     }
-    
-}
 
-#[derive(Accounts)]
-pub struct Initialize<'info> {
-    #[account(
-        init, 
-        seeds = [b"merkle_tree".as_ref(), mint.key().to_bytes().as_ref()],
-        bump,
-        payer = authority, 
-        space = 8 + 32 + 32 + 32 + 8 + 8 + 1
-    )]
-    pub airdrop_state: Account<'info, AirdropState>,
-    #[account(
-        init,
-        payer = authority,
-        mint::authority = authority,
-        mint::decimals = 6,
-    )]
-    pub mint: Account<'info, Mint>,
-    #[account(
-        init_if_needed,
-        payer = authority,
-        associated_token::mint = mint,
-        associated_token::authority = airdrop_state,
-    )]
-    pub vault: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-}
+    /// Appends `leaves` to the concurrent tree of the airdrop, at the next free indices.
+    pub fn append_concurrent_leaves(
+        ctx: Context<ModifyConcurrentTree>,
+        leaves: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        let airdrop_state = &mut ctx.accounts.airdrop_state;
 
-#[derive(Accounts)]
-pub struct Update<'info> {
-    #[account(
-        mut, 
-        has_one = authority,
-        seeds = [b"merkle_tree".as_ref(), airdrop_state.mint.key().to_bytes().as_ref()],
-        bump = airdrop_state.bump
-    )]
-    pub airdrop_state: Account<'info, AirdropState>,
-    pub authority: Signer<'info>,
-}
+        require!(!airdrop_state.is_finalized, WhitelistError::TreeFinalized);
 
-#[derive(Accounts)]
-pub struct Claim<'info> {
-    #[account(
-        mut,
-        has_one = mint,
-        seeds = [b"merkle_tree".as_ref(), mint.key().to_bytes().as_ref()],
-        bump = airdrop_state.bump
+        let mint_key = airdrop_state.mint.to_bytes();
+        let id_seed = AirdropState::id_seed(airdrop_state.airdrop_id);
+        let signer_seeds = &[
+            b"merkle_tree".as_ref(),
+            mint_key.as_ref(),
+            id_seed.as_slice(),
+            &[airdrop_state.bump],
+        ];
+        let compression = CompressionAccounts {
+            merkle_tree: &ctx.accounts.merkle_tree,
+            authority: &airdrop_state.to_account_info(),
+            noop_program: &ctx.accounts.noop_program,
+            compression_program: &ctx.accounts.compression_program,
+        };
+        for leaf in &leaves {
+            compression.append(leaf, &[signer_seeds])?;
+        }
+
+        airdrop_state.num_leaves = airdrop_state
+            .num_leaves
+            .checked_add(leaves.len() as u64)
+            .ok_or(WhitelistError::OverFlow)?;
+
+        Ok(())
+    }
+
+    /// Replaces the leaf at `index` of the concurrent tree, `previous_leaf` proven by the nodes
+    /// passed as remaining accounts against `root`. Claims of the other leaves go on, their
+    /// proofs verify as long as `root` is within the change log of the tree.
+    pub fn replace_concurrent_leaf<'info>(
+        ctx: Context<'_, '_, '_, 'info, ModifyConcurrentTree<'info>>,
+        root: [u8; 32],
+        previous_leaf: [u8; 32],
+        new_leaf: [u8; 32],
+        index: u64,
+    ) -> Result<()> {
+        let airdrop_state = &ctx.accounts.airdrop_state;
+
+        require!(!airdrop_state.is_finalized, WhitelistError::TreeFinalized);
+        require!(index < airdrop_state.num_leaves, WhitelistError::IndexOutOfRange);
+
+        let mint_key = airdrop_state.mint.to_bytes();
+        let id_seed = AirdropState::id_seed(airdrop_state.airdrop_id);
+        let signer_seeds = &[
+            b"merkle_tree".as_ref(),
+            mint_key.as_ref(),
+            id_seed.as_slice(),
+            &[airdrop_state.bump],
+        ];
+        CompressionAccounts {
+            merkle_tree: &ctx.accounts.merkle_tree,
+            authority: &airdrop_state.to_account_info(),
+            noop_program: &ctx.accounts.noop_program,
+            compression_program: &ctx.accounts.compression_program,
+        }
+        .replace_leaf(
+            &root,
+            &previous_leaf,
+            &new_leaf,
+            proof_index(index)?,
+            ctx.remaining_accounts,
+            &[signer_seeds],
+        )
+    }
+
+    /// Starts uploading the canopy of the current root, the nodes `height` levels above the
+    /// leaves, which claims then prove their leaf up to instead of up to the root. The nodes
+    /// are written with `write_canopy` and checked against the root by `seal_canopy`. Only
+    /// for trees with index-ordered pairs, whose proofs all have a level for every height.
+    pub fn create_canopy(ctx: Context<CreateCanopy>, height: u8) -> Result<()> {
+        let airdrop_state = &ctx.accounts.airdrop_state;
+
+        require!(
+            airdrop_state.pair_ordering != PAIR_ORDERING_SORTED,
+            WhitelistError::UnsupportedPairOrdering
+        );
+        let num_nodes = 1u64
+            .checked_shl(u32::from(height))
+            .map(|width| airdrop_state.num_leaves.div_ceil(width))
+            .unwrap_or(1)
+            .max(1);
+        require!(num_nodes <= MAX_CANOPY_NODES as u64, WhitelistError::CanopyTooLarge);
+
+        ctx.accounts.canopy.set_inner(Canopy {
+            airdrop_state: airdrop_state.key(),
+            root: airdrop_state.merkle_root,
+            height,
+            nodes: vec![[0; 32]; num_nodes as usize],
+            is_sealed: false,
+            bump: ctx.bumps.canopy,
+        });
+
+        Ok(())
+    }
+
+    /// Writes `nodes` into the canopy from `offset` on, unsealing it until `seal_canopy`.
+    pub fn write_canopy(ctx: Context<ModifyCanopy>, offset: u16, nodes: Vec<[u8; 32]>) -> Result<()> {
+        let canopy = &mut ctx.accounts.canopy;
+        let offset = usize::from(offset);
+
+        let end = offset
+            .checked_add(nodes.len())
+            .filter(|end| *end <= canopy.nodes.len())
+            .ok_or(WhitelistError::CanopyMismatch)?;
+        canopy.nodes[offset..end].copy_from_slice(&nodes);
+        canopy.is_sealed = false;
+
+        Ok(())
+    }
+
+    /// Seals the canopy once its nodes hash up to the root it was created for, which has to
+    /// still be the root of the airdrop. Claims only use a sealed canopy.
+    pub fn seal_canopy(ctx: Context<ModifyCanopy>) -> Result<()> {
+        let airdrop_state = &ctx.accounts.airdrop_state;
+        let canopy = &mut ctx.accounts.canopy;
+
+        require!(canopy.root == airdrop_state.merkle_root, WhitelistError::CanopyMismatch);
+        require!(
+            canopy_root(airdrop_state.hashing_algorithm, airdrop_state.pair_ordering, &canopy.nodes)?
+                == canopy.root,
+            WhitelistError::InvalidCanopy
+        );
+        canopy.is_sealed = true;
+
+        Ok(())
+    }
+
+    /// Pulls the allocation at `index` without re-issuing the tree, claims of it fail from
+    /// now on. The authority pays the rent of the revocation marker.
+    pub fn revoke_leaf(
+        ctx: Context<RevokeLeaf>,
+        index: u64
+    ) -> Result<()> {
+        let airdrop_state = &mut ctx.accounts.airdrop_state;
+
+        require!(index < airdrop_state.num_leaves, WhitelistError::IndexOutOfRange);
+        airdrop_state.num_revoked = airdrop_state
+            .num_revoked
+            .checked_add(1)
+            .ok_or(WhitelistError::OverFlow)?;
+        ctx.accounts.revoked_leaf.set_inner(RevokedLeaf {
+            airdrop_state: airdrop_state.key(),
+            index,
+            bump: ctx.bumps.revoked_leaf,
+        });
+
+        emit_cpi!(LeafRevoked {
+            airdrop_state: airdrop_state.key(),
+            index,
+        });
+
+        Ok(())
+    }
+
+    /// Restores a revoked allocation, refunding the marker rent to the authority. Only until
+    /// the tree is finalized, a revocation is for good from then on.
+    pub fn unrevoke_leaf(
+        ctx: Context<UnrevokeLeaf>,
+        index: u64
+    ) -> Result<()> {
+        let airdrop_state = &mut ctx.accounts.airdrop_state;
+
+        require!(!airdrop_state.is_finalized, WhitelistError::TreeFinalized);
+        airdrop_state.num_revoked = airdrop_state
+            .num_revoked
+            .checked_sub(1)
+            .ok_or(WhitelistError::OverFlow)?;
+
+        emit_cpi!(LeafUnrevoked {
+            airdrop_state: airdrop_state.key(),
+            index,
+        });
+
+        Ok(())
+    }
+
+    pub fn set_start_time(
+        ctx: Context<Update>, 
+        new_start_time: i64
+    ) -> Result<()> {
+        let airdrop_state = &mut ctx.accounts.airdrop_state;
+
+        // Claimants plan around the published start, it may only ever come sooner
+        require!(
+            new_start_time <= airdrop_state.start_time,
+            WhitelistError::StartTimeNotEarlier
+        );
+
+        airdrop_state.start_time = new_start_time;
+
+        Ok(())
+    }
+
+    pub fn nominate_update_authority(
+        ctx: Context<NominateUpdateAuthority>, 
+        new_update_authority: Pubkey
+    ) -> Result<()> {
+
+        ctx.accounts.airdrop_state.pending_update_authority = Some(new_update_authority);
+
+        Ok(())
+    }
+
+    pub fn accept_update_authority(
+        ctx: Context<AcceptUpdateAuthority>
+    ) -> Result<()> {
+        let airdrop_state = &mut ctx.accounts.airdrop_state;
+
+        airdrop_state.update_authority = ctx.accounts.pending_update_authority.key();
+        airdrop_state.pending_update_authority = None;
+
+        Ok(())
+    }
+
+    /// Nominates `new_withdraw_authority` to take over the withdraw authority, which it only
+    /// does once it accepts, so funds are never handed to a key nobody holds.
+    pub fn nominate_withdraw_authority(
+        ctx: Context<NominateWithdrawAuthority>,
+        new_withdraw_authority: Pubkey
+    ) -> Result<()> {
+
+        ctx.accounts.airdrop_state.pending_withdraw_authority = Some(new_withdraw_authority);
+
+        Ok(())
+    }
+
+    pub fn accept_withdraw_authority(
+        ctx: Context<AcceptWithdrawAuthority>
+    ) -> Result<()> {
+        let airdrop_state = &mut ctx.accounts.airdrop_state;
+
+        airdrop_state.withdraw_authority = ctx.accounts.pending_withdraw_authority.key();
+        airdrop_state.pending_withdraw_authority = None;
+
+        Ok(())
+    }
+
+    /// Creates the registry granting roles of the airdrop to other keys, with the authority as
+    /// its super-admin. Until it exists only the authorities act on the airdrop.
+    pub fn create_admin_registry(ctx: Context<CreateAdminRegistry>) -> Result<()> {
+        ctx.accounts.admin_registry.set_inner(AdminRegistry {
+            airdrop_state: ctx.accounts.airdrop_state.key(),
+            super_admin: ctx.accounts.update_authority.key(),
+            pending_super_admin: None,
+            members: Vec::new(),
+            bump: ctx.bumps.admin_registry,
+        });
+
+        Ok(())
+    }
+
+    /// Grants `role`, one of the `ROLE_*` bits, to `member`.
+    pub fn grant_role(ctx: Context<ManageRoles>, member: Pubkey, role: u8) -> Result<()> {
+        require!(role.count_ones() == 1 && role & ALL_ROLES == role, WhitelistError::InvalidRole);
+        let admin_registry = &mut ctx.accounts.admin_registry;
+
+        match admin_registry.members.iter_mut().find(|grant| grant.member == member) {
+            Some(grant) => grant.roles |= role,
+            None => {
+                require!(admin_registry.members.len() < MAX_ROLE_MEMBERS, WhitelistError::RoleMembersFull);
+                admin_registry.members.push(RoleGrant { member, roles: role });
+            }
+        }
+
+        emit_cpi!(RoleGranted {
+            airdrop_state: admin_registry.airdrop_state,
+            member,
+            role,
+        });
+
+        Ok(())
+    }
+
+    /// Revokes `role` from `member`, dropping the member once it holds no role.
+    pub fn revoke_role(ctx: Context<ManageRoles>, member: Pubkey, role: u8) -> Result<()> {
+        require!(role.count_ones() == 1 && role & ALL_ROLES == role, WhitelistError::InvalidRole);
+        let admin_registry = &mut ctx.accounts.admin_registry;
+
+        if let Some(grant) = admin_registry.members.iter_mut().find(|grant| grant.member == member) {
+            grant.roles &= !role;
+        }
+        admin_registry.members.retain(|grant| grant.roles != 0);
+
+        emit_cpi!(RoleRevoked {
+            airdrop_state: admin_registry.airdrop_state,
+            member,
+            role,
+        });
+
+        Ok(())
+    }
+
+    /// Nominates the next super-admin of the registry, who has to accept like a nominated
+    /// authority does.
+    pub fn nominate_super_admin(ctx: Context<ManageRoles>, new_super_admin: Pubkey) -> Result<()> {
+
+        ctx.accounts.admin_registry.pending_super_admin = Some(new_super_admin);
+
+        Ok(())
+    }
+
+    pub fn accept_super_admin(ctx: Context<AcceptSuperAdmin>) -> Result<()> {
+        let admin_registry = &mut ctx.accounts.admin_registry;
+
+        admin_registry.super_admin = ctx.accounts.pending_super_admin.key();
+        admin_registry.pending_super_admin = None;
+
+        Ok(())
+    }
+
+    /// Rewrites an airdrop state still in one of the layouts `migration` keeps in the current
+    /// one, growing the account with the payer covering the extra rent.
+    pub fn migrate_state(
+        ctx: Context<MigrateState>
+    ) -> Result<()> {
+        let account = ctx.accounts.airdrop_state.to_account_info();
+
+        let legacy = {
+            let data = account.try_borrow_data()?;
+            require!(
+                data.len() > 8 && data[..8] == AirdropState::DISCRIMINATOR,
+                WhitelistError::UnsupportedStateVersion
+            );
+            // Only the first layout went without a version, the size tells it apart
+            match data.len() {
+                AirdropStateV1::SPACE => AirdropStateV1::deserialize(&mut &data[8..])?.into_v2(),
+                AirdropStateV2::SPACE if data[8] == 2 => AirdropStateV2::deserialize(&mut &data[8..])?,
+                _ => return err!(WhitelistError::UnsupportedStateVersion),
+            }
+        };
+        require_keys_eq!(
+            legacy.authority,
+            ctx.accounts.update_authority.key(),
+            anchor_lang::error::ErrorCode::ConstraintHasOne
+        );
+
+        let space = 8 + AirdropState::INIT_SPACE;
+        let rent_due = Rent::get()?
+            .minimum_balance(space)
+            .saturating_sub(account.lamports());
+        if rent_due > 0 {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.payer.to_account_info(),
+                        to: account.clone(),
+                    },
+                ),
+                rent_due,
+            )?;
+        }
+        account.realloc(space, false)?;
+
+        let mut data = account.try_borrow_mut_data()?;
+        legacy.into_current().try_serialize(&mut &mut data[..])?;
+
+        Ok(())
+    }
+
+    /// Caps every claim at `max_claim_amount`, tokens of a leaf above it stay in the vault
+    /// whatever the tree says. 0 lifts the cap.
+    pub fn set_max_claim_amount(
+        ctx: Context<Update>, 
+        max_claim_amount: u64
+    ) -> Result<()> {
+
+        ctx.accounts.airdrop_state.max_claim_amount = max_claim_amount;
+
+        Ok(())
+    }
+
+    /// Pauses or resumes claims, either the update or the withdraw authority can.
+    pub fn set_paused(
+        ctx: Context<Pause>, 
+        paused: bool
+    ) -> Result<()> {
+
+        ctx.accounts.airdrop_state.paused = paused;
+
+        Ok(())
+    }
+
+    /// Lowers the fee taken on every payout, raising it would charge claimants more than the
+    /// airdrop was launched with.
+    pub fn lower_fee(
+        ctx: Context<ManageFees>, 
+        fee_bps: u16
+    ) -> Result<()> {
+
+        require!(fee_bps <= ctx.accounts.airdrop_state.fee_bps, WhitelistError::FeeNotLowered);
+        ctx.accounts.airdrop_state.fee_bps = fee_bps;
+
+        Ok(())
+    }
+
+    /// Lowers the flat fee in lamports charged on every claim, the same way `lower_fee` lowers
+    /// the share taken of the payout.
+    pub fn lower_claim_fee(
+        ctx: Context<ManageFees>, 
+        claim_fee_lamports: u64
+    ) -> Result<()> {
+
+        require!(
+            claim_fee_lamports <= ctx.accounts.airdrop_state.claim_fee_lamports,
+            WhitelistError::FeeNotLowered
+        );
+        ctx.accounts.airdrop_state.claim_fee_lamports = claim_fee_lamports;
+
+        Ok(())
+    }
+
+    /// Rotates the key claims have to be co-signed by, `None` stops requiring one. Takes
+    /// effect from the next claim on.
+    pub fn set_cosigner(
+        ctx: Context<Update>, 
+        cosigner: Option<Pubkey>
+    ) -> Result<()> {
+
+        ctx.accounts.airdrop_state.cosigner = cosigner;
+
+        Ok(())
+    }
+
+    /// Sets the program notified of every paid claim, see `call_claim_hook`. `None` removes it.
+    /// Only before the first claim and while the tree is not finalized, so claimants know what
+    /// their claim calls into. The claims signed by their claimant call it: `claim_airdrop` and
+    /// the other `Claim` instructions, `claim_round`, `claim_partial`, `claim_many`,
+    /// `claim_to_account` and `claim_nft`. Pushed claims, `claim_for` and `claim_for_many`, are
+    /// refused while there is a hook, and airdrops paying SOL or on a concurrent tree, whose
+    /// claims cannot call it, cannot have one.
+    pub fn set_claim_hook(
+        ctx: Context<Update>,
+        hook_program: Option<Pubkey>
+    ) -> Result<()> {
+        let airdrop_state = &mut ctx.accounts.airdrop_state;
+
+        require!(
+            !airdrop_state.is_finalized && airdrop_state.num_claims == 0,
+            WhitelistError::HookLocked
+        );
+        require!(
+            hook_program.is_none()
+                || (airdrop_state.mint != NATIVE_SOL && airdrop_state.tree_mode != TREE_MODE_CONCURRENT),
+            WhitelistError::ClaimHookUnsupported
+        );
+        airdrop_state.hook_program = hook_program;
+
+        Ok(())
+    }
+
+    pub fn set_label(
+        ctx: Context<Update>, 
+        label: String
+    ) -> Result<()> {
+
+        require!(label.len() <= MAX_LABEL_LEN, WhitelistError::LabelTooLong);
+        ctx.accounts.airdrop_state.label = label;
+
+        Ok(())
+    }
+
+    pub fn set_metadata_uri(
+        ctx: Context<Update>, 
+        metadata_uri: String
+    ) -> Result<()> {
+        let airdrop_state = &mut ctx.accounts.airdrop_state;
+
+        // The published list is as much part of the commitment as the root it was built into
+        require!(!airdrop_state.is_finalized, WhitelistError::TreeFinalized);
+        require!(metadata_uri.len() <= MAX_METADATA_URI_LEN, WhitelistError::MetadataUriTooLong);
+
+        airdrop_state.metadata_uri = metadata_uri.clone();
+        emit_cpi!(MetadataUriUpdated {
+            airdrop_state: airdrop_state.key(),
+            metadata_uri,
+        });
+
+        Ok(())
+    }
+
+    pub fn set_claim_mode(
+        ctx: Context<Update>, 
+        claim_mode: u8
+    ) -> Result<()> {
+        let airdrop_state = &mut ctx.accounts.airdrop_state;
+
+        // Claims already paid were tracked the way the previous mode does
+        require!(airdrop_state.num_claims == 0, WhitelistError::ClaimModeLocked);
+        require!(claim_mode <= CLAIM_MODE_CUMULATIVE, WhitelistError::UnsupportedClaimMode);
+
+        airdrop_state.claim_mode = claim_mode;
+
+        Ok(())
+    }
+
+    /// Switches the airdrop to `CLAIM_MODE_DECAYING`, leaves decaying linearly to nothing over
+    /// `decay_duration` seconds from the start of the airdrop.
+    pub fn configure_decay(
+        ctx: Context<ConfigureDecay>,
+        decay_duration: i64,
+    ) -> Result<()> {
+        let airdrop_state = &mut ctx.accounts.airdrop_state;
+
+        require!(airdrop_state.num_claims == 0, WhitelistError::ClaimModeLocked);
+        require!(decay_duration > 0, WhitelistError::InvalidDecaySchedule);
+
+        ctx.accounts.decay_config.set_inner(DecayConfig {
+            airdrop_state: airdrop_state.key(),
+            decay_duration,
+            configured_at: Clock::get()?.unix_timestamp,
+            total_forfeited: 0,
+            bump: ctx.bumps.decay_config,
+        });
+        airdrop_state.claim_mode = CLAIM_MODE_DECAYING;
+
+        Ok(())
+    }
+
+    /// Switches the airdrop to `CLAIM_MODE_VESTING`, claims vesting over `vesting_duration`
+    /// seconds from `vesting_start`, or from the claim itself when `vesting_start` is 0. Nothing
+    /// is released before `cliff_seconds` into the schedule.
+    pub fn configure_vesting(
+        ctx: Context<ConfigureVesting>,
+        vesting_start: i64,
+        vesting_duration: i64,
+        cliff_seconds: i64,
+    ) -> Result<()> {
+        let airdrop_state = &mut ctx.accounts.airdrop_state;
+
+        require!(airdrop_state.num_claims == 0, WhitelistError::ClaimModeLocked);
+        require!(
+            vesting_start >= 0
+                && vesting_duration > 0
+                && (0..=vesting_duration).contains(&cliff_seconds),
+            WhitelistError::InvalidVestingSchedule
+        );
+
+        ctx.accounts.vesting_config.set_inner(VestingConfig {
+            airdrop_state: airdrop_state.key(),
+            vesting_start,
+            vesting_duration,
+            cliff_seconds,
+            amount_vesting: 0,
+            bump: ctx.bumps.vesting_config,
+        });
+        airdrop_state.claim_mode = CLAIM_MODE_VESTING;
+
+        Ok(())
+    }
+
+    /// Sets the staking program `claim_and_stake` deposits claims into, along with the accounts
+    /// its deposit takes after the ones of the claim. Only before the first claim, so every
+    /// staker deposits into the same place.
+    pub fn configure_staking(
+        ctx: Context<ConfigureStaking>,
+        stake_program: Pubkey,
+        accounts: Vec<StakeAccountMeta>,
+    ) -> Result<()> {
+        let airdrop_state = &ctx.accounts.airdrop_state;
+
+        require!(airdrop_state.num_claims == 0, WhitelistError::ClaimModeLocked);
+        require!(accounts.len() <= MAX_STAKE_ACCOUNTS, WhitelistError::TooManyStakeAccounts);
+
+        ctx.accounts.stake_config.set_inner(StakeConfig {
+            airdrop_state: airdrop_state.key(),
+            stake_program,
+            accounts,
+            bump: ctx.bumps.stake_config,
+        });
+
+        Ok(())
+    }
+
+    /// Adds `mint` to the basket of a `LEAF_VERSION_BASKET` airdrop, funding its vault with
+    /// `amount` from the authority. Every leaf of the airdrop holds an amount of each mint of
+    /// the basket after the amount of the airdrop mint, in the order they were added. Only
+    /// before the first claim, so the basket matches the tree it was built for.
+    pub fn add_basket_mint<'info>(
+        ctx: Context<'_, '_, '_, 'info, AddBasketMint<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        let airdrop_state = &ctx.accounts.airdrop_state;
+        let mint = ctx.accounts.mint.key();
+
+        require!(airdrop_state.num_claims == 0, WhitelistError::ClaimModeLocked);
+        require!(
+            airdrop_state.leaf_version == LEAF_VERSION_BASKET,
+            WhitelistError::UnsupportedLeafVersion
+        );
+        require!(amount > 0, WhitelistError::InvalidAmount);
+        require!(
+            ctx.accounts.authority_ata.amount >= amount,
+            WhitelistError::InsufficientFunds
+        );
+
+        let basket = &mut ctx.accounts.basket;
+        require!(basket.mints.len() < MAX_BASKET_MINTS, WhitelistError::BasketFull);
+        require!(
+            mint != airdrop_state.mint && basket.mints.iter().all(|entry| entry.mint != mint),
+            WhitelistError::DuplicateBasketMint
+        );
+        basket.airdrop_state = airdrop_state.key();
+        basket.bump = ctx.bumps.basket;
+        basket.mints.push(BasketMint {
+            mint,
+            airdrop_amount: amount,
+            amount_claimed: 0,
+            amount_withdrawn: 0,
+        });
+
+        transfer_checked_with_hook(
+            &ctx.accounts.token_program,
+            ctx.accounts.authority_ata.to_account_info(),
+            &ctx.accounts.mint,
+            ctx.accounts.vault.to_account_info(),
+            ctx.accounts.update_authority.to_account_info(),
+            ctx.remaining_accounts,
+            amount,
+            &[],
+        )
+    }
+
+    /// Gates claims on tickets, every claim burning `ticket_cost` of `ticket_mint` from the
+    /// claimant on top of the proof. `None` lifts the gate. Only before the first claim, so
+    /// every claimant is held to the same gate.
+    pub fn set_ticket_gate(
+        ctx: Context<Update>,
+        ticket_mint: Option<Pubkey>,
+        ticket_cost: u64,
+    ) -> Result<()> {
+        let airdrop_state = &mut ctx.accounts.airdrop_state;
+
+        require!(airdrop_state.num_claims == 0, WhitelistError::GateLocked);
+        require!(ticket_mint.is_none() || ticket_cost > 0, WhitelistError::InvalidTicketCost);
+
+        airdrop_state.ticket_mint = ticket_mint;
+        airdrop_state.ticket_cost = if ticket_mint.is_some() { ticket_cost } else { 0 };
+
+        Ok(())
+    }
+
+    /// Gates claims on holding an NFT of the verified collection `gate_collection`, on top of
+    /// the proof governing the amount. `None` lifts the gate. Only before the first claim.
+    pub fn set_collection_gate(
+        ctx: Context<Update>,
+        gate_collection: Option<Pubkey>,
+    ) -> Result<()> {
+        let airdrop_state = &mut ctx.accounts.airdrop_state;
+
+        require!(airdrop_state.num_claims == 0, WhitelistError::GateLocked);
+        airdrop_state.gate_collection = gate_collection;
+
+        Ok(())
+    }
+
+    pub fn extend_deadline(
+        ctx: Context<Update>, 
+        new_end_time: i64
+    ) -> Result<()> {
+        let airdrop_state = &mut ctx.accounts.airdrop_state;
+
+        // An open-ended airdrop has no deadline to extend, and a window may never shrink
+        require!(
+            airdrop_state.end_time != 0
+                && new_end_time > airdrop_state.end_time
+                && new_end_time > Clock::get()?.unix_timestamp,
+            WhitelistError::DeadlineNotExtended
+        );
+
+        emit_cpi!(DeadlineExtended {
+            airdrop_state: airdrop_state.key(),
+            old_end_time: airdrop_state.end_time,
+            new_end_time,
+        });
+
+        airdrop_state.end_time = new_end_time;
+
+        Ok(())
+    }
+
+    /// Withdraws the free balance of the vault once claims are over, see `free_vault_balance`.
+    pub fn withdraw_unclaimed<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawUnclaimed<'info>>,
+    ) -> Result<()> {
+        let airdrop_state = &mut ctx.accounts.airdrop_state;
+
+        require!(
+            airdrop_state.has_ended(Clock::get()?.unix_timestamp),
+            WhitelistError::ClaimsStillOpen
+        );
+
+        let amount = free_vault_balance(airdrop_state, &ctx.accounts.vault, ctx.accounts.vesting_config.as_deref())?;
+        let mint_key = ctx.accounts.mint.key().to_bytes();
+        let id_seed = AirdropState::id_seed(airdrop_state.airdrop_id);
+        let signer_seeds = &[
+            b"merkle_tree".as_ref(),
+            mint_key.as_ref(),
+            id_seed.as_slice(),
+            &[airdrop_state.bump],
+        ];
+        transfer_checked_with_hook(
+            &ctx.accounts.token_program,
+            ctx.accounts.vault.to_account_info(),
+            &ctx.accounts.mint,
+            ctx.accounts.destination.to_account_info(),
+            airdrop_state.to_account_info(),
+            ctx.remaining_accounts,
+            amount,
+            &[signer_seeds],
+        )?;
+
+        airdrop_state.amount_withdrawn = airdrop_state
+            .amount_withdrawn
+            .checked_add(amount)
+            .ok_or(WhitelistError::OverFlow)?;
+
+        emit!(UnclaimedWithdrawn {
+            airdrop_state: airdrop_state.key(),
+            destination: ctx.accounts.destination.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Withdraws what is left in the SOL vault of a native SOL airdrop to the withdraw authority
+    /// once claims are over, down to the rent exemption of the vault.
+    pub fn withdraw_unclaimed_sol(ctx: Context<WithdrawUnclaimedSol>) -> Result<()> {
+        let airdrop_state = &mut ctx.accounts.airdrop_state;
+
+        require!(
+            airdrop_state.has_ended(Clock::get()?.unix_timestamp),
+            WhitelistError::ClaimsStillOpen
+        );
+
+        let amount = ctx.accounts.sol_vault
+            .lamports()
+            .saturating_sub(Rent::get()?.minimum_balance(0));
+        transfer_from_sol_vault(
+            &ctx.accounts.sol_vault,
+            ctx.accounts.withdraw_authority.to_account_info(),
+            &ctx.accounts.system_program,
+            airdrop_state.key(),
+            ctx.bumps.sol_vault,
+            amount,
+        )?;
+
+        airdrop_state.amount_withdrawn = airdrop_state
+            .amount_withdrawn
+            .checked_add(amount)
+            .ok_or(WhitelistError::OverFlow)?;
+
+        emit!(UnclaimedWithdrawn {
+            airdrop_state: airdrop_state.key(),
+            destination: ctx.accounts.withdraw_authority.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Withdraws a deposited NFT no claim took once claims are over.
+    pub fn withdraw_unclaimed_nft<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawUnclaimedNft<'info>>,
+    ) -> Result<()> {
+        let airdrop_state = &mut ctx.accounts.airdrop_state;
+
+        require!(
+            airdrop_state.has_ended(Clock::get()?.unix_timestamp),
+            WhitelistError::ClaimsStillOpen
+        );
+
+        let id_seed = AirdropState::id_seed(airdrop_state.airdrop_id);
+        let signer_seeds = &[
+            b"merkle_tree".as_ref(),
+            NFT_AIRDROP.as_ref(),
+            id_seed.as_slice(),
+            &[airdrop_state.bump],
+        ];
+        transfer_checked_with_hook(
+            &ctx.accounts.token_program,
+            ctx.accounts.vault.to_account_info(),
+            &ctx.accounts.nft_mint,
+            ctx.accounts.destination.to_account_info(),
+            airdrop_state.to_account_info(),
+            ctx.remaining_accounts,
+            1,
+            &[signer_seeds],
+        )?;
+
+        airdrop_state.amount_withdrawn = airdrop_state
+            .amount_withdrawn
+            .checked_add(1)
+            .ok_or(WhitelistError::OverFlow)?;
+
+        emit!(UnclaimedWithdrawn {
+            airdrop_state: airdrop_state.key(),
+            destination: ctx.accounts.destination.key(),
+            amount: 1,
+        });
+
+        Ok(())
+    }
+
+    /// Withdraws the unclaimed balance of one mint of the basket once claims are over, the
+    /// airdrop mint itself is withdrawn with `withdraw_unclaimed`.
+    pub fn withdraw_basket_unclaimed(ctx: Context<WithdrawBasketUnclaimed>) -> Result<()> {
+        let airdrop_state = &ctx.accounts.airdrop_state;
+
+        require!(
+            airdrop_state.has_ended(Clock::get()?.unix_timestamp),
+            WhitelistError::ClaimsStillOpen
+        );
+
+        let mint = ctx.accounts.mint.key();
+        let entry = ctx.accounts.basket.mints
+            .iter_mut()
+            .find(|entry| entry.mint == mint)
+            .ok_or(WhitelistError::BasketAccountMismatch)?;
+        let amount = ctx.accounts.vault.amount;
+        let mint_key = airdrop_state.mint.to_bytes();
+        let id_seed = AirdropState::id_seed(airdrop_state.airdrop_id);
+        let signer_seeds = &[
+            b"merkle_tree".as_ref(),
+            mint_key.as_ref(),
+            id_seed.as_slice(),
+            &[airdrop_state.bump],
+        ];
+        transfer_checked_with_hook(
+            &ctx.accounts.token_program,
+            ctx.accounts.vault.to_account_info(),
+            &ctx.accounts.mint,
+            ctx.accounts.destination.to_account_info(),
+            airdrop_state.to_account_info(),
+            ctx.remaining_accounts,
+            amount,
+            &[signer_seeds],
+        )?;
+
+        entry.amount_withdrawn = entry
+            .amount_withdrawn
+            .checked_add(amount)
+            .ok_or(WhitelistError::OverFlow)?;
+
+        emit!(UnclaimedWithdrawn {
+            airdrop_state: airdrop_state.key(),
+            destination: ctx.accounts.destination.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Burns the free balance of the vault once claims are over, see `free_vault_balance`.
+    pub fn burn_unclaimed(
+        ctx: Context<BurnUnclaimed>,
+    ) -> Result<()> {
+        let airdrop_state = &mut ctx.accounts.airdrop_state;
+
+        require!(
+            airdrop_state.has_ended(Clock::get()?.unix_timestamp),
+            WhitelistError::ClaimsStillOpen
+        );
+
+        let amount = free_vault_balance(airdrop_state, &ctx.accounts.vault, ctx.accounts.vesting_config.as_deref())?;
+        let mint_key = ctx.accounts.mint.key().to_bytes();
+        let id_seed = AirdropState::id_seed(airdrop_state.airdrop_id);
+        let signer_seeds = &[
+            b"merkle_tree".as_ref(),
+            mint_key.as_ref(),
+            id_seed.as_slice(),
+            &[airdrop_state.bump],
+        ];
+        burn(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.mint.to_account_info(),
+                    from: ctx.accounts.vault.to_account_info(),
+                    authority: airdrop_state.to_account_info(),
+                },
+                &[signer_seeds],
+            ),
+            amount
+        )?;
+
+        airdrop_state.amount_burned = airdrop_state
+            .amount_burned
+            .checked_add(amount)
+            .ok_or(WhitelistError::OverFlow)?;
+
+        emit!(UnclaimedBurned {
+            airdrop_state: airdrop_state.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+
+    pub fn close_airdrop(
+        ctx: Context<CloseAirdrop>,
+    ) -> Result<()> {
+        let airdrop_state = &ctx.accounts.airdrop_state;
+
+        require!(
+            ctx.accounts.vault.amount == 0,
+            WhitelistError::VaultNotEmpty
+        );
+
+        // A mint-on-claim vault is always empty, claims stay live until the deadline or the cap
+        require!(
+            !airdrop_state.mint_on_claim
+                || airdrop_state.amount_claimed >= airdrop_state.airdrop_amount
+                || airdrop_state.has_ended(Clock::get()?.unix_timestamp),
+            WhitelistError::ClaimsStillOpen
+        );
+
+        let mint_key = ctx.accounts.mint.key().to_bytes();
+        let id_seed = AirdropState::id_seed(airdrop_state.airdrop_id);
+        let signer_seeds = &[
+            b"merkle_tree".as_ref(),
+            mint_key.as_ref(),
+            id_seed.as_slice(),
+            &[airdrop_state.bump],
+        ];
+        close_account(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                CloseAccount {
+                    account: ctx.accounts.vault.to_account_info(),
+                    destination: ctx.accounts.withdraw_authority.to_account_info(),
+                    authority: airdrop_state.to_account_info(),
+                },
+                &[signer_seeds],
+            )
+        )?;
+
+        Ok(())
+    }
+
+    pub fn top_up<'info>(
+        ctx: Context<'_, '_, '_, 'info, TopUp<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+
+        require!(amount > 0, WhitelistError::InvalidAmount);
+        require!(
+            ctx.accounts.authority_ata.amount >= amount,
+            WhitelistError::InsufficientFunds
+        );
+
+        transfer_checked_with_hook(
+            &ctx.accounts.token_program,
+            ctx.accounts.authority_ata.to_account_info(),
+            &ctx.accounts.mint,
+            ctx.accounts.vault.to_account_info(),
+            ctx.accounts.update_authority.to_account_info(),
+            ctx.remaining_accounts,
+            amount,
+            &[],
+        )?;
+
+        let airdrop_state = &mut ctx.accounts.airdrop_state;
+        airdrop_state.airdrop_amount = airdrop_state
+            .airdrop_amount
+            .checked_add(amount)
+            .ok_or(WhitelistError::OverFlow)?;
+
+        ctx.accounts.vault.reload()?;
+        emit!(ToppedUp {
+            airdrop_state: airdrop_state.key(),
+            amount,
+            vault_balance: ctx.accounts.vault.amount,
+        });
+
+        Ok(())
+    }
+
+    /// Deposits a reserve of `reserve` tokens into the vault, paying claims made with
+    /// `claim_airdrop` up to `bonus_window_end` a bonus of `bonus_bps` of their leaf out of it
+    /// for as long as it lasts. The reserve is tracked apart from `airdrop_amount`.
+    pub fn fund_bonus<'info>(
+        ctx: Context<'_, '_, '_, 'info, FundBonus<'info>>,
+        bonus_bps: u16,
+        bonus_window_end: i64,
+        reserve: u64,
+    ) -> Result<()> {
+        // A bonus minted on the spot would have no reserve to be bounded by
+        require!(!ctx.accounts.airdrop_state.mint_on_claim, WhitelistError::MintOnClaimUnsupported);
+        require!(
+            bonus_bps > 0 && u64::from(bonus_bps) <= BPS_DENOMINATOR && reserve > 0,
+            WhitelistError::InvalidBonus
+        );
+        require!(
+            ctx.accounts.authority_ata.amount >= reserve,
+            WhitelistError::InsufficientFunds
+        );
+
+        transfer_checked_with_hook(
+            &ctx.accounts.token_program,
+            ctx.accounts.authority_ata.to_account_info(),
+            &ctx.accounts.mint,
+            ctx.accounts.vault.to_account_info(),
+            ctx.accounts.update_authority.to_account_info(),
+            ctx.remaining_accounts,
+            reserve,
+            &[],
+        )?;
+
+        ctx.accounts.bonus_config.set_inner(BonusConfig {
+            airdrop_state: ctx.accounts.airdrop_state.key(),
+            bonus_bps,
+            bonus_window_end,
+            bonus_reserve: reserve,
+            bonus_paid: 0,
+            bump: ctx.bumps.bonus_config,
+        });
+
+        Ok(())
+    }
+
+    pub fn mint_additional(
+        ctx: Context<MintAdditional>, 
+        amount: u64
+    ) -> Result<()> {
+        let airdrop_state = &mut ctx.accounts.airdrop_state;
+
+        let mint_key = ctx.accounts.mint.key().to_bytes();
+        let id_seed = AirdropState::id_seed(airdrop_state.airdrop_id);
+        let signer_seeds = &[
+            b"merkle_tree".as_ref(),
+            mint_key.as_ref(),
+            id_seed.as_slice(),
+            &[airdrop_state.bump],
+        ];
+        mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(), 
+                MintTo {
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                    authority: airdrop_state.to_account_info(),
+                },
+                &[signer_seeds],
+            ),
+            amount
+        )?;
+
+        airdrop_state.airdrop_amount = airdrop_state
+            .airdrop_amount
+            .checked_add(amount)
+            .ok_or(WhitelistError::OverFlow)?;
+
+        Ok(())
+    }
+
+    pub fn create_bitmap_shard(
+        ctx: Context<CreateBitmapShard>,
+        shard_index: u64,
+    ) -> Result<()> {
+
+        require!(
+            shard_index
+                .checked_mul(ClaimBitmap::LEAVES_PER_SHARD)
+                .ok_or(WhitelistError::OverFlow)?
+                < ctx.accounts.airdrop_state.num_leaves,
+            WhitelistError::IndexOutOfRange
+        );
+
+        let mut claim_bitmap = ctx.accounts.claim_bitmap.load_init()?;
+        claim_bitmap.airdrop_state = ctx.accounts.airdrop_state.key();
+        claim_bitmap.shard_index = shard_index;
+        claim_bitmap.bump = ctx.bumps.claim_bitmap;
+
+        Ok(())
+    }
+
+    /// Opens the counters of `referrer` for the airdrop, idempotently, so claims crediting it
+    /// can count into them.
+    pub fn create_referral_stats(
+        ctx: Context<CreateReferralStats>,
+        referrer: Pubkey,
+    ) -> Result<()> {
+
+        let referral_stats = &mut ctx.accounts.referral_stats;
+        if referral_stats.referrer == Pubkey::default() {
+            referral_stats.set_inner(ReferralStats {
+                airdrop_state: ctx.accounts.airdrop_state.key(),
+                referrer,
+                claims: 0,
+                volume: 0,
+                bump: ctx.bumps.referral_stats,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Opens the next round of the airdrop, claimed against `merkle_root` between `start_time`
+    /// and `end_time` out of the shared vault, paying at most `allocation`.
+    pub fn create_round(
+        ctx: Context<CreateRound>,
+        merkle_root: [u8; 32],
+        num_leaves: u64,
+        start_time: i64,
+        end_time: i64,
+        allocation: u64,
+    ) -> Result<()> {
+        let airdrop_state = &mut ctx.accounts.airdrop_state;
+
+        require!(allocation > 0, WhitelistError::InvalidAmount);
+        let round_allocations = airdrop_state
+            .round_allocations
+            .checked_add(allocation)
+            .ok_or(WhitelistError::OverFlow)?;
+        require!(
+            round_allocations <= airdrop_state.airdrop_amount,
+            WhitelistError::RoundAllocationExceeded
+        );
+
+        ctx.accounts.round_state.set_inner(RoundState {
+            airdrop_state: airdrop_state.key(),
+            round: airdrop_state.num_rounds,
+            merkle_root,
+            num_leaves,
+            start_time,
+            end_time,
+            allocation,
+            amount_claimed: 0,
+            num_claims: 0,
+            bump: ctx.bumps.round_state,
+        });
+        airdrop_state.num_rounds = airdrop_state
+            .num_rounds
+            .checked_add(1)
+            .ok_or(WhitelistError::OverFlow)?;
+        airdrop_state.round_allocations = round_allocations;
+
+        Ok(())
+    }
+
+    pub fn create_round_bitmap_shard(
+        ctx: Context<CreateRoundBitmapShard>,
+        shard_index: u64,
+    ) -> Result<()> {
+
+        require!(
+            shard_index
+                .checked_mul(ClaimBitmap::LEAVES_PER_SHARD)
+                .ok_or(WhitelistError::OverFlow)?
+                < ctx.accounts.round_state.num_leaves,
+            WhitelistError::IndexOutOfRange
+        );
+
+        let mut claim_bitmap = ctx.accounts.claim_bitmap.load_init()?;
+        claim_bitmap.airdrop_state = ctx.accounts.round_state.key();
+        claim_bitmap.shard_index = shard_index;
+        claim_bitmap.bump = ctx.bumps.claim_bitmap;
+
+        Ok(())
+    }
+
+    pub fn verify_eligibility(
+        ctx: Context<VerifyEligibility>,
+        claimant: Pubkey,
+        amount: u64,
+        hashes: Vec<u8>,
+        index: u64,
+        expected_root_version: u64,
+    ) -> Result<EligibilityResult> {
+        let airdrop_state = &ctx.accounts.airdrop_state;
+
+        // Same leaf and proof check as `claim_airdrop`, reported instead of enforced
+        let leaf_data = encode_leaf(airdrop_state.leaf_version, index, &claimant, amount)?;
+        let slot = Clock::get()?.slot;
+        let eligible = airdrop_state
+            .root_for_version(expected_root_version, slot)
+            .is_ok_and(|merkle_root| {
+                verify_claim(&airdrop_state.tree_layout(), &merkle_root, &leaf_data, index, &hashes).is_ok()
+            });
+        let leaf = prepare_leaf(airdrop_state.leaf_hashing, airdrop_state.hashing_algorithm, leaf_data)?;
+        let already_claimed = match &ctx.accounts.claim_bitmap {
+            Some(claim_bitmap) => claim_bitmap.load()?.is_claimed(index),
+            None => false,
+        };
+
+        Ok(EligibilityResult {
+            eligible,
+            already_claimed,
+            leaf_hash: leaf_hash(airdrop_state.hashing_algorithm, airdrop_state.pair_ordering, &leaf),
+        })
+    }
+
+    pub fn get_claim_status(
+        ctx: Context<GetClaimStatus>,
+        index: u64,
+    ) -> Result<ClaimStatus> {
+
+        require!(
+            index < ctx.accounts.airdrop_state.num_leaves,
+            WhitelistError::IndexOutOfRange
+        );
+
+        let claim_receipt = ctx.accounts.claim_receipt.as_ref();
+        Ok(ClaimStatus {
+            index,
+            claimed: ctx.accounts.claim_bitmap.load()?.is_claimed(index),
+            amount: claim_receipt.map(|receipt| receipt.amount),
+            claimed_at: claim_receipt.map(|receipt| receipt.claimed_at),
+        })
+    }
+
+    /// Claims the allocation at `index`. `referrer` credits a community referrer with the claim
+    /// in its `ReferralStats`, which has to be passed and created beforehand. `unwrap` has a
+    /// wrapped SOL claim paid in SOL, see `unwrap_payout`.
+    pub fn claim_airdrop<'info>(
+        ctx: Context<'_, '_, '_, 'info, Claim<'info>>,
+        amount: u64,
+        hashes: Vec<u8>,
+        index: u64,
+        expected_root_version: u64,
+        referrer: Option<Pubkey>,
+        unwrap: bool,
+    ) -> Result<()> {    
+        claim_prologue(&ctx.accounts.airdrop_state, ctx.accounts.claim_gates(), Some((amount, index)))?;
+        record_receipt(ctx.accounts, ctx.bumps.claim_receipt, index, amount)?;
+
+        let claimant = ctx.accounts.claimant.key();
+        let leaf_data = encode_leaf(
+            ctx.accounts.airdrop_state.leaf_version,
+            index,
+            &claimant,
+            amount,
+        )?;
+
+        if ctx.accounts.airdrop_state.claim_mode == CLAIM_MODE_VESTING {
+            // The claim only escrows, closing the claimant's wrapped SOL account would unwrap nothing of it
+            require!(!unwrap, WhitelistError::NothingToUnwrap);
+            require!(amount > 0, WhitelistError::InvalidAmount);
+            ctx.accounts.airdrop_state.check_claim_amount(amount)?;
+            let merkle_root = claims_root(&ctx.accounts.airdrop_state, expected_root_version)?;
+            verify_claim(&ctx.accounts.airdrop_state.tree_layout(), &merkle_root, &leaf_data, index, &hashes)?;
+            ctx.accounts.claim_bitmap.load_mut()?.set_claimed(index)?;
+
+            let (vesting_account, start_time) = open_vesting(ctx.accounts, ctx.bumps.vesting_account, claimant, index, amount)?;
+            record_referral(ctx.accounts.referral_stats.as_mut(), referrer, claimant, amount)?;
+            call_claim_hook(
+                &ctx.accounts.airdrop_state,
+                ctx.accounts.hook_program.as_ref(),
+                ctx.accounts.hook_authority.as_ref(),
+                &ctx.accounts.claimant,
+                amount,
+                index,
+                ctx.remaining_accounts,
+            )?;
+
+            let airdrop_state = &mut ctx.accounts.airdrop_state;
+            airdrop_state.num_claims = airdrop_state
+                .num_claims
+                .checked_add(1)
+                .ok_or(WhitelistError::OverFlow)?;
+
+            set_return_data(&ClaimResult {
+                amount_transferred: 0,
+                claimant,
+                amount_claimed: airdrop_state.amount_claimed,
+                merkle_root: airdrop_state.merkle_root,
+                amount_locked: amount,
+            }.try_to_vec()?);
+            emit_cpi!(VestingCreated {
+                airdrop_state: airdrop_state.key(),
+                vesting_account,
+                beneficiary: claimant,
+                index,
+                total: amount,
+                start_time,
+            });
+            return Ok(());
+        }
+        if ctx.accounts.airdrop_state.claim_mode == CLAIM_MODE_DECAYING {
+            require!(amount > 0, WhitelistError::InvalidAmount);
+            ctx.accounts.airdrop_state.check_claim_amount(amount)?;
+            let merkle_root = claims_root(&ctx.accounts.airdrop_state, expected_root_version)?;
+            verify_claim(&ctx.accounts.airdrop_state.tree_layout(), &merkle_root, &leaf_data, index, &hashes)?;
+            ctx.accounts.claim_bitmap.load_mut()?.set_claimed(index)?;
+
+            let decay_config = ctx
+                .accounts
+                .decay_config
+                .as_mut()
+                .ok_or(WhitelistError::MissingDecayConfig)?;
+            let claimable = decay_config.claimable_amount(
+                &ctx.accounts.airdrop_state,
+                amount,
+                Clock::get()?.unix_timestamp,
+            )?;
+            require!(claimable > 0, WhitelistError::AllocationDecayed);
+            decay_config.total_forfeited = decay_config
+                .total_forfeited
+                .checked_add(amount - claimable)
+                .ok_or(WhitelistError::OverFlow)?;
+
+            let payout = pay_out(&mut ctx.accounts.claim_accounts(ctx.remaining_accounts).payout(), claimable, 1)?;
+            record_referral(ctx.accounts.referral_stats.as_mut(), referrer, claimant, claimable)?;
+            call_claim_hook(
+                &ctx.accounts.airdrop_state,
+                ctx.accounts.hook_program.as_ref(),
+                ctx.accounts.hook_authority.as_ref(),
+                &ctx.accounts.claimant,
+                claimable,
+                index,
+                ctx.remaining_accounts,
+            )?;
+            let airdrop_state = &ctx.accounts.airdrop_state;
+
+            set_return_data(&ClaimResult {
+                amount_transferred: payout,
+                claimant,
+                amount_claimed: airdrop_state.amount_claimed,
+                merkle_root: airdrop_state.merkle_root,
+                amount_locked: 0,
+            }.try_to_vec()?);
+            emit_cpi!(ClaimEvent {
+                claimer: claimant,
+                mint: airdrop_state.mint,
+                amount: claimable,
+                index,
+                total_claimed: airdrop_state.amount_claimed,
+                num_claims: airdrop_state.num_claims,
+                root_version: expected_root_version,
+                allocation_claimed: amount,
+                round: None,
+                amount_locked: 0,
+                bonus: 0,
+                fee: airdrop_state.claim_fee(claimable)?,
+                net_amount: airdrop_state.net_of_fee(claimable)?,
+                token_fee_mint: airdrop_state.fee_mint,
+                token_fee: airdrop_state.token_fee(),
+                referrer,
+            });
+            unwrap_payout(ctx.accounts, unwrap)?;
+            return Ok(());
+        }
+        if ctx.accounts.airdrop_state.claim_mode != CLAIM_MODE_CUMULATIVE {
+            let leaves = [ClaimedLeaf { amount, index, leaf_data, hashes: &hashes }];
+            let mut events = process_claim(
+                ctx.accounts.claim_accounts(ctx.remaining_accounts),
+                claimant,
+                &leaves,
+                expected_root_version,
+            )?;
+            record_referral(ctx.accounts.referral_stats.as_mut(), referrer, claimant, amount)?;
+            call_claim_hook(
+                &ctx.accounts.airdrop_state,
+                ctx.accounts.hook_program.as_ref(),
+                ctx.accounts.hook_authority.as_ref(),
+                &ctx.accounts.claimant,
+                amount,
+                index,
+                ctx.remaining_accounts,
+            )?;
+            for event in events.iter_mut() {
+                event.referrer = referrer;
+            }
+
+            // The claim holds by now, the bonus comes on top of it out of its own reserve
+            let bonus = match ctx.accounts.bonus_config.as_mut() {
+                Some(bonus_config) => bonus_config.take_bonus(amount, Clock::get()?.unix_timestamp)?,
+                None => 0,
+            };
+            if bonus > 0 {
+                pay_bonus(&mut ctx.accounts.claim_accounts(ctx.remaining_accounts).payout(), bonus)?;
+                for event in events.iter_mut() {
+                    event.bonus = bonus;
+                }
+            }
+            for event in events {
+                emit_cpi!(event);
+            }
+            unwrap_payout(ctx.accounts, unwrap)?;
+            return Ok(());
+        }
+
+        // `amount` is everything owed to the claimant so far, under any root
+        let merkle_root = claims_root(&ctx.accounts.airdrop_state, expected_root_version)?;
+        verify_claim(&ctx.accounts.airdrop_state.tree_layout(), &merkle_root, &leaf_data, index, &hashes)?;
+
+        let airdrop_state_key = ctx.accounts.airdrop_state.key();
+        let claim_progress = ctx
+            .accounts
+            .claim_progress
+            .as_mut()
+            .ok_or(WhitelistError::MissingClaimProgress)?;
+        let paid = claim_progress.claimed;
+        require!(amount > paid, WhitelistError::AlreadyClaimed);
+        // The cap applies to what this claim pays, the leaf amount only ever grows
+        ctx.accounts.airdrop_state.check_claim_amount(amount - paid)?;
+        claim_progress.set_inner(ClaimProgress {
+            airdrop_state: airdrop_state_key,
+            claimant,
+            claimed: amount,
+            bump: ctx.bumps.claim_progress.ok_or(WhitelistError::MissingClaimProgress)?,
+        });
+
+        // A claimant counts once, however many roots they claim under
+        let payout = pay_out(
+            &mut ctx.accounts.claim_accounts(ctx.remaining_accounts).payout(),
+            amount - paid,
+            u64::from(paid == 0),
+        )?;
+        record_referral(ctx.accounts.referral_stats.as_mut(), referrer, claimant, amount - paid)?;
+        call_claim_hook(
+            &ctx.accounts.airdrop_state,
+            ctx.accounts.hook_program.as_ref(),
+            ctx.accounts.hook_authority.as_ref(),
+            &ctx.accounts.claimant,
+            amount - paid,
+            index,
+            ctx.remaining_accounts,
+        )?;
+        let airdrop_state = &ctx.accounts.airdrop_state;
+
+        set_return_data(&ClaimResult {
+            amount_transferred: payout,
+            claimant,
+            amount_claimed: airdrop_state.amount_claimed,
+            merkle_root: airdrop_state.merkle_root,
+            amount_locked: 0,
+        }.try_to_vec()?);
+        emit_cpi!(ClaimEvent {
+            claimer: claimant,
+            mint: airdrop_state.mint,
+            amount: amount - paid,
+            index,
+            total_claimed: airdrop_state.amount_claimed,
+            num_claims: airdrop_state.num_claims,
+            root_version: expected_root_version,
+            allocation_claimed: amount,
+            round: None,
+            amount_locked: 0,
+            bonus: 0,
+            fee: airdrop_state.claim_fee(amount - paid)?,
+            net_amount: airdrop_state.net_of_fee(amount - paid)?,
+            token_fee_mint: airdrop_state.fee_mint,
+            token_fee: airdrop_state.token_fee(),
+            referrer,
+        });
+        unwrap_payout(ctx.accounts, unwrap)?;
+
+        Ok(())
+    }
+
+    /// Claims the allocation at `index` of a round, proven against the root of the round and
+    /// tracked in its own bitmap shards, so a wallet can claim once in every round.
+    pub fn claim_round<'info>(
+        ctx: Context<'_, '_, '_, 'info, ClaimRound<'info>>,
+        amount: u64,
+        hashes: Vec<u8>,
+        index: u64,
+    ) -> Result<()> {
+        claim_prologue(&ctx.accounts.airdrop_state, ctx.accounts.claim_gates(), Some((amount, index)))?;
+
+        require!(
+            ctx.accounts.airdrop_state.claim_mode == CLAIM_MODE_EXACT,
+            WhitelistError::ClaimModeMismatch
+        );
+        require!(amount > 0, WhitelistError::InvalidAmount);
+        ctx.accounts.airdrop_state.check_claim_amount(amount)?;
+        let now = Clock::get()?.unix_timestamp;
+        let round_state = &ctx.accounts.round_state;
+        require!(now >= round_state.start_time, WhitelistError::AirdropNotStarted);
+        require!(
+            round_state.end_time == 0 || now <= round_state.end_time,
+            WhitelistError::AirdropEnded
+        );
+
+        let claimant = ctx.accounts.claimant.key();
+        let leaf_data = encode_leaf(ctx.accounts.airdrop_state.leaf_version, index, &claimant, amount)?;
+        let layout = TreeLayout {
+            num_leaves: round_state.num_leaves,
+            ..ctx.accounts.airdrop_state.tree_layout()
+        };
+        verify_claim(&layout, &round_state.merkle_root, &leaf_data, index, &hashes)?;
+        ctx.accounts.claim_bitmap.load_mut()?.set_claimed(index)?;
+
+        let round_claimed = round_state
+            .amount_claimed
+            .checked_add(amount)
+            .ok_or(WhitelistError::OverFlow)?;
+        require!(round_claimed <= round_state.allocation, WhitelistError::AllocationExceeded);
+
+        let payout = pay_out(&mut ctx.accounts.claim_accounts(ctx.remaining_accounts).payout(), amount, 1)?;
+        call_claim_hook(
+            &ctx.accounts.airdrop_state,
+            ctx.accounts.hook_program.as_ref(),
+            ctx.accounts.hook_authority.as_ref(),
+            &ctx.accounts.claimant,
+            amount,
+            index,
+            ctx.remaining_accounts,
+        )?;
+        let round_state = &mut ctx.accounts.round_state;
+        round_state.amount_claimed = round_claimed;
+        round_state.num_claims = round_state
+            .num_claims
+            .checked_add(1)
+            .ok_or(WhitelistError::OverFlow)?;
+        let round = round_state.round;
+        let airdrop_state = &ctx.accounts.airdrop_state;
+
+        set_return_data(&ClaimResult {
+            amount_transferred: payout,
+            claimant,
+            amount_claimed: airdrop_state.amount_claimed,
+            merkle_root: ctx.accounts.round_state.merkle_root,
+            amount_locked: 0,
+        }.try_to_vec()?);
+        emit_cpi!(ClaimEvent {
+            claimer: claimant,
+            mint: airdrop_state.mint,
+            amount,
+            index,
+            total_claimed: airdrop_state.amount_claimed,
+            num_claims: airdrop_state.num_claims,
+            root_version: 0,
+            allocation_claimed: amount,
+            round: Some(round),
+            amount_locked: 0,
+            bonus: 0,
+            fee: airdrop_state.claim_fee(amount)?,
+            net_amount: airdrop_state.net_of_fee(amount)?,
+            token_fee_mint: airdrop_state.fee_mint,
+            token_fee: airdrop_state.token_fee(),
+            referrer: None,
+        });
+
+        Ok(())
+    }
+
+    /// Claims `claim_amount` of the allocation of `leaf_amount` at `index`, on an airdrop in
+    /// `CLAIM_MODE_PARTIAL`. What was claimed so far is tracked per index, the index is only
+    /// marked claimed in its bitmap shard once the whole allocation was.
+    pub fn claim_partial<'info>(
+        ctx: Context<'_, '_, '_, 'info, ClaimPartial<'info>>,
+        leaf_amount: u64,
+        claim_amount: u64,
+        hashes: Vec<u8>,
+        index: u64,
+        expected_root_version: u64,
+    ) -> Result<()> {
+        claim_prologue(&ctx.accounts.airdrop_state, ctx.accounts.claim_gates(), Some((leaf_amount, index)))?;
+
+        require!(
+            ctx.accounts.airdrop_state.claim_mode == CLAIM_MODE_PARTIAL,
+            WhitelistError::ClaimModeMismatch
+        );
+        require!(claim_amount > 0, WhitelistError::InvalidAmount);
+        ctx.accounts.airdrop_state.check_claim_amount(leaf_amount)?;
+        let claimant = ctx.accounts.claimant.key();
+        let merkle_root = claims_root(&ctx.accounts.airdrop_state, expected_root_version)?;
+        let leaf_data = encode_leaf(ctx.accounts.airdrop_state.leaf_version, index, &claimant, leaf_amount)?;
+        verify_claim(&ctx.accounts.airdrop_state.tree_layout(), &merkle_root, &leaf_data, index, &hashes)?;
+        require!(
+            !ctx.accounts.claim_bitmap.load()?.is_claimed(index),
+            WhitelistError::AlreadyClaimed
+        );
+
+        let claim_progress = &mut ctx.accounts.claim_progress;
+        let first_claim = claim_progress.claimed == 0;
+        let allocation_claimed = claim_progress
+            .claimed
+            .checked_add(claim_amount)
+            .ok_or(WhitelistError::OverFlow)?;
+        require!(allocation_claimed <= leaf_amount, WhitelistError::AllocationExhausted);
+        claim_progress.set_inner(ClaimProgress {
+            airdrop_state: ctx.accounts.airdrop_state.key(),
+            claimant,
+            claimed: allocation_claimed,
+            bump: ctx.bumps.claim_progress,
+        });
+        if allocation_claimed == leaf_amount {
+            ctx.accounts.claim_bitmap.load_mut()?.set_claimed(index)?;
+        }
+
+        // An allocation claimed in parts still counts as a single claim
+        let payout = pay_out(
+            &mut ctx.accounts.claim_accounts(ctx.remaining_accounts).payout(),
+            claim_amount,
+            u64::from(first_claim),
+        )?;
+        call_claim_hook(
+            &ctx.accounts.airdrop_state,
+            ctx.accounts.hook_program.as_ref(),
+            ctx.accounts.hook_authority.as_ref(),
+            &ctx.accounts.claimant,
+            claim_amount,
+            index,
+            ctx.remaining_accounts,
+        )?;
+        let airdrop_state = &ctx.accounts.airdrop_state;
+
+        set_return_data(&ClaimResult {
+            amount_transferred: payout,
+            claimant,
+            amount_claimed: airdrop_state.amount_claimed,
+            merkle_root: airdrop_state.merkle_root,
+            amount_locked: 0,
+        }.try_to_vec()?);
+        emit_cpi!(ClaimEvent {
+            claimer: claimant,
+            mint: airdrop_state.mint,
+            amount: claim_amount,
+            index,
+            total_claimed: airdrop_state.amount_claimed,
+            num_claims: airdrop_state.num_claims,
+            root_version: expected_root_version,
+            allocation_claimed,
+            round: None,
+            amount_locked: 0,
+            bonus: 0,
+            fee: airdrop_state.claim_fee(claim_amount)?,
+            net_amount: airdrop_state.net_of_fee(claim_amount)?,
+            token_fee_mint: airdrop_state.fee_mint,
+            token_fee: airdrop_state.token_fee(),
+            referrer: None,
+        });
+
+        Ok(())
+    }
+
+    /// Claims several allocations of the claimant in one payout, the leaves at the indices of
+    /// `claims` within bitmap shard `shard`. `hashes` concatenates their proofs in order, each
+    /// `proof_len` hashes long. Any claim that does not hold fails the whole batch. While the
+    /// airdrop has revoked leaves, `remaining_accounts` opens with the revocation marker of the
+    /// index of every claim in order, the accounts of the payout following them.
+    pub fn claim_many<'info>(
+        ctx: Context<'_, '_, '_, 'info, ClaimMany<'info>>,
+        _shard: u64,
+        claims: Vec<BatchClaim>,
+        hashes: Vec<u8>,
+        expected_root_version: u64,
+    ) -> Result<()> {
+        claim_prologue(&ctx.accounts.airdrop_state, ctx.accounts.claim_gates(), None)?;
+
+        require!(
+            !claims.is_empty() && claims.len() <= MAX_BATCH_CLAIMS,
+            WhitelistError::InvalidBatchSize
+        );
+        let num_markers = if ctx.accounts.airdrop_state.num_revoked == 0 { 0 } else { claims.len() };
+        require!(
+            ctx.remaining_accounts.len() >= num_markers,
+            WhitelistError::MissingRevocationAccount
+        );
+        let (revoked_leaves, payout_accounts) = ctx.remaining_accounts.split_at(num_markers);
+        for (claim, revoked_leaf) in claims.iter().zip(revoked_leaves) {
+            require_keys_eq!(
+                revoked_leaf.key(),
+                RevokedLeaf::address(&ctx.accounts.airdrop_state.key(), claim.index),
+                WhitelistError::MissingRevocationAccount
+            );
+            require!(revoked_leaf.data_is_empty(), WhitelistError::AllocationRevoked);
+        }
+        require!(
+            hashes.len() == claims.iter().map(|claim| usize::from(claim.proof_len) * HASH_SIZE).sum::<usize>(),
+            WhitelistError::MalformedProof
+        );
+
+        let claimant = ctx.accounts.claimant.key();
+        let mut proofs = hashes.as_slice();
+        let leaves = claims
+            .iter()
+            .map(|claim| {
+                let (proof, rest) = proofs.split_at(usize::from(claim.proof_len) * HASH_SIZE);
+                proofs = rest;
+                Ok(ClaimedLeaf {
+                    amount: claim.amount,
+                    index: claim.index,
+                    leaf_data: encode_leaf(ctx.accounts.airdrop_state.leaf_version, claim.index, &claimant, claim.amount)?,
+                    hashes: proof,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let events = process_claim(
+            ctx.accounts.claim_accounts(payout_accounts),
+            claimant,
+            &leaves,
+            expected_root_version,
+        )?;
+        for claim in &claims {
+            call_claim_hook(
+                &ctx.accounts.airdrop_state,
+                ctx.accounts.hook_program.as_ref(),
+                ctx.accounts.hook_authority.as_ref(),
+                &ctx.accounts.claimant,
+                claim.amount,
+                claim.index,
+                payout_accounts,
+            )?;
+        }
+        for event in events {
+            emit_cpi!(event);
+        }
+
+        Ok(())
+    }
+
+    /// Pushes allocations to their recipients, cranked by the authority for those who never
+    /// claim themselves. `remaining_accounts` holds each recipient followed by its associated
+    /// token account, created when missing if a `payer` is given, and while the airdrop has
+    /// revoked leaves by the revocation marker of its index. A claim that cannot be paid, for a
+    /// wrong proof, a revoked leaf or a frozen account, is skipped with a `ClaimSkipped` event instead of
+    /// failing the crank; skipped or not, any batch of a shard can be pushed in any transaction.
+    /// Mints with a transfer hook are not supported, their extra accounts would not fit, and are
+    /// refused before the first claim of the batch rather than failing partway through it, and so
+    /// are airdrops with a claim hook.
+    pub fn claim_for_many<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimForMany<'info>>,
+        _shard: u64,
+        claims: Vec<PushClaim>,
+        hashes: Vec<u8>,
+        expected_root_version: u64,
+    ) -> Result<()> {
+        // The hook takes the signature of the claimant, which a pushed claim does not have
+        require!(
+            ctx.accounts.airdrop_state.hook_program.is_none(),
+            WhitelistError::ClaimHookUnsupported
+        );
+        claim_prologue(&ctx.accounts.airdrop_state, ctx.accounts.claim_gates(), None)?;
+        require!(
+            !has_transfer_hook(&ctx.accounts.mint.to_account_info())?,
+            WhitelistError::TransferHookUnsupported
+        );
+        require!(
+            !claims.is_empty() && claims.len() <= MAX_BATCH_CLAIMS,
+            WhitelistError::InvalidBatchSize
+        );
+        require!(
+            hashes.len() == claims.iter().map(|claim| usize::from(claim.proof_len) * HASH_SIZE).sum::<usize>(),
+            WhitelistError::MalformedProof
+        );
+        // The revocation markers are only passed while there is any leaf they could mark
+        let accounts_per_claim = if ctx.accounts.airdrop_state.num_revoked == 0 { 2 } else { 3 };
+        require!(
+            ctx.remaining_accounts.len() == accounts_per_claim * claims.len(),
+            WhitelistError::RecipientAccountsMismatch
+        );
+
+        require!(
+            ctx.accounts.airdrop_state.claim_mode == CLAIM_MODE_EXACT,
+            WhitelistError::ClaimModeMismatch
+        );
+        let merkle_root = claims_root(&ctx.accounts.airdrop_state, expected_root_version)?;
+        let layout = ctx.accounts.airdrop_state.tree_layout();
+        let mint_key = ctx.accounts.mint.key();
+        let mut proofs = hashes.as_slice();
+        for (claim, recipient_accounts) in claims.iter().zip(ctx.remaining_accounts.chunks_exact(accounts_per_claim)) {
+            let (proof, rest) = proofs.split_at(usize::from(claim.proof_len) * HASH_SIZE);
+            proofs = rest;
+            let (recipient, recipient_ata) = (&recipient_accounts[0], &recipient_accounts[1]);
+            let revoked_leaf = recipient_accounts.get(2);
+
+            let reason = if recipient.key() != claim.recipient
+                || recipient_ata.key() != get_associated_token_address_with_program_id(&claim.recipient, &mint_key, &ctx.accounts.token_program.key())
+                || revoked_leaf.is_some_and(|revoked_leaf| revoked_leaf.key() != RevokedLeaf::address(&ctx.accounts.airdrop_state.key(), claim.index))
+            {
+                Some(SkipReason::AccountMismatch)
+            } else if revoked_leaf.is_some_and(|revoked_leaf| !revoked_leaf.data_is_empty()) {
+                Some(SkipReason::Revoked)
+            } else if !ctx.accounts.airdrop_state.within_claim_cap(claim.amount) {
+                Some(SkipReason::ClaimTooLarge)
+            } else if claim.amount == 0
+                || verify_claim(
+                    &layout,
+                    &merkle_root,
+                    &encode_leaf(ctx.accounts.airdrop_state.leaf_version, claim.index, &claim.recipient, claim.amount)?,
+                    claim.index,
+                    proof,
+                ).is_err()
+            {
+                Some(SkipReason::InvalidProof)
+            } else if ctx.accounts.claim_bitmap.load()?.is_claimed(claim.index) {
+                Some(SkipReason::AlreadyClaimed)
+            } else if recipient_ata.data_is_empty() {
+                match &ctx.accounts.payer {
+                    Some(payer) => {
+                        create_idempotent(CpiContext::new(
+                            ctx.accounts.associated_token_program.to_account_info(),
+                            Create {
+                                payer: payer.to_account_info(),
+                                associated_token: recipient_ata.clone(),
+                                authority: recipient.clone(),
+                                mint: ctx.accounts.mint.to_account_info(),
+                                system_program: ctx.accounts.system_program.to_account_info(),
+                                token_program: ctx.accounts.token_program.to_account_info(),
+                            },
+                        ))?;
+                        None
+                    }
+                    None => Some(SkipReason::MissingTokenAccount),
+                }
+            } else {
+                None
+            };
+            if let Some(reason) = reason {
+                emit_cpi!(ClaimSkipped {
+                    recipient: claim.recipient,
+                    index: claim.index,
+                    reason,
+                });
+                continue;
+            }
+
+            let destination = InterfaceAccount::<TokenAccount>::try_from(recipient_ata)?;
+            if destination.is_frozen() {
+                emit_cpi!(ClaimSkipped {
+                    recipient: claim.recipient,
+                    index: claim.index,
+                    reason: SkipReason::FrozenTokenAccount,
+                });
+                continue;
+            }
+
+            // The index is in range and unclaimed, this only fails for an index of another shard
+            ctx.accounts.claim_bitmap.load_mut()?.set_claimed(claim.index)?;
+            let accounts = &mut ctx.accounts;
+            pay_out(
+                &mut PayoutAccounts {
+                    airdrop_state: &mut accounts.airdrop_state,
+                    mint: &accounts.mint,
+                    vault: &accounts.vault,
+                    destination: &destination,
+                    fee_destination: accounts.fee_ata.as_ref(),
+                    token_program: &accounts.token_program,
+                    remaining_accounts: &[],
+                },
+                claim.amount,
+                1,
+            )?;
+
+            emit_cpi!(ClaimEvent {
+                claimer: claim.recipient,
+                mint: mint_key,
+                amount: claim.amount,
+                index: claim.index,
+                total_claimed: ctx.accounts.airdrop_state.amount_claimed,
+                num_claims: ctx.accounts.airdrop_state.num_claims,
+                root_version: expected_root_version,
+                allocation_claimed: claim.amount,
+                round: None,
+                amount_locked: 0,
+                bonus: 0,
+                fee: ctx.accounts.airdrop_state.claim_fee(claim.amount)?,
+                net_amount: ctx.accounts.airdrop_state.net_of_fee(claim.amount)?,
+                token_fee_mint: None,
+                token_fee: 0,
+                referrer: None,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Claims the allocation of `recipient` on their behalf. The leaf binds the recipient, so
+    /// whoever signs only pays the fees and the rent of the recipient's associated token account,
+    /// letting recipients without any SOL receive their tokens. Airdrops with a claim hook are
+    /// refused, the hook takes the signature of the claimant.
+    pub fn claim_for<'info>(
+        ctx: Context<'_, '_, '_, 'info, ClaimFor<'info>>,
+        amount: u64,
+        hashes: Vec<u8>,
+        index: u64,
+        expected_root_version: u64,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.airdrop_state.hook_program.is_none(),
+            WhitelistError::ClaimHookUnsupported
+        );
+        claim_prologue(&ctx.accounts.airdrop_state, ctx.accounts.claim_gates(), Some((amount, index)))?;
+
+        let recipient = ctx.accounts.recipient.key();
+        let leaf_data = encode_leaf(
+            ctx.accounts.airdrop_state.leaf_version,
+            index,
+            &recipient,
+            amount,
+        )?;
+
+        let leaves = [ClaimedLeaf { amount, index, leaf_data, hashes: &hashes }];
+        let events = process_claim(
+            ctx.accounts.claim_accounts(ctx.remaining_accounts),
+            recipient,
+            &leaves,
+            expected_root_version,
+        )?;
+        for event in events {
+            emit_cpi!(event);
+        }
+
+        Ok(())
+    }
+
+    /// Claims into `destination`, any token account of the airdrop's mint rather than the
+    /// claimant's associated one. It has to be owned by the claimant unless `custodial`
+    /// acknowledges paying out to someone else's account, such as an exchange deposit address.
+    pub fn claim_to_account<'info>(
+        ctx: Context<'_, '_, '_, 'info, ClaimToAccount<'info>>,
+        amount: u64,
+        hashes: Vec<u8>,
+        index: u64,
+        expected_root_version: u64,
+        custodial: bool,
+    ) -> Result<()> {
+        claim_prologue(&ctx.accounts.airdrop_state, ctx.accounts.claim_gates(), Some((amount, index)))?;
+        let claimant = ctx.accounts.claimant.key();
+        require!(
+            custodial || ctx.accounts.destination.owner == claimant,
+            WhitelistError::DestinationOwnerMismatch
+        );
+        let leaf_data = encode_leaf(
+            ctx.accounts.airdrop_state.leaf_version,
+            index,
+            &claimant,
+            amount,
+        )?;
+
+        let leaves = [ClaimedLeaf { amount, index, leaf_data, hashes: &hashes }];
+        let events = process_claim(
+            ctx.accounts.claim_accounts(ctx.remaining_accounts),
+            claimant,
+            &leaves,
+            expected_root_version,
+        )?;
+        call_claim_hook(
+            &ctx.accounts.airdrop_state,
+            ctx.accounts.hook_program.as_ref(),
+            ctx.accounts.hook_authority.as_ref(),
+            &ctx.accounts.claimant,
+            amount,
+            index,
+            ctx.remaining_accounts,
+        )?;
+        for event in events {
+            emit_cpi!(event);
+        }
+
+        Ok(())
+    }
+
+    /// Claims from a `LEAF_VERSION_COMMITTED` airdrop, whose leaves are
+    /// `hash(hash(claimant ‖ amount) ‖ data_hash)`. The program binds the leaf to the claimant
+    /// and `amount` itself, `data_hash` stands for fields of the allocation it does not know
+    /// about and is trusted as whatever the tree builder committed to.
+    pub fn claim_committed_leaf<'info>(
+        ctx: Context<'_, '_, '_, 'info, Claim<'info>>,
+        amount: u64,
+        hashes: Vec<u8>,
+        index: u64,
+        expected_root_version: u64,
+        data_hash: [u8; 32],
+    ) -> Result<()> {
+
+        claim_prologue(&ctx.accounts.airdrop_state, ctx.accounts.claim_gates(), Some((amount, index)))?;
+        record_receipt(ctx.accounts, ctx.bumps.claim_receipt, index, amount)?;
+
+        require!(
+            ctx.accounts.airdrop_state.leaf_version == LEAF_VERSION_COMMITTED,
+            WhitelistError::UnsupportedLeafVersion
+        );
+        let claimant = ctx.accounts.claimant.key();
+        let leaf_data = committed_leaf(
+            ctx.accounts.airdrop_state.hashing_algorithm,
+            &claimant,
+            amount,
+            &data_hash,
+        );
+
+        let leaves = [ClaimedLeaf { amount, index, leaf_data, hashes: &hashes }];
+        let events = process_claim(
+            ctx.accounts.claim_accounts(ctx.remaining_accounts),
+            claimant,
+            &leaves,
+            expected_root_version,
+        )?;
+        call_claim_hook(
+            &ctx.accounts.airdrop_state,
+            ctx.accounts.hook_program.as_ref(),
+            ctx.accounts.hook_authority.as_ref(),
+            &ctx.accounts.claimant,
+            amount,
+            index,
+            ctx.remaining_accounts,
+        )?;
+        for event in events {
+            emit_cpi!(event);
+        }
+
+        Ok(())
+    }
+
+    /// Claims from a `LEAF_VERSION_DEADLINE` airdrop, whose leaves are
+    /// `claimant ‖ amount ‖ deadline`. A leaf past its deadline fails to claim even while the
+    /// airdrop itself still takes claims.
+    pub fn claim_with_deadline<'info>(
+        ctx: Context<'_, '_, '_, 'info, Claim<'info>>,
+        amount: u64,
+        hashes: Vec<u8>,
+        index: u64,
+        expected_root_version: u64,
+        deadline: i64,
+    ) -> Result<()> {
+        claim_prologue(&ctx.accounts.airdrop_state, ctx.accounts.claim_gates(), Some((amount, index)))?;
+        record_receipt(ctx.accounts, ctx.bumps.claim_receipt, index, amount)?;
+
+        require!(
+            ctx.accounts.airdrop_state.leaf_version == LEAF_VERSION_DEADLINE,
+            WhitelistError::UnsupportedLeafVersion
+        );
+        require!(
+            Clock::get()?.unix_timestamp <= deadline,
+            WhitelistError::AllocationExpired
+        );
+        let claimant = ctx.accounts.claimant.key();
+        let leaf_data = deadline_leaf(&claimant, amount, deadline);
+
+        let leaves = [ClaimedLeaf { amount, index, leaf_data, hashes: &hashes }];
+        let events = process_claim(
+            ctx.accounts.claim_accounts(ctx.remaining_accounts),
+            claimant,
+            &leaves,
+            expected_root_version,
+        )?;
+        call_claim_hook(
+            &ctx.accounts.airdrop_state,
+            ctx.accounts.hook_program.as_ref(),
+            ctx.accounts.hook_authority.as_ref(),
+            &ctx.accounts.claimant,
+            amount,
+            index,
+            ctx.remaining_accounts,
+        )?;
+        for event in events {
+            emit_cpi!(event);
+        }
+
+        Ok(())
+    }
+
+    /// Claims the allocation at `index` of a `CLAIM_MODE_EXACT` airdrop and deposits what the
+    /// claimant was paid into the staking program of the airdrop on their behalf, see
+    /// `deposit_stake`. The accounts of its `StakeConfig` come first among the remaining
+    /// accounts, and a failing deposit fails the claim.
+    pub fn claim_and_stake<'info>(
+        ctx: Context<'_, '_, '_, 'info, Claim<'info>>,
+        amount: u64,
+        hashes: Vec<u8>,
+        index: u64,
+        expected_root_version: u64,
+    ) -> Result<()> {
+        claim_prologue(&ctx.accounts.airdrop_state, ctx.accounts.claim_gates(), Some((amount, index)))?;
+        record_receipt(ctx.accounts, ctx.bumps.claim_receipt, index, amount)?;
+
+        let num_stake_accounts = ctx.accounts.stake_config
+            .as_ref()
+            .ok_or(WhitelistError::MissingStakeConfig)?
+            .accounts
+            .len();
+        require!(
+            ctx.remaining_accounts.len() >= num_stake_accounts,
+            WhitelistError::StakeAccountMismatch
+        );
+        let (stake_accounts, remaining_accounts) = ctx.remaining_accounts.split_at(num_stake_accounts);
+
+        let claimant = ctx.accounts.claimant.key();
+        let leaf_data = encode_leaf(
+            ctx.accounts.airdrop_state.leaf_version,
+            index,
+            &claimant,
+            amount,
+        )?;
+
+        let leaves = [ClaimedLeaf { amount, index, leaf_data, hashes: &hashes }];
+        let events = process_claim(
+            ctx.accounts.claim_accounts(remaining_accounts),
+            claimant,
+            &leaves,
+            expected_root_version,
+        )?;
+        // The claimant holds what they were paid for the length of the instruction only
+        let net_amount = ctx.accounts.airdrop_state.net_of_fee(amount)?;
+        deposit_stake(ctx.accounts, stake_accounts, net_amount)?;
+        call_claim_hook(
+            &ctx.accounts.airdrop_state,
+            ctx.accounts.hook_program.as_ref(),
+            ctx.accounts.hook_authority.as_ref(),
+            &ctx.accounts.claimant,
+            amount,
+            index,
+            remaining_accounts,
+        )?;
+        for event in events {
+            emit_cpi!(event);
+        }
+
+        Ok(())
+    }
+
+    /// Claims from a `LEAF_VERSION_BASKET` airdrop, whose leaves are
+    /// `claimant ‖ amount ‖ basket_amounts…` with an amount of every mint of its `Basket`.
+    /// `amount` of the airdrop mint is paid as by `claim_airdrop`, the basket amounts out of
+    /// their own vaults, see `pay_basket`, all in the one instruction.
+    pub fn claim_basket<'info>(
+        ctx: Context<'_, '_, '_, 'info, Claim<'info>>,
+        amount: u64,
+        hashes: Vec<u8>,
+        index: u64,
+        expected_root_version: u64,
+        basket_amounts: Vec<u64>,
+    ) -> Result<()> {
+        claim_prologue(&ctx.accounts.airdrop_state, ctx.accounts.claim_gates(), Some((amount, index)))?;
+        record_receipt(ctx.accounts, ctx.bumps.claim_receipt, index, amount)?;
+
+        require!(
+            ctx.accounts.airdrop_state.leaf_version == LEAF_VERSION_BASKET,
+            WhitelistError::UnsupportedLeafVersion
+        );
+        let num_basket_accounts = ctx.accounts.basket
+            .as_ref()
+            .ok_or(WhitelistError::MissingBasket)?
+            .mints
+            .len()
+            * BASKET_ACCOUNTS_PER_MINT;
+        require!(
+            ctx.remaining_accounts.len() >= num_basket_accounts,
+            WhitelistError::BasketAccountMismatch
+        );
+        let (basket_accounts, remaining_accounts) = ctx.remaining_accounts.split_at(num_basket_accounts);
+
+        let claimant = ctx.accounts.claimant.key();
+        let leaf_data = basket_leaf(&claimant, amount, &basket_amounts);
+
+        let leaves = [ClaimedLeaf { amount, index, leaf_data, hashes: &hashes }];
+        let events = process_claim(
+            ctx.accounts.claim_accounts(remaining_accounts),
+            claimant,
+            &leaves,
+            expected_root_version,
+        )?;
+        let basket_events = pay_basket(ctx.accounts, basket_accounts, &basket_amounts, index)?;
+        call_claim_hook(
+            &ctx.accounts.airdrop_state,
+            ctx.accounts.hook_program.as_ref(),
+            ctx.accounts.hook_authority.as_ref(),
+            &ctx.accounts.claimant,
+            amount,
+            index,
+            remaining_accounts,
+        )?;
+        for event in events {
+            emit_cpi!(event);
+        }
+        for event in basket_events {
+            emit_cpi!(event);
+        }
+
+        Ok(())
+    }
+
+    /// Claims the allocation at `index` of an airdrop in `TREE_MODE_CONCURRENT`, replacing its
+    /// leaf with `CLAIMED_LEAF` in the concurrent tree so it cannot be claimed again. The proof
+    /// is passed as remaining accounts, one per node, and leads to `root`, which may be any
+    /// root still in the change log of the tree: claims landing meanwhile do not invalidate it.
+    /// Claims go without the claim bitmap, the tree itself records them.
+    pub fn claim_concurrent<'info>(
+        ctx: Context<'_, '_, '_, 'info, ClaimConcurrent<'info>>,
+        amount: u64,
+        root: [u8; 32],
+        index: u64,
+    ) -> Result<()> {
+        claim_prologue(&ctx.accounts.airdrop_state, ctx.accounts.claim_gates(), Some((amount, index)))?;
+        require!(
+            ctx.accounts.airdrop_state.claim_mode == CLAIM_MODE_EXACT,
+            WhitelistError::ClaimModeMismatch
+        );
+        require!(amount > 0, WhitelistError::InvalidAmount);
+        ctx.accounts.airdrop_state.check_claim_amount(amount)?;
+        require!(index < ctx.accounts.airdrop_state.num_leaves, WhitelistError::IndexOutOfRange);
+
+        let claimant = ctx.accounts.claimant.key();
+        let airdrop_state = &ctx.accounts.airdrop_state;
+        let leaf_data = prepare_leaf(
+            airdrop_state.leaf_hashing,
+            HASHING_ALGORITHM_KECCAK,
+            encode_leaf(airdrop_state.leaf_version, index, &claimant, amount)?,
+        )?;
+        let leaf = leaf_hash(HASHING_ALGORITHM_KECCAK, PAIR_ORDERING_INDEXED, &leaf_data);
+
+        let mint_key = airdrop_state.mint.to_bytes();
+        let id_seed = AirdropState::id_seed(airdrop_state.airdrop_id);
+        let signer_seeds = &[
+            b"merkle_tree".as_ref(),
+            mint_key.as_ref(),
+            id_seed.as_slice(),
+            &[airdrop_state.bump],
+        ];
+        // Fails unless the leaf is still unclaimed at `index`, the proof checked by the
+        // compression program
+        CompressionAccounts {
+            merkle_tree: &ctx.accounts.merkle_tree,
+            authority: &airdrop_state.to_account_info(),
+            noop_program: &ctx.accounts.noop_program,
+            compression_program: &ctx.accounts.compression_program,
+        }
+        .replace_leaf(
+            &root,
+            &leaf,
+            &CLAIMED_LEAF,
+            proof_index(index)?,
+            ctx.remaining_accounts,
+            &[signer_seeds],
+        )?;
+
+        // The remaining accounts are the proof, the mint gets no transfer hook accounts
+        let payout = pay_out(
+            &mut PayoutAccounts {
+                airdrop_state: &mut ctx.accounts.airdrop_state,
+                mint: &ctx.accounts.mint,
+                vault: &ctx.accounts.vault,
+                destination: &ctx.accounts.signer_ata,
+                fee_destination: ctx.accounts.fee_ata.as_ref(),
+                token_program: &ctx.accounts.token_program,
+                remaining_accounts: &[],
+            },
+            amount,
+            1,
+        )?;
+        let airdrop_state = &ctx.accounts.airdrop_state;
+
+        set_return_data(&ClaimResult {
+            amount_transferred: payout,
+            claimant,
+            amount_claimed: airdrop_state.amount_claimed,
+            merkle_root: root,
+            amount_locked: 0,
+        }.try_to_vec()?);
+        emit_cpi!(ClaimEvent {
+            claimer: claimant,
+            mint: airdrop_state.mint,
+            amount,
+            index,
+            total_claimed: airdrop_state.amount_claimed,
+            num_claims: airdrop_state.num_claims,
+            root_version: airdrop_state.root_version,
+            allocation_claimed: amount,
+            round: None,
+            amount_locked: 0,
+            bonus: 0,
+            fee: airdrop_state.claim_fee(amount)?,
+            net_amount: airdrop_state.net_of_fee(amount)?,
+            token_fee_mint: None,
+            token_fee: 0,
+            referrer: None,
+        });
+
+        Ok(())
+    }
+
+    /// Claims the leaf `claimant ‖ nft_mint` at `index` of an NFT airdrop, paying the NFT out of
+    /// its vault. A listed NFT that was never deposited fails with `NftNotDeposited`, and the
+    /// bitmap keeps the leaf from paying again should the NFT find its way back to the vault.
+    pub fn claim_nft<'info>(
+        ctx: Context<'_, '_, '_, 'info, ClaimNft<'info>>,
+        hashes: Vec<u8>,
+        index: u64,
+        expected_root_version: u64,
+    ) -> Result<()> {
+        claim_prologue(&ctx.accounts.airdrop_state, ctx.accounts.claim_gates(), Some((1, index)))?;
+        require!(
+            ctx.accounts.airdrop_state.leaf_version == LEAF_VERSION_NFT,
+            WhitelistError::UnsupportedLeafVersion
+        );
+
+        let merkle_root = claims_root(&ctx.accounts.airdrop_state, expected_root_version)?;
+        let claimant = ctx.accounts.claimant.key();
+        let nft_mint = ctx.accounts.nft_mint.key();
+        let leaf_data = nft_leaf(&claimant, &nft_mint);
+        verify_claim(&ctx.accounts.airdrop_state.tree_layout(), &merkle_root, &leaf_data, index, &hashes)?;
+        ctx.accounts.claim_bitmap.load_mut()?.set_claimed(index)?;
+
+        // Checked here rather than by the account constraints, which would fail on an ATA
+        // nobody created with an error that says nothing about the deposit
+        let vault = &ctx.accounts.vault;
+        require!(
+            vault.owner == &ctx.accounts.token_program.key()
+                && InterfaceAccount::<TokenAccount>::try_from(vault.as_ref())?.amount == 1,
+            WhitelistError::NftNotDeposited
+        );
+
+        let airdrop_state = &mut ctx.accounts.airdrop_state;
+        let id_seed = AirdropState::id_seed(airdrop_state.airdrop_id);
+        let signer_seeds = &[
+            b"merkle_tree".as_ref(),
+            NFT_AIRDROP.as_ref(),
+            id_seed.as_slice(),
+            &[airdrop_state.bump],
+        ];
+        transfer_checked_with_hook(
+            &ctx.accounts.token_program,
+            ctx.accounts.vault.to_account_info(),
+            &ctx.accounts.nft_mint,
+            ctx.accounts.signer_ata.to_account_info(),
+            airdrop_state.to_account_info(),
+            ctx.remaining_accounts,
+            1,
+            &[signer_seeds],
+        )?;
+        airdrop_state.amount_claimed = airdrop_state
+            .amount_claimed
+            .checked_add(1)
+            .ok_or(WhitelistError::OverFlow)?;
+        airdrop_state.num_claims = airdrop_state
+            .num_claims
+            .checked_add(1)
+            .ok_or(WhitelistError::OverFlow)?;
+        call_claim_hook(
+            airdrop_state,
+            ctx.accounts.hook_program.as_ref(),
+            ctx.accounts.hook_authority.as_ref(),
+            &ctx.accounts.claimant,
+            1,
+            index,
+            ctx.remaining_accounts,
+        )?;
+
+        set_return_data(&ClaimResult {
+            amount_transferred: 1,
+            claimant,
+            amount_claimed: airdrop_state.amount_claimed,
+            merkle_root: airdrop_state.merkle_root,
+            amount_locked: 0,
+        }.try_to_vec()?);
+        emit_cpi!(ClaimEvent {
+            claimer: claimant,
+            mint: nft_mint,
+            amount: 1,
+            index,
+            total_claimed: airdrop_state.amount_claimed,
+            num_claims: airdrop_state.num_claims,
+            root_version: expected_root_version,
+            allocation_claimed: 1,
+            round: None,
+            amount_locked: 0,
+            bonus: 0,
+            fee: 0,
+            net_amount: 1,
+            token_fee_mint: None,
+            token_fee: 0,
+            referrer: None,
+        });
+
+        Ok(())
+    }
+
+    /// Claims the allocation at `index` of a native SOL airdrop, paying it in lamports out of
+    /// the SOL vault. Only `CLAIM_MODE_EXACT` claims are supported, without tickets, gates,
+    /// cosigner or hook.
+    pub fn claim_sol(
+        ctx: Context<ClaimSol>,
+        amount: u64,
+        hashes: Vec<u8>,
+        index: u64,
+        expected_root_version: u64,
+    ) -> Result<()> {
+        claim_prologue(&ctx.accounts.airdrop_state, ctx.accounts.claim_gates(), Some((amount, index)))?;
+        require!(
+            ctx.accounts.airdrop_state.claim_mode == CLAIM_MODE_EXACT,
+            WhitelistError::ClaimModeMismatch
+        );
+        require!(amount > 0, WhitelistError::InvalidAmount);
+        ctx.accounts.airdrop_state.check_claim_amount(amount)?;
+
+        let merkle_root = claims_root(&ctx.accounts.airdrop_state, expected_root_version)?;
+        let claimant = ctx.accounts.claimant.key();
+        let leaf_data = encode_leaf(
+            ctx.accounts.airdrop_state.leaf_version,
+            index,
+            &claimant,
+            amount,
+        )?;
+        verify_claim(&ctx.accounts.airdrop_state.tree_layout(), &merkle_root, &leaf_data, index, &hashes)?;
+        ctx.accounts.claim_bitmap.load_mut()?.set_claimed(index)?;
+
+        let airdrop_state = &mut ctx.accounts.airdrop_state;
+        require!(
+            airdrop_state
+                .amount_claimed
+                .checked_add(amount)
+                .ok_or(WhitelistError::OverFlow)?
+                <= airdrop_state.airdrop_amount,
+            WhitelistError::AllocationExceeded
+        );
+        transfer_from_sol_vault(
+            &ctx.accounts.sol_vault,
+            ctx.accounts.claimant.to_account_info(),
+            &ctx.accounts.system_program,
+            airdrop_state.key(),
+            ctx.bumps.sol_vault,
+            amount,
+        )?;
+        airdrop_state.amount_claimed = airdrop_state
+            .amount_claimed
+            .checked_add(amount)
+            .ok_or(WhitelistError::OverFlow)?;
+        airdrop_state.num_claims = airdrop_state
+            .num_claims
+            .checked_add(1)
+            .ok_or(WhitelistError::OverFlow)?;
+
+        set_return_data(&ClaimResult {
+            amount_transferred: amount,
+            claimant,
+            amount_claimed: airdrop_state.amount_claimed,
+            merkle_root: airdrop_state.merkle_root,
+            amount_locked: 0,
+        }.try_to_vec()?);
+        emit_cpi!(ClaimEvent {
+            claimer: claimant,
+            mint: NATIVE_SOL,
+            amount,
+            index,
+            total_claimed: airdrop_state.amount_claimed,
+            num_claims: airdrop_state.num_claims,
+            root_version: expected_root_version,
+            allocation_claimed: amount,
+            round: None,
+            amount_locked: 0,
+            bonus: 0,
+            fee: 0,
+            net_amount: amount,
+            token_fee_mint: None,
+            token_fee: 0,
+            referrer: None,
+        });
+
+        Ok(())
+    }
+
+    /// Claims from a `LEAF_VERSION_SPLIT` airdrop in `CLAIM_MODE_VESTING`, whose leaves are
+    /// `claimant ‖ unlocked_amount ‖ locked_amount`. The unlocked amount is paid right away and
+    /// the locked amount escrowed to vest, both in the one instruction.
+    pub fn claim_split_leaf<'info>(
+        ctx: Context<'_, '_, '_, 'info, Claim<'info>>,
+        unlocked_amount: u64,
+        hashes: Vec<u8>,
+        index: u64,
+        expected_root_version: u64,
+        locked_amount: u64,
+    ) -> Result<()> {
+        claim_prologue(&ctx.accounts.airdrop_state, ctx.accounts.claim_gates(), Some((unlocked_amount, index)))?;
+        record_receipt(ctx.accounts, ctx.bumps.claim_receipt, index, unlocked_amount.checked_add(locked_amount).ok_or(WhitelistError::OverFlow)?)?;
+
+        require!(
+            ctx.accounts.airdrop_state.leaf_version == LEAF_VERSION_SPLIT,
+            WhitelistError::UnsupportedLeafVersion
+        );
+        require!(
+            ctx.accounts.airdrop_state.claim_mode == CLAIM_MODE_VESTING,
+            WhitelistError::ClaimModeMismatch
+        );
+        let allocation = unlocked_amount
+            .checked_add(locked_amount)
+            .ok_or(WhitelistError::OverFlow)?;
+        require!(allocation > 0, WhitelistError::InvalidAmount);
+        ctx.accounts.airdrop_state.check_claim_amount(allocation)?;
+
+        let claimant = ctx.accounts.claimant.key();
+        let leaf_data = split_leaf(&claimant, unlocked_amount, locked_amount);
+        let merkle_root = claims_root(&ctx.accounts.airdrop_state, expected_root_version)?;
+        verify_claim(&ctx.accounts.airdrop_state.tree_layout(), &merkle_root, &leaf_data, index, &hashes)?;
+        ctx.accounts.claim_bitmap.load_mut()?.set_claimed(index)?;
+
+        // Paid first, so the escrow is reserved on top of what just left the vault
+        let payout = if unlocked_amount > 0 {
+            pay_out(&mut ctx.accounts.claim_accounts(ctx.remaining_accounts).payout(), unlocked_amount, 1)?
+        } else {
+            let airdrop_state = &mut ctx.accounts.airdrop_state;
+            airdrop_state.num_claims = airdrop_state
+                .num_claims
+                .checked_add(1)
+                .ok_or(WhitelistError::OverFlow)?;
+            0
+        };
+        let vesting = if locked_amount > 0 {
+            Some(open_vesting(ctx.accounts, ctx.bumps.vesting_account, claimant, index, locked_amount)?)
+        } else {
+            None
+        };
+        call_claim_hook(
+            &ctx.accounts.airdrop_state,
+            ctx.accounts.hook_program.as_ref(),
+            ctx.accounts.hook_authority.as_ref(),
+            &ctx.accounts.claimant,
+            allocation,
+            index,
+            ctx.remaining_accounts,
+        )?;
+        let airdrop_state = &ctx.accounts.airdrop_state;
+
+        set_return_data(&ClaimResult {
+            amount_transferred: payout,
+            claimant,
+            amount_claimed: airdrop_state.amount_claimed,
+            merkle_root: airdrop_state.merkle_root,
+            amount_locked: locked_amount,
+        }.try_to_vec()?);
+        emit_cpi!(ClaimEvent {
+            claimer: claimant,
+            mint: airdrop_state.mint,
+            amount: unlocked_amount,
+            index,
+            total_claimed: airdrop_state.amount_claimed,
+            num_claims: airdrop_state.num_claims,
+            root_version: expected_root_version,
+            allocation_claimed: allocation,
+            round: None,
+            amount_locked: locked_amount,
+            bonus: 0,
+            fee: airdrop_state.claim_fee(unlocked_amount)?,
+            net_amount: airdrop_state.net_of_fee(unlocked_amount)?,
+            token_fee_mint: airdrop_state.fee_mint,
+            token_fee: airdrop_state.token_fee(),
+            referrer: None,
+        });
+        if let Some((vesting_account, start_time)) = vesting {
+            emit_cpi!(VestingCreated {
+                airdrop_state: ctx.accounts.airdrop_state.key(),
+                vesting_account,
+                beneficiary: claimant,
+                index,
+                total: locked_amount,
+                start_time,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Pays the beneficiary of `vesting_account` what vested since the last release. Anyone can
+    /// crank it, the tokens only ever go to the associated token account of the beneficiary.
+    pub fn release<'info>(
+        ctx: Context<'_, '_, '_, 'info, Release<'info>>,
+    ) -> Result<()> {
+        require!(!ctx.accounts.airdrop_state.paused, WhitelistError::ClaimsPaused);
+
+        let vesting_account = &ctx.accounts.vesting_account;
+        let vested = ctx
+            .accounts
+            .vesting_config
+            .vested_amount(vesting_account, Clock::get()?.unix_timestamp)?;
+        let amount = vested
+            .checked_sub(vesting_account.released)
+            .ok_or(WhitelistError::OverFlow)?;
+
+        // Cranks can run on any schedule, one with nothing newly vested is a no-op
+        if amount == 0 {
+            msg!("Nothing vested since the last release");
+            return Ok(());
+        }
+
+        let payout = pay_out(&mut ctx.accounts.payout_accounts(ctx.remaining_accounts), amount, 0)?;
+
+        let vesting_config = &mut ctx.accounts.vesting_config;
+        vesting_config.amount_vesting = vesting_config
+            .amount_vesting
+            .checked_sub(amount)
+            .ok_or(WhitelistError::OverFlow)?;
+        let vesting_account = &mut ctx.accounts.vesting_account;
+        vesting_account.released = vesting_account
+            .released
+            .checked_add(amount)
+            .ok_or(WhitelistError::OverFlow)?;
+
+        emit_cpi!(VestingReleased {
+            vesting_account: vesting_account.key(),
+            beneficiary: vesting_account.beneficiary,
+            amount: payout,
+            released: vesting_account.released,
+            total: vesting_account.total,
+        });
+
+        Ok(())
+    }
+    
+}
+
+/// Accounts a claim is checked against and paid out through, whichever instruction it came in.
+struct ClaimAccounts<'a, 'info> {
+    airdrop_state: &'a mut Account<'info, AirdropState>,
+    mint: &'a InterfaceAccount<'info, Mint>,
+    vault: &'a InterfaceAccount<'info, TokenAccount>,
+    destination: &'a InterfaceAccount<'info, TokenAccount>,
+    fee_destination: Option<&'a InterfaceAccount<'info, TokenAccount>>,
+    claim_bitmap: &'a AccountLoader<'info, ClaimBitmap>,
+    /// Upper levels of the tree, only needed to claim with a proof that stops at them.
+    canopy: Option<&'a Account<'info, Canopy>>,
+    token_program: &'a Interface<'info, TokenInterface>,
+    remaining_accounts: &'a [AccountInfo<'info>],
+}
+
+impl<'info> ClaimAccounts<'_, 'info> {
+    fn payout(&mut self) -> PayoutAccounts<'_, 'info> {
+        PayoutAccounts {
+            airdrop_state: self.airdrop_state,
+            mint: self.mint,
+            vault: self.vault,
+            destination: self.destination,
+            fee_destination: self.fee_destination,
+            token_program: self.token_program,
+            remaining_accounts: self.remaining_accounts,
+        }
+    }
+}
+
+/// Accounts a payout goes through, tokens leaving the vault or minted into the destination.
+struct PayoutAccounts<'a, 'info> {
+    airdrop_state: &'a mut Account<'info, AirdropState>,
+    mint: &'a InterfaceAccount<'info, Mint>,
+    vault: &'a InterfaceAccount<'info, TokenAccount>,
+    destination: &'a InterfaceAccount<'info, TokenAccount>,
+    /// Token account of the fee recipient, only needed while the airdrop charges a fee.
+    fee_destination: Option<&'a InterfaceAccount<'info, TokenAccount>>,
+    token_program: &'a Interface<'info, TokenInterface>,
+    remaining_accounts: &'a [AccountInfo<'info>],
+}
+
+/// One leaf being claimed, its bytes before any leaf hashing and its proof.
+struct ClaimedLeaf<'a> {
+    amount: u64,
+    index: u64,
+    leaf_data: Vec<u8>,
+    hashes: &'a [u8],
+}
+
+/// Checks the claims of `leaves` by `claimant` and pays out their sum in a single payout,
+/// failing as a whole if any of them does not hold. Returns one event per leaf for the calling
+/// instruction to emit.
+fn process_claim(
+    mut accounts: ClaimAccounts<'_, '_>,
+    claimant: Pubkey,
+    leaves: &[ClaimedLeaf],
+    expected_root_version: u64,
+) -> Result<Vec<ClaimEvent>> {
+    require!(
+        accounts.airdrop_state.claim_mode == CLAIM_MODE_EXACT,
+        WhitelistError::ClaimModeMismatch
+    );
+    require!(leaves.iter().all(|leaf| leaf.amount > 0), WhitelistError::InvalidAmount);
+    let merkle_root = claims_root(accounts.airdrop_state, expected_root_version)?;
+
+    // Step 1: Verify that the leaf of each Claimant and Amount is proven against the root it was
+    // generated for, and flip the bit of its index in the bitmap shard
+    let layout = accounts.airdrop_state.tree_layout();
+    let mut amount: u64 = 0;
+    {
+        let mut claim_bitmap = accounts.claim_bitmap.load_mut()?;
+        for leaf in leaves {
+            accounts.airdrop_state.check_claim_amount(leaf.amount)?;
+            // Proofs stopping at the canopy complete against it, full proofs against the root
+            match accounts.canopy.filter(|canopy| canopy.covers(&merkle_root, leaf.hashes)) {
+                Some(canopy) => verify_below_canopy(
+                    &layout,
+                    canopy.height,
+                    &canopy.nodes,
+                    &leaf.leaf_data,
+                    leaf.index,
+                    leaf.hashes,
+                )?,
+                None => verify_claim(&layout, &merkle_root, &leaf.leaf_data, leaf.index, leaf.hashes)?,
+            }
+            claim_bitmap.set_claimed(leaf.index)?;
+            amount = amount.checked_add(leaf.amount).ok_or(WhitelistError::OverFlow)?;
+        }
+    }
+
+    // Step 2: Execute the payout and record it
+    let payout = pay_out(&mut accounts.payout(), amount, leaves.len() as u64)?;
+    let airdrop_state = &accounts.airdrop_state;
+
+    // CPI callers read the outcome back with `get_return_data`
+    set_return_data(&ClaimResult {
+        amount_transferred: payout,
+        claimant,
+        amount_claimed: airdrop_state.amount_claimed,
+        merkle_root: airdrop_state.merkle_root,
+        amount_locked: 0,
+    }.try_to_vec()?);
+
+    leaves
+        .iter()
+        .map(|leaf| Ok(ClaimEvent {
+            claimer: claimant,
+            mint: airdrop_state.mint,
+            amount: leaf.amount,
+            index: leaf.index,
+            total_claimed: airdrop_state.amount_claimed,
+            num_claims: airdrop_state.num_claims,
+            root_version: expected_root_version,
+            allocation_claimed: leaf.amount,
+            round: None,
+            amount_locked: 0,
+            bonus: 0,
+            fee: airdrop_state.claim_fee(leaf.amount)?,
+            net_amount: airdrop_state.net_of_fee(leaf.amount)?,
+            token_fee_mint: airdrop_state.fee_mint,
+            token_fee: airdrop_state.token_fee(),
+            referrer: None,
+        }))
+        .collect()
+}
+
+/// Root proofs made for `expected_root_version` verify against at the current slot.
+fn claims_root(airdrop_state: &AirdropState, expected_root_version: u64) -> Result<[u8; 32]> {
+    airdrop_state.root_for_version(expected_root_version, Clock::get()?.slot)
+}
+
+/// Fails unless the airdrop takes claims at `now`, unpaused and within its window.
+fn require_claims_open(airdrop_state: &AirdropState, now: i64) -> Result<()> {
+    require!(!airdrop_state.paused, WhitelistError::ClaimsPaused);
+    require!(
+        now >= airdrop_state.start_time,
+        WhitelistError::AirdropNotStarted
+    );
+    require!(
+        airdrop_state.end_time == 0 || now <= airdrop_state.end_time,
+        WhitelistError::AirdropEnded
+    );
+
+    Ok(())
+}
+
+/// Accounts of the gates a claim passes, each only needed while the airdrop has the gate. A
+/// claim instruction without the accounts of a gate leaves them `None` and fails on airdrops
+/// with it, instead of letting its claims around the gate.
+#[derive(Default)]
+struct ClaimGates<'a, 'info> {
+    /// Signer of the claim fees, `None` on paths where only the claimant signs.
+    payer: Option<&'a Signer<'info>>,
+    /// The claimant, `None` for claims pushed to a recipient who does not sign.
+    claimant: Option<&'a Signer<'info>>,
+    fee_treasury: Option<&'a SystemAccount<'info>>,
+    token_fee: Option<TokenFeeAccounts<'a, 'info>>,
+    tickets: Option<TicketAccounts<'a, 'info>>,
+    gate_nft_account: Option<&'a InterfaceAccount<'info, TokenAccount>>,
+    gate_nft_metadata: Option<&'a Account<'info, MetadataAccount>>,
+    cosigner: Option<&'a Signer<'info>>,
+    instructions_sysvar: Option<&'a UncheckedAccount<'info>>,
+    revoked_leaf: Option<&'a UncheckedAccount<'info>>,
+    hook_program: Option<&'a UncheckedAccount<'info>>,
+    system_program: Option<&'a Program<'info, System>>,
+}
+
+/// Prologue every claim instruction runs before any leaf is verified: the airdrop takes claims,
+/// the leaf is not revoked, the claim fees are charged, the tickets burned, and the claimant
+/// holds the gate collection and has the claim cosigned. `leaf` is the amount and index of the
+/// one leaf claimed, `None` for batches, which check the revocation of their leaves themselves
+/// and cannot be cosigned leaf by leaf.
+fn claim_prologue(
+    airdrop_state: &Account<AirdropState>,
+    gates: ClaimGates,
+    leaf: Option<(u64, u64)>,
+) -> Result<()> {
+    require_claims_open(airdrop_state, Clock::get()?.unix_timestamp)?;
+    if leaf.is_some() {
+        require_not_revoked(airdrop_state, gates.revoked_leaf)?;
+    }
+    // The hook itself runs once the claim is paid, only whether it is there is checked here
+    require!(
+        airdrop_state.hook_program.is_none() || gates.hook_program.is_some(),
+        WhitelistError::MissingClaimHook
+    );
+
+    match (gates.payer, gates.system_program) {
+        (Some(payer), Some(system_program)) => charge_claim_fee(
+            airdrop_state,
+            payer,
+            gates.fee_treasury,
+            gates.token_fee,
+            system_program,
+        )?,
+        _ => {
+            require!(airdrop_state.claim_fee_lamports == 0, WhitelistError::MissingFeeTreasury);
+            require!(airdrop_state.fee_mint.is_none(), WhitelistError::MissingFeeMintAccounts);
+        }
+    }
+
+    let Some(claimant) = gates.claimant else {
+        // Nobody signs for the recipient of a pushed claim to pass the gates on the claimant
+        require!(airdrop_state.ticket_mint.is_none(), WhitelistError::MissingTicketAccount);
+        require!(airdrop_state.gate_collection.is_none(), WhitelistError::MissingGateNft);
+        require!(airdrop_state.cosigner.is_none(), WhitelistError::MissingCosigner);
+        return Ok(());
+    };
+    burn_tickets(airdrop_state, claimant, gates.tickets)?;
+    require_collection_holder(airdrop_state, claimant, gates.gate_nft_account, gates.gate_nft_metadata)?;
+    match leaf {
+        Some((amount, index)) => require_cosigner(
+            airdrop_state,
+            gates.cosigner,
+            gates.instructions_sysvar,
+            claimant,
+            amount,
+            index,
+        ),
+        None => {
+            require!(airdrop_state.cosigner.is_none(), WhitelistError::MissingCosigner);
+            Ok(())
+        }
+    }
+}
+
+/// Accounts a claim fee in the fee mint of an airdrop is paid through.
+struct TokenFeeAccounts<'a, 'info> {
+    fee_mint: &'a InterfaceAccount<'info, Mint>,
+    payer_fee_ata: &'a InterfaceAccount<'info, TokenAccount>,
+    treasury_fee_ata: &'a InterfaceAccount<'info, TokenAccount>,
+    fee_token_program: &'a Interface<'info, TokenInterface>,
+}
+
+/// Charges the payer of a claim the claim fees of the airdrop, the flat lamport fee into its
+/// treasury and the fee in its fee mint into the treasury's ATA, nothing for airdrops without.
+fn charge_claim_fee<'info>(
+    airdrop_state: &AirdropState,
+    payer: &Signer<'info>,
+    fee_treasury: Option<&SystemAccount<'info>>,
+    token_fee: Option<TokenFeeAccounts<'_, 'info>>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    let fee = airdrop_state.claim_fee_lamports;
+    if fee > 0 {
+        let fee_treasury = fee_treasury.ok_or(WhitelistError::MissingFeeTreasury)?;
+        require!(payer.lamports() >= fee, WhitelistError::InsufficientFeeBalance);
+
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: payer.to_account_info(),
+                    to: fee_treasury.to_account_info(),
+                },
+            ),
+            fee,
+        )?;
+    }
+
+    let Some(fee_mint) = airdrop_state.fee_mint else {
+        return Ok(());
+    };
+    let accounts = token_fee.ok_or(WhitelistError::MissingFeeMintAccounts)?;
+    // Token accounts of any other mint or owner would let the payer settle in something else
+    let token_program = accounts.fee_token_program.key();
+    require!(
+        accounts.fee_mint.key() == fee_mint
+            && accounts.payer_fee_ata.key() == get_associated_token_address_with_program_id(&payer.key(), &fee_mint, &token_program)
+            && accounts.treasury_fee_ata.key() == get_associated_token_address_with_program_id(&airdrop_state.fee_treasury, &fee_mint, &token_program),
+        WhitelistError::FeeAccountMismatch
+    );
+    require!(
+        accounts.payer_fee_ata.amount >= airdrop_state.fee_amount,
+        WhitelistError::InsufficientFeeBalance
+    );
+
+    transfer_checked_with_hook(
+        accounts.fee_token_program,
+        accounts.payer_fee_ata.to_account_info(),
+        accounts.fee_mint,
+        accounts.treasury_fee_ata.to_account_info(),
+        payer.to_account_info(),
+        &[],
+        airdrop_state.fee_amount,
+        &[],
+    )
+}
+
+/// Accounts the tickets a claim costs are burned through.
+struct TicketAccounts<'a, 'info> {
+    ticket_mint: &'a InterfaceAccount<'info, Mint>,
+    ticket_ata: &'a InterfaceAccount<'info, TokenAccount>,
+    ticket_token_program: &'a Interface<'info, TokenInterface>,
+}
+
+/// Burns the tickets a claim costs from the claimant, nothing for airdrops without a ticket
+/// gate. Runs in the claim instruction itself, a claim failing later leaves the tickets unburned.
+fn burn_tickets<'info>(
+    airdrop_state: &AirdropState,
+    claimant: &Signer<'info>,
+    tickets: Option<TicketAccounts<'_, 'info>>,
+) -> Result<()> {
+    let Some(ticket_mint) = airdrop_state.ticket_mint else {
+        return Ok(());
+    };
+    let accounts = tickets.ok_or(WhitelistError::MissingTicketAccount)?;
+    require!(
+        accounts.ticket_mint.key() == ticket_mint
+            && accounts.ticket_ata.mint == ticket_mint
+            && accounts.ticket_ata.owner == claimant.key(),
+        WhitelistError::TicketAccountMismatch
+    );
+    require!(
+        accounts.ticket_ata.amount >= airdrop_state.ticket_cost,
+        WhitelistError::InsufficientTickets
+    );
+
+    burn(
+        CpiContext::new(
+            accounts.ticket_token_program.to_account_info(),
+            Burn {
+                mint: accounts.ticket_mint.to_account_info(),
+                from: accounts.ticket_ata.to_account_info(),
+                authority: claimant.to_account_info(),
+            },
+        ),
+        airdrop_state.ticket_cost,
+    )
+}
+
+/// Fails unless the claimant holds an NFT of the gate collection of the airdrop, nothing for
+/// airdrops without one. Only holding is checked, a frozen account such as a pNFT's passes.
+fn require_collection_holder(
+    airdrop_state: &AirdropState,
+    claimant: &Signer,
+    nft_account: Option<&InterfaceAccount<TokenAccount>>,
+    nft_metadata: Option<&Account<MetadataAccount>>,
+) -> Result<()> {
+    let Some(gate_collection) = airdrop_state.gate_collection else {
+        return Ok(());
+    };
+    let (Some(nft_account), Some(nft_metadata)) = (nft_account, nft_metadata) else {
+        return err!(WhitelistError::MissingGateNft);
+    };
+
+    require!(
+        nft_account.owner == claimant.key() && nft_account.amount == 1,
+        WhitelistError::GateNftNotHeld
+    );
+    // The metadata account is owned by the metadata program, its mint field ties it to the NFT
+    require_keys_eq!(nft_metadata.mint, nft_account.mint, WhitelistError::GateNftNotHeld);
+    require!(
+        nft_metadata
+            .collection
+            .as_ref()
+            .is_some_and(|collection| collection.verified && collection.key == gate_collection),
+        WhitelistError::GateCollectionMismatch
+    );
+
+    Ok(())
+}
+
+/// Anchor discriminator of an `on_claim` instruction, the first 8 bytes of
+/// `sha256("global:on_claim")`.
+pub const CLAIM_HOOK_DISCRIMINATOR: [u8; 8] = [122, 131, 34, 165, 135, 239, 77, 108];
+
+/// Notifies the hook program of the airdrop of a paid claim, nothing for airdrops without one.
+///
+/// The hook is called with the data `CLAIM_HOOK_DISCRIMINATOR ‖ claimant ‖ amount ‖ index ‖
+/// airdrop_state`, the integers little endian, and the accounts
+/// 0. `[]` the airdrop state
+/// 1. `[signer]` the hook authority of the airdrop, the PDA `["hook_authority", airdrop_state]`
+///    signing so the hook can tell claims from other callers. It holds nothing, unlike the
+///    airdrop state, whose signature would hand the hook the vault.
+/// 2. `[signer]` the claimant
+/// 3. every remaining account of the claim, as passed, past the revocation markers of a batch
+///
+/// Whatever the hook fails with fails the claim.
+fn call_claim_hook<'info>(
+    airdrop_state: &Account<'info, AirdropState>,
+    hook_program: Option<&UncheckedAccount<'info>>,
+    hook_authority: Option<&UncheckedAccount<'info>>,
+    claimant: &Signer<'info>,
+    amount: u64,
+    index: u64,
+    remaining_accounts: &[AccountInfo<'info>],
+) -> Result<()> {
+    let Some(expected) = airdrop_state.hook_program else {
+        return Ok(());
+    };
+    let hook_program = hook_program.ok_or(WhitelistError::MissingClaimHook)?;
+    require_keys_eq!(hook_program.key(), expected, WhitelistError::MissingClaimHook);
+    let airdrop_key = airdrop_state.key();
+    let (authority_key, bump) = Pubkey::find_program_address(&[b"hook_authority".as_ref(), airdrop_key.as_ref()], &crate::ID);
+    let hook_authority = hook_authority.ok_or(WhitelistError::MissingClaimHook)?;
+    require_keys_eq!(hook_authority.key(), authority_key, WhitelistError::MissingClaimHook);
+
+    let mut data = CLAIM_HOOK_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(claimant.key().as_ref());
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.extend_from_slice(&index.to_le_bytes());
+    data.extend_from_slice(airdrop_state.key().as_ref());
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(airdrop_key, false),
+        AccountMeta::new_readonly(authority_key, true),
+        AccountMeta::new_readonly(claimant.key(), true),
+    ];
+    accounts.extend(remaining_accounts.iter().map(|account| AccountMeta {
+        pubkey: account.key(),
+        is_signer: account.is_signer,
+        is_writable: account.is_writable,
+    }));
+    let mut account_infos = vec![
+        airdrop_state.to_account_info(),
+        hook_authority.to_account_info(),
+        claimant.to_account_info(),
+    ];
+    account_infos.extend_from_slice(remaining_accounts);
+    account_infos.push(hook_program.to_account_info());
+
+    let signer_seeds = &[b"hook_authority".as_ref(), airdrop_key.as_ref(), &[bump]];
+    invoke_signed(
+        &Instruction { program_id: expected, accounts, data },
+        &account_infos,
+        &[signer_seeds],
+    )
+    .map_err(Into::into)
+}
+
+/// Remaining accounts `claim_basket` takes for every mint of the basket.
+const BASKET_ACCOUNTS_PER_MINT: usize = 3;
+
+/// Pays `amounts` of a basket claim, one per mint of the `Basket` in its order, each out of
+/// the vault of its mint into the claimant's ATA, created if needed. Returns one event per
+/// mint paid for the calling instruction to emit.
+///
+/// `basket_accounts` holds, for every mint of the basket in order,
+/// 0. `[]` the mint
+/// 1. `[writable]` its vault, the ATA of the airdrop state
+/// 2. `[writable]` the claimant's ATA
+///
+/// The mints share the token program of the airdrop mint, their transfer hooks get no extra
+/// accounts.
+fn pay_basket<'info>(
+    accounts: &mut Claim<'info>,
+    basket_accounts: &[AccountInfo<'info>],
+    amounts: &[u64],
+    index: u64,
+) -> Result<Vec<BasketClaimed>> {
+    let airdrop_key = accounts.airdrop_state.key();
+    let claimant = accounts.claimant.key();
+    let mint_key = accounts.airdrop_state.mint.to_bytes();
+    let id_seed = AirdropState::id_seed(accounts.airdrop_state.airdrop_id);
+    let bump = [accounts.airdrop_state.bump];
+    let signer_seeds = &[b"merkle_tree".as_ref(), mint_key.as_ref(), id_seed.as_slice(), &bump];
+
+    let basket = accounts.basket.as_mut().ok_or(WhitelistError::MissingBasket)?;
+    require!(amounts.len() == basket.mints.len(), WhitelistError::BasketAccountMismatch);
+
+    let mut events = Vec::with_capacity(amounts.len());
+    for ((entry, amount), entry_accounts) in basket.mints
+        .iter_mut()
+        .zip(amounts)
+        .zip(basket_accounts.chunks_exact(BASKET_ACCOUNTS_PER_MINT))
+    {
+        // A recipient can be left out of some mints of the basket
+        if *amount == 0 {
+            continue;
+        }
+        let [mint_info, vault, destination] = entry_accounts else {
+            return err!(WhitelistError::BasketAccountMismatch);
+        };
+        let mint = InterfaceAccount::<Mint>::try_from(mint_info)?;
+        require!(
+            mint.key() == entry.mint
+                && vault.key() == get_associated_token_address_with_program_id(&airdrop_key, &entry.mint, &accounts.token_program.key()),
+            WhitelistError::BasketAccountMismatch
+        );
+        entry.amount_claimed = entry
+            .amount_claimed
+            .checked_add(*amount)
+            .ok_or(WhitelistError::OverFlow)?;
+        require!(entry.amount_claimed <= entry.airdrop_amount, WhitelistError::AllocationExceeded);
+
+        create_idempotent(CpiContext::new(
+            accounts.associated_token_program.to_account_info(),
+            Create {
+                payer: accounts.payer.to_account_info(),
+                associated_token: destination.clone(),
+                authority: accounts.claimant.to_account_info(),
+                mint: mint_info.clone(),
+                system_program: accounts.system_program.to_account_info(),
+                token_program: accounts.token_program.to_account_info(),
+            },
+        ))?;
+        transfer_checked_with_hook(
+            &accounts.token_program,
+            vault.clone(),
+            &mint,
+            destination.clone(),
+            accounts.airdrop_state.to_account_info(),
+            &[],
+            *amount,
+            &[signer_seeds],
+        )?;
+
+        events.push(BasketClaimed {
+            airdrop_state: airdrop_key,
+            claimer: claimant,
+            mint: entry.mint,
+            amount: *amount,
+            index,
+            total_claimed: entry.amount_claimed,
+        });
+    }
+
+    Ok(events)
+}
+
+/// Whether `mint` is the wrapped SOL mint of either token program.
+fn is_native_mint(mint: &Pubkey) -> bool {
+    *mint == spl_token::native_mint::ID || *mint == spl_token_2022::native_mint::ID
+}
+
+/// Closes the wrapped SOL ATA of the claimant into their system account when `unwrap` asks
+/// for it, so the claim reaches them as SOL. Closing unwraps everything the ATA holds and
+/// returns its rent, leaving the claimant without a wrapped SOL account.
+fn unwrap_payout(accounts: &Claim, unwrap: bool) -> Result<()> {
+    if !unwrap {
+        return Ok(());
+    }
+    require!(is_native_mint(&accounts.mint.key()), WhitelistError::NotNativeMint);
+    let destination = accounts.unwrap_destination
+        .as_ref()
+        .ok_or(WhitelistError::MissingUnwrapDestination)?;
+
+    close_account(CpiContext::new(
+        accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: accounts.signer_ata.to_account_info(),
+            destination: destination.to_account_info(),
+            authority: accounts.claimant.to_account_info(),
+        },
+    ))
+}
+
+/// Anchor discriminator of a `deposit` instruction, the first 8 bytes of
+/// `sha256("global:deposit")`.
+pub const STAKE_DEPOSIT_DISCRIMINATOR: [u8; 8] = [242, 35, 198, 137, 82, 225, 242, 182];
+
+/// Deposits `amount` the claim paid into `signer_ata` into the staking program of the airdrop,
+/// on behalf of the claimant.
+///
+/// The staking program is called with the data `STAKE_DEPOSIT_DISCRIMINATOR ‖ amount`, little
+/// endian, and the accounts
+/// 0. `[signer]` the claimant, depositing
+/// 1. `[writable]` the token account of the claimant the claim was paid into
+/// 2. `[]` the mint
+/// 3. `[]` the token program
+/// 4. every account of the `StakeConfig`, in order and writable as configured
+///
+/// Whatever the deposit fails with fails the claim.
+fn deposit_stake<'info>(
+    accounts: &Claim<'info>,
+    stake_accounts: &[AccountInfo<'info>],
+    amount: u64,
+) -> Result<()> {
+    let stake_config = accounts.stake_config.as_ref().ok_or(WhitelistError::MissingStakeConfig)?;
+    let stake_program = accounts.stake_program.as_ref().ok_or(WhitelistError::MissingStakeConfig)?;
+    require_keys_eq!(stake_program.key(), stake_config.stake_program, WhitelistError::StakeAccountMismatch);
+    require!(
+        stake_accounts.len() == stake_config.accounts.len()
+            && stake_accounts
+                .iter()
+                .zip(&stake_config.accounts)
+                .all(|(account, meta)| account.key() == meta.pubkey),
+        WhitelistError::StakeAccountMismatch
+    );
+
+    let mut data = STAKE_DEPOSIT_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&amount.to_le_bytes());
+
+    let mut metas = vec![
+        AccountMeta::new_readonly(accounts.claimant.key(), true),
+        AccountMeta::new(accounts.signer_ata.key(), false),
+        AccountMeta::new_readonly(accounts.mint.key(), false),
+        AccountMeta::new_readonly(accounts.token_program.key(), false),
+    ];
+    metas.extend(stake_config.accounts.iter().map(|meta| AccountMeta {
+        pubkey: meta.pubkey,
+        is_signer: false,
+        is_writable: meta.is_writable,
+    }));
+    let mut account_infos = vec![
+        accounts.claimant.to_account_info(),
+        accounts.signer_ata.to_account_info(),
+        accounts.mint.to_account_info(),
+        accounts.token_program.to_account_info(),
+    ];
+    account_infos.extend_from_slice(stake_accounts);
+    account_infos.push(stake_program.to_account_info());
+
+    invoke(
+        &Instruction { program_id: stake_config.stake_program, accounts: metas, data },
+        &account_infos,
+    )
+    .map_err(Into::into)
+}
+
+/// Counts a claim of `volume` into the stats of the referrer it credits, if any.
+fn record_referral(
+    referral_stats: Option<&mut Account<ReferralStats>>,
+    referrer: Option<Pubkey>,
+    claimant: Pubkey,
+    volume: u64,
+) -> Result<()> {
+    let Some(referrer) = referrer else {
+        return Ok(());
+    };
+    require_keys_neq!(referrer, claimant, WhitelistError::SelfReferral);
+    let referral_stats = referral_stats.ok_or(WhitelistError::MissingReferralStats)?;
+    require_keys_eq!(referral_stats.referrer, referrer, WhitelistError::MissingReferralStats);
+
+    referral_stats.claims = referral_stats
+        .claims
+        .checked_add(1)
+        .ok_or(WhitelistError::OverFlow)?;
+    referral_stats.volume = referral_stats
+        .volume
+        .checked_add(volume)
+        .ok_or(WhitelistError::OverFlow)?;
+
+    Ok(())
+}
+
+/// Fails if the leaf of the claim is revoked, its marker is only needed while any leaf is.
+fn require_not_revoked(airdrop_state: &AirdropState, revoked_leaf: Option<&UncheckedAccount>) -> Result<()> {
+    if airdrop_state.num_revoked == 0 {
+        return Ok(());
+    }
+    let revoked_leaf = revoked_leaf.ok_or(WhitelistError::MissingRevocationAccount)?;
+
+    require!(revoked_leaf.data_is_empty(), WhitelistError::AllocationRevoked);
+
+    Ok(())
+}
+
+/// Fails unless the cosigner of the airdrop signed the claim, nothing for airdrops without one.
+/// Instead of signing the transaction the cosigner can issue a permit, an ed25519 signature
+/// over `claimant ‖ amount ‖ index ‖ expiry ‖ airdrop_state` verified by the ed25519 program
+/// in the instruction right before the claim.
+fn require_cosigner(
+    airdrop_state: &Account<AirdropState>,
+    cosigner: Option<&Signer>,
+    instructions: Option<&UncheckedAccount>,
+    claimant: &Signer,
+    amount: u64,
+    index: u64,
+) -> Result<()> {
+    let Some(expected) = airdrop_state.cosigner else {
+        return Ok(());
+    };
+    if cosigner.is_some_and(|cosigner| cosigner.key() == expected) {
+        return Ok(());
+    }
+    let Some(instructions) = instructions else {
+        return err!(WhitelistError::MissingCosigner);
+    };
+
+    let message = permit_message(instructions, &expected)?;
+    let (expiry, airdrop) = message[PERMIT_EXPIRY_OFFSET..].split_at(8);
+    require!(
+        message[..32] == claimant.key().to_bytes()
+            && message[32..40] == amount.to_le_bytes()
+            && message[40..48] == index.to_le_bytes()
+            && airdrop == airdrop_state.key().to_bytes(),
+        WhitelistError::InvalidPermit
+    );
+    // Replaying a permit is no concern, the claim tracking refuses the leaf a second time
+    let expiry = i64::from_le_bytes(expiry.try_into().unwrap());
+    require!(Clock::get()?.unix_timestamp <= expiry, WhitelistError::PermitExpired);
+
+    Ok(())
+}
+
+/// Byte length of a claim permit and where its expiry starts, after claimant, amount and index.
+const PERMIT_LEN: usize = 32 + 8 + 8 + 8 + 32;
+const PERMIT_EXPIRY_OFFSET: usize = 48;
+
+/// Message of the ed25519 program instruction right before the current one, which has to
+/// verify exactly one signature by `signer`, with the key and message in its own data.
+fn permit_message(instructions: &UncheckedAccount, signer: &Pubkey) -> Result<Vec<u8>> {
+    let current = load_current_index_checked(instructions)?;
+    require!(current > 0, WhitelistError::InvalidPermit);
+    let verify = load_instruction_at_checked(usize::from(current - 1), instructions)?;
+    require_keys_eq!(verify.program_id, ed25519_program::ID, WhitelistError::InvalidPermit);
+
+    // One signature: count and padding, then seven u16 offsets of which instruction indices
+    // of u16::MAX point into this same instruction
+    let data = &verify.data;
+    require!(data.len() >= 16 && data[0] == 1, WhitelistError::InvalidPermit);
+    let offset = |at: usize| usize::from(u16::from_le_bytes([data[at], data[at + 1]]));
+    let in_place = [4, 8, 14].iter().all(|&at| offset(at) == usize::from(u16::MAX));
+    let (public_key, message, message_len) = (offset(6), offset(10), offset(12));
+    require!(
+        in_place
+            && message_len == PERMIT_LEN
+            && data.get(public_key..public_key + 32) == Some(signer.as_ref()),
+        WhitelistError::InvalidPermit
+    );
+
+    data.get(message..message + PERMIT_LEN)
+        .map(<[u8]>::to_vec)
+        .ok_or(error!(WhitelistError::InvalidPermit))
+}
+
+/// Transfers `amount` lamports out of the SOL vault of `airdrop_state`, which keeps its rent
+/// exemption: a vault short of lamports above it fails rather than paying into its rent.
+fn transfer_from_sol_vault<'info>(
+    sol_vault: &SystemAccount<'info>,
+    destination: AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    airdrop_state: Pubkey,
+    bump: u8,
+    amount: u64,
+) -> Result<()> {
+    let available = sol_vault
+        .lamports()
+        .saturating_sub(Rent::get()?.minimum_balance(0));
+    if amount > available {
+        msg!("Vault is short by {} lamports", amount - available);
+        return err!(WhitelistError::VaultInsufficientFunds);
+    }
+
+    let airdrop_key = airdrop_state.to_bytes();
+    let signer_seeds = &[b"sol_vault".as_ref(), airdrop_key.as_ref(), &[bump]];
+    anchor_lang::system_program::transfer(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: sol_vault.to_account_info(),
+                to: destination,
+            },
+            &[signer_seeds],
+        ),
+        amount,
+    )
+}
+
+/// Pays `amount` claimed over `num_claims` leaves into the destination, either minted on the
+/// spot or transferred from the vault, and records it. The fee of the airdrop comes out of
+/// `amount` into the fee destination. Returns what was minted or left the vault, fee included.
+fn pay_out(accounts: &mut PayoutAccounts<'_, '_>, amount: u64, num_claims: u64) -> Result<u64> {
+    let fee = accounts.airdrop_state.claim_fee(amount)?;
+    // Airdrops without a fee never need the fee recipient to hold a token account
+    let fee_destination = if fee > 0 {
+        Some(accounts.fee_destination.ok_or(WhitelistError::MissingFeeAccount)?)
+    } else {
+        None
+    };
+    let net_amount = amount - fee;
+    let (net_payout, fee_payout) = if accounts.airdrop_state.mint_on_claim {
+        (net_amount, fee)
+    } else {
+        let mint_info = accounts.mint.to_account_info();
+        let fee_payout = match fee_destination {
+            Some(_) => gross_transfer_amount(&mint_info, fee)?,
+            None => 0,
+        };
+        (gross_transfer_amount(&mint_info, net_amount)?, fee_payout)
+    };
+    let payout = net_payout
+        .checked_add(fee_payout)
+        .ok_or(WhitelistError::OverFlow)?;
+    let mint_key = accounts.mint.key().to_bytes();
+    let id_seed = AirdropState::id_seed(accounts.airdrop_state.airdrop_id);
+    let signer_seeds = &[
+        b"merkle_tree".as_ref(),
+        mint_key.as_ref(),
+        id_seed.as_slice(),
+        &[accounts.airdrop_state.bump],
+    ];
+    if accounts.airdrop_state.mint_on_claim {
+        require!(
+            accounts.airdrop_state
+                .amount_claimed
+                .checked_add(amount)
+                .ok_or(WhitelistError::OverFlow)?
+                <= accounts.airdrop_state.airdrop_amount,
+            WhitelistError::MintCapExceeded
+        );
+
+        let destinations = std::iter::once((accounts.destination, net_payout))
+            .chain(fee_destination.map(|fee_destination| (fee_destination, fee_payout)));
+        for (destination, amount) in destinations {
+            mint_to(
+                CpiContext::new_with_signer(
+                    accounts.token_program.to_account_info(),
+                    MintTo {
+                        mint: accounts.mint.to_account_info(),
+                        to: destination.to_account_info(),
+                        authority: accounts.airdrop_state.to_account_info(),
+                    },
+                    &[signer_seeds],
+                ),
+                amount,
+            )?;
+        }
+    } else {
+        // An over-allocated tree would otherwise run the vault dry into a token program error
+        require!(
+            accounts.airdrop_state
+                .amount_claimed
+                .checked_add(payout)
+                .ok_or(WhitelistError::OverFlow)?
+                <= accounts.airdrop_state.airdrop_amount,
+            WhitelistError::AllocationExceeded
+        );
+        // A vault drained by a withdrawal or burn would otherwise surface the token program's error
+        let vault_balance = accounts.vault.amount;
+        if payout > vault_balance {
+            msg!("Vault is short by {} tokens", payout - vault_balance);
+            return err!(WhitelistError::VaultInsufficientFunds);
+        }
+
+        let destinations = std::iter::once((accounts.destination, net_payout))
+            .chain(fee_destination.map(|fee_destination| (fee_destination, fee_payout)));
+        for (destination, amount) in destinations {
+            transfer_checked_with_hook(
+                accounts.token_program,
+                accounts.vault.to_account_info(),
+                accounts.mint,
+                destination.to_account_info(),
+                accounts.airdrop_state.to_account_info(),
+                accounts.remaining_accounts,
+                amount,
+                &[signer_seeds],
+            )?;
+        }
+    }
+
+    // Update the airdrop state with what actually left the vault
+    accounts.airdrop_state.amount_claimed = accounts.airdrop_state
+        .amount_claimed
+        .checked_add(payout)
+        .ok_or(WhitelistError::OverFlow)?;
+    accounts.airdrop_state.num_claims = accounts.airdrop_state
+        .num_claims
+        .checked_add(num_claims)
+        .ok_or(WhitelistError::OverFlow)?;
+
+    Ok(payout)
+}
+
+/// Transfers a bonus of `amount` from the vault into the destination. The bonus reserve bounds
+/// it rather than `airdrop_amount`, so it is left out of `amount_claimed`.
+fn pay_bonus(accounts: &mut PayoutAccounts<'_, '_>, amount: u64) -> Result<u64> {
+    let payout = gross_transfer_amount(&accounts.mint.to_account_info(), amount)?;
+    let mint_key = accounts.mint.key().to_bytes();
+    let id_seed = AirdropState::id_seed(accounts.airdrop_state.airdrop_id);
+    let signer_seeds = &[
+        b"merkle_tree".as_ref(),
+        mint_key.as_ref(),
+        id_seed.as_slice(),
+        &[accounts.airdrop_state.bump],
+    ];
+
+    transfer_checked_with_hook(
+        accounts.token_program,
+        accounts.vault.to_account_info(),
+        accounts.mint,
+        accounts.destination.to_account_info(),
+        accounts.airdrop_state.to_account_info(),
+        accounts.remaining_accounts,
+        payout,
+        &[signer_seeds],
+    )?;
+
+    Ok(payout)
+}
+
+/// Escrows `amount` of the leaf at `index` in the vesting account the claim opens, reserving
+/// it on top of what the airdrop paid so far. Returns the escrow and when it starts vesting.
+fn open_vesting(
+    accounts: &mut Claim<'_>,
+    vesting_bump: Option<u8>,
+    claimant: Pubkey,
+    index: u64,
+    amount: u64,
+) -> Result<(Pubkey, i64)> {
+    let vesting_config = accounts
+        .vesting_config
+        .as_mut()
+        .ok_or(WhitelistError::MissingVestingAccount)?;
+    let amount_vesting = vesting_config
+        .amount_vesting
+        .checked_add(amount)
+        .ok_or(WhitelistError::OverFlow)?;
+    require!(
+        amount_vesting
+            .checked_add(accounts.airdrop_state.amount_claimed)
+            .ok_or(WhitelistError::OverFlow)?
+            <= accounts.airdrop_state.airdrop_amount,
+        WhitelistError::AllocationExceeded
+    );
+    vesting_config.amount_vesting = amount_vesting;
+    let start_time = match vesting_config.vesting_start {
+        0 => Clock::get()?.unix_timestamp,
+        vesting_start => vesting_start,
+    };
+
+    let vesting_account = accounts
+        .vesting_account
+        .as_mut()
+        .ok_or(WhitelistError::MissingVestingAccount)?;
+    vesting_account.set_inner(VestingAccount {
+        airdrop_state: accounts.airdrop_state.key(),
+        beneficiary: claimant,
+        index,
+        total: amount,
+        released: 0,
+        start_time,
+        bump: vesting_bump.ok_or(WhitelistError::MissingVestingAccount)?,
+    });
+
+    Ok((vesting_account.key(), start_time))
+}
+
+/// Mints `amount` into the vault of a program-created mint, unless claims mint on the
+/// spot, then hands the mint authority over as requested.
+#[allow(clippy::too_many_arguments)]
+fn fund_new_mint<'info>(
+    token_program: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    vault: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    airdrop_state: Pubkey,
+    amount: u64,
+    authority_mode: MintAuthorityMode,
+    mint_on_claim: bool,
+) -> Result<()> {
+    // In mint-on-claim mode `amount` is only the cap, the vault stays empty
+    if !mint_on_claim {
+        mint_to(
+            CpiContext::new(
+                token_program.clone(), 
+                MintTo {
+                    mint: mint.clone(),
+                    to: vault,
+                    authority: authority.clone(),
+                }
+            ),
+            amount
+        )?;
+    }
+
+    if authority_mode != MintAuthorityMode::KeepWithCreator {
+        let new_authority = match authority_mode {
+            MintAuthorityMode::TransferToPda => Some(airdrop_state),
+            _ => None,
+        };
+
+        set_authority(
+            CpiContext::new(
+                token_program, 
+                SetAuthority {
+                    current_authority: authority,
+                    account_or_mint: mint,
+                }
+            ), 
+            AuthorityType::MintTokens,
+            new_authority
+        )?;
+    }
+
+    Ok(())
+}
+
+/// `transfer_checked` CPI that, for mints with a transfer hook, appends the extra accounts
+/// listed in the mint's `ExtraAccountMetaList` out of `remaining_accounts`. Mints without
+/// a hook ignore `remaining_accounts` entirely.
+#[allow(clippy::too_many_arguments)]
+fn transfer_checked_with_hook<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    from: AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    spl_token_2022::onchain::invoke_transfer_checked(
+        &token_program.key(),
+        from,
+        mint.to_account_info(),
+        to,
+        authority,
+        remaining_accounts,
+        amount,
+        mint.decimals,
+        signer_seeds,
+    )
+    .map_err(Into::into)
+}
+
+/// What the vault holds beyond the escrows of vesting claims and the locked parts of split
+/// leaves, which are released out of it after claims are over. Only this much can leave the
+/// vault other than to claimants.
+fn free_vault_balance(
+    airdrop_state: &AirdropState,
+    vault: &InterfaceAccount<TokenAccount>,
+    vesting_config: Option<&VestingConfig>,
+) -> Result<u64> {
+    if airdrop_state.claim_mode != CLAIM_MODE_VESTING {
+        return Ok(vault.amount);
+    }
+    let vesting_config = vesting_config.ok_or(WhitelistError::MissingVestingAccount)?;
+
+    Ok(vault.amount.saturating_sub(vesting_config.amount_vesting))
+}
+
+/// Whether transfers of `mint` call a transfer hook program, which needs extra accounts.
+fn has_transfer_hook(mint: &AccountInfo) -> Result<bool> {
+    if *mint.owner != spl_token_2022::ID {
+        return Ok(false);
+    }
+
+    let mint_data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    Ok(spl_token_2022::extension::transfer_hook::get_program_id(&mint_state).is_some())
+}
+
+/// Amount the vault has to send so the recipient nets exactly `amount` once the
+/// Token-2022 transfer fee of the mint, if any, has been withheld.
+fn gross_transfer_amount(mint: &AccountInfo, amount: u64) -> Result<u64> {
+    if *mint.owner != spl_token_2022::ID {
+        return Ok(amount);
+    }
+
+    let mint_data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    let fee_config = match mint_state.get_extension::<TransferFeeConfig>() {
+        Ok(fee_config) => fee_config,
+        Err(_) => return Ok(amount),
+    };
+
+    let epoch = Clock::get()?.epoch;
+    let gross = fee_config
+        .calculate_inverse_epoch_fee(epoch, amount)
+        .and_then(|fee| amount.checked_add(fee))
+        .ok_or(WhitelistError::TransferFeeUnreachable)?;
+
+    // Rounding and the maximum fee can make some net amounts unreachable, check the result
+    let fee = fee_config
+        .calculate_epoch_fee(epoch, gross)
+        .ok_or(WhitelistError::TransferFeeUnreachable)?;
+    require!(
+        gross.checked_sub(fee) == Some(amount),
+        WhitelistError::TransferFeeUnreachable
+    );
+
+    Ok(gross)
+}
+
+/// Event for a root change through any path, so the full root lineage can be rebuilt from events.
+fn root_updated_event(airdrop_state: &Account<AirdropState>, old_root: [u8; 32], clock: &Clock) -> RootUpdated {
+    RootUpdated {
+        airdrop_state: airdrop_state.key(),
+        authority: airdrop_state.update_authority,
+        old_root,
+        new_root: airdrop_state.merkle_root,
+        root_version: airdrop_state.root_version,
+        slot: clock.slot,
+        unix_timestamp: clock.unix_timestamp,
+    }
+}
+
+/// Writes the amount and time of the claim of `index` to its receipt, if the claim opens one.
+/// A cumulative leaf claimed again has its receipt overwritten with the latest claim.
+fn record_receipt(accounts: &mut Claim<'_>, receipt_bump: Option<u8>, index: u64, amount: u64) -> Result<()> {
+    let airdrop_state = accounts.airdrop_state.key();
+    let (Some(claim_receipt), Some(bump)) = (accounts.claim_receipt.as_mut(), receipt_bump) else {
+        return Ok(());
+    };
+    claim_receipt.set_inner(ClaimReceipt {
+        airdrop_state,
+        index,
+        amount,
+        claimed_at: Clock::get()?.unix_timestamp,
+        bump,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(params: AirdropParams, payout: PayoutParams, decimals: u8)]
+#[event_cpi]
+pub struct Initialize<'info> {
+    #[account(
+        init, 
+        seeds = [b"merkle_tree".as_ref(), mint.key().to_bytes().as_ref(), AirdropState::id_seed(params.airdrop_id).as_slice()],
+        bump,
+        payer = payer, 
+        space = 8 + AirdropState::INIT_SPACE
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    #[account(
+        init,
+        payer = payer,
+        mint::authority = authority,
+        mint::decimals = decimals,
+        mint::token_program = token_program,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = airdrop_state,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    /// Becomes the authority of the airdrop and signs as the authority of the new mint.
+    pub authority: Signer<'info>,
+    /// Pays the rent of every account created, the authority itself in the simple case.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: Metaplex metadata PDA of the mint, validated by the token metadata program
+    #[account(mut)]
+    pub metadata: Option<UncheckedAccount<'info>>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_metadata_program: Option<Program<'info, Metadata>>,
+    pub rent: Option<Sysvar<'info, Rent>>,
+}
+
+#[derive(Accounts)]
+#[instruction(params: AirdropParams, payout: PayoutParams, decimals: u8)]
+#[event_cpi]
+pub struct InitializeWithMetadata<'info> {
+    #[account(
+        init, 
+        seeds = [b"merkle_tree".as_ref(), mint.key().to_bytes().as_ref(), AirdropState::id_seed(params.airdrop_id).as_slice()],
+        bump,
+        payer = payer, 
+        space = 8 + AirdropState::INIT_SPACE
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    #[account(
+        init,
+        payer = payer,
+        mint::authority = authority,
+        mint::decimals = decimals,
+        mint::token_program = token_program,
+        extensions::metadata_pointer::authority = authority,
+        extensions::metadata_pointer::metadata_address = mint,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = airdrop_state,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    /// Becomes the authority of the airdrop and signs as the authority of the new mint.
+    pub authority: Signer<'info>,
+    /// Pays the rent of every account created, the authority itself in the simple case.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[derive(Accounts)]
+#[instruction(params: AirdropParams)]
+#[event_cpi]
+pub struct InitializeNonTransferable<'info> {
+    #[account(
+        init, 
+        seeds = [b"merkle_tree".as_ref(), mint.key().to_bytes().as_ref(), AirdropState::id_seed(params.airdrop_id).as_slice()],
+        bump,
+        payer = payer, 
+        space = 8 + AirdropState::INIT_SPACE
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    /// New mint, created and initialized with its extension by the instruction
+    #[account(mut)]
+    pub mint: Signer<'info>,
+    /// CHECK: Empty ATA of the airdrop state in the mint, created by the instruction once the
+    /// mint exists
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(&airdrop_state.key(), &mint.key(), &token_program.key())
+    )]
+    pub vault: UncheckedAccount<'info>,
+    /// Becomes the authority of the airdrop.
+    pub authority: Signer<'info>,
+    /// Pays the rent of every account created, the authority itself in the simple case.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[derive(Accounts)]
+#[instruction(params: AirdropParams)]
+#[event_cpi]
+pub struct InitializeSolAirdrop<'info> {
+    #[account(
+        init, 
+        seeds = [b"merkle_tree".as_ref(), NATIVE_SOL.to_bytes().as_ref(), AirdropState::id_seed(params.airdrop_id).as_slice()],
+        bump,
+        payer = payer, 
+        space = 8 + AirdropState::INIT_SPACE
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    /// System account of the airdrop holding the lamports it pays out.
+    #[account(
+        mut,
+        seeds = [b"sol_vault".as_ref(), airdrop_state.key().to_bytes().as_ref()],
+        bump
+    )]
+    pub sol_vault: SystemAccount<'info>,
+    /// Becomes the authority of the airdrop and funds its SOL vault.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// Pays the rent of every account created, the authority itself in the simple case.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(params: AirdropParams)]
+#[event_cpi]
+pub struct InitializeNftAirdrop<'info> {
+    #[account(
+        init, 
+        seeds = [b"merkle_tree".as_ref(), NFT_AIRDROP.to_bytes().as_ref(), AirdropState::id_seed(params.airdrop_id).as_slice()],
+        bump,
+        payer = payer, 
+        space = 8 + AirdropState::INIT_SPACE
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    /// Becomes the authority of the airdrop.
+    pub authority: Signer<'info>,
+    /// Pays the rent of every account created, the authority itself in the simple case.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DepositNft<'info> {
+    #[account(
+        seeds = [b"merkle_tree".as_ref(), NFT_AIRDROP.to_bytes().as_ref(), AirdropState::id_seed(airdrop_state.airdrop_id).as_slice()],
+        bump = airdrop_state.bump,
+        constraint = airdrop_state.version == STATE_VERSION @ WhitelistError::UnsupportedStateVersion
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    #[account(
+        mint::decimals = 0,
+        mint::token_program = token_program,
+        constraint = nft_mint.supply == 1 @ WhitelistError::NotAnNft
+    )]
+    pub nft_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = update_authority,
+        associated_token::mint = nft_mint,
+        associated_token::authority = airdrop_state,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = update_authority,
+        token::token_program = token_program,
+    )]
+    pub authority_ata: InterfaceAccount<'info, TokenAccount>,
+    /// Registry granting roles to keys other than the authorities, only needed to act with one.
+    #[account(constraint = admin_registry.airdrop_state == airdrop_state.key() @ WhitelistError::AdminRegistryMismatch)]
+    pub admin_registry: Option<Account<'info, AdminRegistry>>,
+    #[account(
+        mut,
+        constraint = airdrop_state.has_role(admin_registry.as_deref(), &update_authority.key(), ROLE_UPDATER) @ WhitelistError::MissingRole
+    )]
+    pub update_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawUnclaimedNft<'info> {
+    #[account(
+        mut,
+        seeds = [b"merkle_tree".as_ref(), NFT_AIRDROP.to_bytes().as_ref(), AirdropState::id_seed(airdrop_state.airdrop_id).as_slice()],
+        bump = airdrop_state.bump,
+        constraint = airdrop_state.version == STATE_VERSION @ WhitelistError::UnsupportedStateVersion
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    #[account(mint::token_program = token_program)]
+    pub nft_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = nft_mint,
+        associated_token::authority = airdrop_state,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = withdraw_authority,
+        token::token_program = token_program,
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+    /// Registry granting roles to keys other than the authorities, only needed to act with one.
+    #[account(constraint = admin_registry.airdrop_state == airdrop_state.key() @ WhitelistError::AdminRegistryMismatch)]
+    pub admin_registry: Option<Account<'info, AdminRegistry>>,
+    #[account(
+        constraint = airdrop_state.has_role(admin_registry.as_deref(), &withdraw_authority.key(), ROLE_WITHDRAWER) @ WhitelistError::MissingRole
+    )]
+    pub withdraw_authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(params: AirdropParams)]
+#[event_cpi]
+pub struct InitializeWithExistingMint<'info> {
+    #[account(
+        init, 
+        seeds = [b"merkle_tree".as_ref(), mint.key().to_bytes().as_ref(), AirdropState::id_seed(params.airdrop_id).as_slice()],
+        bump,
+        payer = payer, 
+        space = 8 + AirdropState::INIT_SPACE
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = airdrop_state,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    /// Token account the vault is funded from, not needed for wrapped SOL, which is funded in
+    /// lamports.
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = authority,
+        token::token_program = token_program,
+    )]
+    pub authority_ata: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// Becomes the authority of the airdrop and funds its vault.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// Pays the rent of every account created, the authority itself in the simple case.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+pub struct Update<'info> {
+    #[account(
+        mut, 
+        seeds = [b"merkle_tree".as_ref(), airdrop_state.mint.key().to_bytes().as_ref(), AirdropState::id_seed(airdrop_state.airdrop_id).as_slice()],
+        bump = airdrop_state.bump,
+        constraint = airdrop_state.version == STATE_VERSION @ WhitelistError::UnsupportedStateVersion
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    /// Registry granting roles to keys other than the authorities, only needed to act with one.
+    #[account(constraint = admin_registry.airdrop_state == airdrop_state.key() @ WhitelistError::AdminRegistryMismatch)]
+    pub admin_registry: Option<Account<'info, AdminRegistry>>,
+    #[account(
+        constraint = airdrop_state.has_role(admin_registry.as_deref(), &update_authority.key(), ROLE_UPDATER) @ WhitelistError::MissingRole
+    )]
+    pub update_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+pub struct ManageFees<'info> {
+    #[account(
+        mut, 
+        seeds = [b"merkle_tree".as_ref(), airdrop_state.mint.key().to_bytes().as_ref(), AirdropState::id_seed(airdrop_state.airdrop_id).as_slice()],
+        bump = airdrop_state.bump,
+        constraint = airdrop_state.version == STATE_VERSION @ WhitelistError::UnsupportedStateVersion
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    /// Registry granting roles to keys other than the authorities, only needed to act with one.
+    #[account(constraint = admin_registry.airdrop_state == airdrop_state.key() @ WhitelistError::AdminRegistryMismatch)]
+    pub admin_registry: Option<Account<'info, AdminRegistry>>,
+    #[account(
+        constraint = airdrop_state.has_role(admin_registry.as_deref(), &update_authority.key(), ROLE_FEE_MANAGER) @ WhitelistError::MissingRole
+    )]
+    pub update_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateCanopy<'info> {
+    #[account(
+        seeds = [b"merkle_tree".as_ref(), airdrop_state.mint.key().to_bytes().as_ref(), AirdropState::id_seed(airdrop_state.airdrop_id).as_slice()],
+        bump = airdrop_state.bump,
+        constraint = airdrop_state.version == STATE_VERSION @ WhitelistError::UnsupportedStateVersion
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    /// Recreated for every root, the canopy of an older root no longer applies
+    #[account(
+        init_if_needed,
+        seeds = [b"canopy".as_ref(), airdrop_state.key().to_bytes().as_ref()],
+        bump,
+        payer = update_authority,
+        space = 8 + Canopy::INIT_SPACE
+    )]
+    pub canopy: Account<'info, Canopy>,
+    /// Registry granting roles to keys other than the authorities, only needed to act with one.
+    #[account(constraint = admin_registry.airdrop_state == airdrop_state.key() @ WhitelistError::AdminRegistryMismatch)]
+    pub admin_registry: Option<Account<'info, AdminRegistry>>,
+    #[account(
+        mut,
+        constraint = airdrop_state.has_role(admin_registry.as_deref(), &update_authority.key(), ROLE_UPDATER) @ WhitelistError::MissingRole
+    )]
+    pub update_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ModifyCanopy<'info> {
+    #[account(
+        seeds = [b"merkle_tree".as_ref(), airdrop_state.mint.key().to_bytes().as_ref(), AirdropState::id_seed(airdrop_state.airdrop_id).as_slice()],
+        bump = airdrop_state.bump,
+        constraint = airdrop_state.version == STATE_VERSION @ WhitelistError::UnsupportedStateVersion
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    #[account(
+        mut,
+        seeds = [b"canopy".as_ref(), airdrop_state.key().to_bytes().as_ref()],
+        bump = canopy.bump
+    )]
+    pub canopy: Account<'info, Canopy>,
+    /// Registry granting roles to keys other than the authorities, only needed to act with one.
+    #[account(constraint = admin_registry.airdrop_state == airdrop_state.key() @ WhitelistError::AdminRegistryMismatch)]
+    pub admin_registry: Option<Account<'info, AdminRegistry>>,
+    #[account(
+        constraint = airdrop_state.has_role(admin_registry.as_deref(), &update_authority.key(), ROLE_UPDATER) @ WhitelistError::MissingRole
+    )]
+    pub update_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct EnableConcurrentTree<'info> {
+    #[account(
+        mut,
+        seeds = [b"merkle_tree".as_ref(), airdrop_state.mint.key().to_bytes().as_ref(), AirdropState::id_seed(airdrop_state.airdrop_id).as_slice()],
+        bump = airdrop_state.bump,
+        constraint = airdrop_state.version == STATE_VERSION @ WhitelistError::UnsupportedStateVersion
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    /// CHECK: Allocated to the compression program and initialized by it
+    #[account(mut, owner = SPL_ACCOUNT_COMPRESSION_ID)]
+    pub merkle_tree: UncheckedAccount<'info>,
+    /// Registry granting roles to keys other than the authorities, only needed to act with one.
+    #[account(constraint = admin_registry.airdrop_state == airdrop_state.key() @ WhitelistError::AdminRegistryMismatch)]
+    pub admin_registry: Option<Account<'info, AdminRegistry>>,
+    #[account(
+        constraint = airdrop_state.has_role(admin_registry.as_deref(), &update_authority.key(), ROLE_UPDATER) @ WhitelistError::MissingRole
+    )]
+    pub update_authority: Signer<'info>,
+    /// CHECK: spl-account-compression
+    #[account(address = SPL_ACCOUNT_COMPRESSION_ID)]
+    pub compression_program: UncheckedAccount<'info>,
+    /// CHECK: spl-noop
+    #[account(address = SPL_NOOP_ID)]
+    pub noop_program: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ModifyConcurrentTree<'info> {
+    #[account(
+        mut,
+        seeds = [b"merkle_tree".as_ref(), airdrop_state.mint.key().to_bytes().as_ref(), AirdropState::id_seed(airdrop_state.airdrop_id).as_slice()],
+        bump = airdrop_state.bump,
+        constraint = airdrop_state.version == STATE_VERSION @ WhitelistError::UnsupportedStateVersion,
+        constraint = airdrop_state.tree_mode == TREE_MODE_CONCURRENT @ WhitelistError::TreeModeMismatch
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    /// CHECK: Concurrent tree of the airdrop, checked by the compression program
+    #[account(mut, address = airdrop_state.concurrent_tree @ WhitelistError::TreeModeMismatch)]
+    pub merkle_tree: UncheckedAccount<'info>,
+    /// Registry granting roles to keys other than the authorities, only needed to act with one.
+    #[account(constraint = admin_registry.airdrop_state == airdrop_state.key() @ WhitelistError::AdminRegistryMismatch)]
+    pub admin_registry: Option<Account<'info, AdminRegistry>>,
+    #[account(
+        constraint = airdrop_state.has_role(admin_registry.as_deref(), &update_authority.key(), ROLE_UPDATER) @ WhitelistError::MissingRole
+    )]
+    pub update_authority: Signer<'info>,
+    /// CHECK: spl-account-compression
+    #[account(address = SPL_ACCOUNT_COMPRESSION_ID)]
+    pub compression_program: UncheckedAccount<'info>,
+    /// CHECK: spl-noop
+    #[account(address = SPL_NOOP_ID)]
+    pub noop_program: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(index: u64)]
+#[event_cpi]
+pub struct RevokeLeaf<'info> {
+    #[account(
+        mut,
+        seeds = [b"merkle_tree".as_ref(), airdrop_state.mint.key().to_bytes().as_ref(), AirdropState::id_seed(airdrop_state.airdrop_id).as_slice()],
+        bump = airdrop_state.bump,
+        constraint = airdrop_state.version == STATE_VERSION @ WhitelistError::UnsupportedStateVersion
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    #[account(
+        init,
+        seeds = [b"revoked".as_ref(), airdrop_state.key().to_bytes().as_ref(), index.to_le_bytes().as_ref()],
+        bump,
+        payer = update_authority,
+        space = 8 + RevokedLeaf::INIT_SPACE
+    )]
+    pub revoked_leaf: Account<'info, RevokedLeaf>,
+    /// Registry granting roles to keys other than the authorities, only needed to act with one.
+    #[account(constraint = admin_registry.airdrop_state == airdrop_state.key() @ WhitelistError::AdminRegistryMismatch)]
+    pub admin_registry: Option<Account<'info, AdminRegistry>>,
+    #[account(
+        mut,
+        constraint = airdrop_state.has_role(admin_registry.as_deref(), &update_authority.key(), ROLE_UPDATER) @ WhitelistError::MissingRole
+    )]
+    pub update_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(index: u64)]
+#[event_cpi]
+pub struct UnrevokeLeaf<'info> {
+    #[account(
+        mut,
+        seeds = [b"merkle_tree".as_ref(), airdrop_state.mint.key().to_bytes().as_ref(), AirdropState::id_seed(airdrop_state.airdrop_id).as_slice()],
+        bump = airdrop_state.bump,
+        constraint = airdrop_state.version == STATE_VERSION @ WhitelistError::UnsupportedStateVersion
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    #[account(
+        mut,
+        close = update_authority,
+        seeds = [b"revoked".as_ref(), airdrop_state.key().to_bytes().as_ref(), index.to_le_bytes().as_ref()],
+        bump = revoked_leaf.bump
+    )]
+    pub revoked_leaf: Account<'info, RevokedLeaf>,
+    /// Registry granting roles to keys other than the authorities, only needed to act with one.
+    #[account(constraint = admin_registry.airdrop_state == airdrop_state.key() @ WhitelistError::AdminRegistryMismatch)]
+    pub admin_registry: Option<Account<'info, AdminRegistry>>,
+    #[account(
+        mut,
+        constraint = airdrop_state.has_role(admin_registry.as_deref(), &update_authority.key(), ROLE_UPDATER) @ WhitelistError::MissingRole
+    )]
+    pub update_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateState<'info> {
+    /// CHECK: Holds a layout `AirdropState` cannot deserialize, read by the migration
+    #[account(mut, owner = crate::ID)]
+    pub airdrop_state: UncheckedAccount<'info>,
+    pub update_authority: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+pub struct ActivateRoot<'info> {
+    #[account(
+        mut, 
+        seeds = [b"merkle_tree".as_ref(), airdrop_state.mint.key().to_bytes().as_ref(), AirdropState::id_seed(airdrop_state.airdrop_id).as_slice()],
+        bump = airdrop_state.bump,
+        constraint = airdrop_state.version == STATE_VERSION @ WhitelistError::UnsupportedStateVersion
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+}
+
+#[derive(Accounts)]
+pub struct NominateUpdateAuthority<'info> {
+    #[account(
+        mut,
+        has_one = update_authority,
+        seeds = [b"merkle_tree".as_ref(), airdrop_state.mint.key().to_bytes().as_ref(), AirdropState::id_seed(airdrop_state.airdrop_id).as_slice()],
+        bump = airdrop_state.bump,
+        constraint = airdrop_state.version == STATE_VERSION @ WhitelistError::UnsupportedStateVersion
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    pub update_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptUpdateAuthority<'info> {
+    #[account(
+        mut, 
+        constraint = airdrop_state.pending_update_authority == Some(pending_update_authority.key()) @ WhitelistError::NotPendingAuthority,
+        seeds = [b"merkle_tree".as_ref(), airdrop_state.mint.key().to_bytes().as_ref(), AirdropState::id_seed(airdrop_state.airdrop_id).as_slice()],
+        bump = airdrop_state.bump,
+        constraint = airdrop_state.version == STATE_VERSION @ WhitelistError::UnsupportedStateVersion
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    pub pending_update_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct NominateWithdrawAuthority<'info> {
+    #[account(
+        mut,
+        has_one = withdraw_authority,
+        seeds = [b"merkle_tree".as_ref(), airdrop_state.mint.key().to_bytes().as_ref(), AirdropState::id_seed(airdrop_state.airdrop_id).as_slice()],
+        bump = airdrop_state.bump,
+        constraint = airdrop_state.version == STATE_VERSION @ WhitelistError::UnsupportedStateVersion
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    pub withdraw_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptWithdrawAuthority<'info> {
+    #[account(
+        mut,
+        constraint = airdrop_state.pending_withdraw_authority == Some(pending_withdraw_authority.key()) @ WhitelistError::NotPendingAuthority,
+        seeds = [b"merkle_tree".as_ref(), airdrop_state.mint.key().to_bytes().as_ref(), AirdropState::id_seed(airdrop_state.airdrop_id).as_slice()],
+        bump = airdrop_state.bump,
+        constraint = airdrop_state.version == STATE_VERSION @ WhitelistError::UnsupportedStateVersion
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    pub pending_withdraw_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Pause<'info> {
+    #[account(
+        mut,
+        seeds = [b"merkle_tree".as_ref(), airdrop_state.mint.key().to_bytes().as_ref(), AirdropState::id_seed(airdrop_state.airdrop_id).as_slice()],
+        bump = airdrop_state.bump,
+        constraint = airdrop_state.version == STATE_VERSION @ WhitelistError::UnsupportedStateVersion
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    /// Registry granting roles to keys other than the authorities, only needed to act with one.
+    #[account(constraint = admin_registry.airdrop_state == airdrop_state.key() @ WhitelistError::AdminRegistryMismatch)]
+    pub admin_registry: Option<Account<'info, AdminRegistry>>,
+    #[account(
+        constraint = airdrop_state.has_role(admin_registry.as_deref(), &authority.key(), ROLE_PAUSER) @ WhitelistError::MissingRole
+    )]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateAdminRegistry<'info> {
+    #[account(
+        has_one = update_authority,
+        seeds = [b"merkle_tree".as_ref(), airdrop_state.mint.key().to_bytes().as_ref(), AirdropState::id_seed(airdrop_state.airdrop_id).as_slice()],
+        bump = airdrop_state.bump,
+        constraint = airdrop_state.version == STATE_VERSION @ WhitelistError::UnsupportedStateVersion
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    #[account(
+        init,
+        seeds = [b"admin_registry".as_ref(), airdrop_state.key().to_bytes().as_ref()],
+        bump,
+        payer = update_authority,
+        space = 8 + AdminRegistry::INIT_SPACE
+    )]
+    pub admin_registry: Account<'info, AdminRegistry>,
+    #[account(mut)]
+    pub update_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+pub struct ManageRoles<'info> {
+    #[account(
+        mut,
+        has_one = super_admin,
+        seeds = [b"admin_registry".as_ref(), admin_registry.airdrop_state.to_bytes().as_ref()],
+        bump = admin_registry.bump
+    )]
+    pub admin_registry: Account<'info, AdminRegistry>,
+    pub super_admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptSuperAdmin<'info> {
+    #[account(
+        mut,
+        constraint = admin_registry.pending_super_admin == Some(pending_super_admin.key()) @ WhitelistError::NotPendingSuperAdmin,
+        seeds = [b"admin_registry".as_ref(), admin_registry.airdrop_state.to_bytes().as_ref()],
+        bump = admin_registry.bump
+    )]
+    pub admin_registry: Account<'info, AdminRegistry>,
+    pub pending_super_admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawUnclaimed<'info> {
+    #[account(
+        mut, 
+        has_one = mint,
+        seeds = [b"merkle_tree".as_ref(), mint.key().to_bytes().as_ref(), AirdropState::id_seed(airdrop_state.airdrop_id).as_slice()],
+        bump = airdrop_state.bump,
+        constraint = airdrop_state.version == STATE_VERSION @ WhitelistError::UnsupportedStateVersion
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = airdrop_state,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = withdraw_authority,
+        token::token_program = token_program,
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+    /// Vesting schedule of the airdrop, only needed in `CLAIM_MODE_VESTING`.
+    #[account(
+        seeds = [b"vesting_config".as_ref(), airdrop_state.key().to_bytes().as_ref()],
+        bump = vesting_config.bump
+    )]
+    pub vesting_config: Option<Account<'info, VestingConfig>>,
+    /// Registry granting roles to keys other than the authorities, only needed to act with one.
+    #[account(constraint = admin_registry.airdrop_state == airdrop_state.key() @ WhitelistError::AdminRegistryMismatch)]
+    pub admin_registry: Option<Account<'info, AdminRegistry>>,
+    #[account(
+        constraint = airdrop_state.has_role(admin_registry.as_deref(), &withdraw_authority.key(), ROLE_WITHDRAWER) @ WhitelistError::MissingRole
+    )]
+    pub withdraw_authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawUnclaimedSol<'info> {
+    #[account(
+        mut, 
+        seeds = [b"merkle_tree".as_ref(), NATIVE_SOL.to_bytes().as_ref(), AirdropState::id_seed(airdrop_state.airdrop_id).as_slice()],
+        bump = airdrop_state.bump,
+        constraint = airdrop_state.version == STATE_VERSION @ WhitelistError::UnsupportedStateVersion
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    #[account(
+        mut,
+        seeds = [b"sol_vault".as_ref(), airdrop_state.key().to_bytes().as_ref()],
+        bump
+    )]
+    pub sol_vault: SystemAccount<'info>,
+    /// Registry granting roles to keys other than the authorities, only needed to act with one.
+    #[account(constraint = admin_registry.airdrop_state == airdrop_state.key() @ WhitelistError::AdminRegistryMismatch)]
+    pub admin_registry: Option<Account<'info, AdminRegistry>>,
+    #[account(
+        mut,
+        constraint = airdrop_state.has_role(admin_registry.as_deref(), &withdraw_authority.key(), ROLE_WITHDRAWER) @ WhitelistError::MissingRole
+    )]
+    pub withdraw_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawBasketUnclaimed<'info> {
+    #[account(
+        seeds = [b"merkle_tree".as_ref(), airdrop_state.mint.key().to_bytes().as_ref(), AirdropState::id_seed(airdrop_state.airdrop_id).as_slice()],
+        bump = airdrop_state.bump,
+        constraint = airdrop_state.version == STATE_VERSION @ WhitelistError::UnsupportedStateVersion
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    #[account(
+        mut,
+        seeds = [b"basket".as_ref(), airdrop_state.key().to_bytes().as_ref()],
+        bump = basket.bump
+    )]
+    pub basket: Account<'info, Basket>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = airdrop_state,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = withdraw_authority,
+        token::token_program = token_program,
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+    /// Registry granting roles to keys other than the authorities, only needed to act with one.
+    #[account(constraint = admin_registry.airdrop_state == airdrop_state.key() @ WhitelistError::AdminRegistryMismatch)]
+    pub admin_registry: Option<Account<'info, AdminRegistry>>,
+    #[account(
+        constraint = airdrop_state.has_role(admin_registry.as_deref(), &withdraw_authority.key(), ROLE_WITHDRAWER) @ WhitelistError::MissingRole
+    )]
+    pub withdraw_authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct BurnUnclaimed<'info> {
+    #[account(
+        mut, 
+        has_one = mint,
+        seeds = [b"merkle_tree".as_ref(), mint.key().to_bytes().as_ref(), AirdropState::id_seed(airdrop_state.airdrop_id).as_slice()],
+        bump = airdrop_state.bump,
+        constraint = airdrop_state.version == STATE_VERSION @ WhitelistError::UnsupportedStateVersion
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = airdrop_state,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    /// Vesting schedule of the airdrop, only needed in `CLAIM_MODE_VESTING`.
+    #[account(
+        seeds = [b"vesting_config".as_ref(), airdrop_state.key().to_bytes().as_ref()],
+        bump = vesting_config.bump
+    )]
+    pub vesting_config: Option<Account<'info, VestingConfig>>,
+    /// Registry granting roles to keys other than the authorities, only needed to act with one.
+    #[account(constraint = admin_registry.airdrop_state == airdrop_state.key() @ WhitelistError::AdminRegistryMismatch)]
+    pub admin_registry: Option<Account<'info, AdminRegistry>>,
+    #[account(
+        constraint = airdrop_state.has_role(admin_registry.as_deref(), &withdraw_authority.key(), ROLE_WITHDRAWER) @ WhitelistError::MissingRole
+    )]
+    pub withdraw_authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CloseAirdrop<'info> {
+    #[account(
+        mut, 
+        close = withdraw_authority,
+        has_one = mint,
+        seeds = [b"merkle_tree".as_ref(), mint.key().to_bytes().as_ref(), AirdropState::id_seed(airdrop_state.airdrop_id).as_slice()],
+        bump = airdrop_state.bump,
+        constraint = airdrop_state.version == STATE_VERSION @ WhitelistError::UnsupportedStateVersion
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = airdrop_state,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    /// Registry granting roles to keys other than the authorities, only needed to act with one.
+    #[account(constraint = admin_registry.airdrop_state == airdrop_state.key() @ WhitelistError::AdminRegistryMismatch)]
+    pub admin_registry: Option<Account<'info, AdminRegistry>>,
+    #[account(
+        mut,
+        constraint = airdrop_state.has_role(admin_registry.as_deref(), &withdraw_authority.key(), ROLE_WITHDRAWER) @ WhitelistError::MissingRole
+    )]
+    pub withdraw_authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct TopUp<'info> {
+    #[account(
+        mut, 
+        has_one = mint,
+        seeds = [b"merkle_tree".as_ref(), mint.key().to_bytes().as_ref(), AirdropState::id_seed(airdrop_state.airdrop_id).as_slice()],
+        bump = airdrop_state.bump,
+        constraint = airdrop_state.version == STATE_VERSION @ WhitelistError::UnsupportedStateVersion
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = airdrop_state,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = update_authority,
+        token::token_program = token_program,
+    )]
+    pub authority_ata: InterfaceAccount<'info, TokenAccount>,
+    /// Registry granting roles to keys other than the authorities, only needed to act with one.
+    #[account(constraint = admin_registry.airdrop_state == airdrop_state.key() @ WhitelistError::AdminRegistryMismatch)]
+    pub admin_registry: Option<Account<'info, AdminRegistry>>,
+    #[account(
+        constraint = airdrop_state.has_role(admin_registry.as_deref(), &update_authority.key(), ROLE_UPDATER) @ WhitelistError::MissingRole
+    )]
+    pub update_authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct FundBonus<'info> {
+    #[account(
+        has_one = mint,
+        seeds = [b"merkle_tree".as_ref(), mint.key().to_bytes().as_ref(), AirdropState::id_seed(airdrop_state.airdrop_id).as_slice()],
+        bump = airdrop_state.bump,
+        constraint = airdrop_state.version == STATE_VERSION @ WhitelistError::UnsupportedStateVersion
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    #[account(
+        init,
+        seeds = [b"bonus_config".as_ref(), airdrop_state.key().to_bytes().as_ref()],
+        bump,
+        payer = update_authority,
+        space = 8 + BonusConfig::INIT_SPACE
+    )]
+    pub bonus_config: Account<'info, BonusConfig>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = airdrop_state,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = update_authority,
+        token::token_program = token_program,
+    )]
+    pub authority_ata: InterfaceAccount<'info, TokenAccount>,
+    /// Registry granting roles to keys other than the authorities, only needed to act with one.
+    #[account(constraint = admin_registry.airdrop_state == airdrop_state.key() @ WhitelistError::AdminRegistryMismatch)]
+    pub admin_registry: Option<Account<'info, AdminRegistry>>,
+    #[account(
+        mut,
+        constraint = airdrop_state.has_role(admin_registry.as_deref(), &update_authority.key(), ROLE_FEE_MANAGER) @ WhitelistError::MissingRole
+    )]
+    pub update_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct MintAdditional<'info> {
+    #[account(
+        mut, 
+        has_one = mint,
+        seeds = [b"merkle_tree".as_ref(), mint.key().to_bytes().as_ref(), AirdropState::id_seed(airdrop_state.airdrop_id).as_slice()],
+        bump = airdrop_state.bump,
+        constraint = airdrop_state.version == STATE_VERSION @ WhitelistError::UnsupportedStateVersion
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    #[account(
+        mut,
+        constraint = mint.mint_authority == COption::Some(airdrop_state.key()) @ WhitelistError::MintAuthorityNotHeld
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = airdrop_state,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    /// Registry granting roles to keys other than the authorities, only needed to act with one.
+    #[account(constraint = admin_registry.airdrop_state == airdrop_state.key() @ WhitelistError::AdminRegistryMismatch)]
+    pub admin_registry: Option<Account<'info, AdminRegistry>>,
+    #[account(
+        constraint = airdrop_state.has_role(admin_registry.as_deref(), &update_authority.key(), ROLE_UPDATER) @ WhitelistError::MissingRole
+    )]
+    pub update_authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(shard_index: u64)]
+pub struct CreateBitmapShard<'info> {
+    #[account(
+        seeds = [b"merkle_tree".as_ref(), airdrop_state.mint.key().to_bytes().as_ref(), AirdropState::id_seed(airdrop_state.airdrop_id).as_slice()],
+        bump = airdrop_state.bump,
+        constraint = airdrop_state.version == STATE_VERSION @ WhitelistError::UnsupportedStateVersion
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    #[account(
+        init,
+        seeds = [b"bitmap".as_ref(), airdrop_state.key().to_bytes().as_ref(), shard_index.to_le_bytes().as_ref()],
+        bump,
+        payer = payer,
+        space = 8 + std::mem::size_of::<ClaimBitmap>()
+    )]
+    pub claim_bitmap: AccountLoader<'info, ClaimBitmap>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(referrer: Pubkey)]
+pub struct CreateReferralStats<'info> {
+    #[account(
+        seeds = [b"merkle_tree".as_ref(), airdrop_state.mint.key().to_bytes().as_ref(), AirdropState::id_seed(airdrop_state.airdrop_id).as_slice()],
+        bump = airdrop_state.bump,
+        constraint = airdrop_state.version == STATE_VERSION @ WhitelistError::UnsupportedStateVersion
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    #[account(
+        init_if_needed,
+        seeds = [b"referral".as_ref(), airdrop_state.key().to_bytes().as_ref(), referrer.to_bytes().as_ref()],
+        bump,
+        payer = payer,
+        space = 8 + ReferralStats::INIT_SPACE
+    )]
+    pub referral_stats: Account<'info, ReferralStats>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigureDecay<'info> {
+    #[account(
+        mut,
+        seeds = [b"merkle_tree".as_ref(), airdrop_state.mint.key().to_bytes().as_ref(), AirdropState::id_seed(airdrop_state.airdrop_id).as_slice()],
+        bump = airdrop_state.bump,
+        constraint = airdrop_state.version == STATE_VERSION @ WhitelistError::UnsupportedStateVersion
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    #[account(
+        init_if_needed,
+        seeds = [b"decay_config".as_ref(), airdrop_state.key().to_bytes().as_ref()],
+        bump,
+        payer = update_authority,
+        space = 8 + DecayConfig::INIT_SPACE
+    )]
+    pub decay_config: Account<'info, DecayConfig>,
+    /// Registry granting roles to keys other than the authorities, only needed to act with one.
+    #[account(constraint = admin_registry.airdrop_state == airdrop_state.key() @ WhitelistError::AdminRegistryMismatch)]
+    pub admin_registry: Option<Account<'info, AdminRegistry>>,
+    #[account(
+        mut,
+        constraint = airdrop_state.has_role(admin_registry.as_deref(), &update_authority.key(), ROLE_UPDATER) @ WhitelistError::MissingRole
+    )]
+    pub update_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigureVesting<'info> {
+    #[account(
+        mut,
+        seeds = [b"merkle_tree".as_ref(), airdrop_state.mint.key().to_bytes().as_ref(), AirdropState::id_seed(airdrop_state.airdrop_id).as_slice()],
+        bump = airdrop_state.bump,
+        constraint = airdrop_state.version == STATE_VERSION @ WhitelistError::UnsupportedStateVersion
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    #[account(
+        init_if_needed,
+        seeds = [b"vesting_config".as_ref(), airdrop_state.key().to_bytes().as_ref()],
+        bump,
+        payer = update_authority,
+        space = 8 + VestingConfig::INIT_SPACE
+    )]
+    pub vesting_config: Account<'info, VestingConfig>,
+    /// Registry granting roles to keys other than the authorities, only needed to act with one.
+    #[account(constraint = admin_registry.airdrop_state == airdrop_state.key() @ WhitelistError::AdminRegistryMismatch)]
+    pub admin_registry: Option<Account<'info, AdminRegistry>>,
+    #[account(
+        mut,
+        constraint = airdrop_state.has_role(admin_registry.as_deref(), &update_authority.key(), ROLE_UPDATER) @ WhitelistError::MissingRole
+    )]
+    pub update_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigureStaking<'info> {
+    #[account(
+        seeds = [b"merkle_tree".as_ref(), airdrop_state.mint.key().to_bytes().as_ref(), AirdropState::id_seed(airdrop_state.airdrop_id).as_slice()],
+        bump = airdrop_state.bump,
+        constraint = airdrop_state.version == STATE_VERSION @ WhitelistError::UnsupportedStateVersion
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    #[account(
+        init_if_needed,
+        seeds = [b"stake_config".as_ref(), airdrop_state.key().to_bytes().as_ref()],
+        bump,
+        payer = update_authority,
+        space = 8 + StakeConfig::INIT_SPACE
+    )]
+    pub stake_config: Account<'info, StakeConfig>,
+    /// Registry granting roles to keys other than the authorities, only needed to act with one.
+    #[account(constraint = admin_registry.airdrop_state == airdrop_state.key() @ WhitelistError::AdminRegistryMismatch)]
+    pub admin_registry: Option<Account<'info, AdminRegistry>>,
+    #[account(
+        mut,
+        constraint = airdrop_state.has_role(admin_registry.as_deref(), &update_authority.key(), ROLE_UPDATER) @ WhitelistError::MissingRole
+    )]
+    pub update_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AddBasketMint<'info> {
+    #[account(
+        seeds = [b"merkle_tree".as_ref(), airdrop_state.mint.key().to_bytes().as_ref(), AirdropState::id_seed(airdrop_state.airdrop_id).as_slice()],
+        bump = airdrop_state.bump,
+        constraint = airdrop_state.version == STATE_VERSION @ WhitelistError::UnsupportedStateVersion
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    #[account(
+        init_if_needed,
+        seeds = [b"basket".as_ref(), airdrop_state.key().to_bytes().as_ref()],
+        bump,
+        payer = update_authority,
+        space = 8 + Basket::INIT_SPACE
+    )]
+    pub basket: Account<'info, Basket>,
+    /// The mint of the airdrop, only there to hold the basket to its token program.
+    #[account(
+        address = airdrop_state.mint,
+        mint::token_program = token_program,
+    )]
+    pub airdrop_mint: InterfaceAccount<'info, Mint>,
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = update_authority,
+        associated_token::mint = mint,
+        associated_token::authority = airdrop_state,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = update_authority,
+        token::token_program = token_program,
+    )]
+    pub authority_ata: InterfaceAccount<'info, TokenAccount>,
+    /// Registry granting roles to keys other than the authorities, only needed to act with one.
+    #[account(constraint = admin_registry.airdrop_state == airdrop_state.key() @ WhitelistError::AdminRegistryMismatch)]
+    pub admin_registry: Option<Account<'info, AdminRegistry>>,
+    #[account(
+        mut,
+        constraint = airdrop_state.has_role(admin_registry.as_deref(), &update_authority.key(), ROLE_UPDATER) @ WhitelistError::MissingRole
+    )]
+    pub update_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[derive(Accounts)]
+pub struct CreateRound<'info> {
+    #[account(
+        mut,
+        seeds = [b"merkle_tree".as_ref(), airdrop_state.mint.key().to_bytes().as_ref(), AirdropState::id_seed(airdrop_state.airdrop_id).as_slice()],
+        bump = airdrop_state.bump,
+        constraint = airdrop_state.version == STATE_VERSION @ WhitelistError::UnsupportedStateVersion
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    #[account(
+        init,
+        seeds = [b"round".as_ref(), airdrop_state.key().to_bytes().as_ref(), airdrop_state.num_rounds.to_le_bytes().as_ref()],
+        bump,
+        payer = update_authority,
+        space = 8 + RoundState::INIT_SPACE
+    )]
+    pub round_state: Account<'info, RoundState>,
+    /// Registry granting roles to keys other than the authorities, only needed to act with one.
+    #[account(constraint = admin_registry.airdrop_state == airdrop_state.key() @ WhitelistError::AdminRegistryMismatch)]
+    pub admin_registry: Option<Account<'info, AdminRegistry>>,
+    #[account(
+        mut,
+        constraint = airdrop_state.has_role(admin_registry.as_deref(), &update_authority.key(), ROLE_UPDATER) @ WhitelistError::MissingRole
+    )]
+    pub update_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(shard_index: u64)]
+pub struct CreateRoundBitmapShard<'info> {
+    #[account(
+        seeds = [b"round".as_ref(), round_state.airdrop_state.key().to_bytes().as_ref(), round_state.round.to_le_bytes().as_ref()],
+        bump = round_state.bump
+    )]
+    pub round_state: Account<'info, RoundState>,
+    #[account(
+        init,
+        seeds = [b"bitmap".as_ref(), round_state.key().to_bytes().as_ref(), shard_index.to_le_bytes().as_ref()],
+        bump,
+        payer = payer,
+        space = 8 + std::mem::size_of::<ClaimBitmap>()
+    )]
+    pub claim_bitmap: AccountLoader<'info, ClaimBitmap>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(claimant: Pubkey, amount: u64, hashes: Vec<u8>, index: u64)]
+pub struct VerifyEligibility<'info> {
+    #[account(
+        seeds = [b"merkle_tree".as_ref(), airdrop_state.mint.key().to_bytes().as_ref(), AirdropState::id_seed(airdrop_state.airdrop_id).as_slice()],
+        bump = airdrop_state.bump,
+        constraint = airdrop_state.version == STATE_VERSION @ WhitelistError::UnsupportedStateVersion
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    #[account(
+        seeds = [b"bitmap".as_ref(), airdrop_state.key().to_bytes().as_ref(), ClaimBitmap::shard_for(index).to_le_bytes().as_ref()],
+        bump = claim_bitmap.load()?.bump
+    )]
+    pub claim_bitmap: Option<AccountLoader<'info, ClaimBitmap>>,
+}
+
+#[derive(Accounts)]
+#[instruction(index: u64)]
+pub struct GetClaimStatus<'info> {
+    #[account(
+        seeds = [b"merkle_tree".as_ref(), airdrop_state.mint.key().to_bytes().as_ref(), AirdropState::id_seed(airdrop_state.airdrop_id).as_slice()],
+        bump = airdrop_state.bump,
+        constraint = airdrop_state.version == STATE_VERSION @ WhitelistError::UnsupportedStateVersion
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    #[account(
+        seeds = [b"bitmap".as_ref(), airdrop_state.key().to_bytes().as_ref(), ClaimBitmap::shard_for(index).to_le_bytes().as_ref()],
+        bump = claim_bitmap.load()?.bump
+    )]
+    pub claim_bitmap: AccountLoader<'info, ClaimBitmap>,
+    /// Receipt of the claim of `index`, only needed to report its amount and time.
+    #[account(
+        seeds = [b"receipt".as_ref(), airdrop_state.key().to_bytes().as_ref(), index.to_le_bytes().as_ref()],
+        bump = claim_receipt.bump
+    )]
+    pub claim_receipt: Option<Account<'info, ClaimReceipt>>,
+}
+
+#[derive(Accounts)]
+#[instruction(amount: u64, hashes: Vec<u8>, index: u64)]
+#[event_cpi]
+pub struct Claim<'info> {
+    #[account(
+        mut,
+        has_one = mint,
+        seeds = [b"merkle_tree".as_ref(), mint.key().to_bytes().as_ref(), AirdropState::id_seed(airdrop_state.airdrop_id).as_slice()],
+        bump = airdrop_state.bump,
+        constraint = airdrop_state.version == STATE_VERSION @ WhitelistError::UnsupportedStateVersion
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = airdrop_state,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = claimant,
+        associated_token::token_program = token_program,
+    )]
+    pub signer_ata: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"bitmap".as_ref(), airdrop_state.key().to_bytes().as_ref(), ClaimBitmap::shard_for(index).to_le_bytes().as_ref()],
+        bump = claim_bitmap.load()?.bump
+    )]
+    pub claim_bitmap: AccountLoader<'info, ClaimBitmap>,
+    /// What the claimant was paid so far, only needed in `CLAIM_MODE_CUMULATIVE`.
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [b"progress".as_ref(), airdrop_state.key().to_bytes().as_ref(), claimant.key().to_bytes().as_ref()],
+        bump,
+        space = 8 + ClaimProgress::INIT_SPACE
+    )]
+    pub claim_progress: Option<Account<'info, ClaimProgress>>,
+    /// Vesting schedule of the airdrop, only needed in `CLAIM_MODE_VESTING`.
+    #[account(
+        mut,
+        seeds = [b"vesting_config".as_ref(), airdrop_state.key().to_bytes().as_ref()],
+        bump = vesting_config.bump
+    )]
+    pub vesting_config: Option<Account<'info, VestingConfig>>,
+    /// Escrow the claim opens in `CLAIM_MODE_VESTING`, one for every claimed index.
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"vesting".as_ref(), airdrop_state.key().to_bytes().as_ref(), index.to_le_bytes().as_ref()],
+        bump,
+        space = 8 + VestingAccount::INIT_SPACE
+    )]
+    pub vesting_account: Option<Account<'info, VestingAccount>>,
+    /// Receipt of the amount and time of the claim, `get_claim_status` reports them from it.
+    /// Claims made without it only mark the bitmap.
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [b"receipt".as_ref(), airdrop_state.key().to_bytes().as_ref(), index.to_le_bytes().as_ref()],
+        bump,
+        space = 8 + ClaimReceipt::INIT_SPACE
+    )]
+    pub claim_receipt: Option<Account<'info, ClaimReceipt>>,
+    /// Decay schedule of the airdrop, only needed in `CLAIM_MODE_DECAYING`.
+    #[account(
+        mut,
+        seeds = [b"decay_config".as_ref(), airdrop_state.key().to_bytes().as_ref()],
+        bump = decay_config.bump
+    )]
+    pub decay_config: Option<Account<'info, DecayConfig>>,
+    /// Bonus reserve of the airdrop, claims made without it are paid no bonus.
+    #[account(
+        mut,
+        seeds = [b"bonus_config".as_ref(), airdrop_state.key().to_bytes().as_ref()],
+        bump = bonus_config.bump
+    )]
+    pub bonus_config: Option<Account<'info, BonusConfig>>,
+    /// Token account of the fee recipient, only needed while the airdrop charges a fee.
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = airdrop_state.fee_recipient,
+        associated_token::token_program = token_program,
+    )]
+    pub fee_ata: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// Receives the claim fee in lamports, only needed while the airdrop charges one.
+    #[account(
+        mut,
+        address = airdrop_state.fee_treasury
+    )]
+    pub fee_treasury: Option<SystemAccount<'info>>,
+    /// Mint the claim fee is charged in, only needed while the airdrop has a fee mint. It and
+    /// the token accounts below are checked against the airdrop by the claim.
+    pub fee_mint: Option<InterfaceAccount<'info, Mint>>,
+    /// ATA of the payer in the fee mint, the claim fee is paid out of.
+    #[account(mut)]
+    pub payer_fee_ata: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// ATA of `fee_treasury` in the fee mint, the claim fee is paid into.
+    #[account(mut)]
+    pub treasury_fee_ata: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub fee_token_program: Option<Interface<'info, TokenInterface>>,
+    /// Mint of the tickets a claim burns, only needed while the airdrop is ticket gated.
+    #[account(mut)]
+    pub ticket_mint: Option<InterfaceAccount<'info, Mint>>,
+    /// Token account of the claimant the tickets are burned from.
+    #[account(mut)]
+    pub ticket_ata: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub ticket_token_program: Option<Interface<'info, TokenInterface>>,
+    /// Token account of the claimant holding an NFT of the gate collection, only needed while
+    /// the airdrop is collection gated.
+    pub gate_nft_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// Metaplex metadata of the NFT in `gate_nft_account`.
+    pub gate_nft_metadata: Option<Account<'info, MetadataAccount>>,
+    /// Backend key co-signing the claim, only needed while the airdrop has a cosigner.
+    pub cosigner: Option<Signer<'info>>,
+    /// CHECK: The instructions sysvar, only needed to claim with a permit of the cosigner
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+    /// CHECK: Revocation marker of the claimed index, only needed while the airdrop has revoked
+    /// leaves, and empty unless the leaf is revoked
+    #[account(
+        seeds = [b"revoked".as_ref(), airdrop_state.key().to_bytes().as_ref(), index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub revoked_leaf: Option<UncheckedAccount<'info>>,
+    /// Counters of the referrer `claim_airdrop` credits, only needed with a referrer.
+    #[account(mut, has_one = airdrop_state)]
+    pub referral_stats: Option<Account<'info, ReferralStats>>,
+    /// CHECK: The hook program of the airdrop, only needed while it has one
+    pub hook_program: Option<UncheckedAccount<'info>>,
+    /// CHECK: The PDA signing the call of the hook program, checked by `call_claim_hook`
+    pub hook_authority: Option<UncheckedAccount<'info>>,
+    /// Staking program and accounts `claim_and_stake` deposits into, only needed to stake.
+    #[account(
+        seeds = [b"stake_config".as_ref(), airdrop_state.key().to_bytes().as_ref()],
+        bump = stake_config.bump
+    )]
+    pub stake_config: Option<Account<'info, StakeConfig>>,
+    /// CHECK: The staking program of `stake_config`, checked against it by `claim_and_stake`
+    pub stake_program: Option<UncheckedAccount<'info>>,
+    /// CHECK: The claimant again, writable to receive an unwrapped claim, only needed to unwrap
+    #[account(mut, address = claimant.key())]
+    pub unwrap_destination: Option<UncheckedAccount<'info>>,
+    /// Mints `claim_basket` pays on top of the airdrop mint, only needed to claim a basket.
+    #[account(
+        mut,
+        seeds = [b"basket".as_ref(), airdrop_state.key().to_bytes().as_ref()],
+        bump = basket.bump
+    )]
+    pub basket: Option<Account<'info, Basket>>,
+    /// Upper levels of the tree, only needed to claim with a proof that stops at them.
+    #[account(
+        seeds = [b"canopy".as_ref(), airdrop_state.key().to_bytes().as_ref()],
+        bump = canopy.bump
+    )]
+    pub canopy: Option<Account<'info, Canopy>>,
+    /// Owner of the allocation, only signs to authorize the claim.
+    pub claimant: Signer<'info>,
+    /// Pays the fees and rent of the claim, the claimant itself unless a relayer covers them.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+impl<'info> Claim<'info> {
+    fn claim_accounts<'a>(&'a mut self, remaining_accounts: &'a [AccountInfo<'info>]) -> ClaimAccounts<'a, 'info> {
+        ClaimAccounts {
+            airdrop_state: &mut self.airdrop_state,
+            mint: &self.mint,
+            vault: &self.vault,
+            destination: &self.signer_ata,
+            fee_destination: self.fee_ata.as_ref(),
+            claim_bitmap: &self.claim_bitmap,
+            canopy: self.canopy.as_ref(),
+            token_program: &self.token_program,
+            remaining_accounts,
+        }
+    }
+
+    fn token_fee_accounts(&self) -> Option<TokenFeeAccounts<'_, 'info>> {
+        match (&self.fee_mint, &self.payer_fee_ata, &self.treasury_fee_ata, &self.fee_token_program) {
+            (Some(fee_mint), Some(payer_fee_ata), Some(treasury_fee_ata), Some(fee_token_program)) => Some(TokenFeeAccounts {
+                fee_mint,
+                payer_fee_ata,
+                treasury_fee_ata,
+                fee_token_program,
+            }),
+            _ => None,
+        }
+    }
+
+    fn ticket_accounts(&self) -> Option<TicketAccounts<'_, 'info>> {
+        match (&self.ticket_mint, &self.ticket_ata, &self.ticket_token_program) {
+            (Some(ticket_mint), Some(ticket_ata), Some(ticket_token_program)) => Some(TicketAccounts {
+                ticket_mint,
+                ticket_ata,
+                ticket_token_program,
+            }),
+            _ => None,
+        }
+    }
+
+    fn claim_gates(&self) -> ClaimGates<'_, 'info> {
+        ClaimGates {
+            payer: Some(&self.payer),
+            claimant: Some(&self.claimant),
+            fee_treasury: self.fee_treasury.as_ref(),
+            token_fee: self.token_fee_accounts(),
+            tickets: self.ticket_accounts(),
+            gate_nft_account: self.gate_nft_account.as_ref(),
+            gate_nft_metadata: self.gate_nft_metadata.as_ref(),
+            cosigner: self.cosigner.as_ref(),
+            instructions_sysvar: self.instructions_sysvar.as_ref(),
+            revoked_leaf: self.revoked_leaf.as_ref(),
+            hook_program: self.hook_program.as_ref(),
+            system_program: Some(&self.system_program),
+        }
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(amount: u64, hashes: Vec<u8>, index: u64)]
+#[event_cpi]
+pub struct ClaimSol<'info> {
+    #[account(
+        mut,
+        seeds = [b"merkle_tree".as_ref(), NATIVE_SOL.to_bytes().as_ref(), AirdropState::id_seed(airdrop_state.airdrop_id).as_slice()],
+        bump = airdrop_state.bump,
+        constraint = airdrop_state.version == STATE_VERSION @ WhitelistError::UnsupportedStateVersion
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    #[account(
+        mut,
+        seeds = [b"sol_vault".as_ref(), airdrop_state.key().to_bytes().as_ref()],
+        bump
+    )]
+    pub sol_vault: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"bitmap".as_ref(), airdrop_state.key().to_bytes().as_ref(), ClaimBitmap::shard_for(index).to_le_bytes().as_ref()],
+        bump = claim_bitmap.load()?.bump
+    )]
+    pub claim_bitmap: AccountLoader<'info, ClaimBitmap>,
+    /// CHECK: Revocation marker of the claimed index, only needed while the airdrop has revoked
+    /// leaves, and empty unless the leaf is revoked
+    #[account(
+        seeds = [b"revoked".as_ref(), airdrop_state.key().to_bytes().as_ref(), index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub revoked_leaf: Option<UncheckedAccount<'info>>,
+    /// Owner of the allocation, paid the claim in lamports.
+    #[account(mut)]
+    pub claimant: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ClaimSol<'info> {
+    fn claim_gates(&self) -> ClaimGates<'_, 'info> {
+        ClaimGates {
+            payer: Some(&self.claimant),
+            claimant: Some(&self.claimant),
+            revoked_leaf: self.revoked_leaf.as_ref(),
+            system_program: Some(&self.system_program),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(amount: u64, root: [u8; 32], index: u64)]
+#[event_cpi]
+pub struct ClaimConcurrent<'info> {
+    #[account(
+        mut,
+        has_one = mint,
+        seeds = [b"merkle_tree".as_ref(), mint.key().to_bytes().as_ref(), AirdropState::id_seed(airdrop_state.airdrop_id).as_slice()],
+        bump = airdrop_state.bump,
+        constraint = airdrop_state.version == STATE_VERSION @ WhitelistError::UnsupportedStateVersion,
+        constraint = airdrop_state.tree_mode == TREE_MODE_CONCURRENT @ WhitelistError::TreeModeMismatch
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = airdrop_state,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = claimant,
+        associated_token::token_program = token_program,
+    )]
+    pub signer_ata: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: Concurrent tree of the airdrop, checked by the compression program
+    #[account(mut, address = airdrop_state.concurrent_tree @ WhitelistError::TreeModeMismatch)]
+    pub merkle_tree: UncheckedAccount<'info>,
+    /// CHECK: Revocation marker of the claimed index, only needed while the airdrop has revoked
+    /// leaves, and empty unless the leaf is revoked
+    #[account(
+        seeds = [b"revoked".as_ref(), airdrop_state.key().to_bytes().as_ref(), index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub revoked_leaf: Option<UncheckedAccount<'info>>,
+    /// Token account of the fee recipient, only needed while the airdrop charges a fee.
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = airdrop_state.fee_recipient,
+        associated_token::token_program = token_program,
+    )]
+    pub fee_ata: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// Receives the claim fee in lamports, only needed while the airdrop charges one.
+    #[account(
+        mut,
+        address = airdrop_state.fee_treasury
+    )]
+    pub fee_treasury: Option<SystemAccount<'info>>,
+    pub claimant: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: spl-account-compression
+    #[account(address = SPL_ACCOUNT_COMPRESSION_ID)]
+    pub compression_program: UncheckedAccount<'info>,
+    /// CHECK: spl-noop
+    #[account(address = SPL_NOOP_ID)]
+    pub noop_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+impl<'info> ClaimConcurrent<'info> {
+    fn claim_gates(&self) -> ClaimGates<'_, 'info> {
+        ClaimGates {
+            payer: Some(&self.payer),
+            claimant: Some(&self.claimant),
+            fee_treasury: self.fee_treasury.as_ref(),
+            revoked_leaf: self.revoked_leaf.as_ref(),
+            system_program: Some(&self.system_program),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(hashes: Vec<u8>, index: u64)]
+#[event_cpi]
+pub struct ClaimNft<'info> {
+    #[account(
+        mut,
+        seeds = [b"merkle_tree".as_ref(), NFT_AIRDROP.to_bytes().as_ref(), AirdropState::id_seed(airdrop_state.airdrop_id).as_slice()],
+        bump = airdrop_state.bump,
+        constraint = airdrop_state.version == STATE_VERSION @ WhitelistError::UnsupportedStateVersion
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    /// Mint the leaf pays, the leaf fails to verify for any other.
+    #[account(mint::token_program = token_program)]
+    pub nft_mint: InterfaceAccount<'info, Mint>,
+    /// CHECK: ATA of the airdrop state the NFT was deposited into, checked to hold it by
+    /// `claim_nft`
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(&airdrop_state.key(), &nft_mint.key(), &token_program.key())
+    )]
+    pub vault: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = claimant,
+        associated_token::mint = nft_mint,
+        associated_token::authority = claimant,
+        associated_token::token_program = token_program,
+    )]
+    pub signer_ata: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"bitmap".as_ref(), airdrop_state.key().to_bytes().as_ref(), ClaimBitmap::shard_for(index).to_le_bytes().as_ref()],
+        bump = claim_bitmap.load()?.bump
+    )]
+    pub claim_bitmap: AccountLoader<'info, ClaimBitmap>,
+    /// CHECK: Revocation marker of the claimed index, only needed while the airdrop has revoked
+    /// leaves, and empty unless the leaf is revoked
+    #[account(
+        seeds = [b"revoked".as_ref(), airdrop_state.key().to_bytes().as_ref(), index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub revoked_leaf: Option<UncheckedAccount<'info>>,
+    /// CHECK: The hook program of the airdrop, only needed while it has one
+    pub hook_program: Option<UncheckedAccount<'info>>,
+    /// CHECK: The PDA signing the call of the hook program, checked by `call_claim_hook`
+    pub hook_authority: Option<UncheckedAccount<'info>>,
+    #[account(mut)]
+    pub claimant: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+impl<'info> ClaimNft<'info> {
+    fn claim_gates(&self) -> ClaimGates<'_, 'info> {
+        ClaimGates {
+            payer: Some(&self.claimant),
+            claimant: Some(&self.claimant),
+            hook_program: self.hook_program.as_ref(),
+            revoked_leaf: self.revoked_leaf.as_ref(),
+            system_program: Some(&self.system_program),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(amount: u64, hashes: Vec<u8>, index: u64)]
+#[event_cpi]
+pub struct ClaimRound<'info> {
+    #[account(
+        mut,
+        has_one = mint,
+        seeds = [b"merkle_tree".as_ref(), mint.key().to_bytes().as_ref(), AirdropState::id_seed(airdrop_state.airdrop_id).as_slice()],
+        bump = airdrop_state.bump,
+        constraint = airdrop_state.version == STATE_VERSION @ WhitelistError::UnsupportedStateVersion
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    #[account(
+        mut,
+        has_one = airdrop_state,
+        seeds = [b"round".as_ref(), airdrop_state.key().to_bytes().as_ref(), round_state.round.to_le_bytes().as_ref()],
+        bump = round_state.bump
+    )]
+    pub round_state: Account<'info, RoundState>,
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = airdrop_state,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = claimant,
+        associated_token::token_program = token_program,
+    )]
+    pub signer_ata: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"bitmap".as_ref(), round_state.key().to_bytes().as_ref(), ClaimBitmap::shard_for(index).to_le_bytes().as_ref()],
+        bump = claim_bitmap.load()?.bump
+    )]
+    pub claim_bitmap: AccountLoader<'info, ClaimBitmap>,
+    /// Token account of the fee recipient, only needed while the airdrop charges a fee.
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = airdrop_state.fee_recipient,
+        associated_token::token_program = token_program,
+    )]
+    pub fee_ata: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// Receives the claim fee in lamports, only needed while the airdrop charges one.
+    #[account(
+        mut,
+        address = airdrop_state.fee_treasury
+    )]
+    pub fee_treasury: Option<SystemAccount<'info>>,
+    /// CHECK: The hook program of the airdrop, only needed while it has one
+    pub hook_program: Option<UncheckedAccount<'info>>,
+    /// CHECK: The PDA signing the call of the hook program, checked by `call_claim_hook`
+    pub hook_authority: Option<UncheckedAccount<'info>>,
+    pub claimant: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+impl<'info> ClaimRound<'info> {
+    fn claim_accounts<'a>(&'a mut self, remaining_accounts: &'a [AccountInfo<'info>]) -> ClaimAccounts<'a, 'info> {
+        ClaimAccounts {
+            airdrop_state: &mut self.airdrop_state,
+            mint: &self.mint,
+            vault: &self.vault,
+            destination: &self.signer_ata,
+            fee_destination: self.fee_ata.as_ref(),
+            claim_bitmap: &self.claim_bitmap,
+            canopy: None,
+            token_program: &self.token_program,
+            remaining_accounts,
+        }
+    }
+
+    fn claim_gates(&self) -> ClaimGates<'_, 'info> {
+        ClaimGates {
+            payer: Some(&self.payer),
+            claimant: Some(&self.claimant),
+            hook_program: self.hook_program.as_ref(),
+            fee_treasury: self.fee_treasury.as_ref(),
+            system_program: Some(&self.system_program),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(leaf_amount: u64, claim_amount: u64, hashes: Vec<u8>, index: u64)]
+#[event_cpi]
+pub struct ClaimPartial<'info> {
+    #[account(
+        mut,
+        has_one = mint,
+        seeds = [b"merkle_tree".as_ref(), mint.key().to_bytes().as_ref(), AirdropState::id_seed(airdrop_state.airdrop_id).as_slice()],
+        bump = airdrop_state.bump,
+        constraint = airdrop_state.version == STATE_VERSION @ WhitelistError::UnsupportedStateVersion
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = airdrop_state,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = claimant,
+        associated_token::token_program = token_program,
+    )]
+    pub signer_ata: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"bitmap".as_ref(), airdrop_state.key().to_bytes().as_ref(), ClaimBitmap::shard_for(index).to_le_bytes().as_ref()],
+        bump = claim_bitmap.load()?.bump
+    )]
+    pub claim_bitmap: AccountLoader<'info, ClaimBitmap>,
+    /// CHECK: Revocation marker of the claimed index, only needed while the airdrop has revoked
+    /// leaves, and empty unless the leaf is revoked
+    #[account(
+        seeds = [b"revoked".as_ref(), airdrop_state.key().to_bytes().as_ref(), index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub revoked_leaf: Option<UncheckedAccount<'info>>,
+    /// Token account of the fee recipient, only needed while the airdrop charges a fee.
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = airdrop_state.fee_recipient,
+        associated_token::token_program = token_program,
+    )]
+    pub fee_ata: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// Receives the claim fee in lamports, only needed while the airdrop charges one.
+    #[account(
+        mut,
+        address = airdrop_state.fee_treasury
+    )]
+    pub fee_treasury: Option<SystemAccount<'info>>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [b"progress".as_ref(), airdrop_state.key().to_bytes().as_ref(), index.to_le_bytes().as_ref()],
+        bump,
+        space = 8 + ClaimProgress::INIT_SPACE
+    )]
+    pub claim_progress: Account<'info, ClaimProgress>,
+    /// CHECK: The hook program of the airdrop, only needed while it has one
+    pub hook_program: Option<UncheckedAccount<'info>>,
+    /// CHECK: The PDA signing the call of the hook program, checked by `call_claim_hook`
+    pub hook_authority: Option<UncheckedAccount<'info>>,
+    pub claimant: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+impl<'info> ClaimPartial<'info> {
+    fn claim_accounts<'a>(&'a mut self, remaining_accounts: &'a [AccountInfo<'info>]) -> ClaimAccounts<'a, 'info> {
+        ClaimAccounts {
+            airdrop_state: &mut self.airdrop_state,
+            mint: &self.mint,
+            vault: &self.vault,
+            destination: &self.signer_ata,
+            fee_destination: self.fee_ata.as_ref(),
+            claim_bitmap: &self.claim_bitmap,
+            canopy: None,
+            token_program: &self.token_program,
+            remaining_accounts,
+        }
+    }
+
+    fn claim_gates(&self) -> ClaimGates<'_, 'info> {
+        ClaimGates {
+            payer: Some(&self.payer),
+            claimant: Some(&self.claimant),
+            hook_program: self.hook_program.as_ref(),
+            fee_treasury: self.fee_treasury.as_ref(),
+            revoked_leaf: self.revoked_leaf.as_ref(),
+            system_program: Some(&self.system_program),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(shard: u64)]
+#[event_cpi]
+pub struct ClaimMany<'info> {
+    #[account(
+        mut,
+        has_one = mint,
+        seeds = [b"merkle_tree".as_ref(), mint.key().to_bytes().as_ref(), AirdropState::id_seed(airdrop_state.airdrop_id).as_slice()],
+        bump = airdrop_state.bump,
+        constraint = airdrop_state.version == STATE_VERSION @ WhitelistError::UnsupportedStateVersion
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = airdrop_state,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = claimant,
+        associated_token::token_program = token_program,
+    )]
+    pub signer_ata: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"bitmap".as_ref(), airdrop_state.key().to_bytes().as_ref(), shard.to_le_bytes().as_ref()],
+        bump = claim_bitmap.load()?.bump
+    )]
+    pub claim_bitmap: AccountLoader<'info, ClaimBitmap>,
+    /// Token account of the fee recipient, only needed while the airdrop charges a fee.
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = airdrop_state.fee_recipient,
+        associated_token::token_program = token_program,
+    )]
+    pub fee_ata: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// Receives the claim fee in lamports, only needed while the airdrop charges one.
+    #[account(
+        mut,
+        address = airdrop_state.fee_treasury
+    )]
+    pub fee_treasury: Option<SystemAccount<'info>>,
+    /// CHECK: The hook program of the airdrop, only needed while it has one
+    pub hook_program: Option<UncheckedAccount<'info>>,
+    /// CHECK: The PDA signing the call of the hook program, checked by `call_claim_hook`
+    pub hook_authority: Option<UncheckedAccount<'info>>,
+    pub claimant: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+impl<'info> ClaimMany<'info> {
+    fn claim_accounts<'a>(&'a mut self, remaining_accounts: &'a [AccountInfo<'info>]) -> ClaimAccounts<'a, 'info> {
+        ClaimAccounts {
+            airdrop_state: &mut self.airdrop_state,
+            mint: &self.mint,
+            vault: &self.vault,
+            destination: &self.signer_ata,
+            fee_destination: self.fee_ata.as_ref(),
+            claim_bitmap: &self.claim_bitmap,
+            canopy: None,
+            token_program: &self.token_program,
+            remaining_accounts,
+        }
+    }
+
+    fn claim_gates(&self) -> ClaimGates<'_, 'info> {
+        ClaimGates {
+            payer: Some(&self.payer),
+            claimant: Some(&self.claimant),
+            hook_program: self.hook_program.as_ref(),
+            fee_treasury: self.fee_treasury.as_ref(),
+            system_program: Some(&self.system_program),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(shard: u64)]
+#[event_cpi]
+pub struct ClaimForMany<'info> {
+    #[account(
+        mut,
+        has_one = mint,
+        seeds = [b"merkle_tree".as_ref(), mint.key().to_bytes().as_ref(), AirdropState::id_seed(airdrop_state.airdrop_id).as_slice()],
+        bump = airdrop_state.bump,
+        constraint = airdrop_state.version == STATE_VERSION @ WhitelistError::UnsupportedStateVersion
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = airdrop_state,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"bitmap".as_ref(), airdrop_state.key().to_bytes().as_ref(), shard.to_le_bytes().as_ref()],
+        bump = claim_bitmap.load()?.bump
+    )]
+    pub claim_bitmap: AccountLoader<'info, ClaimBitmap>,
+    /// Token account of the fee recipient, only needed while the airdrop charges a fee.
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = airdrop_state.fee_recipient,
+        associated_token::token_program = token_program,
+    )]
+    pub fee_ata: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// Receives the claim fee in lamports, only needed while the airdrop charges one.
+    #[account(
+        mut,
+        address = airdrop_state.fee_treasury
+    )]
+    pub fee_treasury: Option<SystemAccount<'info>>,
+    /// Registry granting roles to keys other than the authorities, only needed to act with one.
+    #[account(constraint = admin_registry.airdrop_state == airdrop_state.key() @ WhitelistError::AdminRegistryMismatch)]
+    pub admin_registry: Option<Account<'info, AdminRegistry>>,
+    #[account(
+        constraint = airdrop_state.has_role(admin_registry.as_deref(), &update_authority.key(), ROLE_UPDATER) @ WhitelistError::MissingRole
+    )]
+    pub update_authority: Signer<'info>,
+    /// Pays the claim fee and the recipients' token accounts that do not exist yet, which are
+    /// skipped without one.
+    #[account(mut)]
+    pub payer: Option<Signer<'info>>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+impl<'info> ClaimForMany<'info> {
+    fn claim_gates(&self) -> ClaimGates<'_, 'info> {
+        ClaimGates {
+            payer: self.payer.as_ref(),
+            fee_treasury: self.fee_treasury.as_ref(),
+            system_program: Some(&self.system_program),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(amount: u64, hashes: Vec<u8>, index: u64)]
+#[event_cpi]
+pub struct ClaimFor<'info> {
+    #[account(
+        mut,
+        has_one = mint,
+        seeds = [b"merkle_tree".as_ref(), mint.key().to_bytes().as_ref(), AirdropState::id_seed(airdrop_state.airdrop_id).as_slice()],
+        bump = airdrop_state.bump,
+        constraint = airdrop_state.version == STATE_VERSION @ WhitelistError::UnsupportedStateVersion
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = airdrop_state,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: Only ever receives tokens, the proof binds it to the allocation
+    pub recipient: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = recipient,
+        associated_token::token_program = token_program,
+    )]
+    pub recipient_ata: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"bitmap".as_ref(), airdrop_state.key().to_bytes().as_ref(), ClaimBitmap::shard_for(index).to_le_bytes().as_ref()],
+        bump = claim_bitmap.load()?.bump
+    )]
+    pub claim_bitmap: AccountLoader<'info, ClaimBitmap>,
+    /// CHECK: Revocation marker of the claimed index, only needed while the airdrop has revoked
+    /// leaves, and empty unless the leaf is revoked
+    #[account(
+        seeds = [b"revoked".as_ref(), airdrop_state.key().to_bytes().as_ref(), index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub revoked_leaf: Option<UncheckedAccount<'info>>,
+    /// Token account of the fee recipient, only needed while the airdrop charges a fee.
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = airdrop_state.fee_recipient,
+        associated_token::token_program = token_program,
+    )]
+    pub fee_ata: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// Receives the claim fee in lamports, only needed while the airdrop charges one.
+    #[account(
+        mut,
+        address = airdrop_state.fee_treasury
+    )]
+    pub fee_treasury: Option<SystemAccount<'info>>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+impl<'info> ClaimFor<'info> {
+    fn claim_accounts<'a>(&'a mut self, remaining_accounts: &'a [AccountInfo<'info>]) -> ClaimAccounts<'a, 'info> {
+        ClaimAccounts {
+            airdrop_state: &mut self.airdrop_state,
+            mint: &self.mint,
+            vault: &self.vault,
+            destination: &self.recipient_ata,
+            fee_destination: self.fee_ata.as_ref(),
+            claim_bitmap: &self.claim_bitmap,
+            canopy: None,
+            token_program: &self.token_program,
+            remaining_accounts,
+        }
+    }
+
+    fn claim_gates(&self) -> ClaimGates<'_, 'info> {
+        ClaimGates {
+            payer: Some(&self.payer),
+            fee_treasury: self.fee_treasury.as_ref(),
+            revoked_leaf: self.revoked_leaf.as_ref(),
+            system_program: Some(&self.system_program),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(amount: u64, hashes: Vec<u8>, index: u64)]
+#[event_cpi]
+pub struct ClaimToAccount<'info> {
+    #[account(
+        mut,
+        has_one = mint,
+        seeds = [b"merkle_tree".as_ref(), mint.key().to_bytes().as_ref(), AirdropState::id_seed(airdrop_state.airdrop_id).as_slice()],
+        bump = airdrop_state.bump,
+        constraint = airdrop_state.version == STATE_VERSION @ WhitelistError::UnsupportedStateVersion
+    )]
+    pub airdrop_state: Account<'info, AirdropState>,
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = airdrop_state,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program,
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"bitmap".as_ref(), airdrop_state.key().to_bytes().as_ref(), ClaimBitmap::shard_for(index).to_le_bytes().as_ref()],
+        bump = claim_bitmap.load()?.bump
+    )]
+    pub claim_bitmap: AccountLoader<'info, ClaimBitmap>,
+    /// CHECK: Revocation marker of the claimed index, only needed while the airdrop has revoked
+    /// leaves, and empty unless the leaf is revoked
+    #[account(
+        seeds = [b"revoked".as_ref(), airdrop_state.key().to_bytes().as_ref(), index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub revoked_leaf: Option<UncheckedAccount<'info>>,
+    /// Token account of the fee recipient, only needed while the airdrop charges a fee.
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = airdrop_state.fee_recipient,
+        associated_token::token_program = token_program,
+    )]
+    pub fee_ata: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// CHECK: The hook program of the airdrop, only needed while it has one
+    pub hook_program: Option<UncheckedAccount<'info>>,
+    /// CHECK: The PDA signing the call of the hook program, checked by `call_claim_hook`
+    pub hook_authority: Option<UncheckedAccount<'info>>,
+    pub claimant: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> ClaimToAccount<'info> {
+    fn claim_accounts<'a>(&'a mut self, remaining_accounts: &'a [AccountInfo<'info>]) -> ClaimAccounts<'a, 'info> {
+        ClaimAccounts {
+            airdrop_state: &mut self.airdrop_state,
+            mint: &self.mint,
+            vault: &self.vault,
+            destination: &self.destination,
+            fee_destination: self.fee_ata.as_ref(),
+            claim_bitmap: &self.claim_bitmap,
+            canopy: None,
+            token_program: &self.token_program,
+            remaining_accounts,
+        }
+    }
+
+    fn claim_gates(&self) -> ClaimGates<'_, 'info> {
+        ClaimGates {
+            claimant: Some(&self.claimant),
+            hook_program: self.hook_program.as_ref(),
+            revoked_leaf: self.revoked_leaf.as_ref(),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Accounts)]
+#[event_cpi]
+pub struct Release<'info> {
+    #[account(
+        mut,
+        has_one = mint,
+        seeds = [b"merkle_tree".as_ref(), mint.key().to_bytes().as_ref(), AirdropState::id_seed(airdrop_state.airdrop_id).as_slice()],
+        bump = airdrop_state.bump,
+        constraint = airdrop_state.version == STATE_VERSION @ WhitelistError::UnsupportedStateVersion
     )]
     pub airdrop_state: Account<'info, AirdropState>,
-    pub mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [b"vesting_config".as_ref(), airdrop_state.key().to_bytes().as_ref()],
+        bump = vesting_config.bump
+    )]
+    pub vesting_config: Account<'info, VestingConfig>,
+    #[account(
+        mut,
+        has_one = airdrop_state,
+        has_one = beneficiary,
+        seeds = [b"vesting".as_ref(), airdrop_state.key().to_bytes().as_ref(), vesting_account.index.to_le_bytes().as_ref()],
+        bump = vesting_account.bump
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
     #[account(
         mut,
         associated_token::mint = mint,
         associated_token::authority = airdrop_state,
+        associated_token::token_program = token_program,
     )]
-    pub vault: Account<'info, TokenAccount>,
+    pub vault: InterfaceAccount<'info, TokenAccount>,
     #[account(
         init_if_needed,
-        payer = signer,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = beneficiary,
+        associated_token::token_program = token_program,
+    )]
+    pub beneficiary_ata: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: Only ever receives tokens, bound to the vesting account by `has_one`
+    pub beneficiary: UncheckedAccount<'info>,
+    /// Token account of the fee recipient, only needed while the airdrop charges a fee.
+    #[account(
+        mut,
         associated_token::mint = mint,
-        associated_token::authority = signer,
+        associated_token::authority = airdrop_state.fee_recipient,
+        associated_token::token_program = token_program,
     )]
-    pub signer_ata: Account<'info, TokenAccount>,
+    pub fee_ata: Option<InterfaceAccount<'info, TokenAccount>>,
     #[account(mut)]
-    pub signer: Signer<'info>,
+    pub payer: Signer<'info>,
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
+impl<'info> Release<'info> {
+    fn payout_accounts<'a>(&'a mut self, remaining_accounts: &'a [AccountInfo<'info>]) -> PayoutAccounts<'a, 'info> {
+        PayoutAccounts {
+            airdrop_state: &mut self.airdrop_state,
+            mint: &self.mint,
+            vault: &self.vault,
+            destination: &self.beneficiary_ata,
+            fee_destination: self.fee_ata.as_ref(),
+            token_program: &self.token_program,
+            remaining_accounts,
+        }
+    }
+}
+
 #[account]
+#[derive(InitSpace)]
 pub struct AirdropState {
+    /// Layout version of the account, `STATE_VERSION` once current.
+    pub version: u8,
+    /// Tells airdrops of the same mint apart, part of the seeds of the state.
+    pub airdrop_id: u64,
+    /// Name shown by indexers and explorers, at most `MAX_LABEL_LEN` bytes.
+    #[max_len(MAX_LABEL_LEN)]
+    pub label: String,
+    /// Where the allocation list committed to by the root is published, frozen with the root.
+    #[max_len(MAX_METADATA_URI_LEN)]
+    pub metadata_uri: String,
     pub merkle_root: [u8; 32],
-    pub authority: Pubkey,
+    /// Bumped on every root change so outdated proofs are told apart from invalid ones.
+    pub root_version: u64,
+    /// Roots replaced most recently, still accepted for `root_grace_slots` after replacement.
+    pub root_history: [HistoricalRoot; 4],
+    pub root_grace_slots: u64,
+    /// Algorithm the tree is hashed with, one of the `HASHING_ALGORITHM_*` discriminants.
+    pub hashing_algorithm: u8,
+    /// One of the `LEAF_HASHING_*` discriminants, double hashing matches OpenZeppelin-style trees.
+    pub leaf_hashing: u8,
+    /// One of the `PAIR_ORDERING_*` discriminants.
+    pub pair_ordering: u8,
+    /// One of the `LEAF_VERSION_*` discriminants.
+    pub leaf_version: u8,
+    /// One of the `CLAIM_MODE_*` discriminants, fixed once claims started.
+    pub claim_mode: u8,
+    /// One of the `TREE_MODE_*` discriminants, where the tree the claims verify against lives.
+    pub tree_mode: u8,
+    /// Concurrent tree of the airdrop in `TREE_MODE_CONCURRENT`, unused in `TREE_MODE_ROOT`.
+    pub concurrent_tree: Pubkey,
+    /// Update authority, managing the root and every setting of the airdrop.
+    pub update_authority: Pubkey,
+    /// Nominated successor, who has to accept before the update authority changes.
+    pub pending_update_authority: Option<Pubkey>,
+    /// Only key that can move unclaimed funds out of the vault or close the airdrop, the
+    /// initializer until it hands it over with `nominate_withdraw_authority`.
+    pub withdraw_authority: Pubkey,
+    /// Nominated successor, who has to accept before the withdraw authority changes.
+    pub pending_withdraw_authority: Option<Pubkey>,
+    pub mint: Pubkey,
+    pub airdrop_amount: u64,
+    pub amount_claimed: u64,
+    /// Vault balance recovered by the withdraw authority once claims closed.
+    pub amount_withdrawn: u64,
+    /// Vault balance destroyed instead of returned to the withdraw authority.
+    pub amount_burned: u64,
+    pub num_leaves: u64,
+    /// Number of claims so far, each leaf index can only be claimed once.
+    pub num_claims: u64,
+    /// Rounds created so far, the next round gets this number.
+    pub num_rounds: u64,
+    /// Allocations reserved by all rounds, within `airdrop_amount`.
+    pub round_allocations: u64,
+    /// Wallets the airdrop is meant for, informational only, 0 if not given.
+    pub expected_recipients: u64,
+    pub decimals: u8,
+    pub mint_on_claim: bool,
+    /// Unix timestamp claims open at, 0 makes the airdrop claimable right away.
+    pub start_time: i64,
+    /// Unix timestamp claims close after, 0 keeps the airdrop open indefinitely.
+    pub end_time: i64,
+    /// Seconds a proposed root waits before activation, 0 lets the authority update it directly.
+    pub root_timelock: i64,
+    pub pending_root: Option<[u8; 32]>,
+    /// Leaf count of `pending_root`, applied along with it.
+    pub pending_num_leaves: u64,
+    pub pending_root_activates_at: i64,
+    /// Share of every payout paid to `fee_recipient` instead, in basis points, only ever lowered.
+    pub fee_bps: u16,
+    /// Owner of the token account fees are paid into, unused while `fee_bps` is 0.
+    pub fee_recipient: Pubkey,
+    /// Lamports the payer of every claim pays `fee_treasury` on top, only ever lowered.
+    pub claim_fee_lamports: u64,
+    /// System account claim fees in lamports are paid into, unused while they are 0.
+    pub fee_treasury: Pubkey,
+    /// Mint the payer of every claim pays `fee_amount` of into the ATA of `fee_treasury`.
+    pub fee_mint: Option<Pubkey>,
+    pub fee_amount: u64,
+    /// Mint of the tickets every claim burns `ticket_cost` of from the claimant.
+    pub ticket_mint: Option<Pubkey>,
+    pub ticket_cost: u64,
+    /// Verified collection the claimant has to hold an NFT of to claim.
+    pub gate_collection: Option<Pubkey>,
+    /// Backend key every claim has to be co-signed by, on top of the claimant.
+    pub cosigner: Option<Pubkey>,
+    /// Leaves revoked by the authority, claims need to show theirs is not while any are.
+    pub num_revoked: u64,
+    /// Most a single claim may be for, 0 for no cap. Set by the authority against a bad tree.
+    pub max_claim_amount: u64,
+    /// Program every claim notifies over CPI once paid, see `call_claim_hook`.
+    pub hook_program: Option<Pubkey>,
+    /// Emergency brake on claims, authority instructions keep working while set.
+    pub paused: bool,
+    /// Set once the root is locked in for good, `update_tree` fails from then on.
+    pub is_finalized: bool,
+    pub bump: u8,
+}
+
+impl AirdropState {
+    pub const ROOT_HISTORY_LEN: usize = 4;
+
+    /// State of an airdrop just initialized by `authority`, holding both of its authorities,
+    /// with a single root and nothing claimed yet.
+    pub fn new(
+        params: &AirdropParams,
+        payout: &PayoutParams,
+        authority: Pubkey,
+        mint: Pubkey,
+        decimals: u8,
+        mint_on_claim: bool,
+        bump: u8,
+    ) -> Self {
+        AirdropState {
+            version: STATE_VERSION,
+            airdrop_id: params.airdrop_id,
+            label: params.label.clone(),
+            metadata_uri: params.metadata_uri.clone(),
+            merkle_root: params.merkle_root,
+            root_version: 0,
+            root_history: [HistoricalRoot::default(); Self::ROOT_HISTORY_LEN],
+            root_grace_slots: 0,
+            hashing_algorithm: params.hashing_algorithm,
+            leaf_hashing: params.leaf_hashing,
+            pair_ordering: params.pair_ordering,
+            leaf_version: payout.leaf_version,
+            claim_mode: CLAIM_MODE_EXACT,
+            tree_mode: TREE_MODE_ROOT,
+            concurrent_tree: Pubkey::default(),
+            update_authority: authority,
+            pending_update_authority: None,
+            withdraw_authority: authority,
+            pending_withdraw_authority: None,
+            mint,
+            airdrop_amount: payout.amount,
+            amount_claimed: 0,
+            amount_withdrawn: 0,
+            amount_burned: 0,
+            num_leaves: params.num_leaves,
+            num_rounds: 0,
+            round_allocations: 0,
+            decimals,
+            mint_on_claim,
+            start_time: params.start_time,
+            end_time: params.end_time,
+            root_timelock: params.root_timelock,
+            expected_recipients: params.expected_recipients,
+            num_claims: 0,
+            pending_root: None,
+            pending_num_leaves: 0,
+            pending_root_activates_at: 0,
+            fee_bps: payout.fee_bps,
+            fee_recipient: payout.fee_recipient,
+            claim_fee_lamports: payout.claim_fee_lamports,
+            fee_treasury: payout.fee_treasury,
+            fee_mint: payout.fee_mint,
+            fee_amount: payout.fee_amount,
+            ticket_mint: None,
+            ticket_cost: 0,
+            gate_collection: None,
+            cosigner: payout.cosigner,
+            num_revoked: 0,
+            max_claim_amount: 0,
+            hook_program: None,
+            paused: false,
+            is_finalized: false,
+            bump,
+        }
+    }
+
+    /// Seed of airdrop `airdrop_id` after the mint. Empty for airdrop 0, which keeps the
+    /// address derived from the mint alone.
+    pub fn id_seed(airdrop_id: u64) -> Vec<u8> {
+        if airdrop_id == 0 {
+            Vec::new()
+        } else {
+            airdrop_id.to_le_bytes().to_vec()
+        }
+    }
+
+    /// Replaces the root and its leaf count, keeping the outgoing root in the history ring buffer.
+    pub fn rotate_root(&mut self, new_root: [u8; 32], num_leaves: u64, slot: u64) -> Result<()> {
+        require!(self.tree_mode == TREE_MODE_ROOT, WhitelistError::TreeModeMismatch);
+
+        self.root_history[self.root_version as usize % Self::ROOT_HISTORY_LEN] = HistoricalRoot {
+            root: self.merkle_root,
+            version: self.root_version,
+            replaced_slot: slot,
+        };
+        self.merkle_root = new_root;
+        self.num_leaves = num_leaves;
+        self.root_version = self
+            .root_version
+            .checked_add(1)
+            .ok_or(WhitelistError::OverFlow)?;
+
+        Ok(())
+    }
+
+    /// Layout proofs against any of this airdrop's roots are verified with.
+    pub fn tree_layout(&self) -> TreeLayout {
+        TreeLayout {
+            hashing_algorithm: self.hashing_algorithm,
+            leaf_hashing: self.leaf_hashing,
+            pair_ordering: self.pair_ordering,
+            num_leaves: self.num_leaves,
+        }
+    }
+
+    /// Root a proof for `version` verifies against: the current one, or a historical one
+    /// replaced less than `root_grace_slots` ago.
+    pub fn root_for_version(&self, version: u64, slot: u64) -> Result<[u8; 32]> {
+        require!(self.tree_mode == TREE_MODE_ROOT, WhitelistError::TreeModeMismatch);
+        if version == self.root_version {
+            return Ok(self.merkle_root);
+        }
+
+        let entry = &self.root_history[version as usize % Self::ROOT_HISTORY_LEN];
+        require!(
+            version < self.root_version
+                && entry.version == version
+                && slot.saturating_sub(entry.replaced_slot) < self.root_grace_slots,
+            WhitelistError::StaleProof
+        );
+
+        Ok(entry.root)
+    }
+
+    /// Whether `signer` may act with `role`, as the authority holding it by default or as a
+    /// member `admin_registry` granted it to.
+    pub fn has_role(&self, admin_registry: Option<&AdminRegistry>, signer: &Pubkey, role: u8) -> bool {
+        let holds_by_default = match role {
+            ROLE_WITHDRAWER => *signer == self.withdraw_authority,
+            ROLE_PAUSER => *signer == self.update_authority || *signer == self.withdraw_authority,
+            _ => *signer == self.update_authority,
+        };
+        holds_by_default || admin_registry.is_some_and(|admin_registry| admin_registry.roles_of(signer) & role != 0)
+    }
+
+    /// Whether the deadline has passed, an airdrop without one never ends.
+    pub fn has_ended(&self, now: i64) -> bool {
+        self.end_time != 0 && now > self.end_time
+    }
+
+    /// Fee taken out of a payout of `amount`, `fee_bps` of it rounded down.
+    pub fn claim_fee(&self, amount: u64) -> Result<u64> {
+        let fee = u128::from(amount)
+            .checked_mul(u128::from(self.fee_bps))
+            .ok_or(WhitelistError::OverFlow)?
+            / u128::from(BPS_DENOMINATOR);
+
+        u64::try_from(fee).map_err(|_| error!(WhitelistError::OverFlow))
+    }
+
+    /// Whether a single claim of `amount` stays within `max_claim_amount`, 0 leaving claims
+    /// uncapped.
+    pub fn within_claim_cap(&self, amount: u64) -> bool {
+        self.max_claim_amount == 0 || amount <= self.max_claim_amount
+    }
+
+    pub fn check_claim_amount(&self, amount: u64) -> Result<()> {
+        require!(self.within_claim_cap(amount), WhitelistError::ClaimTooLarge);
+        Ok(())
+    }
+
+    /// Claim fee charged in the fee mint, 0 for airdrops without one.
+    pub fn token_fee(&self) -> u64 {
+        match self.fee_mint {
+            Some(_) => self.fee_amount,
+            None => 0,
+        }
+    }
+
+    /// What the claimant of a payout of `amount` receives once the fee is taken out.
+    pub fn net_of_fee(&self, amount: u64) -> Result<u64> {
+        let fee = self.claim_fee(amount)?;
+
+        Ok(amount.checked_sub(fee).ok_or(WhitelistError::OverFlow)?)
+    }
+}
+
+/// Arguments every initializer takes: who the airdrop is, the tree it is claimed against and
+/// when.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct AirdropParams {
+    pub airdrop_id: u64,
+    pub label: String,
+    pub metadata_uri: String,
+    pub merkle_root: [u8; 32],
+    pub num_leaves: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub root_timelock: i64,
+    pub expected_recipients: u64,
+    pub hashing_algorithm: u8,
+    pub leaf_hashing: u8,
+    pub pair_ordering: u8,
+}
+
+impl AirdropParams {
+    pub fn validate(&self) -> Result<()> {
+        require!(self.label.len() <= MAX_LABEL_LEN, WhitelistError::LabelTooLong);
+        require!(self.metadata_uri.len() <= MAX_METADATA_URI_LEN, WhitelistError::MetadataUriTooLong);
+        check_hashing_algorithm(self.hashing_algorithm)?;
+        require!(self.leaf_hashing <= LEAF_HASHING_DOUBLE, WhitelistError::UnsupportedLeafHashing);
+        require!(self.pair_ordering <= PAIR_ORDERING_INDEXED_PREFIXED, WhitelistError::UnsupportedPairOrdering);
+        Ok(())
+    }
+}
+
+/// What the airdrop pays out and what its claims cost: the amount, the layout of its leaves,
+/// its fees and the cosigner its claims need, if any.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct PayoutParams {
+    pub amount: u64,
+    pub leaf_version: u8,
+    pub fee_bps: u16,
+    pub fee_recipient: Pubkey,
+    pub claim_fee_lamports: u64,
+    pub fee_treasury: Pubkey,
+    pub fee_mint: Option<Pubkey>,
+    pub fee_amount: u64,
+    pub cosigner: Option<Pubkey>,
+}
+
+impl PayoutParams {
+    pub fn validate(&self) -> Result<()> {
+        require!(self.amount > 0, WhitelistError::InvalidAmount);
+        require!(self.leaf_version <= LEAF_VERSION_BASKET, WhitelistError::UnsupportedLeafVersion);
+        require!(u64::from(self.fee_bps) <= BPS_DENOMINATOR, WhitelistError::InvalidFee);
+        require!(
+            self.fee_mint.is_none() || (self.fee_amount > 0 && self.fee_treasury != Pubkey::default()),
+            WhitelistError::InvalidFeeMint
+        );
+        Ok(())
+    }
+}
+
+/// A root that has been replaced, along with the slot it stopped being current.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct HistoricalRoot {
+    pub root: [u8; 32],
+    pub version: u64,
+    pub replaced_slot: u64,
+}
+
+/// What happens to the mint authority of a program-created mint once the vault is funded.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum MintAuthorityMode {
+    Revoke,
+    KeepWithCreator,
+    TransferToPda,
+}
+
+/// Token metadata of a program-created mint, written as Token-2022 extensions or as a
+/// Metaplex metadata account.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct TokenMetadataArgs {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    /// Keeps the airdrop authority able to update the metadata, otherwise it is frozen.
+    pub keep_update_authority: bool,
+}
+
+impl TokenMetadataArgs {
+    /// Size of the `TokenMetadata` TLV entry: type and length header, update authority,
+    /// mint, the three borsh strings and an empty additional metadata vector.
+    pub fn tlv_space(&self) -> usize {
+        8 + 4 + 32 + 32 + (4 + self.name.len()) + (4 + self.symbol.len()) + (4 + self.uri.len()) + 4
+    }
+}
+
+/// One allocation of a `claim_many` batch, proven by the next `proof_len` hashes of the batch.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BatchClaim {
+    pub amount: u64,
+    pub index: u64,
+    pub proof_len: u8,
+}
+
+/// One allocation pushed by `claim_for_many`, proven by the next `proof_len` hashes of the batch.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PushClaim {
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub index: u64,
+    pub proof_len: u8,
+}
+
+/// Why `claim_for_many` left an allocation unpaid.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SkipReason {
+    /// The accounts passed are not the recipient and its associated token account.
+    AccountMismatch,
+    InvalidProof,
+    AlreadyClaimed,
+    /// The recipient has no token account and no payer was given to create it.
+    MissingTokenAccount,
+    FrozenTokenAccount,
+    /// The allocation is above the `max_claim_amount` of the airdrop.
+    ClaimTooLarge,
+    /// The leaf is revoked.
+    Revoked,
+}
+
+/// Return data of the claim instructions, Borsh encoded.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ClaimResult {
+    /// What left the vault or was minted, including any transfer fee.
+    pub amount_transferred: u64,
+    pub claimant: Pubkey,
+    /// `amount_claimed` of the airdrop after this claim.
+    pub amount_claimed: u64,
+    /// Root the airdrop is currently on, which may be newer than the one proven against.
+    pub merkle_root: [u8; 32],
+    /// Escrowed by the claim to vest rather than paid.
+    pub amount_locked: u64,
+}
+
+/// Return data of `verify_eligibility`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct EligibilityResult {
+    /// Whether the proof is valid for the leaf, regardless of claim status.
+    pub eligible: bool,
+    /// Only reported when the bitmap shard of the index is passed.
+    pub already_claimed: bool,
+    /// Keccak hash of the leaf built from the claimant and amount.
+    pub leaf_hash: [u8; 32],
+}
+
+/// Return data of `get_claim_status`. The bitmap only records whether an index was claimed,
+/// the amount and time of the claim come from its `ClaimReceipt`, if the claim opened one.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ClaimStatus {
+    pub index: u64,
+    pub claimed: bool,
+    pub amount: Option<u64>,
+    pub claimed_at: Option<i64>,
+}
+
+/// Amount and time of the claim of one index, the latest one for a cumulative leaf.
+#[account]
+#[derive(InitSpace)]
+pub struct ClaimReceipt {
+    pub airdrop_state: Pubkey,
+    pub index: u64,
+    pub amount: u64,
+    pub claimed_at: i64,
+    pub bump: u8,
+}
+
+/// One round of a multi-round airdrop, claimed against its own root and window out of the
+/// vault of the airdrop.
+#[account]
+#[derive(InitSpace)]
+pub struct RoundState {
+    pub airdrop_state: Pubkey,
+    pub round: u64,
+    pub merkle_root: [u8; 32],
+    pub num_leaves: u64,
+    /// Unix timestamp claims open at, 0 opens the round right away.
+    pub start_time: i64,
+    /// Unix timestamp claims close after, 0 keeps the round open indefinitely.
+    pub end_time: i64,
+    /// Most the round pays out, reserved out of `AirdropState::airdrop_amount`.
+    pub allocation: u64,
+    pub amount_claimed: u64,
+    pub num_claims: u64,
+    pub bump: u8,
+}
+
+/// What a claimant was paid of an allocation claimed in parts, or of their cumulative leaf.
+#[account]
+#[derive(InitSpace)]
+pub struct ClaimProgress {
+    pub airdrop_state: Pubkey,
+    pub claimant: Pubkey,
+    pub claimed: u64,
+    pub bump: u8,
+}
+
+/// Nodes of the tree of an airdrop `height` levels above its leaves, in index order, so claims
+/// can send a proof of only the `height` levels below. Tied to the root it was uploaded for.
+#[account]
+#[derive(InitSpace)]
+pub struct Canopy {
+    pub airdrop_state: Pubkey,
+    pub root: [u8; 32],
+    pub height: u8,
+    #[max_len(MAX_CANOPY_NODES)]
+    pub nodes: Vec<[u8; 32]>,
+    /// Set by `seal_canopy` once the nodes hash up to `root`.
+    pub is_sealed: bool,
+    pub bump: u8,
+}
+
+impl Canopy {
+    /// Whether a proof of `hashes` against `root` stops at this canopy.
+    pub fn covers(&self, root: &[u8; 32], hashes: &[u8]) -> bool {
+        self.is_sealed && self.root == *root && hashes.len() == usize::from(self.height) * HASH_SIZE
+    }
+}
+
+/// Roles of an airdrop granted to keys besides its authorities, by a super-admin that starts
+/// as the authority and is handed over in two steps.
+#[account]
+#[derive(InitSpace)]
+pub struct AdminRegistry {
+    pub airdrop_state: Pubkey,
+    pub super_admin: Pubkey,
+    pub pending_super_admin: Option<Pubkey>,
+    #[max_len(MAX_ROLE_MEMBERS)]
+    pub members: Vec<RoleGrant>,
+    pub bump: u8,
+}
+
+impl AdminRegistry {
+    /// `ROLE_*` bits granted to `member`, 0 for keys without a grant.
+    pub fn roles_of(&self, member: &Pubkey) -> u8 {
+        self.members
+            .iter()
+            .find(|grant| grant.member == *member)
+            .map_or(0, |grant| grant.roles)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct RoleGrant {
+    pub member: Pubkey,
+    pub roles: u8,
+}
+
+/// Mints a `LEAF_VERSION_BASKET` airdrop pays on top of its own, in the order their amounts
+/// follow the airdrop mint's in a leaf.
+#[account]
+#[derive(InitSpace)]
+pub struct Basket {
+    pub airdrop_state: Pubkey,
+    #[max_len(MAX_BASKET_MINTS)]
+    pub mints: Vec<BasketMint>,
+    pub bump: u8,
+}
+
+/// A mint of a `Basket`, accounted for like `AirdropState` accounts for the airdrop mint.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct BasketMint {
     pub mint: Pubkey,
+    /// Funded into the vault of the mint, the most claims can pay of it.
     pub airdrop_amount: u64,
     pub amount_claimed: u64,
+    pub amount_withdrawn: u64,
+}
+
+/// Staking program `claim_and_stake` deposits the claims of an airdrop into.
+#[account]
+#[derive(InitSpace)]
+pub struct StakeConfig {
+    pub airdrop_state: Pubkey,
+    pub stake_program: Pubkey,
+    /// Accounts the deposit takes after the ones of the claim, see `deposit_stake`.
+    #[max_len(MAX_STAKE_ACCOUNTS)]
+    pub accounts: Vec<StakeAccountMeta>,
+    pub bump: u8,
+}
+
+/// An account the deposit of a staking program takes, never a signer.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct StakeAccountMeta {
+    pub pubkey: Pubkey,
+    pub is_writable: bool,
+}
+
+/// Claims crediting `referrer` in an airdrop and what they claimed in total.
+#[account]
+#[derive(InitSpace)]
+pub struct ReferralStats {
+    pub airdrop_state: Pubkey,
+    pub referrer: Pubkey,
+    pub claims: u64,
+    pub volume: u64,
+    pub bump: u8,
+}
+
+/// Marks the leaf at `index` revoked by the authority for as long as it exists.
+#[account]
+#[derive(InitSpace)]
+pub struct RevokedLeaf {
+    pub airdrop_state: Pubkey,
+    pub index: u64,
+    pub bump: u8,
+}
+
+impl RevokedLeaf {
+    /// Address of the marker of the leaf at `index` of `airdrop_state`, revoked or not.
+    pub fn address(airdrop_state: &Pubkey, index: u64) -> Pubkey {
+        Pubkey::find_program_address(&[b"revoked".as_ref(), airdrop_state.as_ref(), index.to_le_bytes().as_ref()], &crate::ID).0
+    }
+}
+
+/// Early-claim bonus of an airdrop and the reserve it is paid out of.
+#[account]
+#[derive(InitSpace)]
+pub struct BonusConfig {
+    pub airdrop_state: Pubkey,
+    /// Bonus on top of a leaf amount, in basis points of it.
+    pub bonus_bps: u16,
+    /// Unix timestamp claims stop earning a bonus after.
+    pub bonus_window_end: i64,
+    /// Deposited into the vault for bonuses, on top of `AirdropState::airdrop_amount`.
+    pub bonus_reserve: u64,
+    pub bonus_paid: u64,
+    pub bump: u8,
+}
+
+impl BonusConfig {
+    /// Bonus earned by a claim of `amount` at `now`, `bonus_bps` of it within the window and
+    /// nothing after, capped by what is left of the reserve. Records it as paid.
+    pub fn take_bonus(&mut self, amount: u64, now: i64) -> Result<u64> {
+        if now > self.bonus_window_end {
+            return Ok(0);
+        }
+
+        let bonus = u128::from(amount)
+            .checked_mul(u128::from(self.bonus_bps))
+            .ok_or(WhitelistError::OverFlow)?
+            / u128::from(BPS_DENOMINATOR);
+        let remaining = self
+            .bonus_reserve
+            .checked_sub(self.bonus_paid)
+            .ok_or(WhitelistError::OverFlow)?;
+        let bonus = u64::try_from(bonus).unwrap_or(u64::MAX).min(remaining);
+
+        self.bonus_paid = self
+            .bonus_paid
+            .checked_add(bonus)
+            .ok_or(WhitelistError::OverFlow)?;
+        Ok(bonus)
+    }
+}
+
+/// Decay schedule of an airdrop in `CLAIM_MODE_DECAYING`.
+#[account]
+#[derive(InitSpace)]
+pub struct DecayConfig {
+    pub airdrop_state: Pubkey,
+    /// Seconds a leaf takes to decay to nothing.
+    pub decay_duration: i64,
+    /// Unix timestamp the schedule was configured at, decay runs from it when the airdrop has
+    /// no `start_time`.
+    pub configured_at: i64,
+    /// Decayed off the leaves claimed so far, left in the vault for the authority to withdraw.
+    pub total_forfeited: u64,
+    pub bump: u8,
+}
+
+impl DecayConfig {
+    /// What is left at `now` of a leaf of `amount`: all of it until the airdrop starts, then
+    /// linearly less down to nothing once `decay_duration` passed.
+    pub fn claimable_amount(&self, airdrop_state: &AirdropState, amount: u64, now: i64) -> Result<u64> {
+        let decay_start = match airdrop_state.start_time {
+            0 => self.configured_at,
+            start_time => start_time,
+        };
+        let elapsed = now.saturating_sub(decay_start);
+        if elapsed <= 0 {
+            return Ok(amount);
+        }
+        if elapsed >= self.decay_duration {
+            return Ok(0);
+        }
+
+        let claimable = u128::from(amount)
+            .checked_mul((self.decay_duration - elapsed) as u128)
+            .ok_or(WhitelistError::OverFlow)?
+            / self.decay_duration as u128;
+        u64::try_from(claimable).map_err(|_| error!(WhitelistError::OverFlow))
+    }
+}
+
+/// Vesting schedule of an airdrop in `CLAIM_MODE_VESTING`.
+#[account]
+#[derive(InitSpace)]
+pub struct VestingConfig {
+    pub airdrop_state: Pubkey,
+    /// Unix timestamp escrows start vesting at, 0 starts every escrow at its claim.
+    pub vesting_start: i64,
+    /// Seconds an escrow takes to vest in full.
+    pub vesting_duration: i64,
+    /// Seconds into the schedule before anything is released, vesting then catches up on them.
+    pub cliff_seconds: i64,
+    /// Escrowed by claims and not released yet, reserved out of `AirdropState::airdrop_amount`
+    /// on top of what was paid.
+    pub amount_vesting: u64,
+    pub bump: u8,
+}
+
+impl VestingConfig {
+    /// How much of `vesting_account` has vested at `now`: nothing before the cliff, the share
+    /// of the time elapsed from the start from the cliff on, then all of it once the schedule
+    /// ran its course, so the last release sweeps what rounding held back.
+    pub fn vested_amount(&self, vesting_account: &VestingAccount, now: i64) -> Result<u64> {
+        let elapsed = now.saturating_sub(vesting_account.start_time);
+        if elapsed <= 0 || elapsed < self.cliff_seconds {
+            return Ok(0);
+        }
+        if elapsed >= self.vesting_duration {
+            return Ok(vesting_account.total);
+        }
+
+        let vested = u128::from(vesting_account.total)
+            .checked_mul(elapsed as u128)
+            .ok_or(WhitelistError::OverFlow)?
+            / self.vesting_duration as u128;
+        u64::try_from(vested).map_err(|_| error!(WhitelistError::OverFlow))
+    }
+}
+
+/// The amount of a claimed leaf, held in the vault and released to its beneficiary as it vests.
+#[account]
+#[derive(InitSpace)]
+pub struct VestingAccount {
+    pub airdrop_state: Pubkey,
+    pub beneficiary: Pubkey,
+    /// Leaf index the escrow was claimed for.
+    pub index: u64,
+    pub total: u64,
+    pub released: u64,
+    /// Unix timestamp the escrow started vesting at.
+    pub start_time: i64,
+    pub bump: u8,
+}
+
+/// One shard of the claimed-index bitmap, covering `LEAVES_PER_SHARD` consecutive leaves.
+#[account(zero_copy)]
+pub struct ClaimBitmap {
+    /// Airdrop the shard tracks claims of, or the round for shards of a round.
+    pub airdrop_state: Pubkey,
+    pub shard_index: u64,
     pub bump: u8,
+    pub _padding: [u8; 7],
+    pub bits: [u8; 1024],
+}
+
+impl ClaimBitmap {
+    pub const SHARD_BYTES: usize = 1024;
+    pub const LEAVES_PER_SHARD: u64 = 8 * Self::SHARD_BYTES as u64;
+
+    pub fn shard_for(index: u64) -> u64 {
+        index / Self::LEAVES_PER_SHARD
+    }
+
+    fn position(index: u64) -> (usize, u8) {
+        let offset = index % Self::LEAVES_PER_SHARD;
+        ((offset / 8) as usize, 1u8 << (offset % 8))
+    }
+
+    pub fn is_claimed(&self, index: u64) -> bool {
+        let (byte, mask) = Self::position(index);
+        self.bits[byte] & mask != 0
+    }
+
+    pub fn set_claimed(&mut self, index: u64) -> Result<()> {
+        require!(
+            Self::shard_for(index) == self.shard_index,
+            WhitelistError::IndexOutOfRange
+        );
+        require!(!self.is_claimed(index), WhitelistError::AlreadyClaimed);
+
+        let (byte, mask) = Self::position(index);
+        self.bits[byte] |= mask;
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct AirdropInitialized {
+    pub airdrop_state: Pubkey,
+    pub airdrop_id: u64,
+    pub label: String,
+    pub metadata_uri: String,
+    pub authority: Pubkey,
+    pub mint: Pubkey,
+    pub merkle_root: [u8; 32],
+    pub airdrop_amount: u64,
+    pub num_leaves: u64,
+}
+
+#[event]
+pub struct ClaimEvent {
+    pub claimer: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub index: u64,
+    pub total_claimed: u64,
+    pub num_claims: u64,
+    /// Version of the root the claim was proven against.
+    pub root_version: u64,
+    /// Claimed of the allocation so far including this claim, `amount` unless claimed in parts.
+    pub allocation_claimed: u64,
+    /// Round claimed in, `None` for claims against the root of the airdrop itself.
+    pub round: Option<u64>,
+    /// Escrowed by the claim to vest, on top of `amount` paid right away.
+    pub amount_locked: u64,
+    /// Early-claim bonus paid on top of `amount` out of the bonus reserve.
+    pub bonus: u64,
+    /// Taken out of `amount` for the fee recipient.
+    pub fee: u64,
+    /// Paid to the claimant of `amount`, what is left of it after the fee.
+    pub net_amount: u64,
+    /// Mint the claim fee was charged in, `None` for claims charged none.
+    pub token_fee_mint: Option<Pubkey>,
+    /// Claim fee charged in `token_fee_mint`.
+    pub token_fee: u64,
+    /// Community referrer the claimant credited with the claim.
+    pub referrer: Option<Pubkey>,
+}
+
+/// Payout of one mint of a basket claim, the airdrop mint's being a `ClaimEvent`.
+#[event]
+pub struct BasketClaimed {
+    pub airdrop_state: Pubkey,
+    pub claimer: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub index: u64,
+    /// Claimed of the mint so far including this claim.
+    pub total_claimed: u64,
+}
+
+#[event]
+pub struct VestingCreated {
+    pub airdrop_state: Pubkey,
+    pub vesting_account: Pubkey,
+    pub beneficiary: Pubkey,
+    pub index: u64,
+    pub total: u64,
+    pub start_time: i64,
+}
+
+#[event]
+pub struct VestingReleased {
+    pub vesting_account: Pubkey,
+    pub beneficiary: Pubkey,
+    /// What left the vault, including any transfer fee.
+    pub amount: u64,
+    pub released: u64,
+    pub total: u64,
+}
+
+#[event]
+pub struct ClaimSkipped {
+    pub recipient: Pubkey,
+    pub index: u64,
+    pub reason: SkipReason,
+}
+
+#[event]
+pub struct MetadataUriUpdated {
+    pub airdrop_state: Pubkey,
+    pub metadata_uri: String,
+}
+
+#[event]
+pub struct RootUpdated {
+    pub airdrop_state: Pubkey,
+    pub authority: Pubkey,
+    pub old_root: [u8; 32],
+    pub new_root: [u8; 32],
+    pub root_version: u64,
+    pub slot: u64,
+    pub unix_timestamp: i64,
+}
+
+#[event]
+pub struct RootForceUpdated {
+    pub airdrop_state: Pubkey,
+    pub old_root: [u8; 32],
+    pub new_root: [u8; 32],
+    pub root_version: u64,
+    pub amount_claimed: u64,
+}
+
+#[event]
+pub struct RootFinalized {
+    pub airdrop_state: Pubkey,
+    pub merkle_root: [u8; 32],
+}
+
+#[event]
+pub struct LeafRevoked {
+    pub airdrop_state: Pubkey,
+    pub index: u64,
+}
+
+#[event]
+pub struct LeafUnrevoked {
+    pub airdrop_state: Pubkey,
+    pub index: u64,
+}
+
+#[event]
+pub struct DeadlineExtended {
+    pub airdrop_state: Pubkey,
+    pub old_end_time: i64,
+    pub new_end_time: i64,
+}
+
+#[event]
+pub struct ToppedUp {
+    pub airdrop_state: Pubkey,
+    pub amount: u64,
+    pub vault_balance: u64,
+}
+
+#[event]
+pub struct UnclaimedWithdrawn {
+    pub airdrop_state: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct UnclaimedBurned {
+    pub airdrop_state: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct RoleGranted {
+    pub airdrop_state: Pubkey,
+    pub member: Pubkey,
+    pub role: u8,
+}
+
+#[event]
+pub struct RoleRevoked {
+    pub airdrop_state: Pubkey,
+    pub member: Pubkey,
+    pub role: u8,
 }
 
 #[error_code]
@@ -232,4 +7713,218 @@ pub enum WhitelistError {
     AlreadyClaimed,
     #[msg("Amount overflow")]
     OverFlow,
+    #[msg("Leaf index out of range")]
+    IndexOutOfRange,
+    #[msg("Insufficient funds to fund the airdrop")]
+    InsufficientFunds,
+    #[msg("Mint decimals exceed the maximum")]
+    InvalidDecimals,
+    #[msg("Mint authority is not held by the airdrop")]
+    MintAuthorityNotHeld,
+    #[msg("Mint-on-claim requires the mint authority to move to the airdrop")]
+    InvalidAuthorityMode,
+    #[msg("Claim would exceed the airdrop mint cap")]
+    MintCapExceeded,
+    #[msg("Transfer fee makes the exact claim amount unreachable")]
+    TransferFeeUnreachable,
+    #[msg("Token metadata field too long")]
+    MetadataTooLong,
+    #[msg("Metadata accounts are required to create token metadata")]
+    MissingMetadataAccounts,
+    #[msg("Airdrop has not started yet")]
+    AirdropNotStarted,
+    #[msg("Start time can only be moved earlier")]
+    StartTimeNotEarlier,
+    #[msg("Airdrop has ended")]
+    AirdropEnded,
+    #[msg("Deadline can only be extended")]
+    DeadlineNotExtended,
+    #[msg("Claims are still open")]
+    ClaimsStillOpen,
+    #[msg("Vault still holds tokens")]
+    VaultNotEmpty,
+    #[msg("Claims are paused")]
+    ClaimsPaused,
+    #[msg("Signer is not the nominated authority")]
+    NotPendingAuthority,
+    #[msg("Merkle tree is finalized")]
+    TreeFinalized,
+    #[msg("Claims have already started, the root can only be force updated")]
+    ClaimsAlreadyStarted,
+    #[msg("Proof was generated against an outdated merkle root")]
+    StaleProof,
+    #[msg("Root changes must be proposed and activated through the timelock")]
+    RootTimelocked,
+    #[msg("No root is pending activation")]
+    NoPendingRoot,
+    #[msg("Root timelock has not elapsed")]
+    TimelockNotElapsed,
+    #[msg("Claim would exceed the funded airdrop amount")]
+    AllocationExceeded,
+    #[msg("Amount must be greater than zero")]
+    InvalidAmount,
+    #[msg("Proof must be a non-empty sequence of 32-byte hashes")]
+    MalformedProof,
+    #[msg("Proof is deeper than the maximum supported tree depth")]
+    ProofTooLong,
+    #[msg("Vault does not hold enough tokens for this claim")]
+    VaultInsufficientFunds,
+    #[msg("Hashing algorithm is not supported")]
+    UnsupportedHashingAlgorithm,
+    #[msg("Leaf hashing mode is not supported")]
+    UnsupportedLeafHashing,
+    #[msg("Pair ordering is not supported")]
+    UnsupportedPairOrdering,
+    #[msg("Leaf version is not supported")]
+    UnsupportedLeafVersion,
+    #[msg("Leaves of this airdrop commit to extra data, claim with its hash")]
+    LeafDataHashRequired,
+    #[msg("Destination is not owned by the claimant")]
+    DestinationOwnerMismatch,
+    #[msg("Batch must hold between one and MAX_BATCH_CLAIMS claims")]
+    InvalidBatchSize,
+    #[msg("Expected a recipient and its token account for every claim")]
+    RecipientAccountsMismatch,
+    #[msg("Claim mode is not supported")]
+    UnsupportedClaimMode,
+    #[msg("Claim mode can only change before the first claim")]
+    ClaimModeLocked,
+    #[msg("Airdrop does not take this kind of claim in its claim mode")]
+    ClaimModeMismatch,
+    #[msg("Claim exceeds what is left of the allocation")]
+    AllocationExhausted,
+    #[msg("Cumulative claims need the claim progress account of the claimant")]
+    MissingClaimProgress,
+    #[msg("Round allocations exceed the airdrop amount")]
+    RoundAllocationExceeded,
+    #[msg("Label is longer than MAX_LABEL_LEN bytes")]
+    LabelTooLong,
+    #[msg("Metadata URI is longer than MAX_METADATA_URI_LEN bytes")]
+    MetadataUriTooLong,
+    #[msg("Airdrop state is not in the layout this program version reads, migrate it first")]
+    UnsupportedStateVersion,
+    #[msg("Vesting needs a positive duration, a start that is not negative and a cliff within the duration")]
+    InvalidVestingSchedule,
+    #[msg("Vesting claims need the vesting config and vesting account")]
+    MissingVestingAccount,
+    #[msg("Leaves of this airdrop split the amount into an unlocked and a locked part, claim with both")]
+    SplitAmountsRequired,
+    #[msg("Decay needs a positive duration")]
+    InvalidDecaySchedule,
+    #[msg("Decaying claims need the decay config of the airdrop")]
+    MissingDecayConfig,
+    #[msg("Allocation decayed to nothing, there is nothing left to claim")]
+    AllocationDecayed,
+    #[msg("Bonus needs basis points between 1 and BPS_DENOMINATOR and a reserve")]
+    InvalidBonus,
+    #[msg("Not available to airdrops minting on claim")]
+    MintOnClaimUnsupported,
+    #[msg("Leaves of this airdrop carry a deadline, claim with it")]
+    DeadlineRequired,
+    #[msg("Allocation expired at the deadline of its leaf")]
+    AllocationExpired,
+    #[msg("Fee cannot exceed BPS_DENOMINATOR basis points")]
+    InvalidFee,
+    #[msg("Fee can only be lowered")]
+    FeeNotLowered,
+    #[msg("Airdrop charges a fee, claim with the token account of the fee recipient")]
+    MissingFeeAccount,
+    #[msg("Airdrop charges a claim fee in lamports, claim with its fee treasury")]
+    MissingFeeTreasury,
+    #[msg("Payer cannot cover the claim fee")]
+    InsufficientFeeBalance,
+    #[msg("Fee mint needs a fee amount and a fee treasury")]
+    InvalidFeeMint,
+    #[msg("Airdrop charges a claim fee in its fee mint, claim with the fee mint accounts")]
+    MissingFeeMintAccounts,
+    #[msg("Fee accounts are not the fee mint and its ATAs of the payer and fee treasury")]
+    FeeAccountMismatch,
+    #[msg("Claim gates can only change before the first claim")]
+    GateLocked,
+    #[msg("Ticket gate needs a ticket cost")]
+    InvalidTicketCost,
+    #[msg("Airdrop is ticket gated, claim with the ticket mint and the claimant's ticket account")]
+    MissingTicketAccount,
+    #[msg("Ticket accounts are not the ticket mint and a token account of the claimant in it")]
+    TicketAccountMismatch,
+    #[msg("Claimant holds fewer tickets than a claim burns")]
+    InsufficientTickets,
+    #[msg("Airdrop is collection gated, claim with an NFT of the collection and its metadata")]
+    MissingGateNft,
+    #[msg("Claimant does not hold the NFT of the gate token account")]
+    GateNftNotHeld,
+    #[msg("NFT is not of the verified gate collection")]
+    GateCollectionMismatch,
+    #[msg("Claim is not co-signed by the cosigner of the airdrop")]
+    MissingCosigner,
+    #[msg("Claim permit is not signed by the cosigner or does not match the claim")]
+    InvalidPermit,
+    #[msg("Claim permit has expired")]
+    PermitExpired,
+    #[msg("Airdrop has revoked leaves, claim with the revocation account of the index")]
+    MissingRevocationAccount,
+    #[msg("Allocation was revoked by the authority")]
+    AllocationRevoked,
+    #[msg("Claim is above the maximum claim amount of the airdrop")]
+    ClaimTooLarge,
+    #[msg("Claimant cannot refer themselves")]
+    SelfReferral,
+    #[msg("Claim credits a referrer, pass its referral stats")]
+    MissingReferralStats,
+    #[msg("Airdrop notifies a claim hook, claim with its program")]
+    MissingClaimHook,
+    #[msg("Airdrop has no staking program, pass its stake config and program")]
+    MissingStakeConfig,
+    #[msg("Stake accounts do not match the stake config of the airdrop")]
+    StakeAccountMismatch,
+    #[msg("Stake config takes too many accounts")]
+    TooManyStakeAccounts,
+    #[msg("Funding the vault needs the token account of the authority")]
+    MissingAuthorityTokenAccount,
+    #[msg("Only wrapped SOL claims can be unwrapped")]
+    NotNativeMint,
+    #[msg("Unwrapping needs the claimant as the unwrap destination")]
+    MissingUnwrapDestination,
+    #[msg("Leaves of this airdrop carry an amount per mint, claim them with claim_basket")]
+    BasketAmountsRequired,
+    #[msg("Claim pays a basket, pass the basket of the airdrop")]
+    MissingBasket,
+    #[msg("Basket holds the most mints it can")]
+    BasketFull,
+    #[msg("Mint is already paid by the airdrop")]
+    DuplicateBasketMint,
+    #[msg("Basket amounts or accounts do not match the basket of the airdrop")]
+    BasketAccountMismatch,
+    #[msg("Leaves of this airdrop name an NFT mint, claim them with claim_nft")]
+    NftMintRequired,
+    #[msg("Mint is not an NFT, it needs a supply of one and no decimals")]
+    NotAnNft,
+    #[msg("NFT of this leaf was not deposited into its vault")]
+    NftNotDeposited,
+    #[msg("Instruction does not apply to where the tree of this airdrop lives")]
+    TreeModeMismatch,
+    #[msg("Canopy would hold more than MAX_CANOPY_NODES nodes, pick a greater height")]
+    CanopyTooLarge,
+    #[msg("Canopy does not fit these nodes or the current root")]
+    CanopyMismatch,
+    #[msg("Canopy does not hash up to its root")]
+    InvalidCanopy,
+    #[msg("Signer does not hold the role this instruction needs")]
+    MissingRole,
+    #[msg("Role is not a single ROLE_* bit")]
+    InvalidRole,
+    #[msg("Admin registry grants roles to MAX_ROLE_MEMBERS keys already")]
+    RoleMembersFull,
+    #[msg("Signer is not the nominated super-admin")]
+    NotPendingSuperAdmin,
+    #[msg("Admin registry belongs to another airdrop")]
+    AdminRegistryMismatch,
+    #[msg("Pushed claims cannot pay out a mint with a transfer hook")]
+    TransferHookUnsupported,
+    #[msg("Claim hook can only change before the first claim and the tree is finalized")]
+    HookLocked,
+    #[msg("Claims of this airdrop cannot call a claim hook")]
+    ClaimHookUnsupported,
+    #[msg("Vesting claims pay nothing out to unwrap")]
+    NothingToUnwrap,
 }
@@ -0,0 +1,202 @@
+//! Layouts `AirdropState` had before the current one, and how `migrate_state` rewrites accounts
+//! still holding them.
+
+use anchor_lang::prelude::*;
+
+use crate::{AirdropState, HistoricalRoot, MAX_LABEL_LEN, MAX_METADATA_URI_LEN, STATE_VERSION, TREE_MODE_ROOT};
+
+/// `AirdropState` before it carried a version, every field of `AirdropStateV2` but
+/// `version`.
+#[derive(AnchorDeserialize, InitSpace)]
+pub struct AirdropStateV1 {
+    pub airdrop_id: u64,
+    #[max_len(MAX_LABEL_LEN)]
+    pub label: String,
+    #[max_len(MAX_METADATA_URI_LEN)]
+    pub metadata_uri: String,
+    pub merkle_root: [u8; 32],
+    pub root_version: u64,
+    pub root_history: [HistoricalRoot; 4],
+    pub root_grace_slots: u64,
+    pub hashing_algorithm: u8,
+    pub leaf_hashing: u8,
+    pub pair_ordering: u8,
+    pub leaf_version: u8,
+    pub claim_mode: u8,
+    pub authority: Pubkey,
+    pub pending_authority: Option<Pubkey>,
+    pub mint: Pubkey,
+    pub airdrop_amount: u64,
+    pub amount_claimed: u64,
+    pub amount_withdrawn: u64,
+    pub amount_burned: u64,
+    pub num_leaves: u64,
+    pub num_claims: u64,
+    pub num_rounds: u64,
+    pub round_allocations: u64,
+    pub expected_recipients: u64,
+    pub decimals: u8,
+    pub mint_on_claim: bool,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub root_timelock: i64,
+    pub pending_root: Option<[u8; 32]>,
+    pub pending_num_leaves: u64,
+    pub pending_root_activates_at: i64,
+    pub paused: bool,
+    pub is_finalized: bool,
+    pub bump: u8,
+}
+
+impl AirdropStateV1 {
+    /// Size of an account holding this layout, discriminator included.
+    pub const SPACE: usize = 8 + Self::INIT_SPACE;
+
+    pub fn into_v2(self) -> AirdropStateV2 {
+        AirdropStateV2 {
+            version: 2,
+            airdrop_id: self.airdrop_id,
+            label: self.label,
+            metadata_uri: self.metadata_uri,
+            merkle_root: self.merkle_root,
+            root_version: self.root_version,
+            root_history: self.root_history,
+            root_grace_slots: self.root_grace_slots,
+            hashing_algorithm: self.hashing_algorithm,
+            leaf_hashing: self.leaf_hashing,
+            pair_ordering: self.pair_ordering,
+            leaf_version: self.leaf_version,
+            claim_mode: self.claim_mode,
+            authority: self.authority,
+            pending_authority: self.pending_authority,
+            mint: self.mint,
+            airdrop_amount: self.airdrop_amount,
+            amount_claimed: self.amount_claimed,
+            amount_withdrawn: self.amount_withdrawn,
+            amount_burned: self.amount_burned,
+            num_leaves: self.num_leaves,
+            num_claims: self.num_claims,
+            num_rounds: self.num_rounds,
+            round_allocations: self.round_allocations,
+            expected_recipients: self.expected_recipients,
+            decimals: self.decimals,
+            mint_on_claim: self.mint_on_claim,
+            start_time: self.start_time,
+            end_time: self.end_time,
+            root_timelock: self.root_timelock,
+            pending_root: self.pending_root,
+            pending_num_leaves: self.pending_num_leaves,
+            pending_root_activates_at: self.pending_root_activates_at,
+            paused: self.paused,
+            is_finalized: self.is_finalized,
+            bump: self.bump,
+        }
+    }
+}
+
+/// `AirdropState` at version 2, every field of the current layout but the fees and gates.
+#[derive(AnchorDeserialize, InitSpace)]
+pub struct AirdropStateV2 {
+    pub version: u8,
+    pub airdrop_id: u64,
+    #[max_len(MAX_LABEL_LEN)]
+    pub label: String,
+    #[max_len(MAX_METADATA_URI_LEN)]
+    pub metadata_uri: String,
+    pub merkle_root: [u8; 32],
+    pub root_version: u64,
+    pub root_history: [HistoricalRoot; 4],
+    pub root_grace_slots: u64,
+    pub hashing_algorithm: u8,
+    pub leaf_hashing: u8,
+    pub pair_ordering: u8,
+    pub leaf_version: u8,
+    pub claim_mode: u8,
+    pub authority: Pubkey,
+    pub pending_authority: Option<Pubkey>,
+    pub mint: Pubkey,
+    pub airdrop_amount: u64,
+    pub amount_claimed: u64,
+    pub amount_withdrawn: u64,
+    pub amount_burned: u64,
+    pub num_leaves: u64,
+    pub num_claims: u64,
+    pub num_rounds: u64,
+    pub round_allocations: u64,
+    pub expected_recipients: u64,
+    pub decimals: u8,
+    pub mint_on_claim: bool,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub root_timelock: i64,
+    pub pending_root: Option<[u8; 32]>,
+    pub pending_num_leaves: u64,
+    pub pending_root_activates_at: i64,
+    pub paused: bool,
+    pub is_finalized: bool,
+    pub bump: u8,
+}
+
+impl AirdropStateV2 {
+    /// Size of an account holding this layout, discriminator included.
+    pub const SPACE: usize = 8 + Self::INIT_SPACE;
+
+    /// Airdrops from before fees and gates charge none and are not gated.
+    pub fn into_current(self) -> AirdropState {
+        AirdropState {
+            version: STATE_VERSION,
+            airdrop_id: self.airdrop_id,
+            label: self.label,
+            metadata_uri: self.metadata_uri,
+            merkle_root: self.merkle_root,
+            root_version: self.root_version,
+            root_history: self.root_history,
+            root_grace_slots: self.root_grace_slots,
+            hashing_algorithm: self.hashing_algorithm,
+            leaf_hashing: self.leaf_hashing,
+            pair_ordering: self.pair_ordering,
+            leaf_version: self.leaf_version,
+            claim_mode: self.claim_mode,
+            tree_mode: TREE_MODE_ROOT,
+            concurrent_tree: Pubkey::default(),
+            update_authority: self.authority,
+            pending_update_authority: self.pending_authority,
+            withdraw_authority: self.authority,
+            pending_withdraw_authority: None,
+            mint: self.mint,
+            airdrop_amount: self.airdrop_amount,
+            amount_claimed: self.amount_claimed,
+            amount_withdrawn: self.amount_withdrawn,
+            amount_burned: self.amount_burned,
+            num_leaves: self.num_leaves,
+            num_claims: self.num_claims,
+            num_rounds: self.num_rounds,
+            round_allocations: self.round_allocations,
+            expected_recipients: self.expected_recipients,
+            decimals: self.decimals,
+            mint_on_claim: self.mint_on_claim,
+            start_time: self.start_time,
+            end_time: self.end_time,
+            root_timelock: self.root_timelock,
+            pending_root: self.pending_root,
+            pending_num_leaves: self.pending_num_leaves,
+            pending_root_activates_at: self.pending_root_activates_at,
+            fee_bps: 0,
+            fee_recipient: Pubkey::default(),
+            claim_fee_lamports: 0,
+            fee_treasury: Pubkey::default(),
+            fee_mint: None,
+            fee_amount: 0,
+            ticket_mint: None,
+            ticket_cost: 0,
+            gate_collection: None,
+            cosigner: None,
+            num_revoked: 0,
+            max_claim_amount: 0,
+            hook_program: None,
+            paused: self.paused,
+            is_finalized: self.is_finalized,
+            bump: self.bump,
+        }
+    }
+}
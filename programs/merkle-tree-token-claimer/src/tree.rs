@@ -0,0 +1,149 @@
+//! Offchain construction of allocation trees, yielding the roots and proofs `claim_airdrop`
+//! accepts for a given hashing algorithm.
+
+use std::str::FromStr;
+
+use anchor_lang::prelude::Pubkey;
+use serde_json::Value;
+use svm_merkle_tree::{HashingAlgorithm, MerkleTree};
+
+use crate::leaf::{encode_leaf, hash_leaf, prepare_leaf};
+use crate::{HASHING_ALGORITHM_KECCAK, HASHING_ALGORITHM_SHA256};
+
+/// `svm_merkle_tree` algorithm a stored hashing algorithm discriminant stands for, `None` for
+/// discriminants the program rejects.
+pub fn hashing_algorithm_for(discriminant: u8) -> Option<HashingAlgorithm> {
+    match discriminant {
+        HASHING_ALGORITHM_KECCAK => Some(HashingAlgorithm::Keccak),
+        HASHING_ALGORITHM_SHA256 => Some(HashingAlgorithm::Sha256),
+        _ => None,
+    }
+}
+
+/// Leaf bytes of the allocation at `index`, exactly as `claim_airdrop` rebuilds them, before any
+/// leaf hashing. `None` for versions that cannot be rebuilt from the claimant and amount alone.
+pub fn encode_allocation_leaf(leaf_version: u8, index: u64, claimant: &Pubkey, amount: u64) -> Option<Vec<u8>> {
+    encode_leaf(leaf_version, index, claimant, amount).ok()
+}
+
+/// Why an allocation list could not be read, with the 1-based line or entry at fault.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AllocationListError {
+    MalformedEntry(usize),
+    InvalidAddress(usize),
+    InvalidAmount(usize),
+    InvalidJson(String),
+}
+
+/// Reads `address,amount` lines, skipping blank lines and an `address,amount` header.
+pub fn allocations_from_csv(csv: &str) -> Result<Vec<(Pubkey, u64)>, AllocationListError> {
+    csv.lines()
+        .enumerate()
+        .map(|(line, text)| (line + 1, text.trim()))
+        .filter(|(line, text)| !text.is_empty() && !(*line == 1 && text.eq_ignore_ascii_case("address,amount")))
+        .map(|(line, text)| {
+            let (address, amount) = text
+                .split_once(',')
+                .ok_or(AllocationListError::MalformedEntry(line))?;
+            parse_allocation(line, address.trim(), amount.trim())
+        })
+        .collect()
+}
+
+/// Reads a JSON array of `{ "address": ..., "amount": ... }` objects, amounts given either as
+/// numbers or as strings for values past what JSON numbers hold exactly.
+pub fn allocations_from_json(json: &str) -> Result<Vec<(Pubkey, u64)>, AllocationListError> {
+    let entries: Vec<Value> = serde_json::from_str(json)
+        .map_err(|error| AllocationListError::InvalidJson(error.to_string()))?;
+
+    entries
+        .iter()
+        .zip(1..)
+        .map(|(entry, position)| {
+            let address = entry["address"]
+                .as_str()
+                .ok_or(AllocationListError::MalformedEntry(position))?;
+            let amount = match &entry["amount"] {
+                Value::Number(number) => number.to_string(),
+                Value::String(text) => text.clone(),
+                _ => return Err(AllocationListError::MalformedEntry(position)),
+            };
+            parse_allocation(position, address, &amount)
+        })
+        .collect()
+}
+
+fn parse_allocation(position: usize, address: &str, amount: &str) -> Result<(Pubkey, u64), AllocationListError> {
+    let address = Pubkey::from_str(address).map_err(|_| AllocationListError::InvalidAddress(position))?;
+    let amount = amount.parse().map_err(|_| AllocationListError::InvalidAmount(position))?;
+    Ok((address, amount))
+}
+
+/// Tree over `(claimant, amount)` allocations, leaf `i` being the allocation claimed at index `i`.
+/// Siblings are ordered by index, as airdrops initialized with `PAIR_ORDERING_INDEXED` expect.
+pub struct AllocationTree {
+    tree: MerkleTree,
+    hashing_algorithm: u8,
+    claimants: Vec<Pubkey>,
+    leaves: Vec<Vec<u8>>,
+}
+
+impl AllocationTree {
+    /// Builds the tree of `allocations`, `None` for an unsupported mode or no allocations.
+    pub fn new(
+        hashing_algorithm: u8,
+        leaf_hashing: u8,
+        leaf_version: u8,
+        allocations: &[(Pubkey, u64)],
+    ) -> Option<Self> {
+        if allocations.is_empty() {
+            return None;
+        }
+
+        let mut tree = MerkleTree::new(hashing_algorithm_for(hashing_algorithm)?, 32);
+        let leaves = allocations
+            .iter()
+            .zip(0u64..)
+            .map(|((claimant, amount), index)| {
+                let leaf = encode_leaf(leaf_version, index, claimant, *amount).ok()?;
+                prepare_leaf(leaf_hashing, hashing_algorithm, leaf).ok()
+            })
+            .collect::<Option<Vec<_>>>()?;
+        leaves.iter().for_each(|leaf| tree.add_leaf(leaf));
+        tree.merklize().ok()?;
+
+        Some(Self {
+            tree,
+            hashing_algorithm,
+            claimants: allocations.iter().map(|(claimant, _)| *claimant).collect(),
+            leaves,
+        })
+    }
+
+    /// Number of leaves, what the airdrop is initialized with as `num_leaves`.
+    pub fn num_leaves(&self) -> u64 {
+        self.leaves.len() as u64
+    }
+
+    /// Root to initialize the airdrop with.
+    pub fn root(&self) -> Option<[u8; 32]> {
+        self.tree.get_merkle_root().ok()?.try_into().ok()
+    }
+
+    /// Proof to pass as `hashes` when claiming the allocation at `index`.
+    pub fn proof(&self, index: u32) -> Option<Vec<u8>> {
+        Some(self.tree.merkle_proof_index(index).ok()?.get_pairing_hashes())
+    }
+
+    /// Index of the first allocation of `claimant`, what it claims with along with its proof.
+    pub fn index_of(&self, claimant: &Pubkey) -> Option<u32> {
+        let index = self.claimants.iter().position(|key| key == claimant)?;
+        u32::try_from(index).ok()
+    }
+
+    /// Hash of the leaf at `index`, as `verify_eligibility` reports it.
+    pub fn leaf_hash(&self, index: u32) -> Option<[u8; 32]> {
+        let leaf = self.leaves.get(index as usize)?;
+        Some(hash_leaf(self.hashing_algorithm, leaf))
+    }
+}
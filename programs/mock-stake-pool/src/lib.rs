@@ -0,0 +1,107 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+declare_id!("HUDgh4AWYqnXpoEZVhqHYoa3UvGGsUMz1HHHC5RHaUNw");
+
+/// Staking pool of one mint capping every deposit, used to check that `claim_and_stake`
+/// deposits claims on behalf of their claimant and fails with the deposit.
+#[program]
+pub mod mock_stake_pool {
+    use super::*;
+
+    pub fn create_pool(ctx: Context<CreatePool>, max_deposit: u64) -> Result<()> {
+        ctx.accounts.pool.set_inner(Pool {
+            mint: ctx.accounts.mint.key(),
+            vault: ctx.accounts.vault.key(),
+            max_deposit,
+            total_staked: 0,
+            bump: ctx.bumps.pool,
+        });
+
+        Ok(())
+    }
+
+    /// Called by the claimer with the layout of `deposit_stake`, the pool and its vault being
+    /// the accounts of the stake config.
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        require!(amount <= ctx.accounts.pool.max_deposit, StakePoolError::DepositTooLarge);
+
+        transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.source.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                    authority: ctx.accounts.owner.to_account_info(),
+                },
+            ),
+            amount,
+            ctx.accounts.mint.decimals,
+        )?;
+
+        let pool = &mut ctx.accounts.pool;
+        pool.total_staked = pool
+            .total_staked
+            .checked_add(amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct CreatePool<'info> {
+    #[account(
+        init,
+        seeds = [b"pool".as_ref(), mint.key().as_ref()],
+        bump,
+        payer = payer,
+        space = 8 + Pool::INIT_SPACE
+    )]
+    pub pool: Account<'info, Pool>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    pub owner: Signer<'info>,
+    #[account(mut, token::mint = mint, token::authority = owner)]
+    pub source: InterfaceAccount<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    #[account(mut, has_one = mint, has_one = vault)]
+    pub pool: Account<'info, Pool>,
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Pool {
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub max_deposit: u64,
+    pub total_staked: u64,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum StakePoolError {
+    #[msg("Deposit is over the cap of the pool")]
+    DepositTooLarge,
+}
@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+use merkle_tree_token_claimer::{leaf::verify_proof, HASHING_ALGORITHM_KECCAK, PAIR_ORDERING_INDEXED};
+use svm_merkle_tree::{HashingAlgorithm, MerkleProof};
+
+declare_id!("H4fXyxqiE25jaJ84DTh2GV7qdPSaMWFDAjhtcTevWhcb");
+
+/// Verifies the same keccak, index-ordered proof two ways, used to compare the compute units
+/// of the claimer's proof verification with the `MerkleProof::merklize` path it replaced.
+#[program]
+pub mod proof_bench {
+    use super::*;
+
+    /// Verifies as claims did before, copying the proof into a `MerkleProof` and merklizing it.
+    pub fn verify_merklize(_ctx: Context<Verify>, root: [u8; 32], leaf: Vec<u8>, hashes: Vec<u8>, index: u32) -> Result<()> {
+        require!(
+            MerkleProof::new(HashingAlgorithm::Keccak, 32, index, hashes)
+                .merklize(&leaf)
+                .is_ok_and(|computed_root| computed_root == root),
+            ProofBenchError::InvalidProof
+        );
+
+        Ok(())
+    }
+
+    /// Verifies as claims do, through the claimer's `verify_proof`.
+    pub fn verify_fold(_ctx: Context<Verify>, root: [u8; 32], leaf: Vec<u8>, hashes: Vec<u8>, index: u32) -> Result<()> {
+        require!(
+            verify_proof(HASHING_ALGORITHM_KECCAK, PAIR_ORDERING_INDEXED, &root, &leaf, &hashes, index)?,
+            ProofBenchError::InvalidProof
+        );
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Verify {}
+
+#[error_code]
+pub enum ProofBenchError {
+    #[msg("Invalid proof")]
+    InvalidProof,
+}
@@ -0,0 +1,103 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token::AssociatedToken, token_interface::{Mint, TokenAccount, TokenInterface}};
+use merkle_tree_token_claimer::{cpi::accounts::Claim, program::MerkleTreeTokenClaimer, AirdropState, ClaimBitmap};
+
+declare_id!("7QYK3BuM8M5PRSThr3uXJ7UnnjFCwh7papX3DQxM1hWb");
+
+/// Holds tokens in a treasury PDA and claims allocations committed to it, the PDA signing the
+/// claim through `invoke_signed` as a DAO or vault program would.
+#[program]
+pub mod treasury_claimer {
+    use super::*;
+
+    pub fn claim<'info>(
+        ctx: Context<'_, '_, '_, 'info, TreasuryClaim<'info>>,
+        amount: u64,
+        hashes: Vec<u8>,
+        index: u64,
+        expected_root_version: u64,
+    ) -> Result<()> {
+
+        let treasury_seeds: &[&[u8]] = &[b"treasury".as_ref(), &[ctx.bumps.treasury]];
+        merkle_tree_token_claimer::cpi::claim_airdrop(
+            CpiContext::new_with_signer(
+                ctx.accounts.claimer_program.to_account_info(),
+                Claim {
+                    airdrop_state: ctx.accounts.airdrop_state.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    vault: ctx.accounts.vault.to_account_info(),
+                    signer_ata: ctx.accounts.treasury_ata.to_account_info(),
+                    claim_bitmap: ctx.accounts.claim_bitmap.to_account_info(),
+                    claim_progress: None,
+                    vesting_config: None,
+                    vesting_account: None,
+                    decay_config: None,
+                    bonus_config: None,
+                    fee_ata: None,
+                    fee_treasury: None,
+                    fee_mint: None,
+                    payer_fee_ata: None,
+                    treasury_fee_ata: None,
+                    fee_token_program: None,
+                    ticket_mint: None,
+                    ticket_ata: None,
+                    ticket_token_program: None,
+                    gate_nft_account: None,
+                    gate_nft_metadata: None,
+                    cosigner: None,
+                    instructions_sysvar: None,
+                    revoked_leaf: None,
+                    referral_stats: None,
+                    hook_program: None,
+                    stake_config: None,
+                    stake_program: None,
+                    unwrap_destination: None,
+                    basket: None,
+                    canopy: None,
+                    claimant: ctx.accounts.treasury.to_account_info(),
+                    payer: ctx.accounts.payer.to_account_info(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                    associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+                    event_authority: ctx.accounts.event_authority.to_account_info(),
+                    program: ctx.accounts.claimer_program.to_account_info(),
+                },
+                &[treasury_seeds],
+            ).with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+            amount,
+            hashes,
+            index,
+            expected_root_version,
+            None,
+            false,
+        )?;
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct TreasuryClaim<'info> {
+    #[account(mut)]
+    pub airdrop_state: Account<'info, AirdropState>,
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: Only signs the claim, the allocation in the tree is committed to its address
+    #[account(seeds = [b"treasury".as_ref()], bump)]
+    pub treasury: UncheckedAccount<'info>,
+    /// CHECK: Created if needed and validated by the claim
+    #[account(mut)]
+    pub treasury_ata: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub claim_bitmap: AccountLoader<'info, ClaimBitmap>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: Event authority of the claimer program, checked by the claim
+    pub event_authority: UncheckedAccount<'info>,
+    pub claimer_program: Program<'info, MerkleTreeTokenClaimer>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
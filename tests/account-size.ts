@@ -0,0 +1,38 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair } from "@solana/web3.js";
+import { initializeAirdrop } from "./utils";
+
+describe("account size", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  const maxLength = (name: string) =>
+    Number(program.idl.constants.find((constant) => constant.name.toLowerCase() === name).value);
+
+  it("Sizes the airdrop state for its largest serialization", async () => {
+    // Every string at its maximum length and every option set
+    const airdrop = await initializeAirdrop(program, authority, [{ address: Keypair.generate().publicKey, amount: 100 }], {
+      label: "l".repeat(maxLength("max_label_len")),
+      metadataUri: "u".repeat(maxLength("max_metadata_uri_len")),
+      rootTimelock: 60,
+    });
+    const update = { airdropState: airdrop.airdropState, updateAuthority: authority.publicKey };
+    await program.methods.nominateUpdateAuthority(Keypair.generate().publicKey).accountsPartial(update).rpc();
+    await program.methods.proposeRoot(Array(32).fill(7), new anchor.BN(1)).accountsPartial(update).rpc();
+
+    const state = await program.account.airdropState.fetch(airdrop.airdropState);
+    expect(state.pendingUpdateAuthority).to.not.equal(null);
+    expect(state.pendingRoot).to.not.equal(null);
+
+    const serialized = await program.coder.accounts.encode("airdropState", state);
+    const { data } = await provider.connection.getAccountInfo(airdrop.airdropState);
+    expect(serialized.length).to.equal(data.length);
+  });
+});
@@ -0,0 +1,165 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { getAccount, getAssociatedTokenAddress, getOrCreateAssociatedTokenAccount } from "@solana/spl-token";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { Airdrop, findCpiEvent, fund, initializeAirdrop } from "./utils";
+
+describe("admin registry", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  // The program's ROLE_* bits
+  const ROLE_PAUSER = 1;
+  const ROLE_UPDATER = 2;
+  const ROLE_WITHDRAWER = 4;
+
+  const pauser = Keypair.generate();
+  const treasury = Keypair.generate();
+  const updater = Keypair.generate();
+  const nextSuperAdmin = Keypair.generate();
+  const allocations = Array.from({ length: 2 }, () => ({ address: Keypair.generate().publicKey, amount: 100 }));
+
+  let airdrop: Airdrop;
+  let adminRegistry: PublicKey;
+
+  const setPaused = (signer: Keypair, paused: boolean, registry: PublicKey | null = adminRegistry) =>
+    program.methods.setPaused(paused)
+      .accountsPartial({ airdropState: airdrop.airdropState, adminRegistry: registry, authority: signer.publicKey })
+      .signers([signer])
+      .rpc();
+
+  const manageRoles = (superAdmin = authority) => ({ adminRegistry, superAdmin: superAdmin.publicKey });
+
+  const expectError = async (call: Promise<unknown>, code: string) => {
+    try {
+      await call;
+      expect.fail(`Call should have failed with ${code}`);
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal(code);
+    }
+  };
+
+  before(async () => {
+    await Promise.all([pauser, treasury, updater, nextSuperAdmin].map((key) => fund(provider, key.publicKey)));
+    airdrop = await initializeAirdrop(program, authority, allocations, { endTime: Math.floor(Date.now() / 1000) - 60 });
+    adminRegistry = PublicKey.findProgramAddressSync([Buffer.from("admin_registry"), airdrop.airdropState.toBuffer()], program.programId)[0];
+  });
+
+  it("Keeps the authority in charge without a registry", async () => {
+    await setPaused(authority, true, null);
+    await setPaused(authority, false, null);
+    await expectError(setPaused(pauser, true, null), "MissingRole");
+  });
+
+  it("Creates the registry with the authority as its super-admin", async () => {
+    await program.methods.createAdminRegistry()
+      .accountsPartial({ airdropState: airdrop.airdropState, adminRegistry, updateAuthority: authority.publicKey, systemProgram: SystemProgram.programId })
+      .rpc();
+
+    const registry = await program.account.adminRegistry.fetch(adminRegistry);
+    expect(registry.superAdmin.toBase58()).to.equal(authority.publicKey.toBase58());
+    expect(registry.members).to.be.empty;
+  });
+
+  it("Lets a member act with the role it was granted only", async () => {
+    const signature = await program.methods.grantRole(pauser.publicKey, ROLE_PAUSER).accountsPartial(manageRoles()).rpc();
+    const event = await findCpiEvent(program, signature, "roleGranted");
+    expect(event.member.toBase58()).to.equal(pauser.publicKey.toBase58());
+    expect(event.role).to.equal(ROLE_PAUSER);
+
+    await setPaused(pauser, true);
+    expect((await program.account.airdropState.fetch(airdrop.airdropState)).paused).to.equal(true);
+    await setPaused(pauser, false);
+
+    await expectError(
+      program.methods.updateTree(Array(32).fill(1), new anchor.BN(allocations.length))
+        .accountsPartial({ airdropState: airdrop.airdropState, adminRegistry, updateAuthority: pauser.publicKey })
+        .signers([pauser])
+        .rpc(),
+      "MissingRole"
+    );
+  });
+
+  it("Fail to grant roles as anyone but the super-admin", async () => {
+    await expectError(
+      program.methods.grantRole(pauser.publicKey, ROLE_UPDATER).accountsPartial(manageRoles(pauser)).signers([pauser]).rpc(),
+      "ConstraintHasOne"
+    );
+  });
+
+  it("Fail to grant a value that is not a single role", async () => {
+    await expectError(
+      program.methods.grantRole(pauser.publicKey, ROLE_PAUSER | ROLE_UPDATER).accountsPartial(manageRoles()).rpc(),
+      "InvalidRole"
+    );
+  });
+
+  it("Withdraws with a granted withdrawer role", async () => {
+    await program.methods.grantRole(treasury.publicKey, ROLE_WITHDRAWER).accountsPartial(manageRoles()).rpc();
+    const destination = await getOrCreateAssociatedTokenAccount(provider.connection, treasury, airdrop.mint, treasury.publicKey);
+
+    await program.methods.withdrawUnclaimed()
+      .accountsPartial({
+        airdropState: airdrop.airdropState,
+        mint: airdrop.mint,
+        vault: airdrop.vault,
+        destination: destination.address,
+        adminRegistry,
+        withdrawAuthority: treasury.publicKey,
+        tokenProgram: airdrop.tokenProgram,
+      })
+      .signers([treasury])
+      .rpc();
+
+    expect(Number((await getAccount(provider.connection, await getAssociatedTokenAddress(airdrop.mint, treasury.publicKey))).amount)).to.equal(200);
+  });
+
+  it("Revokes a leaf with a granted updater role", async () => {
+    await program.methods.grantRole(updater.publicKey, ROLE_UPDATER).accountsPartial(manageRoles()).rpc();
+    const revokedLeaf = PublicKey.findProgramAddressSync(
+      [Buffer.from("revoked"), airdrop.airdropState.toBuffer(), new anchor.BN(1).toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+    const revokeLeaf = (signer: Keypair, registry: PublicKey | null) =>
+      program.methods.revokeLeaf(new anchor.BN(1))
+        .accountsPartial({ airdropState: airdrop.airdropState, revokedLeaf, adminRegistry: registry, updateAuthority: signer.publicKey, systemProgram: SystemProgram.programId })
+        .signers([signer])
+        .rpc();
+
+    await expectError(revokeLeaf(updater, null), "MissingRole");
+    await revokeLeaf(updater, adminRegistry);
+
+    expect((await program.account.revokedLeaf.fetch(revokedLeaf)).index.toNumber()).to.equal(1);
+    expect((await program.account.airdropState.fetch(airdrop.airdropState)).numRevoked.toNumber()).to.equal(1);
+  });
+
+  it("Revokes a role", async () => {
+    const signature = await program.methods.revokeRole(pauser.publicKey, ROLE_PAUSER).accountsPartial(manageRoles()).rpc();
+    expect((await findCpiEvent(program, signature, "roleRevoked")).role).to.equal(ROLE_PAUSER);
+
+    const registry = await program.account.adminRegistry.fetch(adminRegistry);
+    expect(registry.members.map((grant) => grant.member.toBase58())).to.deep.equal([treasury.publicKey.toBase58(), updater.publicKey.toBase58()]);
+    await expectError(setPaused(pauser, true), "MissingRole");
+  });
+
+  it("Hands the super-admin over once the nominee accepts", async () => {
+    await program.methods.nominateSuperAdmin(nextSuperAdmin.publicKey).accountsPartial(manageRoles()).rpc();
+    await program.methods.acceptSuperAdmin()
+      .accountsPartial({ adminRegistry, pendingSuperAdmin: nextSuperAdmin.publicKey })
+      .signers([nextSuperAdmin])
+      .rpc();
+
+    expect((await program.account.adminRegistry.fetch(adminRegistry)).superAdmin.toBase58()).to.equal(nextSuperAdmin.publicKey.toBase58());
+    await expectError(
+      program.methods.grantRole(pauser.publicKey, ROLE_PAUSER).accountsPartial(manageRoles()).rpc(),
+      "ConstraintHasOne"
+    );
+    await program.methods.grantRole(pauser.publicKey, ROLE_PAUSER).accountsPartial(manageRoles(nextSuperAdmin)).signers([nextSuperAdmin]).rpc();
+  });
+});
@@ -0,0 +1,45 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair } from "@solana/web3.js";
+import { getAccount } from "@solana/spl-token";
+import { claimAirdrop, createBitmapShard, fund, initializeAirdrop } from "./utils";
+
+describe("allocation cap", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  const claimants = [Keypair.generate(), Keypair.generate()];
+  const allocations = [
+    { address: claimants[0].publicKey, amount: 100 },
+    { address: claimants[1].publicKey, amount: 150 },
+  ];
+
+  before(async () => {
+    for (const claimant of claimants) {
+      await fund(provider, claimant.publicKey);
+    }
+  });
+
+  it("Fail cleanly once claims of an over-allocated tree exceed the funded amount", async () => {
+    // The tree allocates 250 but only 200 are funded
+    const airdrop = await initializeAirdrop(program, authority, allocations, { amount: 200 });
+    await createBitmapShard(program, airdrop.airdropState, 0);
+    await claimAirdrop(program, claimants[0], airdrop, 100, 0);
+
+    try {
+      await claimAirdrop(program, claimants[1], airdrop, 150, 1);
+      expect.fail("Claim should have failed past the funded amount");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Claim would exceed the funded airdrop amount");
+    }
+
+    expect(Number((await getAccount(provider.connection, airdrop.vault)).amount)).to.equal(100);
+    expect((await program.account.airdropState.fetch(airdrop.airdropState)).amountClaimed.toNumber()).to.equal(100);
+  });
+});
@@ -0,0 +1,77 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { fund, initializeAirdrop } from "./utils";
+
+describe("authority transfer", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+  const newAuthority = Keypair.generate();
+
+  const allocations = Array.from({ length: 2 }, () => ({
+    address: Keypair.generate().publicKey,
+    amount: 100,
+  }));
+
+  const nominate = (airdropState: PublicKey, nominee: PublicKey) =>
+    program.methods.nominateUpdateAuthority(nominee)
+      .accountsPartial({ airdropState, updateAuthority: authority.publicKey })
+      .rpc();
+
+  const accept = (airdropState: PublicKey, signer: Keypair) =>
+    program.methods.acceptUpdateAuthority()
+      .accountsPartial({ airdropState, pendingUpdateAuthority: signer.publicKey })
+      .signers([signer])
+      .rpc();
+
+  before(async () => {
+    await fund(provider, newAuthority.publicKey);
+  });
+
+  it("Hands the airdrop over once the nominee accepts", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations);
+
+    await nominate(airdrop.airdropState, newAuthority.publicKey);
+    let account = await program.account.airdropState.fetch(airdrop.airdropState);
+    expect(account.updateAuthority.toString()).to.equal(authority.publicKey.toString());
+    expect(account.pendingUpdateAuthority.toString()).to.equal(newAuthority.publicKey.toString());
+
+    await accept(airdrop.airdropState, newAuthority);
+    account = await program.account.airdropState.fetch(airdrop.airdropState);
+    expect(account.updateAuthority.toString()).to.equal(newAuthority.publicKey.toString());
+    expect(account.pendingUpdateAuthority).to.be.null;
+
+    // The new authority can now manage the tree, the old one no longer can
+    await program.methods.updateTree(Array(32).fill(1), new anchor.BN(allocations.length))
+      .accountsPartial({ airdropState: airdrop.airdropState, updateAuthority: newAuthority.publicKey })
+      .signers([newAuthority])
+      .rpc();
+
+    try {
+      await program.methods.updateTree(Array(32).fill(2), new anchor.BN(allocations.length))
+        .accountsPartial({ airdropState: airdrop.airdropState, updateAuthority: authority.publicKey })
+        .rpc();
+      expect.fail("The previous authority should no longer update the tree");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("MissingRole");
+    }
+  });
+
+  it("Fail to accept the authority without being nominated", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations);
+    await nominate(airdrop.airdropState, Keypair.generate().publicKey);
+
+    try {
+      await accept(airdrop.airdropState, newAuthority);
+      expect.fail("Accept should have failed for a signer that was not nominated");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Signer is not the nominated authority");
+    }
+  });
+});
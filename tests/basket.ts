@@ -0,0 +1,200 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { AccountMeta, Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { createMint, getAccount, getAssociatedTokenAddress, getOrCreateAssociatedTokenAccount, mintTo, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
+import { Airdrop, createBitmapShard, fund, getBitmapShard, getProof, initializeAirdrop } from "./utils";
+
+describe("basket", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  const claimants = [Keypair.generate(), Keypair.generate()];
+  // The second claimant gets nothing of the first basket mint
+  const allocations = [
+    { address: claimants[0].publicKey, amount: 100, basket: [10, 1_000] },
+    { address: claimants[1].publicKey, amount: 200, basket: [0, 2_000] },
+  ];
+
+  let airdrop: Airdrop;
+  let basketMints: PublicKey[];
+
+  const getBasket = (airdropState: PublicKey) =>
+    PublicKey.findProgramAddressSync([Buffer.from("basket"), airdropState.toBuffer()], program.programId)[0];
+
+  const addBasketMint = async (airdrop: Airdrop, amount: number, mint?: PublicKey) => {
+    mint = mint ?? await createMint(provider.connection, authority, authority.publicKey, null, 6);
+    const authorityAta = await getOrCreateAssociatedTokenAccount(provider.connection, authority, mint, authority.publicKey);
+    await mintTo(provider.connection, authority, mint, authorityAta.address, authority, amount);
+    await program.methods.addBasketMint(new anchor.BN(amount))
+      .accountsPartial({
+        airdropState: airdrop.airdropState,
+        basket: getBasket(airdrop.airdropState),
+        airdropMint: airdrop.mint,
+        mint,
+        vault: await getAssociatedTokenAddress(mint, airdrop.airdropState, true),
+        authorityAta: authorityAta.address,
+        updateAuthority: authority.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .rpc();
+    return mint;
+  };
+
+  const claimBasket = async (index: number, basketAmounts = allocations[index].basket, mints = basketMints) => {
+    const claimant = claimants[index];
+    const remainingAccounts: AccountMeta[] = [];
+    for (const mint of mints) {
+      remainingAccounts.push(
+        { pubkey: mint, isSigner: false, isWritable: false },
+        { pubkey: await getAssociatedTokenAddress(mint, airdrop.airdropState, true), isSigner: false, isWritable: true },
+        { pubkey: await getAssociatedTokenAddress(mint, claimant.publicKey), isSigner: false, isWritable: true },
+      );
+    }
+    await program.methods.claimBasket(
+      new anchor.BN(allocations[index].amount),
+      getProof(airdrop.tree, index),
+      new anchor.BN(index),
+      new anchor.BN(0),
+      basketAmounts.map((amount) => new anchor.BN(amount))
+    )
+      .accountsPartial({
+        airdropState: airdrop.airdropState,
+        mint: airdrop.mint,
+        vault: airdrop.vault,
+        signerAta: await getAssociatedTokenAddress(airdrop.mint, claimant.publicKey),
+        claimBitmap: getBitmapShard(program.programId, airdrop.airdropState, 0),
+        basket: getBasket(airdrop.airdropState),
+        claimant: claimant.publicKey,
+        payer: claimant.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: airdrop.tokenProgram,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .remainingAccounts(remainingAccounts)
+      .signers([claimant])
+      .rpc();
+  };
+
+  const withdrawBasketUnclaimed = async (airdrop: Airdrop, mint: PublicKey) => {
+    const destination = await getOrCreateAssociatedTokenAccount(provider.connection, authority, mint, authority.publicKey);
+    await program.methods.withdrawBasketUnclaimed()
+      .accountsPartial({
+        airdropState: airdrop.airdropState,
+        basket: getBasket(airdrop.airdropState),
+        mint,
+        vault: await getAssociatedTokenAddress(mint, airdrop.airdropState, true),
+        destination: destination.address,
+        withdrawAuthority: authority.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    return destination.address;
+  };
+
+  const expectError = async (call: Promise<unknown>, code: string) => {
+    try {
+      await call;
+      expect.fail(`Call should have failed with ${code}`);
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal(code);
+    }
+  };
+
+  const balance = async (mint: PublicKey, owner: PublicKey) =>
+    Number((await getAccount(provider.connection, await getAssociatedTokenAddress(mint, owner, true))).amount);
+
+  before(async () => {
+    await Promise.all(claimants.map((claimant) => fund(provider, claimant.publicKey)));
+    airdrop = await initializeAirdrop(program, authority, allocations, { leafVersion: 5 });
+    await createBitmapShard(program, airdrop.airdropState, 0);
+    basketMints = [await addBasketMint(airdrop, 10), await addBasketMint(airdrop, 3_000)];
+  });
+
+  it("Funds a vault per mint of the basket", async () => {
+    const basket = await program.account.basket.fetch(getBasket(airdrop.airdropState));
+    expect(basket.mints.map((entry) => entry.mint.toBase58())).to.deep.equal(basketMints.map((mint) => mint.toBase58()));
+    expect(basket.mints.map((entry) => entry.airdropAmount.toNumber())).to.deep.equal([10, 3_000]);
+    expect(await balance(basketMints[1], airdrop.airdropState)).to.equal(3_000);
+  });
+
+  it("Fail to add the airdrop mint to its basket", async () => {
+    await expectError(addBasketMint(airdrop, 1, airdrop.mint), "DuplicateBasketMint");
+  });
+
+  it("Fail to claim a basket leaf with claim_airdrop", async () => {
+    const claimant = claimants[0];
+    await expectError(
+      program.methods.claimAirdrop(new anchor.BN(100), getProof(airdrop.tree, 0), new anchor.BN(0), new anchor.BN(0), null, false)
+        .accountsPartial({
+          airdropState: airdrop.airdropState,
+          mint: airdrop.mint,
+          vault: airdrop.vault,
+          signerAta: await getAssociatedTokenAddress(airdrop.mint, claimant.publicKey),
+          claimBitmap: getBitmapShard(program.programId, airdrop.airdropState, 0),
+          claimant: claimant.publicKey,
+          payer: claimant.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: airdrop.tokenProgram,
+          associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+        })
+        .signers([claimant])
+        .rpc(),
+      "BasketAmountsRequired"
+    );
+  });
+
+  it("Fail to claim other basket amounts than the leaf's", async () => {
+    await expectError(claimBasket(0, [10, 2_000]), "InvalidProof");
+  });
+
+  it("Fail to claim with the basket accounts out of order", async () => {
+    await expectError(claimBasket(0, allocations[0].basket, [...basketMints].reverse()), "BasketAccountMismatch");
+  });
+
+  it("Pays every mint of the basket in one claim", async () => {
+    await claimBasket(0);
+
+    expect(await balance(airdrop.mint, claimants[0].publicKey)).to.equal(100);
+    expect(await balance(basketMints[0], claimants[0].publicKey)).to.equal(10);
+    expect(await balance(basketMints[1], claimants[0].publicKey)).to.equal(1_000);
+
+    const basket = await program.account.basket.fetch(getBasket(airdrop.airdropState));
+    expect(basket.mints.map((entry) => entry.amountClaimed.toNumber())).to.deep.equal([10, 1_000]);
+    expect((await program.account.airdropState.fetch(airdrop.airdropState)).amountClaimed.toNumber()).to.equal(100);
+  });
+
+  it("Skips the mints a claimant has no amount of", async () => {
+    await claimBasket(1);
+
+    expect(await balance(basketMints[1], claimants[1].publicKey)).to.equal(2_000);
+    expect(await provider.connection.getAccountInfo(await getAssociatedTokenAddress(basketMints[0], claimants[1].publicKey))).to.be.null;
+  });
+
+  it("Fail to add to the basket once claims started", async () => {
+    await expectError(addBasketMint(airdrop, 1), "ClaimModeLocked");
+  });
+
+  it("Fail to withdraw a basket mint while claims are still open", async () => {
+    await expectError(withdrawBasketUnclaimed(airdrop, basketMints[0]), "ClaimsStillOpen");
+  });
+
+  it("Withdraws the unclaimed balance of a basket mint after the deadline", async () => {
+    const ended = await initializeAirdrop(program, authority, allocations, { leafVersion: 5, endTime: Math.floor(Date.now() / 1000) - 60 });
+    const mint = await addBasketMint(ended, 500);
+
+    const destination = await withdrawBasketUnclaimed(ended, mint);
+
+    expect(Number((await getAccount(provider.connection, destination)).amount)).to.equal(500);
+    const basket = await program.account.basket.fetch(getBasket(ended.airdropState));
+    expect(basket.mints[0].amountWithdrawn.toNumber()).to.equal(500);
+  });
+});
@@ -0,0 +1,65 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair } from "@solana/web3.js";
+import { getAccount, getMint } from "@solana/spl-token";
+import { burnUnclaimed, claimAirdrop, closeAirdrop, createBitmapShard, fund, initializeAirdrop } from "./utils";
+
+describe("burn unclaimed", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  const claimants = [Keypair.generate(), Keypair.generate()];
+  const allocations = claimants.map((claimant) => ({ address: claimant.publicKey, amount: 100 }));
+
+  const now = () => Math.floor(Date.now() / 1000);
+
+  before(async () => {
+    for (const claimant of claimants) {
+      await fund(provider, claimant.publicKey);
+    }
+  });
+
+  it("Burns what is left after the deadline so claimed and burned add up to the airdrop", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations, { endTime: now() + 5 });
+    await createBitmapShard(program, airdrop.airdropState, 0);
+    await claimAirdrop(program, claimants[0], airdrop, 100, 0);
+
+    // Let the short claim window run out
+    await new Promise((resolve) => setTimeout(resolve, 8_000));
+
+    let event: any;
+    const listener = program.addEventListener("unclaimedBurned", (emitted) => {
+      event = emitted;
+    });
+    await burnUnclaimed(program, authority, airdrop);
+    await new Promise((resolve) => setTimeout(resolve, 1_000));
+    await program.removeEventListener(listener);
+
+    const account = await program.account.airdropState.fetch(airdrop.airdropState);
+    expect(account.amountBurned.toNumber()).to.equal(100);
+    expect(account.amountClaimed.toNumber() + account.amountBurned.toNumber()).to.equal(account.airdropAmount.toNumber());
+    expect(event.amount.toNumber()).to.equal(100);
+    expect(Number((await getAccount(provider.connection, airdrop.vault)).amount)).to.equal(0);
+    expect(Number((await getMint(provider.connection, airdrop.mint)).supply)).to.equal(100);
+
+    await closeAirdrop(program, authority, airdrop);
+    expect(await provider.connection.getAccountInfo(airdrop.airdropState)).to.be.null;
+  });
+
+  it("Fail to burn while claims are still open", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations, { endTime: now() + 3_600 });
+
+    try {
+      await burnUnclaimed(program, authority, airdrop);
+      expect.fail("Burn should have failed before the deadline");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Claims are still open");
+    }
+  });
+});
@@ -0,0 +1,119 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { getAccount, getAssociatedTokenAddress } from "@solana/spl-token";
+import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
+import { Airdrop, claimAirdrop, createBitmapShard, fund, getBitmapShard, getProof, initializeAirdrop } from "./utils";
+
+describe("canopy", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  // The canopy holds the two nodes two levels above the leaves, proofs stop below them
+  const HEIGHT = 2;
+
+  const claimants = Array.from({ length: 8 }, () => Keypair.generate());
+  const allocations = claimants.map((claimant, i) => ({ address: claimant.publicKey, amount: (i + 1) * 100 }));
+
+  let airdrop: Airdrop;
+  let canopy: PublicKey;
+  let canopyNodes: number[][];
+
+  const modifyCanopy = () => ({ airdropState: airdrop.airdropState, canopy, updateAuthority: authority.publicKey });
+
+  const claimWithProof = async (index: number, proof: Buffer) => {
+    const claimant = claimants[index];
+    const signerAta = await getAssociatedTokenAddress(airdrop.mint, claimant.publicKey);
+    await program.methods.claimAirdrop(new anchor.BN(allocations[index].amount), proof, new anchor.BN(index), new anchor.BN(0), null, false)
+      .accountsPartial({
+        airdropState: airdrop.airdropState,
+        mint: airdrop.mint,
+        vault: airdrop.vault,
+        signerAta,
+        claimBitmap: getBitmapShard(program.programId, airdrop.airdropState, 0),
+        canopy,
+        claimant: claimant.publicKey,
+        payer: claimant.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: airdrop.tokenProgram,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .signers([claimant])
+      .rpc();
+    return signerAta;
+  };
+
+  const expectError = async (call: Promise<unknown>, code: string) => {
+    try {
+      await call;
+      expect.fail(`Call should have failed with ${code}`);
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal(code);
+    }
+  };
+
+  before(async () => {
+    await Promise.all(claimants.map((claimant) => fund(provider, claimant.publicKey)));
+    airdrop = await initializeAirdrop(program, authority, allocations);
+    await createBitmapShard(program, airdrop.airdropState, 0);
+    canopy = PublicKey.findProgramAddressSync([Buffer.from("canopy"), airdrop.airdropState.toBuffer()], program.programId)[0];
+
+    // Each node is the sibling the top level of a proof from the other half names
+    const nodeAt = (index: number) => Array.from(getProof(airdrop.tree, index).subarray(HEIGHT * 32, (HEIGHT + 1) * 32));
+    canopyNodes = [nodeAt(7), nodeAt(0)];
+
+    await program.methods.createCanopy(HEIGHT)
+      .accountsPartial({ ...modifyCanopy(), systemProgram: SystemProgram.programId })
+      .rpc();
+  });
+
+  it("Fail to seal a canopy that does not hash up to the root", async () => {
+    await program.methods.writeCanopy(0, [canopyNodes[1], canopyNodes[0]]).accountsPartial(modifyCanopy()).rpc();
+    await expectError(program.methods.sealCanopy().accountsPartial(modifyCanopy()).rpc(), "InvalidCanopy");
+  });
+
+  it("Fail to write past the end of the canopy", async () => {
+    await expectError(
+      program.methods.writeCanopy(1, canopyNodes).accountsPartial(modifyCanopy()).rpc(),
+      "CanopyMismatch"
+    );
+  });
+
+  it("Seals the canopy of the current root", async () => {
+    await program.methods.writeCanopy(0, canopyNodes).accountsPartial(modifyCanopy()).rpc();
+    await program.methods.sealCanopy().accountsPartial(modifyCanopy()).rpc();
+
+    const state = await program.account.canopy.fetch(canopy);
+    expect(state.isSealed).to.equal(true);
+    expect(state.nodes).to.deep.equal(canopyNodes);
+  });
+
+  it("Claims with a proof that stops at the canopy", async () => {
+    const proof = getProof(airdrop.tree, 5).subarray(0, HEIGHT * 32);
+    const signerAta = await claimWithProof(5, proof);
+
+    expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(600);
+  });
+
+  it("Claims with a full proof next to the canopy", async () => {
+    const signerAta = await claimWithProof(2, getProof(airdrop.tree, 2));
+
+    expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(300);
+  });
+
+  it("Claims with a full proof without the canopy", async () => {
+    const signerAta = await claimAirdrop(program, claimants[3], airdrop, 400, 3);
+
+    expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(400);
+  });
+
+  it("Fail to claim with a short proof leading to another canopy node", async () => {
+    await expectError(claimWithProof(1, getProof(airdrop.tree, 5).subarray(0, HEIGHT * 32)), "InvalidProof");
+  });
+});
@@ -0,0 +1,116 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { MockStakePool } from "../target/types/mock_stake_pool";
+import { expect } from "chai";
+import { AccountMeta, Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { getAccount, getAssociatedTokenAddress } from "@solana/spl-token";
+import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
+import { Airdrop, createBitmapShard, fund, getBitmapShard, getProof, initializeAirdrop } from "./utils";
+
+describe("claim and stake", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const stakePool = anchor.workspace.MockStakePool as Program<MockStakePool>;
+  const authority = wallet.payer;
+
+  const claimants = [Keypair.generate(), Keypair.generate()];
+  const allocations = [
+    { address: claimants[0].publicKey, amount: 100 },
+    { address: claimants[1].publicKey, amount: 500 },
+  ];
+
+  let airdrop: Airdrop;
+  let pool: PublicKey;
+  let poolVault: PublicKey;
+
+  const getStakeConfig = () =>
+    PublicKey.findProgramAddressSync([Buffer.from("stake_config"), airdrop.airdropState.toBuffer()], program.programId)[0];
+
+  const configureStaking = () =>
+    program.methods.configureStaking(stakePool.programId, [
+      { pubkey: pool, isWritable: true },
+      { pubkey: poolVault, isWritable: true },
+    ])
+      .accountsPartial({ airdropState: airdrop.airdropState, stakeConfig: getStakeConfig(), updateAuthority: authority.publicKey })
+      .rpc();
+
+  const claimAndStake = async (index: number, stakeAccounts: PublicKey[] = [pool, poolVault]) => {
+    const claimant = claimants[index];
+    const signerAta = await getAssociatedTokenAddress(airdrop.mint, claimant.publicKey);
+    const remainingAccounts: AccountMeta[] = stakeAccounts.map((pubkey) => ({ pubkey, isSigner: false, isWritable: true }));
+    await program.methods.claimAndStake(new anchor.BN(allocations[index].amount), getProof(airdrop.tree, index), new anchor.BN(index), new anchor.BN(0))
+      .accountsPartial({
+        airdropState: airdrop.airdropState,
+        mint: airdrop.mint,
+        vault: airdrop.vault,
+        signerAta,
+        claimBitmap: getBitmapShard(program.programId, airdrop.airdropState, 0),
+        stakeConfig: getStakeConfig(),
+        stakeProgram: stakePool.programId,
+        claimant: claimant.publicKey,
+        payer: claimant.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: airdrop.tokenProgram,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .remainingAccounts(remainingAccounts)
+      .signers([claimant])
+      .rpc();
+    return signerAta;
+  };
+
+  const expectError = async (call: Promise<unknown>, code: string) => {
+    try {
+      await call;
+      expect.fail(`Call should have failed with ${code}`);
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal(code);
+    }
+  };
+
+  before(async () => {
+    await Promise.all(claimants.map((claimant) => fund(provider, claimant.publicKey)));
+    airdrop = await initializeAirdrop(program, authority, allocations);
+    await createBitmapShard(program, airdrop.airdropState, 0);
+
+    pool = PublicKey.findProgramAddressSync([Buffer.from("pool"), airdrop.mint.toBuffer()], stakePool.programId)[0];
+    poolVault = await getAssociatedTokenAddress(airdrop.mint, pool, true, airdrop.tokenProgram);
+    await stakePool.methods.createPool(new anchor.BN(200))
+      .accountsPartial({
+        pool,
+        mint: airdrop.mint,
+        vault: poolVault,
+        payer: authority.publicKey,
+        tokenProgram: airdrop.tokenProgram,
+      })
+      .rpc();
+    await configureStaking();
+  });
+
+  it("Fail to stake into accounts other than the configured ones", async () => {
+    await expectError(claimAndStake(0, [pool, Keypair.generate().publicKey]), "StakeAccountMismatch");
+  });
+
+  it("Fails the whole claim when the deposit fails", async () => {
+    await expectError(claimAndStake(1), "DepositTooLarge");
+
+    const state = await program.account.airdropState.fetch(airdrop.airdropState);
+    expect(state.amountClaimed.toNumber()).to.equal(0);
+  });
+
+  it("Deposits the claim into the pool on behalf of the claimant", async () => {
+    const signerAta = await claimAndStake(0);
+
+    expect(Number((await getAccount(provider.connection, signerAta, undefined, airdrop.tokenProgram)).amount)).to.equal(0);
+    expect(Number((await getAccount(provider.connection, poolVault, undefined, airdrop.tokenProgram)).amount)).to.equal(100);
+    expect((await stakePool.account.pool.fetch(pool)).totalStaked.toNumber()).to.equal(100);
+  });
+
+  it("Fail to configure staking once claims started", async () => {
+    await expectError(configureStaking(), "ClaimModeLocked");
+  });
+});
@@ -0,0 +1,92 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair, SystemProgram } from "@solana/web3.js";
+import { getAccount, getAssociatedTokenAddressSync } from "@solana/spl-token";
+import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
+import { Airdrop, claimAirdrop, createBitmapShard, fund, getBitmapShard, getCpiEvents, getProof, initializeAirdrop } from "./utils";
+
+describe("claim cap", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  // The second allocation got a few extra zeros
+  const claimants = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  const allocations = claimants.map((claimant, i) => ({ address: claimant.publicKey, amount: i === 1 ? 100_000_000 : 100 }));
+
+  let airdrop: Airdrop;
+
+  const setMaxClaimAmount = (maxClaimAmount: number) =>
+    program.methods.setMaxClaimAmount(new anchor.BN(maxClaimAmount))
+      .accountsPartial({ airdropState: airdrop.airdropState, updateAuthority: authority.publicKey })
+      .rpc();
+
+  const push = (index: number) =>
+    program.methods.claimForMany(
+      new anchor.BN(0),
+      [{ recipient: claimants[index].publicKey, amount: new anchor.BN(allocations[index].amount), index: new anchor.BN(index), proofLen: getProof(airdrop.tree, index).length / 32 }],
+      getProof(airdrop.tree, index),
+      new anchor.BN(0)
+    )
+      .accountsPartial({
+        airdropState: airdrop.airdropState,
+        mint: airdrop.mint,
+        vault: airdrop.vault,
+        claimBitmap: getBitmapShard(program.programId, airdrop.airdropState, 0),
+        updateAuthority: authority.publicKey,
+        payer: authority.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: airdrop.tokenProgram,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .remainingAccounts([
+        { pubkey: claimants[index].publicKey, isSigner: false, isWritable: false },
+        { pubkey: getAssociatedTokenAddressSync(airdrop.mint, claimants[index].publicKey, false, airdrop.tokenProgram), isSigner: false, isWritable: true },
+      ])
+      .rpc();
+
+  const expectError = async (call: Promise<unknown>, code: string) => {
+    try {
+      await call;
+      expect.fail(`Call should have failed with ${code}`);
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal(code);
+    }
+  };
+
+  before(async () => {
+    await Promise.all(claimants.map((claimant) => fund(provider, claimant.publicKey)));
+    airdrop = await initializeAirdrop(program, authority, allocations);
+    await createBitmapShard(program, airdrop.airdropState, 0);
+    await setMaxClaimAmount(1_000);
+  });
+
+  it("Fail to claim above the cap even with a valid proof", async () => {
+    await expectError(claimAirdrop(program, claimants[1], airdrop, 100_000_000, 1), "ClaimTooLarge");
+  });
+
+  it("Skips a pushed claim above the cap", async () => {
+    const signature = await push(1);
+
+    const skipped = (await getCpiEvents(program, signature)).filter((event) => event.name === "claimSkipped");
+    expect(skipped.map((event) => Object.keys(event.data.reason)[0])).to.deep.equal(["claimTooLarge"]);
+    expect((await program.account.airdropState.fetch(airdrop.airdropState)).amountClaimed.toNumber()).to.equal(0);
+  });
+
+  it("Claims below the cap", async () => {
+    const signerAta = await claimAirdrop(program, claimants[0], airdrop, 100, 0);
+    expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(100);
+  });
+
+  it("Claims anything once the cap is lifted", async () => {
+    await setMaxClaimAmount(0);
+
+    const signerAta = await claimAirdrop(program, claimants[1], airdrop, 100_000_000, 1);
+    expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(100_000_000);
+  });
+});
@@ -0,0 +1,91 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { createAccount, createMint, getAccount } from "@solana/spl-token";
+import { Airdrop, createBitmapShard, fund, getBitmapShard, getProof, initializeAirdrop } from "./utils";
+
+describe("claim destination", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  const claimant = Keypair.generate();
+  const custodialClaimant = Keypair.generate();
+  const allocations = [
+    { address: claimant.publicKey, amount: 100 },
+    { address: custodialClaimant.publicKey, amount: 200 },
+  ];
+
+  let airdrop: Airdrop;
+
+  // Token account at a fresh keypair address, so never the owner's associated one
+  const auxiliaryAccount = (mint: PublicKey, owner: PublicKey) =>
+    createAccount(provider.connection, authority, mint, owner, Keypair.generate());
+
+  const claimToAccount = (signer: Keypair, destination: PublicKey, amount: number, index: number, custodial = false) =>
+    program.methods.claimToAccount(new anchor.BN(amount), getProof(airdrop.tree, index), new anchor.BN(index), new anchor.BN(0), custodial)
+      .accountsPartial({
+        airdropState: airdrop.airdropState,
+        mint: airdrop.mint,
+        vault: airdrop.vault,
+        destination,
+        claimBitmap: getBitmapShard(program.programId, airdrop.airdropState, 0),
+        claimant: signer.publicKey,
+        tokenProgram: airdrop.tokenProgram,
+      })
+      .signers([signer])
+      .rpc();
+
+  before(async () => {
+    airdrop = await initializeAirdrop(program, authority, allocations);
+    await createBitmapShard(program, airdrop.airdropState, 0);
+    await fund(provider, claimant.publicKey);
+    await fund(provider, custodialClaimant.publicKey);
+  });
+
+  it("Fail to claim into an account of another mint", async () => {
+    const otherMint = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+    const destination = await auxiliaryAccount(otherMint, claimant.publicKey);
+
+    try {
+      await claimToAccount(claimant, destination, 100, 0);
+      expect.fail("Claim should have failed into an account of another mint");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("ConstraintTokenMint");
+    }
+  });
+
+  it("Fail to claim into an account owned by someone else", async () => {
+    const destination = await auxiliaryAccount(airdrop.mint, Keypair.generate().publicKey);
+
+    try {
+      await claimToAccount(claimant, destination, 100, 0);
+      expect.fail("Claim should have failed into an account the claimant does not own");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("DestinationOwnerMismatch");
+    }
+  });
+
+  it("Claims into an auxiliary account of the claimant", async () => {
+    const destination = await auxiliaryAccount(airdrop.mint, claimant.publicKey);
+    await claimToAccount(claimant, destination, 100, 0);
+
+    const account = await getAccount(provider.connection, destination);
+    expect(Number(account.amount)).to.equal(100);
+  });
+
+  it("Claims into a custodial deposit account once acknowledged", async () => {
+    const custodian = Keypair.generate();
+    const destination = await auxiliaryAccount(airdrop.mint, custodian.publicKey);
+    await claimToAccount(custodialClaimant, destination, 200, 1, true);
+
+    const account = await getAccount(provider.connection, destination);
+    expect(Number(account.amount)).to.equal(200);
+    expect(account.owner.toBase58()).to.equal(custodian.publicKey.toBase58());
+  });
+});
@@ -0,0 +1,78 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair } from "@solana/web3.js";
+import { getAssociatedTokenAddress, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
+import { Airdrop, createBitmapShard, findCpiEvent, fund, getBitmapShard, getProof, initializeAirdrop } from "./utils";
+
+describe("claim event", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  const claimants = [Keypair.generate(), Keypair.generate()];
+  const allocations = claimants.map((claimant, i) => ({ address: claimant.publicKey, amount: 100 * (i + 1) }));
+
+  const claim = async (airdrop: Airdrop, index: number) =>
+    program.methods.claimAirdrop(new anchor.BN(allocations[index].amount), getProof(airdrop.tree, index), new anchor.BN(index), new anchor.BN(0), null, false)
+      .accountsPartial({
+        airdropState: airdrop.airdropState,
+        mint: airdrop.mint,
+        vault: airdrop.vault,
+        signerAta: await getAssociatedTokenAddress(airdrop.mint, claimants[index].publicKey),
+        claimBitmap: getBitmapShard(program.programId, airdrop.airdropState, 0),
+        claimant: claimants[index].publicKey,
+        payer: claimants[index].publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .signers([claimants[index]])
+      .rpc();
+
+  before(async () => {
+    for (const claimant of claimants) {
+      await fund(provider, claimant.publicKey);
+    }
+  });
+
+  it("Emits the claimer, amount, index and running totals of every claim", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations, { expectedRecipients: 2 });
+    await createBitmapShard(program, airdrop.airdropState, 0);
+
+    const first = await findCpiEvent(program, await claim(airdrop, 0), "claimEvent");
+    const second = await findCpiEvent(program, await claim(airdrop, 1), "claimEvent");
+
+    expect(first.claimer.toString()).to.equal(claimants[0].publicKey.toString());
+    expect(first.mint.toString()).to.equal(airdrop.mint.toString());
+    expect(first.amount.toNumber()).to.equal(100);
+    expect(first.index.toNumber()).to.equal(0);
+    expect(first.totalClaimed.toNumber()).to.equal(100);
+    expect(second.claimer.toString()).to.equal(claimants[1].publicKey.toString());
+    expect(second.index.toNumber()).to.equal(1);
+    expect(second.totalClaimed.toNumber()).to.equal(300);
+    expect(first.numClaims.toNumber()).to.equal(1);
+    expect(second.numClaims.toNumber()).to.equal(2);
+    expect(second.rootVersion.toNumber()).to.equal(0);
+
+    const account = await program.account.airdropState.fetch(airdrop.airdropState);
+    expect(account.numClaims.toNumber()).to.equal(2);
+    expect(account.expectedRecipients.toNumber()).to.equal(2);
+  });
+
+  it("Emits the initialization through self-CPI", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations);
+    const event = await findCpiEvent(program, airdrop.signature, "airdropInitialized");
+
+    expect(event.airdropState.toString()).to.equal(airdrop.airdropState.toString());
+    expect(event.authority.toString()).to.equal(authority.publicKey.toString());
+    expect(event.mint.toString()).to.equal(airdrop.mint.toString());
+    expect(event.merkleRoot).to.deep.equal(Array.from(airdrop.tree.get_merkle_root()));
+    expect(event.airdropAmount.toNumber()).to.equal(300);
+    expect(event.numLeaves.toNumber()).to.equal(2);
+  });
+});
@@ -0,0 +1,97 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair, LAMPORTS_PER_SOL, SystemProgram } from "@solana/web3.js";
+import { getAccount, getAssociatedTokenAddress } from "@solana/spl-token";
+import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
+import { Airdrop, claimAirdrop, createBitmapShard, fund, getBitmapShard, getProof, initializeAirdrop } from "./utils";
+
+describe("claim fee in lamports", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+  const feeTreasury = Keypair.generate().publicKey;
+  const claimFeeLamports = LAMPORTS_PER_SOL / 1_000;
+
+  const claimants = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  const allocations = claimants.map((claimant) => ({ address: claimant.publicKey, amount: 100 }));
+
+  let airdrop: Airdrop;
+
+  const claimWithFee = async (airdrop: Airdrop, index: number) => {
+    const claimant = claimants[index];
+    const signerAta = await getAssociatedTokenAddress(airdrop.mint, claimant.publicKey);
+    await program.methods.claimAirdrop(new anchor.BN(allocations[index].amount), getProof(airdrop.tree, index), new anchor.BN(index), new anchor.BN(0), null, false)
+      .accountsPartial({
+        airdropState: airdrop.airdropState,
+        mint: airdrop.mint,
+        vault: airdrop.vault,
+        signerAta,
+        claimBitmap: getBitmapShard(program.programId, airdrop.airdropState, 0),
+        feeTreasury,
+        claimant: claimant.publicKey,
+        payer: claimant.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: airdrop.tokenProgram,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .signers([claimant])
+      .rpc();
+    return signerAta;
+  };
+
+  const lowerClaimFee = (claimFeeLamports: number) =>
+    program.methods.lowerClaimFee(new anchor.BN(claimFeeLamports))
+      .accountsPartial({ airdropState: airdrop.airdropState, updateAuthority: authority.publicKey })
+      .rpc();
+
+  const expectError = async (call: Promise<unknown>, code: string) => {
+    try {
+      await call;
+      expect.fail(`Call should have failed with ${code}`);
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal(code);
+    }
+  };
+
+  before(async () => {
+    // Funded up front, the first fee alone would not keep the treasury rent exempt
+    await fund(provider, feeTreasury);
+    await Promise.all(claimants.map((claimant) => fund(provider, claimant.publicKey)));
+    airdrop = await initializeAirdrop(program, authority, allocations, { claimFeeLamports, feeTreasury });
+    await createBitmapShard(program, airdrop.airdropState, 0);
+  });
+
+  it("Charges the payer the claim fee into the treasury", async () => {
+    const before = await provider.connection.getBalance(feeTreasury);
+    const signerAta = await claimWithFee(airdrop, 0);
+
+    expect(await provider.connection.getBalance(feeTreasury)).to.equal(before + claimFeeLamports);
+    expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(100);
+  });
+
+  it("Fail to claim without the fee treasury", async () => {
+    await expectError(claimAirdrop(program, claimants[1], airdrop, 100, 1), "MissingFeeTreasury");
+  });
+
+  it("Fail to claim with a payer short of the claim fee", async () => {
+    const expensive = await initializeAirdrop(program, authority, allocations, { claimFeeLamports: 10 * LAMPORTS_PER_SOL, feeTreasury });
+    await createBitmapShard(program, expensive.airdropState, 0);
+    await expectError(claimWithFee(expensive, 1), "InsufficientFeeBalance");
+  });
+
+  it("Fail to raise the claim fee", async () => {
+    await expectError(lowerClaimFee(claimFeeLamports + 1), "FeeNotLowered");
+  });
+
+  it("Claims without the fee treasury once the claim fee is lowered to nothing", async () => {
+    await lowerClaimFee(0);
+
+    const signerAta = await claimAirdrop(program, claimants[2], airdrop, 100, 2);
+    expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(100);
+  });
+});
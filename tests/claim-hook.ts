@@ -0,0 +1,196 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { ClaimPointsHook } from "../target/types/claim_points_hook";
+import { expect } from "chai";
+import { AccountMeta, Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { getAccount, getAssociatedTokenAddress, getOrCreateAssociatedTokenAccount } from "@solana/spl-token";
+import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
+import { Airdrop, claimAirdrop, createBitmapShard, fund, getBitmapShard, getProof, initializeAirdrop } from "./utils";
+
+describe("claim hook", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const hook = anchor.workspace.ClaimPointsHook as Program<ClaimPointsHook>;
+  const authority = wallet.payer;
+
+  const claimants = [Keypair.generate(), Keypair.generate()];
+  const allocations = claimants.map((claimant) => ({ address: claimant.publicKey, amount: 100 }));
+
+  let airdrop: Airdrop;
+
+  const getPoints = (claimant: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("points"), airdrop.airdropState.toBuffer(), claimant.toBuffer()],
+      hook.programId
+    )[0];
+
+  const getHookAuthority = () =>
+    PublicKey.findProgramAddressSync([Buffer.from("hook_authority"), airdrop.airdropState.toBuffer()], program.programId)[0];
+
+  // What the points hook needs after the airdrop state, the hook authority and the claimant
+  const hookAccounts = (claimant: PublicKey): AccountMeta[] => [
+    { pubkey: getPoints(claimant), isSigner: false, isWritable: true },
+    { pubkey: claimant, isSigner: true, isWritable: true },
+    { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+  ];
+
+  const claimWithHook = async (index: number, remainingAccounts = hookAccounts(claimants[index].publicKey)) => {
+    const claimant = claimants[index];
+    const signerAta = await getAssociatedTokenAddress(airdrop.mint, claimant.publicKey);
+    await program.methods.claimAirdrop(new anchor.BN(allocations[index].amount), getProof(airdrop.tree, index), new anchor.BN(index), new anchor.BN(0), null, false)
+      .accountsPartial({
+        airdropState: airdrop.airdropState,
+        mint: airdrop.mint,
+        vault: airdrop.vault,
+        signerAta,
+        claimBitmap: getBitmapShard(program.programId, airdrop.airdropState, 0),
+        hookProgram: hook.programId,
+        hookAuthority: getHookAuthority(),
+        claimant: claimant.publicKey,
+        payer: claimant.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: airdrop.tokenProgram,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .remainingAccounts(remainingAccounts)
+      .signers([claimant])
+      .rpc();
+    return signerAta;
+  };
+
+  const expectError = async (call: Promise<unknown>, code: string) => {
+    try {
+      await call;
+      expect.fail(`Call should have failed with ${code}`);
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal(code);
+    }
+  };
+
+  before(async () => {
+    await Promise.all(claimants.map((claimant) => fund(provider, claimant.publicKey)));
+    airdrop = await initializeAirdrop(program, authority, allocations);
+    await createBitmapShard(program, airdrop.airdropState, 0);
+    await program.methods.setClaimHook(hook.programId)
+      .accountsPartial({ airdropState: airdrop.airdropState, updateAuthority: authority.publicKey })
+      .rpc();
+  });
+
+  it("Fail to claim without the hook program", async () => {
+    await expectError(claimAirdrop(program, claimants[0], airdrop, 100, 0), "MissingClaimHook");
+  });
+
+  it("Fails the whole claim when the hook fails", async () => {
+    await expectError(claimWithHook(0, []), "AccountNotEnoughKeys");
+
+    const state = await program.account.airdropState.fetch(airdrop.airdropState);
+    expect(state.amountClaimed.toNumber()).to.equal(0);
+  });
+
+  it("Notifies the hook of a paid claim", async () => {
+    const signerAta = await claimWithHook(0);
+
+    expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(100);
+    const points = await hook.account.points.fetch(getPoints(claimants[0].publicKey));
+    expect(points.claimant.toBase58()).to.equal(claimants[0].publicKey.toBase58());
+    expect(points.points.toNumber()).to.equal(100);
+  });
+
+  it("Notifies the hook of a claim into another token account", async () => {
+    const claimant = claimants[1];
+    const destination = await getOrCreateAssociatedTokenAccount(provider.connection, claimant, airdrop.mint, claimant.publicKey);
+    await program.methods.claimToAccount(new anchor.BN(100), getProof(airdrop.tree, 1), new anchor.BN(1), new anchor.BN(0), false)
+      .accountsPartial({
+        airdropState: airdrop.airdropState,
+        mint: airdrop.mint,
+        vault: airdrop.vault,
+        destination: destination.address,
+        claimBitmap: getBitmapShard(program.programId, airdrop.airdropState, 0),
+        hookProgram: hook.programId,
+        hookAuthority: getHookAuthority(),
+        claimant: claimant.publicKey,
+        tokenProgram: airdrop.tokenProgram,
+      })
+      .remainingAccounts(hookAccounts(claimant.publicKey))
+      .signers([claimant])
+      .rpc();
+
+    expect((await hook.account.points.fetch(getPoints(claimant.publicKey))).points.toNumber()).to.equal(100);
+  });
+
+  it("Fail to push a claim past the hook", async () => {
+    const recipient = claimants[1].publicKey;
+    await expectError(
+      program.methods.claimFor(new anchor.BN(100), getProof(airdrop.tree, 1), new anchor.BN(1), new anchor.BN(0))
+        .accountsPartial({
+          airdropState: airdrop.airdropState,
+          mint: airdrop.mint,
+          vault: airdrop.vault,
+          recipient,
+          recipientAta: await getAssociatedTokenAddress(airdrop.mint, recipient),
+          claimBitmap: getBitmapShard(program.programId, airdrop.airdropState, 0),
+          payer: authority.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: airdrop.tokenProgram,
+          associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+        })
+        .rpc(),
+      "ClaimHookUnsupported"
+    );
+  });
+
+  it("Fail to change the hook once claims started", async () => {
+    await expectError(
+      program.methods.setClaimHook(null)
+        .accountsPartial({ airdropState: airdrop.airdropState, updateAuthority: authority.publicKey })
+        .rpc(),
+      "HookLocked"
+    );
+  });
+
+  it("Fail to award points for an airdrop with any signer but its hook authority", async () => {
+    const outsider = Keypair.generate();
+    await fund(provider, outsider.publicKey);
+    try {
+      await hook.methods.onClaim(outsider.publicKey, new anchor.BN(1_000), new anchor.BN(0), airdrop.airdropState)
+        .accountsPartial({
+          airdropState: airdrop.airdropState,
+          hookAuthority: outsider.publicKey,
+          claimant: outsider.publicKey,
+          points: getPoints(outsider.publicKey),
+          payer: outsider.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([outsider])
+        .rpc();
+      expect.fail("Points should only be awarded by claims");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("ConstraintSeeds");
+    }
+  });
+
+  it("Fail to award points outside of a claim", async () => {
+    const outsider = Keypair.generate();
+    await fund(provider, outsider.publicKey);
+    try {
+      await hook.methods.onClaim(outsider.publicKey, new anchor.BN(1_000), new anchor.BN(0), outsider.publicKey)
+        .accountsPartial({
+          airdropState: outsider.publicKey,
+          hookAuthority: outsider.publicKey,
+          claimant: outsider.publicKey,
+          points: PublicKey.findProgramAddressSync([Buffer.from("points"), outsider.publicKey.toBuffer(), outsider.publicKey.toBuffer()], hook.programId)[0],
+          payer: outsider.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([outsider])
+        .rpc();
+      expect.fail("Points should only be awarded by claims");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("ConstraintOwner");
+    }
+  });
+});
@@ -0,0 +1,105 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair, SystemProgram } from "@solana/web3.js";
+import { getAccount, getAssociatedTokenAddress } from "@solana/spl-token";
+import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
+import { Airdrop, createBitmapShard, fund, getBitmapShard, getCpiEvents, getProof, initializeAirdrop } from "./utils";
+
+describe("claim many", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  // Listed once per campaign, at indices 0, 2 and 3
+  const claimant = Keypair.generate();
+  const allocations = [
+    { address: claimant.publicKey, amount: 100 },
+    { address: Keypair.generate().publicKey, amount: 50 },
+    { address: claimant.publicKey, amount: 200 },
+    { address: claimant.publicKey, amount: 300 },
+  ];
+
+  let airdrop: Airdrop;
+
+  const claimMany = async (entries: { amount: number; index: number; proof: Buffer }[]) => {
+    const signerAta = await getAssociatedTokenAddress(airdrop.mint, claimant.publicKey, false, airdrop.tokenProgram);
+    const claims = entries.map(({ amount, index, proof }) => ({
+      amount: new anchor.BN(amount),
+      index: new anchor.BN(index),
+      proofLen: proof.length / 32,
+    }));
+    const signature = await program.methods.claimMany(new anchor.BN(0), claims, Buffer.concat(entries.map(({ proof }) => proof)), new anchor.BN(0))
+      .accountsPartial({
+        airdropState: airdrop.airdropState,
+        mint: airdrop.mint,
+        vault: airdrop.vault,
+        signerAta,
+        claimBitmap: getBitmapShard(program.programId, airdrop.airdropState, 0),
+        claimant: claimant.publicKey,
+        payer: claimant.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: airdrop.tokenProgram,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .signers([claimant])
+      .rpc();
+    return { signature, signerAta };
+  };
+
+  const entry = (index: number) => ({ amount: allocations[index].amount, index, proof: getProof(airdrop.tree, index) });
+
+  before(async () => {
+    await fund(provider, claimant.publicKey);
+    airdrop = await initializeAirdrop(program, authority, allocations);
+    await createBitmapShard(program, airdrop.airdropState, 0);
+  });
+
+  it("Fail the whole batch when one proof is invalid", async () => {
+    try {
+      await claimMany([entry(0), { ...entry(2), amount: 2_000 }]);
+      expect.fail("Batch should have failed with an invalid proof in it");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("InvalidProof");
+    }
+
+    const state = await program.account.airdropState.fetch(airdrop.airdropState);
+    expect(state.numClaims.toNumber()).to.equal(0);
+  });
+
+  it("Fail to claim the same index twice in a batch", async () => {
+    try {
+      await claimMany([entry(0), entry(0)]);
+      expect.fail("Batch should have failed with a repeated index");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("AlreadyClaimed");
+    }
+  });
+
+  it("Fail a batch larger than the cap", async () => {
+    const max = program.idl.constants.find((constant) => constant.name.toLowerCase() === "max_batch_claims");
+    try {
+      await claimMany(Array.from({ length: Number(max.value) + 1 }, () => entry(0)));
+      expect.fail("Batch should have failed above the cap");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("InvalidBatchSize");
+    }
+  });
+
+  it("Claims every allocation of the signer in one payout", async () => {
+    const { signature, signerAta } = await claimMany([entry(0), entry(2), entry(3)]);
+
+    expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(600);
+    const state = await program.account.airdropState.fetch(airdrop.airdropState);
+    expect(state.amountClaimed.toNumber()).to.equal(600);
+    expect(state.numClaims.toNumber()).to.equal(3);
+
+    const events = (await getCpiEvents(program, signature)).filter((event) => event.name === "claimEvent");
+    expect(events.map((event) => event.data.index.toNumber())).to.deep.equal([0, 2, 3]);
+    expect(events.map((event) => event.data.amount.toNumber())).to.deep.equal([100, 200, 300]);
+  });
+});
@@ -0,0 +1,100 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Ed25519Program, Keypair, PublicKey, SystemProgram, SYSVAR_INSTRUCTIONS_PUBKEY } from "@solana/web3.js";
+import { getAccount, getAssociatedTokenAddress } from "@solana/spl-token";
+import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
+import { Airdrop, createBitmapShard, fund, getBitmapShard, getProof, initializeAirdrop } from "./utils";
+
+// claimant ‖ amount ‖ index ‖ expiry ‖ airdrop_state, the integers little endian
+const permitMessage = (claimant: PublicKey, amount: number, index: number, expiry: number, airdropState: PublicKey) =>
+  Buffer.concat([
+    claimant.toBuffer(),
+    new anchor.BN(amount).toArrayLike(Buffer, "le", 8),
+    new anchor.BN(index).toArrayLike(Buffer, "le", 8),
+    new anchor.BN(expiry).toArrayLike(Buffer, "le", 8),
+    airdropState.toBuffer(),
+  ]);
+
+describe("claim permit", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+  const cosigner = Keypair.generate();
+
+  const now = Math.floor(Date.now() / 1000);
+  const claimants = [Keypair.generate(), Keypair.generate()];
+  const allocations = claimants.map((claimant) => ({ address: claimant.publicKey, amount: 100 }));
+
+  let airdrop: Airdrop;
+
+  const claimWithPermit = async (index: number, message: Buffer, signer = cosigner) => {
+    const claimant = claimants[index];
+    const signerAta = await getAssociatedTokenAddress(airdrop.mint, claimant.publicKey);
+    await program.methods.claimAirdrop(new anchor.BN(allocations[index].amount), getProof(airdrop.tree, index), new anchor.BN(index), new anchor.BN(0), null, false)
+      .accountsPartial({
+        airdropState: airdrop.airdropState,
+        mint: airdrop.mint,
+        vault: airdrop.vault,
+        signerAta,
+        claimBitmap: getBitmapShard(program.programId, airdrop.airdropState, 0),
+        instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+        claimant: claimant.publicKey,
+        payer: claimant.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: airdrop.tokenProgram,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .preInstructions([Ed25519Program.createInstructionWithPrivateKey({ privateKey: signer.secretKey, message })])
+      .signers([claimant])
+      .rpc();
+    return signerAta;
+  };
+
+  const permitFor = (index: number, expiry = now + 3_600, amount = allocations[index].amount) =>
+    permitMessage(claimants[index].publicKey, amount, index, expiry, airdrop.airdropState);
+
+  const expectError = async (call: Promise<unknown>, code: string) => {
+    try {
+      await call;
+      expect.fail(`Call should have failed with ${code}`);
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal(code);
+    }
+  };
+
+  before(async () => {
+    await Promise.all(claimants.map((claimant) => fund(provider, claimant.publicKey)));
+    airdrop = await initializeAirdrop(program, authority, allocations, { cosigner: cosigner.publicKey });
+    await createBitmapShard(program, airdrop.airdropState, 0);
+  });
+
+  it("Fail to claim with a permit signed by another key", async () => {
+    await expectError(claimWithPermit(0, permitFor(0), Keypair.generate()), "InvalidPermit");
+  });
+
+  it("Fail to claim with a permit for another amount", async () => {
+    await expectError(claimWithPermit(0, permitFor(0, now + 3_600, 1_000)), "InvalidPermit");
+  });
+
+  it("Fail to claim with a permit for another claimant", async () => {
+    await expectError(claimWithPermit(1, permitFor(0)), "InvalidPermit");
+  });
+
+  it("Fail to claim with an expired permit", async () => {
+    await expectError(claimWithPermit(0, permitFor(0, now - 60)), "PermitExpired");
+  });
+
+  it("Claims with a permit of the cosigner", async () => {
+    const signerAta = await claimWithPermit(0, permitFor(0));
+    expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(100);
+  });
+
+  it("Fail to claim again with a fresh permit", async () => {
+    await expectError(claimWithPermit(0, permitFor(0, now + 7_200)), "AlreadyClaimed");
+  });
+});
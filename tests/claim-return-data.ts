@@ -0,0 +1,64 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { ClaimWrapper } from "../target/types/claim_wrapper";
+import { expect } from "chai";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { getAccount, getAssociatedTokenAddress, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { createBitmapShard, fund, getBitmapShard, getProof, initializeAirdrop } from "./utils";
+
+describe("claim return data", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const wrapper = anchor.workspace.ClaimWrapper as Program<ClaimWrapper>;
+  const authority = wallet.payer;
+
+  const claimants = [Keypair.generate(), Keypair.generate()];
+  const allocations = claimants.map((claimant, i) => ({ address: claimant.publicKey, amount: 100 * (i + 1) }));
+
+  before(async () => {
+    for (const claimant of claimants) {
+      await fund(provider, claimant.publicKey);
+    }
+  });
+
+  it("Hands the claim outcome back to a calling program", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations);
+    await createBitmapShard(program, airdrop.airdropState, 0);
+
+    for (const [index, claimant] of claimants.entries()) {
+      const signerAta = await getAssociatedTokenAddress(airdrop.mint, claimant.publicKey);
+      const record = PublicKey.findProgramAddressSync([Buffer.from("record"), claimant.publicKey.toBuffer()], wrapper.programId)[0];
+
+      await wrapper.methods.claim(new anchor.BN(allocations[index].amount), getProof(airdrop.tree, index), new anchor.BN(index), new anchor.BN(0))
+        .accountsPartial({
+          airdropState: airdrop.airdropState,
+          mint: airdrop.mint,
+          vault: airdrop.vault,
+          signerAta,
+          claimBitmap: getBitmapShard(program.programId, airdrop.airdropState, 0),
+          record,
+          signer: claimant.publicKey,
+          eventAuthority: PublicKey.findProgramAddressSync([Buffer.from("__event_authority")], program.programId)[0],
+          claimerProgram: program.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([claimant])
+        .rpc();
+
+      const result = await wrapper.account.claimRecord.fetch(record);
+      expect(result.amountTransferred.toNumber()).to.equal(allocations[index].amount);
+      expect(result.claimant.toString()).to.equal(claimant.publicKey.toString());
+      expect(result.merkleRoot).to.deep.equal(Array.from(airdrop.tree.get_merkle_root()));
+      expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(allocations[index].amount);
+    }
+
+    const last = await wrapper.account.claimRecord.fetch(
+      PublicKey.findProgramAddressSync([Buffer.from("record"), claimants[1].publicKey.toBuffer()], wrapper.programId)[0]
+    );
+    expect(last.amountClaimed.toNumber()).to.equal(300);
+  });
+});
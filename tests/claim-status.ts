@@ -0,0 +1,103 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { getAssociatedTokenAddress } from "@solana/spl-token";
+import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
+import { Airdrop, claimAirdrop, createBitmapShard, fund, getBitmapShard, getProof, initializeAirdrop } from "./utils";
+
+describe("claim status", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  const claimants = [Keypair.generate(), Keypair.generate()];
+  const allocations = claimants.map((claimant) => ({ address: claimant.publicKey, amount: 100 }));
+
+  const getClaimReceipt = (airdrop: Airdrop, index: number) =>
+    PublicKey.findProgramAddressSync([Buffer.from("receipt"), airdrop.airdropState.toBuffer(), new anchor.BN(index).toArrayLike(Buffer, "le", 8)], program.programId)[0];
+
+  const getClaimStatus = (airdrop: Airdrop, index: number, claimReceipt: PublicKey | null = null) =>
+    program.methods.getClaimStatus(new anchor.BN(index))
+      .accountsPartial({
+        airdropState: airdrop.airdropState,
+        claimBitmap: getBitmapShard(program.programId, airdrop.airdropState, 0),
+        claimReceipt,
+      })
+      .view();
+
+  // Claims like `claimAirdrop` and opens the receipt of the index along
+  const claimWithReceipt = async (airdrop: Airdrop, index: number) => {
+    const claimant = claimants[index];
+    await program.methods.claimAirdrop(new anchor.BN(allocations[index].amount), getProof(airdrop.tree, index), new anchor.BN(index), new anchor.BN(0), null, false)
+      .accountsPartial({
+        airdropState: airdrop.airdropState,
+        mint: airdrop.mint,
+        vault: airdrop.vault,
+        signerAta: await getAssociatedTokenAddress(airdrop.mint, claimant.publicKey, false, airdrop.tokenProgram),
+        claimBitmap: getBitmapShard(program.programId, airdrop.airdropState, 0),
+        claimReceipt: getClaimReceipt(airdrop, index),
+        claimant: claimant.publicKey,
+        payer: claimant.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: airdrop.tokenProgram,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .signers([claimant])
+      .rpc();
+  };
+
+  before(async () => {
+    for (const claimant of claimants) {
+      await fund(provider, claimant.publicKey);
+    }
+  });
+
+  it("Reports per index whether it was claimed", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations);
+    await createBitmapShard(program, airdrop.airdropState, 0);
+    await claimAirdrop(program, claimants[1], airdrop, 100, 1);
+
+    const unclaimed = await getClaimStatus(airdrop, 0);
+    expect(unclaimed.index.toNumber()).to.equal(0);
+    expect(unclaimed.claimed).to.equal(false);
+
+    const claimed = await getClaimStatus(airdrop, 1);
+    expect(claimed.index.toNumber()).to.equal(1);
+    expect(claimed.claimed).to.equal(true);
+    expect(claimed.amount).to.be.null;
+    expect(claimed.claimedAt).to.be.null;
+  });
+
+  it("Reports the amount and time of a claim that opened a receipt", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations);
+    await createBitmapShard(program, airdrop.airdropState, 0);
+    const before = Math.floor(Date.now() / 1000) - 60;
+    await claimWithReceipt(airdrop, 0);
+
+    const status = await getClaimStatus(airdrop, 0, getClaimReceipt(airdrop, 0));
+    expect(status.claimed).to.equal(true);
+    expect(status.amount.toNumber()).to.equal(100);
+    expect(status.claimedAt.toNumber()).to.be.at.least(before);
+
+    const receipt = await program.account.claimReceipt.fetch(getClaimReceipt(airdrop, 0));
+    expect(receipt.index.toNumber()).to.equal(0);
+    expect(receipt.claimedAt.toNumber()).to.equal(status.claimedAt.toNumber());
+  });
+
+  it("Fail to query an index past the number of leaves", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations);
+    await createBitmapShard(program, airdrop.airdropState, 0);
+
+    try {
+      await getClaimStatus(airdrop, allocations.length);
+      expect.fail("Query should have failed for an out of range index");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Leaf index out of range");
+    }
+  });
+});
@@ -0,0 +1,131 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair } from "@solana/web3.js";
+import { getAccount } from "@solana/spl-token";
+import { claimAirdrop, createBitmapShard, findCpiEvent, fund, initializeAirdrop } from "./utils";
+
+describe("claim window", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  const claimants = [Keypair.generate(), Keypair.generate()];
+  const allocations = claimants.map((claimant) => ({ address: claimant.publicKey, amount: 100 }));
+
+  const now = () => Math.floor(Date.now() / 1000);
+
+  const setStartTime = (airdropState: anchor.web3.PublicKey, startTime: number) =>
+    program.methods.setStartTime(new anchor.BN(startTime))
+      .accountsPartial({ airdropState, updateAuthority: authority.publicKey })
+      .rpc();
+
+  const extendDeadline = (airdropState: anchor.web3.PublicKey, endTime: number) =>
+    program.methods.extendDeadline(new anchor.BN(endTime))
+      .accountsPartial({ airdropState, updateAuthority: authority.publicKey })
+      .rpc();
+
+  before(async () => {
+    for (const claimant of claimants) {
+      await fund(provider, claimant.publicKey);
+    }
+  });
+
+  it("Fail to claim before the start time", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations, { startTime: now() + 86_400 });
+    await createBitmapShard(program, airdrop.airdropState, 0);
+
+    try {
+      await claimAirdrop(program, claimants[0], airdrop, 100, 0);
+      expect.fail("Claim should have failed before the start time");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Airdrop has not started yet");
+    }
+  });
+
+  it("Claims once the authority moves the start time earlier", async () => {
+    const startTime = now() + 86_400;
+    const airdrop = await initializeAirdrop(program, authority, allocations, { startTime });
+    await createBitmapShard(program, airdrop.airdropState, 0);
+
+    await setStartTime(airdrop.airdropState, startTime - 3_600);
+    expect((await program.account.airdropState.fetch(airdrop.airdropState)).startTime.toNumber()).to.equal(startTime - 3_600);
+
+    await setStartTime(airdrop.airdropState, 0);
+    const signerAta = await claimAirdrop(program, claimants[0], airdrop, 100, 0);
+    expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(100);
+  });
+
+  it("Fail to move the start time later", async () => {
+    const startTime = now() + 86_400;
+    const airdrop = await initializeAirdrop(program, authority, allocations, { startTime });
+
+    try {
+      await setStartTime(airdrop.airdropState, startTime + 1);
+      expect.fail("Start time should not move later");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Start time can only be moved earlier");
+    }
+  });
+
+  it("Claims before the deadline", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations, { endTime: now() + 86_400 });
+    await createBitmapShard(program, airdrop.airdropState, 0);
+
+    const signerAta = await claimAirdrop(program, claimants[1], airdrop, 100, 1);
+    expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(100);
+  });
+
+  it("Fail to claim after the deadline", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations, { endTime: now() - 60 });
+    await createBitmapShard(program, airdrop.airdropState, 0);
+
+    try {
+      await claimAirdrop(program, claimants[0], airdrop, 100, 0);
+      expect.fail("Claim should have failed after the deadline");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Airdrop has ended");
+    }
+  });
+
+  it("Extends the deadline and emits the old and new end times", async () => {
+    const endTime = now() + 3_600;
+    const airdrop = await initializeAirdrop(program, authority, allocations, { endTime });
+
+    const signature = await extendDeadline(airdrop.airdropState, endTime + 86_400);
+    const event = await findCpiEvent(program, signature, "deadlineExtended");
+
+    expect(event.airdropState.toString()).to.equal(airdrop.airdropState.toString());
+    expect(event.oldEndTime.toNumber()).to.equal(endTime);
+    expect(event.newEndTime.toNumber()).to.equal(endTime + 86_400);
+    expect((await program.account.airdropState.fetch(airdrop.airdropState)).endTime.toNumber()).to.equal(endTime + 86_400);
+  });
+
+  it("Fail to shorten the deadline", async () => {
+    const endTime = now() + 3_600;
+    const airdrop = await initializeAirdrop(program, authority, allocations, { endTime });
+
+    try {
+      await extendDeadline(airdrop.airdropState, endTime - 60);
+      expect.fail("Deadline should not move earlier");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Deadline can only be extended");
+    }
+  });
+
+  it("Fail to extend a deadline that already passed to another past time", async () => {
+    const endTime = now() - 3_600;
+    const airdrop = await initializeAirdrop(program, authority, allocations, { endTime });
+
+    try {
+      await extendDeadline(airdrop.airdropState, endTime + 60);
+      expect.fail("Deadline should not be extended into the past");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Deadline can only be extended");
+    }
+  });
+});
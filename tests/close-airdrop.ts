@@ -0,0 +1,71 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair } from "@solana/web3.js";
+import { claimAirdrop, closeAirdrop, createBitmapShard, fund, initializeAirdrop, withdrawUnclaimed } from "./utils";
+
+describe("close airdrop", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  const claimants = [Keypair.generate(), Keypair.generate()];
+  const allocations = claimants.map((claimant) => ({ address: claimant.publicKey, amount: 100 }));
+
+  const now = () => Math.floor(Date.now() / 1000);
+
+  before(async () => {
+    for (const claimant of claimants) {
+      await fund(provider, claimant.publicKey);
+    }
+  });
+
+  it("Closes the state and vault once the unclaimed balance is withdrawn", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations, { endTime: now() - 60 });
+    await createBitmapShard(program, airdrop.airdropState, 0);
+    await withdrawUnclaimed(program, authority, airdrop);
+
+    const balanceBefore = await provider.connection.getBalance(authority.publicKey);
+    await closeAirdrop(program, authority, airdrop);
+
+    expect(await provider.connection.getAccountInfo(airdrop.airdropState)).to.be.null;
+    expect(await provider.connection.getAccountInfo(airdrop.vault)).to.be.null;
+    expect(await provider.connection.getBalance(authority.publicKey)).to.be.greaterThan(balanceBefore);
+
+    try {
+      await claimAirdrop(program, claimants[0], airdrop, 100, 0, [], 0);
+      expect.fail("Claim should have failed against a closed airdrop");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("AccountNotInitialized");
+    }
+  });
+
+  it("Fail to close while the vault still holds tokens", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations, { endTime: now() - 60 });
+
+    try {
+      await closeAirdrop(program, authority, airdrop);
+      expect.fail("Close should have failed with a funded vault");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Vault still holds tokens");
+    }
+  });
+
+  it("Fail to close a mint-on-claim airdrop while claims are live", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations, {
+      authorityMode: { transferToPda: {} },
+      mintOnClaim: true,
+    });
+
+    try {
+      await closeAirdrop(program, authority, airdrop);
+      expect.fail("Close should have failed while claims are live");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Claims are still open");
+    }
+  });
+});
@@ -0,0 +1,130 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair, PublicKey, SystemProgram, Transaction, TransactionInstruction } from "@solana/web3.js";
+import { createMint, getAccount, getAssociatedTokenAddress, getOrCreateAssociatedTokenAccount, mintTo } from "@solana/spl-token";
+import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
+import { Airdrop, claimAirdrop, createBitmapShard, fund, getBitmapShard, getMetaplexMetadata, getProof, initializeAirdrop, TOKEN_METADATA_PROGRAM_ID } from "./utils";
+
+// CreateMetadataAccountV3 with an unverified collection, verifying it takes master editions
+const createMetadataInstruction = (mint: PublicKey, authority: PublicKey, collection: PublicKey) => {
+  const string = (value: string) => {
+    const bytes = Buffer.from(value, "utf8");
+    const length = Buffer.alloc(4);
+    length.writeUInt32LE(bytes.length);
+    return Buffer.concat([length, bytes]);
+  };
+  const data = Buffer.concat([
+    Buffer.from([33]),
+    string("Gate"),
+    string("GATE"),
+    string("https://example.com/gate.json"),
+    Buffer.from([0, 0]), // seller fee basis points
+    Buffer.from([0]), // no creators
+    Buffer.from([1, 0]), // some collection, unverified
+    collection.toBuffer(),
+    Buffer.from([0]), // no uses
+    Buffer.from([1]), // mutable
+    Buffer.from([0]), // no collection details
+  ]);
+  return new TransactionInstruction({
+    programId: TOKEN_METADATA_PROGRAM_ID,
+    keys: [
+      { pubkey: getMetaplexMetadata(mint), isSigner: false, isWritable: true },
+      { pubkey: mint, isSigner: false, isWritable: false },
+      { pubkey: authority, isSigner: true, isWritable: false },
+      { pubkey: authority, isSigner: true, isWritable: true },
+      { pubkey: authority, isSigner: true, isWritable: false },
+      { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+    ],
+    data,
+  });
+};
+
+describe("collection gate", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+  const collection = Keypair.generate().publicKey;
+
+  const claimants = [Keypair.generate(), Keypair.generate()];
+  const allocations = claimants.map((claimant) => ({ address: claimant.publicKey, amount: 100 }));
+
+  let airdrop: Airdrop;
+  let nftMint: PublicKey;
+  let nftAccount: PublicKey;
+
+  const setCollectionGate = (gateCollection: PublicKey | null) =>
+    program.methods.setCollectionGate(gateCollection)
+      .accountsPartial({ airdropState: airdrop.airdropState, updateAuthority: authority.publicKey })
+      .rpc();
+
+  const claimWithNft = async (index: number, gateNftAccount = nftAccount) => {
+    const claimant = claimants[index];
+    await program.methods.claimAirdrop(new anchor.BN(allocations[index].amount), getProof(airdrop.tree, index), new anchor.BN(index), new anchor.BN(0), null, false)
+      .accountsPartial({
+        airdropState: airdrop.airdropState,
+        mint: airdrop.mint,
+        vault: airdrop.vault,
+        signerAta: await getAssociatedTokenAddress(airdrop.mint, claimant.publicKey),
+        claimBitmap: getBitmapShard(program.programId, airdrop.airdropState, 0),
+        gateNftAccount,
+        gateNftMetadata: getMetaplexMetadata(nftMint),
+        claimant: claimant.publicKey,
+        payer: claimant.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: airdrop.tokenProgram,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .signers([claimant])
+      .rpc();
+  };
+
+  const expectError = async (call: Promise<unknown>, code: string) => {
+    try {
+      await call;
+      expect.fail(`Call should have failed with ${code}`);
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal(code);
+    }
+  };
+
+  before(async () => {
+    await Promise.all(claimants.map((claimant) => fund(provider, claimant.publicKey)));
+    nftMint = await createMint(provider.connection, authority, authority.publicKey, null, 0);
+    await provider.sendAndConfirm(new Transaction().add(createMetadataInstruction(nftMint, authority.publicKey, collection)));
+    nftAccount = (await getOrCreateAssociatedTokenAccount(provider.connection, authority, nftMint, claimants[0].publicKey)).address;
+    await mintTo(provider.connection, authority, nftMint, nftAccount, authority, 1);
+
+    airdrop = await initializeAirdrop(program, authority, allocations);
+    await createBitmapShard(program, airdrop.airdropState, 0);
+    await setCollectionGate(collection);
+  });
+
+  it("Fail to claim without the gate NFT accounts", async () => {
+    await expectError(claimAirdrop(program, claimants[0], airdrop, 100, 0), "MissingGateNft");
+  });
+
+  it("Fail to claim with an NFT held by someone else", async () => {
+    await expectError(claimWithNft(1), "GateNftNotHeld");
+  });
+
+  it("Fail to claim with an NFT of an unverified collection", async () => {
+    await expectError(claimWithNft(0), "GateCollectionMismatch");
+  });
+
+  it("Claims without the gate NFT once the gate is lifted", async () => {
+    await setCollectionGate(null);
+
+    const signerAta = await claimAirdrop(program, claimants[1], airdrop, 100, 1);
+    expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(100);
+  });
+
+  it("Fail to change the collection gate once claims started", async () => {
+    await expectError(setCollectionGate(collection), "GateLocked");
+  });
+});
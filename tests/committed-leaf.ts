@@ -0,0 +1,86 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair } from "@solana/web3.js";
+import { getAccount, getAssociatedTokenAddress, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
+import { keccak_256 } from "@noble/hashes/sha3";
+import { Airdrop, claimAirdrop, createBitmapShard, fund, getBitmapShard, getProof, initializeAirdrop } from "./utils";
+
+describe("committed leaves", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  const claimants = [Keypair.generate(), Keypair.generate()];
+  // Fields of the allocation schema the program knows nothing about
+  const allocations = claimants.map((claimant, i) => ({
+    address: claimant.publicKey,
+    amount: 100 * (i + 1),
+    dataHash: Buffer.from(keccak_256(`cohort-${i}`)),
+  }));
+
+  const claimCommittedLeaf = async (airdrop: Airdrop, signer: Keypair, index: number, amount: number, dataHash: Buffer) =>
+    program.methods.claimCommittedLeaf(new anchor.BN(amount), getProof(airdrop.tree, index), new anchor.BN(index), new anchor.BN(0), Array.from(dataHash))
+      .accountsPartial({
+        airdropState: airdrop.airdropState,
+        mint: airdrop.mint,
+        vault: airdrop.vault,
+        signerAta: await getAssociatedTokenAddress(airdrop.mint, signer.publicKey),
+        claimBitmap: getBitmapShard(program.programId, airdrop.airdropState, 0),
+        claimant: signer.publicKey,
+        payer: signer.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .signers([signer])
+      .rpc();
+
+  let airdrop: Airdrop;
+
+  before(async () => {
+    for (const claimant of claimants) {
+      await fund(provider, claimant.publicKey);
+    }
+    airdrop = await initializeAirdrop(program, authority, allocations, { leafVersion: 2 });
+    await createBitmapShard(program, airdrop.airdropState, 0);
+  });
+
+  it("Fail to claim with data that was not committed to", async () => {
+    try {
+      await claimCommittedLeaf(airdrop, claimants[0], 0, 100, allocations[1].dataHash);
+      expect.fail("Claim should have failed for another data hash");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Invalid Merkle proof");
+    }
+  });
+
+  it("Fail to claim someone else's committed leaf", async () => {
+    try {
+      await claimCommittedLeaf(airdrop, claimants[1], 0, 100, allocations[0].dataHash);
+      expect.fail("Claim should have failed for another signer");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Invalid Merkle proof");
+    }
+  });
+
+  it("Fail to claim a committed leaf without its data hash", async () => {
+    try {
+      await claimAirdrop(program, claimants[0], airdrop, 100, 0);
+      expect.fail("Claim should have failed without the data hash");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Leaves of this airdrop commit to extra data, claim with its hash");
+    }
+  });
+
+  it("Claims a committed leaf with its data hash", async () => {
+    await claimCommittedLeaf(airdrop, claimants[0], 0, 100, allocations[0].dataHash);
+
+    const signerAta = await getAssociatedTokenAddress(airdrop.mint, claimants[0].publicKey);
+    expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(100);
+  });
+});
@@ -0,0 +1,97 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { ProofBench } from "../target/types/proof_bench";
+import { expect } from "chai";
+import { Keypair } from "@solana/web3.js";
+import { Airdrop, buildPathTree, claimAirdrop, createBitmapShard, encodeLeaf, fund, getComputeUnits, getProof, initializeAirdrop } from "./utils";
+
+describe("compute units", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const bench = anchor.workspace.ProofBench as Program<ProofBench>;
+  const authority = wallet.payer;
+
+  // Ceilings, well above what a claim costs today, a proof hashed twice or more per level trips them
+  const PER_LEVEL_CEILING = 1_000;
+  const CLAIM_CEILING = 100_000;
+
+  const claimant = Keypair.generate();
+  const amount = 100;
+
+  // Airdrop whose tree is `depth` levels deep, the claimant's leaf sitting at index 0
+  const initializeDeepAirdrop = (depth: number) =>
+    initializeAirdrop(program, authority, [{ address: claimant.publicKey, amount }], {
+      tree: buildPathTree(encodeLeaf(claimant.publicKey, amount), depth),
+      numLeaves: 2 ** depth,
+    });
+
+  // Compute units of verifying the claimant's proof, by simulating `verify_eligibility`
+  const verificationUnits = async (airdrop: Airdrop) => {
+    const simulation = await program.methods.verifyEligibility(claimant.publicKey, new anchor.BN(amount), getProof(airdrop.tree, 0), new anchor.BN(0), new anchor.BN(0))
+      .accountsPartial({ airdropState: airdrop.airdropState, claimBitmap: null })
+      .simulate();
+    return getComputeUnits(program, simulation.raw as string[]);
+  };
+
+  // Compute units of verifying a `depth` levels deep proof through each path of the bench program
+  const benchUnits = async (depth: number) => {
+    const leaf = encodeLeaf(claimant.publicKey, amount);
+    const tree = buildPathTree(leaf, depth);
+    const args = [Array.from(tree.get_merkle_root()), leaf, getProof(tree, 0), 0] as const;
+    const [merklize, fold] = [
+      await bench.methods.verifyMerklize(...args).simulate(),
+      await bench.methods.verifyFold(...args).simulate(),
+    ].map((simulation) => getComputeUnits(bench, simulation.raw as string[]));
+    return { merklize, fold };
+  };
+
+  before(async () => {
+    await fund(provider, claimant.publicKey);
+  });
+
+  it("Hashes every level of a proof once", async () => {
+    const shallow = await verificationUnits(await initializeDeepAirdrop(4));
+    const deep = await verificationUnits(await initializeDeepAirdrop(16));
+    const perLevel = (deep - shallow) / 12;
+    console.log(`      verification: ${shallow} CU at depth 4, ${deep} CU at depth 16, ${perLevel} CU per level`);
+
+    expect(perLevel).to.be.below(PER_LEVEL_CEILING);
+  });
+
+  it("Verifies depth 16 and depth 24 proofs at the same cost per level", async () => {
+    const shallow = await verificationUnits(await initializeDeepAirdrop(4));
+    const [depth16, depth24] = [await verificationUnits(await initializeDeepAirdrop(16)), await verificationUnits(await initializeDeepAirdrop(24))];
+    console.log(`      verification: ${depth16} CU at depth 16, ${depth24} CU at depth 24`);
+
+    // One hash syscall per level, nothing allocated along the proof
+    expect((depth16 - shallow) / 12).to.be.below(PER_LEVEL_CEILING);
+    expect((depth24 - shallow) / 20).to.be.below(PER_LEVEL_CEILING);
+    expect(Math.abs((depth24 - depth16) / 8 - (depth16 - shallow) / 12)).to.be.below(PER_LEVEL_CEILING / 10);
+  });
+
+  it("Verifies depth 16 and depth 24 proofs for less than the merklize path", async () => {
+    for (const depth of [16, 24]) {
+      const { merklize, fold } = await benchUnits(depth);
+      console.log(`      depth ${depth}: ${merklize} CU before, ${fold} CU after`);
+
+      expect(fold).to.be.below(merklize);
+    }
+  });
+
+  it("Claims a depth 16 proof within the budget", async () => {
+    const airdrop = await initializeDeepAirdrop(16);
+    await createBitmapShard(program, airdrop.airdropState, 0);
+
+    const signerAta = await claimAirdrop(program, claimant, airdrop, amount, 0);
+    const [{ signature }] = await provider.connection.getSignaturesForAddress(signerAta, { limit: 1 }, "confirmed");
+    const tx = await provider.connection.getTransaction(signature, { commitment: "confirmed", maxSupportedTransactionVersion: 0 });
+    const units = getComputeUnits(program, tx.meta.logMessages);
+    console.log(`      claim: ${units} CU at depth 16`);
+
+    expect(units).to.be.below(CLAIM_CEILING);
+  });
+});
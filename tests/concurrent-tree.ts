@@ -0,0 +1,176 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { AccountMeta, Keypair, PublicKey, SystemProgram, Transaction } from "@solana/web3.js";
+import { getAccount, getAssociatedTokenAddress } from "@solana/spl-token";
+import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
+import { keccak_256 } from "@noble/hashes/sha3";
+import { Airdrop, claimAirdrop, createBitmapShard, encodeLeaf, fund, initializeAirdrop } from "./utils";
+
+describe("concurrent tree", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  const COMPRESSION_PROGRAM = new PublicKey("cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK");
+  const NOOP_PROGRAM = new PublicKey("noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV");
+  const MAX_DEPTH = 3;
+  const MAX_BUFFER_SIZE = 8;
+
+  const claimants = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  const allocations = claimants.map((claimant, i) => ({ address: claimant.publicKey, amount: (i + 1) * 100 }));
+
+  // Header, sequence number, active index and buffer size, the change log, then the rightmost path
+  const concurrentTreeSize = (maxDepth: number, maxBufferSize: number) =>
+    56 + 24 + maxBufferSize * (32 + 32 * maxDepth + 8) + (32 * maxDepth + 32 + 8);
+
+  const leafNode = (address: PublicKey, amount: number) => Buffer.from(keccak_256(encodeLeaf(address, amount)));
+
+  // The tree as the compression program holds it, unused leaves being empty nodes
+  const levels = (leaves: Buffer[]) => {
+    const tree = [Array.from({ length: 2 ** MAX_DEPTH }, (_, i) => leaves[i] ?? Buffer.alloc(32))];
+    for (let depth = 0; depth < MAX_DEPTH; depth++) {
+      const level = tree[depth];
+      tree.push(level.filter((_, i) => i % 2 === 0).map((left, i) => Buffer.from(keccak_256(Buffer.concat([left, level[2 * i + 1]])))));
+    }
+    return tree;
+  };
+  const rootOf = (leaves: Buffer[]) => Array.from(levels(leaves)[MAX_DEPTH][0]);
+  const proofOf = (leaves: Buffer[], index: number): AccountMeta[] =>
+    levels(leaves)
+      .slice(0, MAX_DEPTH)
+      .map((level, depth) => ({ pubkey: new PublicKey(level[(index >> depth) ^ 1]), isSigner: false, isWritable: false }));
+
+  let airdrop: Airdrop;
+  let merkleTree: Keypair;
+  let leaves: Buffer[];
+
+  const modifyTree = () => ({
+    airdropState: airdrop.airdropState,
+    merkleTree: merkleTree.publicKey,
+    updateAuthority: authority.publicKey,
+    compressionProgram: COMPRESSION_PROGRAM,
+    noopProgram: NOOP_PROGRAM,
+  });
+
+  const claimConcurrent = async (index: number, amount: number, root: number[], proof: AccountMeta[]) => {
+    const claimant = claimants[index];
+    const signerAta = await getAssociatedTokenAddress(airdrop.mint, claimant.publicKey);
+    await program.methods.claimConcurrent(new anchor.BN(amount), root, new anchor.BN(index))
+      .accountsPartial({
+        airdropState: airdrop.airdropState,
+        mint: airdrop.mint,
+        vault: airdrop.vault,
+        signerAta,
+        merkleTree: merkleTree.publicKey,
+        revokedLeaf: null,
+        feeAta: null,
+        feeTreasury: null,
+        claimant: claimant.publicKey,
+        payer: claimant.publicKey,
+        compressionProgram: COMPRESSION_PROGRAM,
+        noopProgram: NOOP_PROGRAM,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: airdrop.tokenProgram,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .remainingAccounts(proof)
+      .signers([claimant])
+      .rpc();
+    return signerAta;
+  };
+
+  const expectError = async (call: Promise<unknown>, code: string) => {
+    try {
+      await call;
+      expect.fail(`Call should have failed with ${code}`);
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal(code);
+    }
+  };
+
+  before(async () => {
+    await Promise.all(claimants.map((claimant) => fund(provider, claimant.publicKey)));
+    airdrop = await initializeAirdrop(program, authority, allocations);
+    await createBitmapShard(program, airdrop.airdropState, 0);
+
+    merkleTree = Keypair.generate();
+    const space = concurrentTreeSize(MAX_DEPTH, MAX_BUFFER_SIZE);
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: authority.publicKey,
+          newAccountPubkey: merkleTree.publicKey,
+          lamports: await provider.connection.getMinimumBalanceForRentExemption(space),
+          space,
+          programId: COMPRESSION_PROGRAM,
+        })
+      ),
+      [merkleTree]
+    );
+    await program.methods.enableConcurrentTree(MAX_DEPTH, MAX_BUFFER_SIZE).accountsPartial(modifyTree()).rpc();
+
+    leaves = allocations.map((entry) => leafNode(entry.address, entry.amount));
+    await program.methods.appendConcurrentLeaves(leaves.map((leaf) => Array.from(leaf))).accountsPartial(modifyTree()).rpc();
+  });
+
+  it("Moves the airdrop onto the concurrent tree", async () => {
+    const state = await program.account.airdropState.fetch(airdrop.airdropState);
+    expect(state.treeMode).to.equal(1);
+    expect(state.concurrentTree.toBase58()).to.equal(merkleTree.publicKey.toBase58());
+    expect(state.numLeaves.toNumber()).to.equal(allocations.length);
+  });
+
+  it("Fail to swap the root of a concurrent airdrop", async () => {
+    const update = { airdropState: airdrop.airdropState, updateAuthority: authority.publicKey };
+    for (const method of [program.methods.updateTree, program.methods.forceUpdateTree, program.methods.proposeRoot]) {
+      await expectError(method(Array(32).fill(1), new anchor.BN(allocations.length)).accountsPartial(update).rpc(), "TreeModeMismatch");
+    }
+  });
+
+  it("Fail to claim a concurrent airdrop against the stored root", async () => {
+    await expectError(claimAirdrop(program, claimants[0], airdrop, 100, 0), "TreeModeMismatch");
+  });
+
+  it("Claims against a root another claim replaced meanwhile", async () => {
+    // Both proofs are made before either claim lands
+    const root = rootOf(leaves);
+    const proofs = [proofOf(leaves, 0), proofOf(leaves, 1)];
+
+    const first = await claimConcurrent(0, 100, root, proofs[0]);
+    const second = await claimConcurrent(1, 200, root, proofs[1]);
+
+    expect(Number((await getAccount(provider.connection, first)).amount)).to.equal(100);
+    expect(Number((await getAccount(provider.connection, second)).amount)).to.equal(200);
+    expect((await program.account.airdropState.fetch(airdrop.airdropState)).numClaims.toNumber()).to.equal(2);
+    leaves[0] = Buffer.alloc(32);
+    leaves[1] = Buffer.alloc(32);
+  });
+
+  it("Fail to claim a leaf twice", async () => {
+    const claimedLeaves = [leafNode(allocations[0].address, 100), ...leaves.slice(1)];
+    try {
+      await claimConcurrent(0, 100, rootOf(claimedLeaves), proofOf(claimedLeaves, 0));
+      expect.fail("The claimed leaf should no longer be in the tree");
+    } catch (error: any) {
+      expect(String(error)).to.not.include("The claimed leaf should no longer be in the tree");
+    }
+    expect((await program.account.airdropState.fetch(airdrop.airdropState)).amountClaimed.toNumber()).to.equal(300);
+  });
+
+  it("Replaces a single allocation without touching the others", async () => {
+    const replaced = leafNode(allocations[2].address, 250);
+    await program.methods.replaceConcurrentLeaf(rootOf(leaves), Array.from(leaves[2]), Array.from(replaced), new anchor.BN(2))
+      .accountsPartial(modifyTree())
+      .remainingAccounts(proofOf(leaves, 2))
+      .rpc();
+    leaves[2] = replaced;
+
+    const signerAta = await claimConcurrent(2, 250, rootOf(leaves), proofOf(leaves, 2));
+    expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(250);
+  });
+});
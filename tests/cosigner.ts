@@ -0,0 +1,93 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair, SystemProgram } from "@solana/web3.js";
+import { getAccount, getAssociatedTokenAddress } from "@solana/spl-token";
+import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
+import { Airdrop, claimAirdrop, createBitmapShard, fund, getBitmapShard, getProof, initializeAirdrop } from "./utils";
+
+describe("cosigner", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+  const cosigner = Keypair.generate();
+  const rotated = Keypair.generate();
+
+  const claimants = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  const allocations = claimants.map((claimant) => ({ address: claimant.publicKey, amount: 100 }));
+
+  let airdrop: Airdrop;
+
+  const claimCosigned = async (index: number, signer: Keypair) => {
+    const claimant = claimants[index];
+    const signerAta = await getAssociatedTokenAddress(airdrop.mint, claimant.publicKey);
+    await program.methods.claimAirdrop(new anchor.BN(allocations[index].amount), getProof(airdrop.tree, index), new anchor.BN(index), new anchor.BN(0), null, false)
+      .accountsPartial({
+        airdropState: airdrop.airdropState,
+        mint: airdrop.mint,
+        vault: airdrop.vault,
+        signerAta,
+        claimBitmap: getBitmapShard(program.programId, airdrop.airdropState, 0),
+        cosigner: signer.publicKey,
+        claimant: claimant.publicKey,
+        payer: claimant.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: airdrop.tokenProgram,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .signers([claimant, signer])
+      .rpc();
+    return signerAta;
+  };
+
+  const expectError = async (call: Promise<unknown>, code: string) => {
+    try {
+      await call;
+      expect.fail(`Call should have failed with ${code}`);
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal(code);
+    }
+  };
+
+  before(async () => {
+    await Promise.all(claimants.map((claimant) => fund(provider, claimant.publicKey)));
+    airdrop = await initializeAirdrop(program, authority, allocations, { cosigner: cosigner.publicKey });
+    await createBitmapShard(program, airdrop.airdropState, 0);
+  });
+
+  it("Fail to claim without the cosigner", async () => {
+    await expectError(claimAirdrop(program, claimants[0], airdrop, 100, 0), "MissingCosigner");
+  });
+
+  it("Fail to claim co-signed by another key", async () => {
+    await expectError(claimCosigned(0, rotated), "MissingCosigner");
+  });
+
+  it("Claims co-signed by the cosigner", async () => {
+    const signerAta = await claimCosigned(0, cosigner);
+    expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(100);
+  });
+
+  it("Requires the rotated cosigner right away", async () => {
+    await program.methods.setCosigner(rotated.publicKey)
+      .accountsPartial({ airdropState: airdrop.airdropState, updateAuthority: authority.publicKey })
+      .rpc();
+
+    await expectError(claimCosigned(1, cosigner), "MissingCosigner");
+    const signerAta = await claimCosigned(1, rotated);
+    expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(100);
+  });
+
+  it("Claims without a cosigner once it is removed", async () => {
+    await program.methods.setCosigner(null)
+      .accountsPartial({ airdropState: airdrop.airdropState, updateAuthority: authority.publicKey })
+      .rpc();
+
+    const signerAta = await claimAirdrop(program, claimants[2], airdrop, 100, 2);
+    expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(100);
+  });
+});
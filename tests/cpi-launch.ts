@@ -0,0 +1,76 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { AirdropLauncher } from "../target/types/airdrop_launcher";
+import { expect } from "chai";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { getAccount, getAssociatedTokenAddress, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
+import { buildTree, createBitmapShard, fund, getAirdropState, getBitmapShard, getProof } from "./utils";
+
+describe("cpi launch", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const launcher = anchor.workspace.AirdropLauncher as Program<AirdropLauncher>;
+  const eventAuthority = PublicKey.findProgramAddressSync([Buffer.from("__event_authority")], program.programId)[0];
+
+  const claimant = Keypair.generate();
+  const allocations = [
+    { address: claimant.publicKey, amount: 300 },
+    { address: Keypair.generate().publicKey, amount: 200 },
+  ];
+  const tree = buildTree(allocations);
+
+  before(async () => {
+    await fund(provider, claimant.publicKey);
+  });
+
+  it("Starts an airdrop and claims from it through another program", async () => {
+    const mint = Keypair.generate();
+    const airdropState = getAirdropState(program.programId, mint.publicKey);
+    const vault = await getAssociatedTokenAddress(mint.publicKey, airdropState, true);
+
+    await launcher.methods.launch(Array.from(tree.get_merkle_root()), new anchor.BN(500), new anchor.BN(allocations.length))
+      .accountsPartial({
+        airdropState,
+        mint: mint.publicKey,
+        vault,
+        authority: wallet.publicKey,
+        eventAuthority,
+        claimerProgram: program.programId,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .signers([mint])
+      .rpc();
+    expect(Number((await getAccount(provider.connection, vault)).amount)).to.equal(500);
+
+    await createBitmapShard(program, airdropState, 0);
+    const claimantAta = await getAssociatedTokenAddress(mint.publicKey, claimant.publicKey);
+    await launcher.methods.claim(new anchor.BN(300), getProof(tree, 0), new anchor.BN(0), new anchor.BN(0))
+      .accountsPartial({
+        airdropState,
+        mint: mint.publicKey,
+        vault,
+        claimantAta,
+        claimBitmap: getBitmapShard(program.programId, airdropState, 0),
+        claimant: claimant.publicKey,
+        eventAuthority,
+        claimerProgram: program.programId,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .signers([claimant])
+      .rpc();
+
+    expect(Number((await getAccount(provider.connection, claimantAta)).amount)).to.equal(300);
+    expect(Number((await getAccount(provider.connection, vault)).amount)).to.equal(200);
+    const state = await program.account.airdropState.fetch(airdropState);
+    expect(state.amountClaimed.toNumber()).to.equal(300);
+  });
+});
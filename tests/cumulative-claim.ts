@@ -0,0 +1,97 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { getAccount, getAssociatedTokenAddress } from "@solana/spl-token";
+import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
+import { Airdrop, buildTree, createBitmapShard, findCpiEvent, fund, getBitmapShard, getProof, initializeAirdrop } from "./utils";
+
+describe("cumulative claim", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  // Weekly totals owed to each wallet, every root republishes them grown
+  const claimant = Keypair.generate();
+  const other = Keypair.generate().publicKey;
+  const weeks = [
+    [{ address: claimant.publicKey, amount: 100 }, { address: other, amount: 50 }],
+    [{ address: claimant.publicKey, amount: 250 }, { address: other, amount: 80 }],
+    [{ address: claimant.publicKey, amount: 400 }, { address: other, amount: 120 }],
+  ];
+
+  let airdrop: Airdrop;
+
+  const getClaimProgress = (owner: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("progress"), airdrop.airdropState.toBuffer(), owner.toBuffer()],
+      program.programId
+    )[0];
+
+  const claim = async (week: number) => {
+    const signerAta = await getAssociatedTokenAddress(airdrop.mint, claimant.publicKey, false, airdrop.tokenProgram);
+    const { rootVersion } = await program.account.airdropState.fetch(airdrop.airdropState);
+    const signature = await program.methods.claimAirdrop(new anchor.BN(weeks[week][0].amount), getProof(buildTree(weeks[week]), 0), new anchor.BN(0), rootVersion, null, false)
+      .accountsPartial({
+        airdropState: airdrop.airdropState,
+        mint: airdrop.mint,
+        vault: airdrop.vault,
+        signerAta,
+        claimBitmap: getBitmapShard(program.programId, airdrop.airdropState, 0),
+        claimProgress: getClaimProgress(claimant.publicKey),
+        claimant: claimant.publicKey,
+        payer: claimant.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: airdrop.tokenProgram,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .signers([claimant])
+      .rpc();
+    return { signature, signerAta };
+  };
+
+  const publish = (week: number) =>
+    program.methods.updateTree(Array.from(buildTree(weeks[week]).get_merkle_root()), new anchor.BN(weeks[week].length))
+      .accountsPartial({ airdropState: airdrop.airdropState, updateAuthority: authority.publicKey })
+      .rpc();
+
+  before(async () => {
+    await fund(provider, claimant.publicKey);
+    airdrop = await initializeAirdrop(program, authority, weeks[0], { amount: 1_000 });
+    await createBitmapShard(program, airdrop.airdropState, 0);
+    await program.methods.setClaimMode(2).accountsPartial({ airdropState: airdrop.airdropState, updateAuthority: authority.publicKey }).rpc();
+  });
+
+  it("Pays what is owed above what was already paid, across root updates", async () => {
+    const first = await claim(0);
+    expect((await findCpiEvent(program, first.signature, "claimEvent")).amount.toNumber()).to.equal(100);
+
+    await publish(1);
+    const second = await claim(1);
+    const event = await findCpiEvent(program, second.signature, "claimEvent");
+    expect(event.amount.toNumber()).to.equal(150);
+    expect(event.allocationClaimed.toNumber()).to.equal(250);
+
+    await publish(2);
+    const { signerAta } = await claim(2);
+
+    expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(400);
+    expect((await program.account.claimProgress.fetch(getClaimProgress(claimant.publicKey))).claimed.toNumber()).to.equal(400);
+    const state = await program.account.airdropState.fetch(airdrop.airdropState);
+    expect(state.amountClaimed.toNumber()).to.equal(400);
+    expect(state.numClaims.toNumber()).to.equal(1);
+  });
+
+  it("Fail to claim again before the next root grows the total", async () => {
+    try {
+      await claim(2);
+      expect.fail("Claim should have failed with nothing left to pay");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("AlreadyClaimed");
+    }
+  });
+});
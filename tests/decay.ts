@@ -0,0 +1,104 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { getAccount, getAssociatedTokenAddress } from "@solana/spl-token";
+import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
+import { Airdrop, claimAirdrop, createBitmapShard, findCpiEvent, fund, getBitmapShard, getProof, initializeAirdrop } from "./utils";
+
+describe("decay", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  const claimant = Keypair.generate();
+  const allocations = [
+    { address: claimant.publicKey, amount: 1_000 },
+    { address: Keypair.generate().publicKey, amount: 500 },
+  ];
+
+  const getDecayConfig = (airdrop: Airdrop) =>
+    PublicKey.findProgramAddressSync([Buffer.from("decay_config"), airdrop.airdropState.toBuffer()], program.programId)[0];
+
+  const configureDecay = (airdrop: Airdrop, decayDuration: number) =>
+    program.methods.configureDecay(new anchor.BN(decayDuration))
+      .accountsPartial({ airdropState: airdrop.airdropState, decayConfig: getDecayConfig(airdrop), updateAuthority: authority.publicKey, systemProgram: SystemProgram.programId })
+      .rpc();
+
+  const claimDecaying = async (airdrop: Airdrop) => {
+    const signerAta = await getAssociatedTokenAddress(airdrop.mint, claimant.publicKey, false, airdrop.tokenProgram);
+    const signature = await program.methods.claimAirdrop(new anchor.BN(1_000), getProof(airdrop.tree, 0), new anchor.BN(0), new anchor.BN(0), null, false)
+      .accountsPartial({
+        airdropState: airdrop.airdropState,
+        mint: airdrop.mint,
+        vault: airdrop.vault,
+        signerAta,
+        claimBitmap: getBitmapShard(program.programId, airdrop.airdropState, 0),
+        decayConfig: getDecayConfig(airdrop),
+        claimant: claimant.publicKey,
+        payer: claimant.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: airdrop.tokenProgram,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .signers([claimant])
+      .rpc();
+    return { signature, signerAta };
+  };
+
+  const expectError = async (call: Promise<unknown>, code: string) => {
+    try {
+      await call;
+      expect.fail(`Call should have failed with ${code}`);
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal(code);
+    }
+  };
+
+  // Opened `openedFor` seconds ago and decaying over `decayDuration` seconds
+  const setUp = async (openedFor: number, decayDuration: number) => {
+    const airdrop = await initializeAirdrop(program, authority, allocations, { startTime: Math.floor(Date.now() / 1000) - openedFor });
+    await createBitmapShard(program, airdrop.airdropState, 0);
+    await configureDecay(airdrop, decayDuration);
+    return airdrop;
+  };
+
+  before(async () => {
+    await fund(provider, claimant.publicKey);
+  });
+
+  it("Fail to configure decay without a duration", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations);
+    await expectError(configureDecay(airdrop, 0), "InvalidDecaySchedule");
+  });
+
+  it("Pays what is left of the leaf and forfeits the rest", async () => {
+    // Halfway through a two hour decay
+    const airdrop = await setUp(3_600, 7_200);
+    const { signature, signerAta } = await claimDecaying(airdrop);
+
+    const paid = Number((await getAccount(provider.connection, signerAta)).amount);
+    expect(paid).to.be.within(450, 550);
+    const event = await findCpiEvent(program, signature, "claimEvent");
+    expect(event.amount.toNumber()).to.equal(paid);
+    expect(event.allocationClaimed.toNumber()).to.equal(1_000);
+
+    const decayConfig = await program.account.decayConfig.fetch(getDecayConfig(airdrop));
+    expect(decayConfig.totalForfeited.toNumber()).to.equal(1_000 - paid);
+    expect((await program.account.airdropState.fetch(airdrop.airdropState)).amountClaimed.toNumber()).to.equal(paid);
+  });
+
+  it("Fail to claim without the decay config", async () => {
+    const airdrop = await setUp(0, 7_200);
+    await expectError(claimAirdrop(program, claimant, airdrop, 1_000, 0), "MissingDecayConfig");
+  });
+
+  it("Fail to claim a fully decayed leaf", async () => {
+    const airdrop = await setUp(7_200, 3_600);
+    await expectError(claimDecaying(airdrop), "AllocationDecayed");
+  });
+});
@@ -0,0 +1,58 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair } from "@solana/web3.js";
+import { getAccount } from "@solana/spl-token";
+import { keccak_256 } from "@noble/hashes/sha3";
+import { claimAirdrop, createBitmapShard, encodeLeaf, fund, getProof, initializeAirdrop } from "./utils";
+
+describe("distributor leaf format", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  const claimants = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  const allocations = claimants.map((claimant, i) => ({ address: claimant.publicKey, amount: 100 * (i + 1) }));
+  // How merkle-distributor trees are built: keccak over `index ‖ claimant ‖ amount`, sorted pairs
+  const distributorTree = { leafVersion: 1, pairOrdering: 1 };
+
+  before(async () => {
+    for (const claimant of claimants) {
+      await fund(provider, claimant.publicKey);
+    }
+  });
+
+  it("Hashes the leaf over index, claimant and amount", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations, distributorTree);
+
+    const result = await program.methods.verifyEligibility(claimants[2].publicKey, new anchor.BN(300), getProof(airdrop.tree, 2), new anchor.BN(2), new anchor.BN(0))
+      .accountsPartial({ airdropState: airdrop.airdropState, claimBitmap: null })
+      .view();
+    expect(result.eligible).to.equal(true);
+    expect(result.leafHash).to.deep.equal(Array.from(keccak_256(encodeLeaf(claimants[2].publicKey, 300, 1, 2))));
+    expect(encodeLeaf(claimants[2].publicKey, 300, 1, 2).length).to.equal(8 + 32 + 8);
+  });
+
+  it("Claims every leaf of a distributor tree", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations, distributorTree);
+    await createBitmapShard(program, airdrop.airdropState, 0);
+
+    for (const [index, entry] of allocations.entries()) {
+      const signerAta = await claimAirdrop(program, claimants[index], airdrop, entry.amount, index);
+      expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(entry.amount);
+    }
+  });
+
+  it("Fail to initialize with an unknown leaf version", async () => {
+    try {
+      await initializeAirdrop(program, authority, allocations, { leafVersion: 255 });
+      expect.fail("Initialization should have failed for an unknown leaf version");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Leaf version is not supported");
+    }
+  });
+});
@@ -0,0 +1,115 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { createMint, getAccount, getAssociatedTokenAddress, getOrCreateAssociatedTokenAccount, mintTo, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
+import { Airdrop, createBitmapShard, findCpiEvent, fund, getBitmapShard, getProof, initializeWithExistingMint } from "./utils";
+
+describe("early-claim bonus", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  const claimants = [Keypair.generate(), Keypair.generate()];
+  const allocations = [
+    { address: claimants[0].publicKey, amount: 100 },
+    { address: claimants[1].publicKey, amount: 200 },
+  ];
+
+  const getBonusConfig = (airdrop: Airdrop) =>
+    PublicKey.findProgramAddressSync([Buffer.from("bonus_config"), airdrop.airdropState.toBuffer()], program.programId)[0];
+
+  // A fresh mint per airdrop, its authority holding the airdrop amount and a reserve to fund
+  const setUp = async () => {
+    const mint = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+    const authorityAta = await getOrCreateAssociatedTokenAccount(provider.connection, authority, mint, authority.publicKey);
+    await mintTo(provider.connection, authority, mint, authorityAta.address, authority, 1_000);
+    const airdrop = await initializeWithExistingMint(program, authority, mint, authorityAta.address, allocations, 300);
+    await createBitmapShard(program, airdrop.airdropState, 0);
+    return { airdrop, authorityAta: authorityAta.address };
+  };
+
+  const fundBonus = (airdrop: Airdrop, authorityAta: PublicKey, bonusBps: number, bonusWindowEnd: number, reserve: number) =>
+    program.methods.fundBonus(bonusBps, new anchor.BN(bonusWindowEnd), new anchor.BN(reserve))
+      .accountsPartial({
+        airdropState: airdrop.airdropState,
+        bonusConfig: getBonusConfig(airdrop),
+        mint: airdrop.mint,
+        vault: airdrop.vault,
+        authorityAta,
+        updateAuthority: authority.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+  const claim = async (airdrop: Airdrop, index: number) => {
+    const claimant = claimants[index];
+    const signerAta = await getAssociatedTokenAddress(airdrop.mint, claimant.publicKey);
+    const signature = await program.methods.claimAirdrop(new anchor.BN(allocations[index].amount), getProof(airdrop.tree, index), new anchor.BN(index), new anchor.BN(0), null, false)
+      .accountsPartial({
+        airdropState: airdrop.airdropState,
+        mint: airdrop.mint,
+        vault: airdrop.vault,
+        signerAta,
+        claimBitmap: getBitmapShard(program.programId, airdrop.airdropState, 0),
+        bonusConfig: getBonusConfig(airdrop),
+        claimant: claimant.publicKey,
+        payer: claimant.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .signers([claimant])
+      .rpc();
+    return { event: await findCpiEvent(program, signature, "claimEvent"), signerAta };
+  };
+
+  before(async () => {
+    await Promise.all(claimants.map((claimant) => fund(provider, claimant.publicKey)));
+  });
+
+  it("Fail to fund a bonus above the whole leaf amount", async () => {
+    const { airdrop, authorityAta } = await setUp();
+    try {
+      await fundBonus(airdrop, authorityAta, 10_001, 0, 100);
+      expect.fail("Funding should have failed with an invalid bonus");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("InvalidBonus");
+    }
+  });
+
+  it("Pays a bonus within the window until the reserve runs out", async () => {
+    const { airdrop, authorityAta } = await setUp();
+    await fundBonus(airdrop, authorityAta, 1_000, Math.floor(Date.now() / 1000) + 3_600, 15);
+
+    const first = await claim(airdrop, 0);
+    expect(first.event.amount.toNumber()).to.equal(100);
+    expect(first.event.bonus.toNumber()).to.equal(10);
+    expect(Number((await getAccount(provider.connection, first.signerAta)).amount)).to.equal(110);
+
+    // 10% of 200 is more than the 5 left of the reserve
+    const second = await claim(airdrop, 1);
+    expect(second.event.bonus.toNumber()).to.equal(5);
+    expect(Number((await getAccount(provider.connection, second.signerAta)).amount)).to.equal(205);
+
+    expect((await program.account.bonusConfig.fetch(getBonusConfig(airdrop))).bonusPaid.toNumber()).to.equal(15);
+    const state = await program.account.airdropState.fetch(airdrop.airdropState);
+    expect(state.amountClaimed.toNumber()).to.equal(300);
+    expect(state.airdropAmount.toNumber()).to.equal(300);
+  });
+
+  it("Pays the plain leaf amount once the window closed", async () => {
+    const { airdrop, authorityAta } = await setUp();
+    await fundBonus(airdrop, authorityAta, 1_000, Math.floor(Date.now() / 1000) - 60, 100);
+
+    const { event, signerAta } = await claim(airdrop, 0);
+    expect(event.bonus.toNumber()).to.equal(0);
+    expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(100);
+  });
+});
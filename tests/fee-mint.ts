@@ -0,0 +1,103 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { createMint, getAccount, getAssociatedTokenAddress, getOrCreateAssociatedTokenAccount, mintTo, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
+import { Airdrop, claimAirdrop, createBitmapShard, findCpiEvent, fund, getBitmapShard, getProof, initializeAirdrop } from "./utils";
+
+describe("claim fee in a fee mint", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+  const feeTreasury = Keypair.generate().publicKey;
+  const feeAmount = 1_000_000;
+
+  const claimants = [Keypair.generate(), Keypair.generate()];
+  const allocations = claimants.map((claimant) => ({ address: claimant.publicKey, amount: 100 }));
+
+  let airdrop: Airdrop;
+  let feeMint: PublicKey;
+  let otherMint: PublicKey;
+
+  // A stablecoin-like mint, every claimant holding enough of it for a few fees
+  const createFeeMint = async () => {
+    const mint = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+    await getOrCreateAssociatedTokenAccount(provider.connection, authority, mint, feeTreasury);
+    for (const claimant of claimants) {
+      const ata = await getOrCreateAssociatedTokenAccount(provider.connection, authority, mint, claimant.publicKey);
+      await mintTo(provider.connection, authority, mint, ata.address, authority, 10 * feeAmount);
+    }
+    return mint;
+  };
+
+  const claimWithFeeMint = async (index: number, mint = feeMint) => {
+    const claimant = claimants[index];
+    const payerFeeAta = await getAssociatedTokenAddress(mint, claimant.publicKey);
+    const signature = await program.methods.claimAirdrop(new anchor.BN(allocations[index].amount), getProof(airdrop.tree, index), new anchor.BN(index), new anchor.BN(0), null, false)
+      .accountsPartial({
+        airdropState: airdrop.airdropState,
+        mint: airdrop.mint,
+        vault: airdrop.vault,
+        signerAta: await getAssociatedTokenAddress(airdrop.mint, claimant.publicKey),
+        claimBitmap: getBitmapShard(program.programId, airdrop.airdropState, 0),
+        feeMint: mint,
+        payerFeeAta,
+        treasuryFeeAta: await getAssociatedTokenAddress(mint, feeTreasury),
+        feeTokenProgram: TOKEN_PROGRAM_ID,
+        claimant: claimant.publicKey,
+        payer: claimant.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: airdrop.tokenProgram,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .signers([claimant])
+      .rpc();
+    return { event: await findCpiEvent(program, signature, "claimEvent"), payerFeeAta };
+  };
+
+  const expectError = async (call: Promise<unknown>, code: string) => {
+    try {
+      await call;
+      expect.fail(`Call should have failed with ${code}`);
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal(code);
+    }
+  };
+
+  before(async () => {
+    await fund(provider, feeTreasury);
+    await Promise.all(claimants.map((claimant) => fund(provider, claimant.publicKey)));
+    feeMint = await createFeeMint();
+    otherMint = await createFeeMint();
+    airdrop = await initializeAirdrop(program, authority, allocations, { feeMint, feeAmount, feeTreasury });
+    await createBitmapShard(program, airdrop.airdropState, 0);
+  });
+
+  it("Fail to initialize with a fee mint but no fee amount", async () => {
+    await expectError(initializeAirdrop(program, authority, allocations, { feeMint, feeTreasury }), "InvalidFeeMint");
+  });
+
+  it("Fail to claim without the fee mint accounts", async () => {
+    await expectError(claimAirdrop(program, claimants[0], airdrop, 100, 0), "MissingFeeMintAccounts");
+  });
+
+  it("Fail to pay the claim fee in another mint", async () => {
+    await expectError(claimWithFeeMint(0, otherMint), "FeeAccountMismatch");
+  });
+
+  it("Charges the claim fee in the fee mint and records it in the claim event", async () => {
+    const treasuryFeeAta = await getAssociatedTokenAddress(feeMint, feeTreasury);
+    const { event, payerFeeAta } = await claimWithFeeMint(1);
+
+    expect(event.tokenFeeMint.toBase58()).to.equal(feeMint.toBase58());
+    expect(event.tokenFee.toNumber()).to.equal(feeAmount);
+    expect(Number((await getAccount(provider.connection, payerFeeAta)).amount)).to.equal(9 * feeAmount);
+    expect(Number((await getAccount(provider.connection, treasuryFeeAta)).amount)).to.equal(feeAmount);
+    expect((await program.account.airdropState.fetch(airdrop.airdropState)).amountClaimed.toNumber()).to.equal(100);
+  });
+});
@@ -0,0 +1,50 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { findCpiEvent, initializeAirdrop } from "./utils";
+
+describe("finalize tree", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  const allocations = Array.from({ length: 2 }, () => ({
+    address: Keypair.generate().publicKey,
+    amount: 100,
+  }));
+
+  const updateTree = (airdropState: PublicKey, root: number[]) =>
+    program.methods.updateTree(root, new anchor.BN(allocations.length))
+      .accountsPartial({ airdropState, updateAuthority: authority.publicKey })
+      .rpc();
+
+  it("Updates the root before finalization and never after", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations);
+    const finalRoot = Array(32).fill(7);
+    await updateTree(airdrop.airdropState, finalRoot);
+
+    const signature = await program.methods.finalizeTree()
+      .accountsPartial({ airdropState: airdrop.airdropState, updateAuthority: authority.publicKey })
+      .rpc();
+    const event = await findCpiEvent(program, signature, "rootFinalized");
+
+    expect(event.airdropState.toString()).to.equal(airdrop.airdropState.toString());
+    expect(event.merkleRoot).to.deep.equal(finalRoot);
+    expect((await program.account.airdropState.fetch(airdrop.airdropState)).isFinalized).to.equal(true);
+
+    try {
+      await updateTree(airdrop.airdropState, Array(32).fill(8));
+      expect.fail("Update should have failed on a finalized tree");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Merkle tree is finalized");
+    }
+
+    const account = await program.account.airdropState.fetch(airdrop.airdropState);
+    expect(account.merkleRoot).to.deep.equal(finalRoot);
+  });
+});
@@ -0,0 +1,66 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair } from "@solana/web3.js";
+import { buildTree, claimAirdrop, createBitmapShard, findCpiEvent, fund, initializeAirdrop } from "./utils";
+
+describe("force update tree", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  const claimants = [Keypair.generate(), Keypair.generate()];
+  const allocations = claimants.map((claimant) => ({ address: claimant.publicKey, amount: 100 }));
+
+  before(async () => {
+    for (const claimant of claimants) {
+      await fund(provider, claimant.publicKey);
+    }
+  });
+
+  it("Only replaces the root through the forced path once claims started", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations);
+    await createBitmapShard(program, airdrop.airdropState, 0);
+    await claimAirdrop(program, claimants[0], airdrop, 100, 0);
+
+    const newRoot = Array.from(buildTree([...allocations].reverse()).get_merkle_root());
+    try {
+      await program.methods.updateTree(newRoot, new anchor.BN(allocations.length))
+        .accountsPartial({ airdropState: airdrop.airdropState, updateAuthority: authority.publicKey })
+        .rpc();
+      expect.fail("Update should have failed after a claim");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Claims have already started, the root can only be force updated");
+    }
+
+    const signature = await program.methods.forceUpdateTree(newRoot, new anchor.BN(allocations.length))
+      .accountsPartial({ airdropState: airdrop.airdropState, updateAuthority: authority.publicKey })
+      .rpc();
+    const event = await findCpiEvent(program, signature, "rootForceUpdated");
+
+    expect((await program.account.airdropState.fetch(airdrop.airdropState)).merkleRoot).to.deep.equal(newRoot);
+    expect(event.oldRoot).to.deep.equal(Array.from(airdrop.tree.get_merkle_root()));
+    expect(event.newRoot).to.deep.equal(newRoot);
+    expect(event.amountClaimed.toNumber()).to.equal(100);
+  });
+
+  it("Fail to force update a finalized tree", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations);
+    await program.methods.finalizeTree()
+      .accountsPartial({ airdropState: airdrop.airdropState, updateAuthority: authority.publicKey })
+      .rpc();
+
+    try {
+      await program.methods.forceUpdateTree(Array(32).fill(1), new anchor.BN(allocations.length))
+        .accountsPartial({ airdropState: airdrop.airdropState, updateAuthority: authority.publicKey })
+        .rpc();
+      expect.fail("Force update should have failed on a finalized tree");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Merkle tree is finalized");
+    }
+  });
+});
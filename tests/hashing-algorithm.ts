@@ -0,0 +1,59 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair } from "@solana/web3.js";
+import { getAccount } from "@solana/spl-token";
+import { buildTree, claimAirdrop, createBitmapShard, fund, initializeAirdrop } from "./utils";
+
+describe("hashing algorithm", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  const claimants = [Keypair.generate(), Keypair.generate()];
+  const allocations = claimants.map((claimant, i) => ({ address: claimant.publicKey, amount: 100 * (i + 1) }));
+
+  before(async () => {
+    for (const claimant of claimants) {
+      await fund(provider, claimant.publicKey);
+    }
+  });
+
+  for (const [name, hashingAlgorithm] of [["Keccak", 0], ["SHA-256", 1]] as const) {
+    it(`Claims from a ${name} tree`, async () => {
+      const airdrop = await initializeAirdrop(program, authority, allocations, { hashingAlgorithm });
+      await createBitmapShard(program, airdrop.airdropState, 0);
+
+      expect((await program.account.airdropState.fetch(airdrop.airdropState)).hashingAlgorithm).to.equal(hashingAlgorithm);
+
+      const signerAta = await claimAirdrop(program, claimants[1], airdrop, 200, 1);
+      expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(200);
+    });
+  }
+
+  it("Fail to claim from a SHA-256 airdrop with a Keccak proof", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations, { hashingAlgorithm: 1 });
+    await createBitmapShard(program, airdrop.airdropState, 0);
+    airdrop.tree = buildTree(allocations);
+
+    try {
+      await claimAirdrop(program, claimants[0], airdrop, 100, 0);
+      expect.fail("Claim should have failed with a proof of the wrong algorithm");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Invalid Merkle proof");
+    }
+  });
+
+  it("Fail to initialize with an unknown hashing algorithm", async () => {
+    try {
+      await initializeAirdrop(program, authority, allocations, { hashingAlgorithm: 7 });
+      expect.fail("Initialization should have failed for an unknown algorithm");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Hashing algorithm is not supported");
+    }
+  });
+});
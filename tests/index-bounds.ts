@@ -0,0 +1,68 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair } from "@solana/web3.js";
+import { getAssociatedTokenAddress, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
+import { claimAirdrop, createBitmapShard, fund, getBitmapShard, getProof, initializeAirdrop, LEAVES_PER_SHARD } from "./utils";
+
+describe("index bounds", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  const claimant = Keypair.generate();
+  const allocations = [
+    { address: claimant.publicKey, amount: 100 },
+    { address: Keypair.generate().publicKey, amount: 100 },
+  ];
+  // First index that no longer fits the u32 proof verification works with
+  const boundary = 2 ** 32;
+
+  before(async () => {
+    await fund(provider, claimant.publicKey);
+  });
+
+  it("Does not verify leaf 0's proof at index 2^32", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations, { numLeaves: boundary + 1 });
+
+    const result = await program.methods.verifyEligibility(claimant.publicKey, new anchor.BN(100), getProof(airdrop.tree, 0), new anchor.BN(boundary), new anchor.BN(0))
+      .accountsPartial({ airdropState: airdrop.airdropState, claimBitmap: null })
+      .view();
+    expect(result.eligible).to.equal(false);
+  });
+
+  it("Fail to claim at index 2^32 with leaf 0's proof", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations, { numLeaves: boundary + 1 });
+    await createBitmapShard(program, airdrop.airdropState, 0);
+    await createBitmapShard(program, airdrop.airdropState, boundary / LEAVES_PER_SHARD);
+
+    try {
+      await program.methods.claimAirdrop(new anchor.BN(100), getProof(airdrop.tree, 0), new anchor.BN(boundary), new anchor.BN(0), null, false)
+        .accountsPartial({
+          airdropState: airdrop.airdropState,
+          mint: airdrop.mint,
+          vault: airdrop.vault,
+          signerAta: await getAssociatedTokenAddress(airdrop.mint, claimant.publicKey),
+          claimBitmap: getBitmapShard(program.programId, airdrop.airdropState, boundary / LEAVES_PER_SHARD),
+          claimant: claimant.publicKey,
+          payer: claimant.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+        })
+        .signers([claimant])
+        .rpc();
+      expect.fail("Claim should have failed past the u32 index range");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Leaf index out of range");
+    }
+
+    // Leaf 0 is still tracked and claimable on its own
+    await claimAirdrop(program, claimant, airdrop, 100, 0);
+    expect((await program.account.airdropState.fetch(airdrop.airdropState)).numClaims.toNumber()).to.equal(1);
+  });
+});
@@ -0,0 +1,57 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair } from "@solana/web3.js";
+import { getAccount } from "@solana/spl-token";
+import { claimAirdrop, createBitmapShard, encodeLeaf, fund, initializeAirdrop } from "./utils";
+
+describe("indexed leaves", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  // The same wallet is allocated the same amount twice
+  const claimant = Keypair.generate();
+  const allocations = [
+    { address: claimant.publicKey, amount: 100 },
+    { address: claimant.publicKey, amount: 100 },
+    { address: Keypair.generate().publicKey, amount: 100 },
+  ];
+
+  before(async () => {
+    await fund(provider, claimant.publicKey);
+  });
+
+  it("Encodes repeated allocations as distinct leaves", () => {
+    expect(encodeLeaf(claimant.publicKey, 100)).to.deep.equal(encodeLeaf(claimant.publicKey, 100));
+    expect(encodeLeaf(claimant.publicKey, 100, 1, 0)).to.not.deep.equal(encodeLeaf(claimant.publicKey, 100, 1, 1));
+  });
+
+  it("Stores the leaf version, the original layout by default", async () => {
+    const original = await initializeAirdrop(program, authority, allocations);
+    const indexed = await initializeAirdrop(program, authority, allocations, { leafVersion: 1 });
+
+    expect((await program.account.airdropState.fetch(original.airdropState)).leafVersion).to.equal(0);
+    expect((await program.account.airdropState.fetch(indexed.airdropState)).leafVersion).to.equal(1);
+  });
+
+  it("Claims each index of a repeated allocation once", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations, { leafVersion: 1 });
+    await createBitmapShard(program, airdrop.airdropState, 0);
+
+    await claimAirdrop(program, claimant, airdrop, 100, 0);
+    const signerAta = await claimAirdrop(program, claimant, airdrop, 100, 1);
+    expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(200);
+
+    try {
+      await claimAirdrop(program, claimant, airdrop, 100, 1);
+      expect.fail("Second claim of the same index should have failed");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Already claimed");
+    }
+  });
+});
@@ -0,0 +1,53 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair } from "@solana/web3.js";
+import { findCpiEvent, initializeAirdrop } from "./utils";
+
+describe("label", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  const allocations = [{ address: Keypair.generate().publicKey, amount: 100 }];
+  const tooLong = "x".repeat(33);
+
+  it("Stores the label and reports it in the initialize event", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations, { label: "Season 1 retroactive" });
+
+    expect((await program.account.airdropState.fetch(airdrop.airdropState)).label).to.equal("Season 1 retroactive");
+    expect((await findCpiEvent(program, airdrop.signature, "airdropInitialized")).label).to.equal("Season 1 retroactive");
+  });
+
+  it("Corrects the label, up to the maximum length", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations, { label: "Sesaon 1" });
+    const label = "y".repeat(32);
+    await program.methods.setLabel(label)
+      .accountsPartial({ airdropState: airdrop.airdropState, updateAuthority: authority.publicKey })
+      .rpc();
+
+    expect((await program.account.airdropState.fetch(airdrop.airdropState)).label).to.equal(label);
+
+    try {
+      await program.methods.setLabel(tooLong)
+        .accountsPartial({ airdropState: airdrop.airdropState, updateAuthority: authority.publicKey })
+        .rpc();
+      expect.fail("Label update should have failed above the maximum length");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("LabelTooLong");
+    }
+  });
+
+  it("Fail to initialize with a label over the maximum length", async () => {
+    try {
+      await initializeAirdrop(program, authority, allocations, { label: tooLong });
+      expect.fail("Initialization should have failed with a label over the maximum length");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("LabelTooLong");
+    }
+  });
+});
@@ -0,0 +1,80 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair, SystemProgram } from "@solana/web3.js";
+import { getAccount, getAssociatedTokenAddress } from "@solana/spl-token";
+import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
+import { Airdrop, claimAirdrop, createBitmapShard, fund, getBitmapShard, getProof, initializeAirdrop } from "./utils";
+
+describe("leaf deadline", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  // A grant still open and a reward that already expired, in the same open-ended airdrop
+  const now = Math.floor(Date.now() / 1000);
+  const claimants = [Keypair.generate(), Keypair.generate()];
+  const allocations = [
+    { address: claimants[0].publicKey, amount: 100, deadline: now + 3_600 },
+    { address: claimants[1].publicKey, amount: 200, deadline: now - 60 },
+  ];
+
+  let airdrop: Airdrop;
+
+  const claimWithDeadline = async (index: number, deadline = allocations[index].deadline) => {
+    const claimant = claimants[index];
+    const signerAta = await getAssociatedTokenAddress(airdrop.mint, claimant.publicKey, false, airdrop.tokenProgram);
+    await program.methods.claimWithDeadline(new anchor.BN(allocations[index].amount), getProof(airdrop.tree, index), new anchor.BN(index), new anchor.BN(0), new anchor.BN(deadline))
+      .accountsPartial({
+        airdropState: airdrop.airdropState,
+        mint: airdrop.mint,
+        vault: airdrop.vault,
+        signerAta,
+        claimBitmap: getBitmapShard(program.programId, airdrop.airdropState, 0),
+        claimant: claimant.publicKey,
+        payer: claimant.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: airdrop.tokenProgram,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .signers([claimant])
+      .rpc();
+    return signerAta;
+  };
+
+  const expectError = async (claim: Promise<unknown>, code: string) => {
+    try {
+      await claim;
+      expect.fail(`Claim should have failed with ${code}`);
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal(code);
+    }
+  };
+
+  before(async () => {
+    await Promise.all(claimants.map((claimant) => fund(provider, claimant.publicKey)));
+    airdrop = await initializeAirdrop(program, authority, allocations, { leafVersion: 4 });
+    await createBitmapShard(program, airdrop.airdropState, 0);
+  });
+
+  it("Fail to claim a leaf with a deadline without it", async () => {
+    await expectError(claimAirdrop(program, claimants[0], airdrop, 100, 0), "DeadlineRequired");
+  });
+
+  it("Fail to claim an expired leaf while the airdrop is open", async () => {
+    await expectError(claimWithDeadline(1), "AllocationExpired");
+  });
+
+  it("Fail to claim an expired leaf with a later deadline than its own", async () => {
+    await expectError(claimWithDeadline(1, now + 3_600), "InvalidProof");
+  });
+
+  it("Claims a leaf before its deadline", async () => {
+    const signerAta = await claimWithDeadline(0);
+    expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(100);
+  });
+});
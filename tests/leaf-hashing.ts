@@ -0,0 +1,70 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair } from "@solana/web3.js";
+import { getAccount } from "@solana/spl-token";
+import { keccak_256 } from "@noble/hashes/sha3";
+import { buildTree, claimAirdrop, createBitmapShard, encodeLeaf, fund, getProof, initializeAirdrop } from "./utils";
+
+describe("leaf hashing", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  const claimants = [Keypair.generate(), Keypair.generate()];
+  const allocations = claimants.map((claimant, i) => ({ address: claimant.publicKey, amount: 100 * (i + 1) }));
+
+  before(async () => {
+    for (const claimant of claimants) {
+      await fund(provider, claimant.publicKey);
+    }
+  });
+
+  for (const [name, leafHashing] of [["single", 0], ["double", 1]] as const) {
+    it(`Claims from a ${name}-hashed tree`, async () => {
+      const airdrop = await initializeAirdrop(program, authority, allocations, { leafHashing });
+      await createBitmapShard(program, airdrop.airdropState, 0);
+
+      expect((await program.account.airdropState.fetch(airdrop.airdropState)).leafHashing).to.equal(leafHashing);
+
+      const signerAta = await claimAirdrop(program, claimants[0], airdrop, 100, 0);
+      expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(100);
+    });
+  }
+
+  it("Reports the double hash of the leaf data", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations, { leafHashing: 1 });
+
+    const result = await program.methods.verifyEligibility(claimants[1].publicKey, new anchor.BN(200), getProof(airdrop.tree, 1), new anchor.BN(1), new anchor.BN(0))
+      .accountsPartial({ airdropState: airdrop.airdropState, claimBitmap: null })
+      .view();
+    expect(result.eligible).to.equal(true);
+    expect(result.leafHash).to.deep.equal(Array.from(keccak_256(keccak_256(encodeLeaf(claimants[1].publicKey, 200)))));
+  });
+
+  it("Fail to claim from a double-hashed tree with a single-hashed proof", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations, { leafHashing: 1 });
+    await createBitmapShard(program, airdrop.airdropState, 0);
+    airdrop.tree = buildTree(allocations);
+
+    try {
+      await claimAirdrop(program, claimants[0], airdrop, 100, 0);
+      expect.fail("Claim should have failed with a proof of the other leaf hashing mode");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Invalid Merkle proof");
+    }
+  });
+
+  it("Fail to initialize with an unknown leaf hashing mode", async () => {
+    try {
+      await initializeAirdrop(program, authority, allocations, { leafHashing: 2 });
+      expect.fail("Initialization should have failed for an unknown leaf hashing mode");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Leaf hashing mode is not supported");
+    }
+  });
+});
@@ -3,9 +3,10 @@ import { Program } from "@coral-xyz/anchor";
 import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
 import { expect } from "chai";
 import { Keypair, PublicKey, SystemProgram, LAMPORTS_PER_SOL, Transaction } from "@solana/web3.js";
-import { getAssociatedTokenAddress, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { createMint, getAccount, getAssociatedTokenAddress, getMint, getOrCreateAssociatedTokenAccount, mintTo, TOKEN_PROGRAM_ID } from "@solana/spl-token";
 import { HashingAlgorithm, MerkleTree } from "svm-merkle-tree";
 import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
+import { airdropParams, payoutParams } from "./utils";
 
 
 describe("merkle-tree-token-claimer", () => {
@@ -26,13 +27,32 @@ describe("merkle-tree-token-claimer", () => {
   interface AirdropTokenData {
     address: PublicKey;
     amount: number;
-    isClaimed: boolean;
   }
   let merkleTreeData: AirdropTokenData[];
 
+  let claimBitmap: PublicKey;
+  const numLeaves = 1024;
+  const leavesPerShard = 8 * 1024;
+
+  const getBitmapShard = (airdrop: PublicKey, shardIndex: number) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("bitmap"), airdrop.toBuffer(), new anchor.BN(shardIndex).toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+
+  const getRootVersion = async (airdrop: PublicKey) =>
+    (await program.account.airdropState.fetch(airdrop)).rootVersion;
+
+  const encodeLeaf = (address: PublicKey, amount: number) =>
+    Buffer.concat([
+      address.toBuffer(),
+      Buffer.from(new Uint8Array(new anchor.BN(amount).toArray('le', 8))),
+    ]);
+
   before(async () => {
     airdropState = PublicKey.findProgramAddressSync([Buffer.from("merkle_tree"), mint.publicKey.toBuffer()], program.programId)[0];
     vault = await getAssociatedTokenAddress(mint.publicKey, airdropState, true);
+    claimBitmap = getBitmapShard(airdropState, 0);
 
     // Airdrop SOL to authority
     await provider.sendAndConfirm(
@@ -50,17 +70,15 @@ describe("merkle-tree-token-claimer", () => {
     merkleTreeData = Array.from({ length: 100 }, () => ({
       address: Keypair.generate().publicKey,
       amount: Math.floor(Math.random() * 1000),           // Example random amount
-      isClaimed: false,                                   // Default value for isClaimed
     }));
     
     // Create Merkle Tree
     merkleTree = new MerkleTree(HashingAlgorithm.Keccak, 32);
     merkleTreeData.forEach((entry) => {
-      // Serialize address, amount, and isClaimed in binary format
+      // Serialize address and amount in binary format
       const entryBytes = Buffer.concat([
         entry.address.toBuffer(),
         Buffer.from(new Uint8Array(new anchor.BN(entry.amount).toArray('le', 8))),
-        Buffer.from([entry.isClaimed ? 1 : 0]),
       ]);
       merkleTree.add_leaf(entryBytes);
     });
@@ -72,12 +90,13 @@ describe("merkle-tree-token-claimer", () => {
     const merkleRoot = Array.from(merkleTree.get_merkle_root());
     const totalAirdropAmount = merkleTreeData.reduce((sum, entry) => sum + entry.amount, 0);
 
-    await program.methods.initializeAirdropData(merkleRoot, new anchor.BN(totalAirdropAmount))
+    await program.methods.initializeAirdropData(airdropParams(merkleRoot, numLeaves), payoutParams(totalAirdropAmount), 6, { revoke: {} }, false, null)
       .accountsPartial({
         airdropState,
         mint: mint.publicKey,
         vault,
         authority: authority.publicKey,
+        payer: authority.publicKey,
         systemProgram: SystemProgram.programId,
         tokenProgram: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
@@ -87,60 +106,91 @@ describe("merkle-tree-token-claimer", () => {
 
     const account = await program.account.airdropState.fetch(airdropState);
     expect(account.merkleRoot).to.deep.equal(merkleRoot);
-    expect(account.authority.toString()).to.equal(authority.publicKey.toString());
+    expect(account.updateAuthority.toString()).to.equal(authority.publicKey.toString());
+    expect(account.numLeaves.toNumber()).to.equal(numLeaves);
+    expect(account.decimals).to.equal(6);
+  });
+
+  it("Create the first bitmap shard", async () => {
+    await program.methods.createBitmapShard(new anchor.BN(0))
+      .accountsPartial({
+        airdropState,
+        claimBitmap,
+        payer: provider.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const shard = await program.account.claimBitmap.fetch(claimBitmap);
+    expect(shard.airdropState.toString()).to.equal(airdropState.toString());
+    expect(shard.shardIndex.toNumber()).to.equal(0);
+  });
+
+  it("Fail to create a bitmap shard past the number of leaves", async () => {
+    try {
+      await program.methods.createBitmapShard(new anchor.BN(1))
+        .accountsPartial({
+          airdropState,
+          claimBitmap: getBitmapShard(airdropState, 1),
+          payer: provider.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+      expect.fail("Shard creation should have failed past the number of leaves");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Leaf index out of range");
+    }
   });
 
   it("Update root", async () => {
     const newData = {
       address: Keypair.generate().publicKey,
       amount: Math.floor(Math.random() * 1000),           // Example random amount
-      isClaimed: false,                                   // Default value for isClaimed
     };
     merkleTreeData.push(newData); 
     const entryBytes = Buffer.concat([
       newData.address.toBuffer(), // PublicKey as bytes
       Buffer.from(new Uint8Array(new anchor.BN(newData.amount).toArray('le', 8))), // Amount as little-endian
-      Buffer.from([newData.isClaimed ? 1 : 0]), // isClaimed as 1 byte
     ]);
     merkleTree.add_leaf(entryBytes);
     merkleTree.merklize();
 
     const newMerkleRoot = Array.from(merkleTree.get_merkle_root());
 
-    await program.methods.updateTree(newMerkleRoot)
+    await program.methods.updateTree(newMerkleRoot, new anchor.BN(merkleTreeData.length))
       .accountsPartial({
         airdropState: airdropState,
-        authority: authority.publicKey,
+        updateAuthority: authority.publicKey,
       })
       .signers([authority])
       .rpc();
 
     const account = await program.account.airdropState.fetch(airdropState);
     expect(account.merkleRoot).to.deep.equal(newMerkleRoot);
+    expect(account.numLeaves.toNumber()).to.equal(merkleTreeData.length);
   });
 
   it("Perform claim with whitelisted address", async () => {
     newAddress = Keypair.generate();
     newData = {
       address: newAddress.publicKey,
-      amount: Math.floor(Math.random() * 1000),           // Example random amount
-      isClaimed: false,                                   // Default value for isClaimed
+      amount: Math.floor(Math.random() * 1000) + 1,       // Example random amount, zero claims are rejected
     }
     merkleTreeData.push(newData); 
     const entryBytes = Buffer.concat([
       newData.address.toBuffer(), // PublicKey as bytes
       Buffer.from(new Uint8Array(new anchor.BN(newData.amount).toArray('le', 8))), // Amount as little-endian
-      Buffer.from([newData.isClaimed ? 1 : 0]), // isClaimed as 1 byte
     ]);
     merkleTree.add_leaf(entryBytes);
     merkleTree.merklize();
   
     const newMerkleRoot = Array.from(merkleTree.get_merkle_root());
   
-    await program.methods.updateTree(newMerkleRoot)
+    await program.methods.updateTree(newMerkleRoot, new anchor.BN(merkleTreeData.length))
       .accountsPartial({
         airdropState: airdropState,
-        authority: authority.publicKey,
+        updateAuthority: authority.publicKey,
       })
       .signers([authority])
       .rpc();
@@ -165,13 +215,15 @@ describe("merkle-tree-token-claimer", () => {
     );
   
     try {
-      await program.methods.claimAirdrop(new anchor.BN(newData.amount), proofArray, new anchor.BN(index))
+      await program.methods.claimAirdrop(new anchor.BN(newData.amount), proofArray, new anchor.BN(index), await getRootVersion(airdropState), null, false)
         .accountsPartial({
           airdropState,
           mint: mint.publicKey,
           vault,
           signerAta: await getAssociatedTokenAddress(mint.publicKey, newAddress.publicKey),
-          signer: newAddress.publicKey,
+          claimBitmap,
+          claimant: newAddress.publicKey,
+          payer: newAddress.publicKey,
           systemProgram: SystemProgram.programId,
           tokenProgram: TOKEN_PROGRAM_ID,
           associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
@@ -183,6 +235,17 @@ describe("merkle-tree-token-claimer", () => {
       console.error("Error performing action:", error);
       throw error;
     }
+
+    const account = await program.account.airdropState.fetch(airdropState);
+    expect(account.merkleRoot).to.deep.equal(newMerkleRoot);
+
+    // The payout is exactly the leaf amount in raw units
+    const signerAta = await getAccount(provider.connection, await getAssociatedTokenAddress(mint.publicKey, newAddress.publicKey));
+    expect(Number(signerAta.amount)).to.equal(newData.amount);
+    expect(Number((await getAccount(provider.connection, vault)).amount)).to.equal(account.airdropAmount.toNumber() - newData.amount);
+
+    const shard = await program.account.claimBitmap.fetch(claimBitmap);
+    expect(shard.bits[Math.floor(index / 8)] & (1 << (index % 8))).to.not.equal(0);
   });
 
   it("Fail to claim after wallet already claimed", async () => {
@@ -195,23 +258,96 @@ describe("merkle-tree-token-claimer", () => {
     const proofArray = Buffer.from(proof.get_pairing_hashes());
 
     try {
-      await program.methods.claimAirdrop(new anchor.BN(newData.amount), proofArray, new anchor.BN(index))
+      await program.methods.claimAirdrop(new anchor.BN(newData.amount), proofArray, new anchor.BN(index), await getRootVersion(airdropState), null, false)
         .accountsPartial({
           airdropState,
           mint: mint.publicKey,
           vault,
           signerAta: await getAssociatedTokenAddress(mint.publicKey, newAddress.publicKey),
-          signer: newAddress.publicKey,
+          claimBitmap,
+          claimant: newAddress.publicKey,
+          payer: newAddress.publicKey,
           systemProgram: SystemProgram.programId,
           tokenProgram: TOKEN_PROGRAM_ID,
           associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
         })
         .signers([newAddress])
         .rpc();
-      expect.fail("Action should have failed for non-whitelisted address");
+      expect.fail("Second claim should have failed for an already claimed address");
     } catch (error: any) {
-      expect(error.error.errorMessage).to.equal("Invalid Merkle proof");
+      expect(error.error.errorCode.code).to.equal("AlreadyClaimed");
+      expect(error.error.errorCode.number).to.equal(6001);
+      expect(error.error.errorMessage).to.equal("Already claimed");
+    }
+  });
+
+  it("Two claimants claim back-to-back against the same root", async () => {
+    const claimants = [Keypair.generate(), Keypair.generate()];
+    const claimantData = claimants.map((claimant) => ({
+      address: claimant.publicKey,
+      amount: Math.floor(Math.random() * 1000) + 1,
+    }));
+    claimantData.forEach((entry) => {
+      merkleTreeData.push(entry);
+      merkleTree.add_leaf(Buffer.concat([
+        entry.address.toBuffer(),
+        Buffer.from(new Uint8Array(new anchor.BN(entry.amount).toArray('le', 8))),
+      ]));
+    });
+    merkleTree.merklize();
+
+    const newMerkleRoot = Array.from(merkleTree.get_merkle_root());
+
+    // An earlier test already claimed, so the root can only change through the forced path
+    await program.methods.forceUpdateTree(newMerkleRoot, new anchor.BN(merkleTreeData.length))
+      .accountsPartial({
+        airdropState: airdropState,
+        updateAuthority: authority.publicKey,
+      })
+      .signers([authority])
+      .rpc();
+
+    // Both proofs are generated against the same published root before anyone claims
+    const claims = claimantData.map((entry) => {
+      const index = merkleTreeData.findIndex(data => data.address.equals(entry.address));
+      const proof = merkleTree.merkle_proof_index(index);
+      return { index, proofArray: Buffer.from(proof.get_pairing_hashes()) };
+    });
+
+    for (const [i, claimant] of claimants.entries()) {
+      await provider.sendAndConfirm(
+        new Transaction().add(
+          SystemProgram.transfer({
+            fromPubkey: provider.publicKey,
+            toPubkey: claimant.publicKey,
+            lamports: LAMPORTS_PER_SOL,
+          })
+        ),
+        []
+      );
+
+      await program.methods.claimAirdrop(new anchor.BN(claimantData[i].amount), claims[i].proofArray, new anchor.BN(claims[i].index), await getRootVersion(airdropState), null, false)
+        .accountsPartial({
+          airdropState,
+          mint: mint.publicKey,
+          vault,
+          signerAta: await getAssociatedTokenAddress(mint.publicKey, claimant.publicKey),
+          claimBitmap,
+          claimant: claimant.publicKey,
+          payer: claimant.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+        })
+        .signers([claimant])
+        .rpc();
+
+      const signerAta = await getAccount(provider.connection, await getAssociatedTokenAddress(mint.publicKey, claimant.publicKey));
+      expect(Number(signerAta.amount)).to.equal(claimantData[i].amount);
     }
+
+    const account = await program.account.airdropState.fetch(airdropState);
+    expect(account.merkleRoot).to.deep.equal(newMerkleRoot);
   });
 
   it("Fail action with non-whitelisted address", async () => {
@@ -241,14 +377,19 @@ describe("merkle-tree-token-claimer", () => {
       await program.methods.claimAirdrop(
         new anchor.BN(whitelistedData.amount), // Use the whitelisted amount
         proofArray,
-        new anchor.BN(index)
+        new anchor.BN(index),
+        await getRootVersion(airdropState),
+        null,
+        false
       )
         .accountsPartial({
           airdropState,
           mint: mint.publicKey,
           vault,
           signerAta: await getAssociatedTokenAddress(mint.publicKey, nonWhitelistedKeypair.publicKey),
-          signer: nonWhitelistedKeypair.publicKey,
+          claimBitmap,
+          claimant: nonWhitelistedKeypair.publicKey,
+          payer: nonWhitelistedKeypair.publicKey,
           systemProgram: SystemProgram.programId,
           tokenProgram: TOKEN_PROGRAM_ID,
           associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
@@ -265,22 +406,294 @@ describe("merkle-tree-token-claimer", () => {
   });
   
 
+  it("Fail to claim an index past the bitmap length", async () => {
+    const proof = merkleTree.merkle_proof_index(0);
+    const proofArray = Buffer.from(proof.get_pairing_hashes());
+
+    try {
+      await program.methods.claimAirdrop(new anchor.BN(newData.amount), proofArray, new anchor.BN(numLeaves), await getRootVersion(airdropState), null, false)
+        .accountsPartial({
+          airdropState,
+          mint: mint.publicKey,
+          vault,
+          signerAta: await getAssociatedTokenAddress(mint.publicKey, newAddress.publicKey),
+          claimBitmap,
+          claimant: newAddress.publicKey,
+          payer: newAddress.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+        })
+        .signers([newAddress])
+        .rpc();
+
+      expect.fail("Claim should have failed for an out of range index");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Leaf index out of range");
+    }
+  });
+
+  it("Claim indices that land in two different bitmap shards", async () => {
+    const shardedMint = Keypair.generate();
+    const shardedAirdropState = PublicKey.findProgramAddressSync([Buffer.from("merkle_tree"), shardedMint.publicKey.toBuffer()], program.programId)[0];
+    const shardedVault = await getAssociatedTokenAddress(shardedMint.publicKey, shardedAirdropState, true);
+
+    // Enough leaves to spill into a second shard, with claimants on both sides of the boundary
+    const claimants = [Keypair.generate(), Keypair.generate()];
+    const claimantIndices = [5, leavesPerShard + 5];
+    const leaves: AirdropTokenData[] = Array.from({ length: leavesPerShard + 16 }, () => ({
+      address: Keypair.generate().publicKey,
+      amount: 10,
+    }));
+    claimantIndices.forEach((leafIndex, i) => {
+      leaves[leafIndex] = { address: claimants[i].publicKey, amount: 100 + i };
+    });
+
+    const shardedTree = new MerkleTree(HashingAlgorithm.Keccak, 32);
+    leaves.forEach((entry) => shardedTree.add_leaf(encodeLeaf(entry.address, entry.amount)));
+    shardedTree.merklize();
+
+    await program.methods.initializeAirdropData(
+      airdropParams(shardedTree.get_merkle_root(), leaves.length),
+      payoutParams(leaves.reduce((sum, entry) => sum + entry.amount, 0)),
+      6,
+      { revoke: {} },
+      false,
+      null
+    )
+      .accountsPartial({
+        airdropState: shardedAirdropState,
+        mint: shardedMint.publicKey,
+        vault: shardedVault,
+        authority: authority.publicKey,
+        payer: authority.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .signers([authority, shardedMint])
+      .rpc();
+
+    for (const [i, claimant] of claimants.entries()) {
+      const leafIndex = claimantIndices[i];
+      const shard = getBitmapShard(shardedAirdropState, Math.floor(leafIndex / leavesPerShard));
+      const proofArray = Buffer.from(shardedTree.merkle_proof_index(leafIndex).get_pairing_hashes());
+
+      await provider.sendAndConfirm(
+        new Transaction().add(
+          SystemProgram.transfer({
+            fromPubkey: provider.publicKey,
+            toPubkey: claimant.publicKey,
+            lamports: LAMPORTS_PER_SOL,
+          })
+        ),
+        []
+      );
+
+      // Shards are created permissionlessly by the first claimant in their range
+      await program.methods.createBitmapShard(new anchor.BN(Math.floor(leafIndex / leavesPerShard)))
+        .accountsPartial({
+          airdropState: shardedAirdropState,
+          claimBitmap: shard,
+          payer: claimant.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([claimant])
+        .rpc();
+
+      const claimAccounts = {
+        airdropState: shardedAirdropState,
+        mint: shardedMint.publicKey,
+        vault: shardedVault,
+        signerAta: await getAssociatedTokenAddress(shardedMint.publicKey, claimant.publicKey),
+        claimant: claimant.publicKey,
+        payer: claimant.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      };
+
+      // The shard of the other claimant does not cover this index
+      if (i === 1) {
+        try {
+          await program.methods.claimAirdrop(new anchor.BN(leaves[leafIndex].amount), proofArray, new anchor.BN(leafIndex), new anchor.BN(0), null, false)
+            .accountsPartial({ ...claimAccounts, claimBitmap: getBitmapShard(shardedAirdropState, 0) })
+            .signers([claimant])
+            .rpc();
+
+          expect.fail("Claim should have failed with a shard not covering the index");
+        } catch (error: any) {
+          expect(error.error.errorMessage).to.equal("A seeds constraint was violated");
+        }
+      }
+
+      await program.methods.claimAirdrop(new anchor.BN(leaves[leafIndex].amount), proofArray, new anchor.BN(leafIndex), new anchor.BN(0), null, false)
+        .accountsPartial({ ...claimAccounts, claimBitmap: shard })
+        .signers([claimant])
+        .rpc();
+
+      const bitmap = await program.account.claimBitmap.fetch(shard);
+      const offset = leafIndex % leavesPerShard;
+      expect(bitmap.bits[Math.floor(offset / 8)] & (1 << (offset % 8))).to.not.equal(0);
+    }
+  });
+
+  it("Initialize and claim an airdrop of an existing mint", async () => {
+    const existingMint = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+    const authorityAta = await getOrCreateAssociatedTokenAccount(provider.connection, authority, existingMint, authority.publicKey);
+    await mintTo(provider.connection, authority, existingMint, authorityAta.address, authority, 1_000_000);
+
+    const existingAirdropState = PublicKey.findProgramAddressSync([Buffer.from("merkle_tree"), existingMint.toBuffer()], program.programId)[0];
+    const existingVault = await getAssociatedTokenAddress(existingMint, existingAirdropState, true);
+
+    const claimant = Keypair.generate();
+    const leaves: AirdropTokenData[] = [
+      { address: claimant.publicKey, amount: 500 },
+      { address: Keypair.generate().publicKey, amount: 250 },
+    ];
+    const existingTree = new MerkleTree(HashingAlgorithm.Keccak, 32);
+    leaves.forEach((entry) => existingTree.add_leaf(encodeLeaf(entry.address, entry.amount)));
+    existingTree.merklize();
+
+    const initializeAccounts = {
+      airdropState: existingAirdropState,
+      mint: existingMint,
+      vault: existingVault,
+      authorityAta: authorityAta.address,
+      authority: authority.publicKey,
+      payer: authority.publicKey,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+    };
+
+    // The authority only holds 1_000_000 tokens
+    try {
+      await program.methods.initializeWithExistingMint(airdropParams(existingTree.get_merkle_root(), leaves.length), payoutParams(2_000_000))
+        .accountsPartial(initializeAccounts)
+        .signers([authority])
+        .rpc();
+
+      expect.fail("Initialization should have failed with an underfunded authority ATA");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Insufficient funds to fund the airdrop");
+    }
+
+    await program.methods.initializeWithExistingMint(airdropParams(existingTree.get_merkle_root(), leaves.length), payoutParams(750))
+      .accountsPartial(initializeAccounts)
+      .signers([authority])
+      .rpc();
+
+    expect(Number((await getAccount(provider.connection, existingVault)).amount)).to.equal(750);
+    expect(Number((await getAccount(provider.connection, authorityAta.address)).amount)).to.equal(1_000_000 - 750);
+
+    const shard = getBitmapShard(existingAirdropState, 0);
+    await program.methods.createBitmapShard(new anchor.BN(0))
+      .accountsPartial({
+        airdropState: existingAirdropState,
+        claimBitmap: shard,
+        payer: provider.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        SystemProgram.transfer({
+          fromPubkey: provider.publicKey,
+          toPubkey: claimant.publicKey,
+          lamports: LAMPORTS_PER_SOL,
+        })
+      ),
+      []
+    );
+
+    const claimantAta = await getAssociatedTokenAddress(existingMint, claimant.publicKey);
+    await program.methods.claimAirdrop(new anchor.BN(500), Buffer.from(existingTree.merkle_proof_index(0).get_pairing_hashes()), new anchor.BN(0), new anchor.BN(0), null, false)
+      .accountsPartial({
+        airdropState: existingAirdropState,
+        mint: existingMint,
+        vault: existingVault,
+        signerAta: claimantAta,
+        claimBitmap: shard,
+        claimant: claimant.publicKey,
+        payer: claimant.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .signers([claimant])
+      .rpc();
+
+    expect(Number((await getAccount(provider.connection, claimantAta)).amount)).to.equal(500);
+  });
+
+  for (const decimals of [0, 9]) {
+    it(`Initialize an airdrop with ${decimals} decimals`, async () => {
+      const decimalsMint = Keypair.generate();
+      const decimalsAirdropState = PublicKey.findProgramAddressSync([Buffer.from("merkle_tree"), decimalsMint.publicKey.toBuffer()], program.programId)[0];
+
+      await program.methods.initializeAirdropData(airdropParams(merkleTree.get_merkle_root(), numLeaves), payoutParams(1_000), decimals, { revoke: {} }, false, null)
+        .accountsPartial({
+          airdropState: decimalsAirdropState,
+          mint: decimalsMint.publicKey,
+          vault: await getAssociatedTokenAddress(decimalsMint.publicKey, decimalsAirdropState, true),
+          authority: authority.publicKey,
+          payer: authority.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+        })
+        .signers([authority, decimalsMint])
+        .rpc();
+
+      const account = await program.account.airdropState.fetch(decimalsAirdropState);
+      expect(account.decimals).to.equal(decimals);
+      expect((await getMint(provider.connection, decimalsMint.publicKey)).decimals).to.equal(decimals);
+    });
+  }
+
+  it("Fail to initialize an airdrop with more than 9 decimals", async () => {
+    const decimalsMint = Keypair.generate();
+    const decimalsAirdropState = PublicKey.findProgramAddressSync([Buffer.from("merkle_tree"), decimalsMint.publicKey.toBuffer()], program.programId)[0];
+
+    try {
+      await program.methods.initializeAirdropData(airdropParams(merkleTree.get_merkle_root(), numLeaves), payoutParams(1_000), 10, { revoke: {} }, false, null)
+        .accountsPartial({
+          airdropState: decimalsAirdropState,
+          mint: decimalsMint.publicKey,
+          vault: await getAssociatedTokenAddress(decimalsMint.publicKey, decimalsAirdropState, true),
+          authority: authority.publicKey,
+          payer: authority.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+        })
+        .signers([authority, decimalsMint])
+        .rpc();
+
+      expect.fail("Initialization should have failed with 10 decimals");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Mint decimals exceed the maximum");
+    }
+  });
+
   it("Fail to update root with non-authority signer", async () => {
     const newMerkleRoot = Array.from(merkleTree.get_merkle_root());
     const nonAuthority = Keypair.generate();
 
     try {
-      await program.methods.updateTree(newMerkleRoot)
+      await program.methods.updateTree(newMerkleRoot, new anchor.BN(merkleTreeData.length))
         .accountsPartial({
           airdropState: airdropState,
-          authority: nonAuthority.publicKey,
+          updateAuthority: nonAuthority.publicKey,
         })
         .signers([nonAuthority])
         .rpc();
       
       expect.fail("Update should have failed for non-authority signer");
     } catch (error: any) {
-      expect(error.error.errorMessage).to.equal("A has one constraint was violated");
+      expect(error.error.errorMessage).to.equal("Signer does not hold the role this instruction needs");
     }
   });
 });
@@ -0,0 +1,60 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair } from "@solana/web3.js";
+import { Airdrop, findCpiEvent, initializeAirdrop } from "./utils";
+
+describe("metadata uri", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  const allocations = [{ address: Keypair.generate().publicKey, amount: 100 }];
+  const initialUri = "ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi/allocations.csv";
+  const correctedUri = "https://arweave.net/Jj7cBm8iKzTk0QlKqHvKk2rHnUQ2PDfYGR5dH6HkLwA";
+
+  let airdrop: Airdrop;
+
+  const setMetadataUri = (metadataUri: string) =>
+    program.methods.setMetadataUri(metadataUri)
+      .accountsPartial({ airdropState: airdrop.airdropState, updateAuthority: authority.publicKey })
+      .rpc();
+
+  before(async () => {
+    airdrop = await initializeAirdrop(program, authority, allocations, { metadataUri: initialUri });
+  });
+
+  it("Stores the allocation list URI and reports it in the initialize event", async () => {
+    expect((await program.account.airdropState.fetch(airdrop.airdropState)).metadataUri).to.equal(initialUri);
+    expect((await findCpiEvent(program, airdrop.signature, "airdropInitialized")).metadataUri).to.equal(initialUri);
+  });
+
+  it("Fail to point at a URI over the maximum length", async () => {
+    try {
+      await setMetadataUri("https://" + "x".repeat(200));
+      expect.fail("Update should have failed above the maximum length");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("MetadataUriTooLong");
+    }
+  });
+
+  it("Updates the URI until the tree is finalized", async () => {
+    const signature = await setMetadataUri(correctedUri);
+    expect((await findCpiEvent(program, signature, "metadataUriUpdated")).metadataUri).to.equal(correctedUri);
+
+    await program.methods.finalizeTree()
+      .accountsPartial({ airdropState: airdrop.airdropState, updateAuthority: authority.publicKey })
+      .rpc();
+    try {
+      await setMetadataUri(initialUri);
+      expect.fail("Update should have failed once the tree is finalized");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("TreeFinalized");
+    }
+    expect((await program.account.airdropState.fetch(airdrop.airdropState)).metadataUri).to.equal(correctedUri);
+  });
+});
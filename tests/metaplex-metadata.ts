@@ -0,0 +1,83 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { getMint } from "@solana/spl-token";
+import { getMetaplexMetadata, initializeAirdrop } from "./utils";
+
+// Reads name, symbol and uri from a Metaplex metadata account: key, update authority and mint come first
+const parseMetadata = (data: Buffer) => {
+  let offset = 1 + 32;
+  const mint = new PublicKey(data.subarray(offset, offset + 32));
+  offset += 32;
+  const readString = () => {
+    const length = data.readUInt32LE(offset);
+    const value = data.subarray(offset + 4, offset + 4 + length).toString("utf8").replace(/\0/g, "");
+    offset += 4 + length;
+    return value;
+  };
+  const name = readString();
+  const symbol = readString();
+  const uri = readString();
+  return { updateAuthority: new PublicKey(data.subarray(1, 33)), mint, name, symbol, uri };
+};
+
+describe("metaplex metadata", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  const allocations = Array.from({ length: 4 }, () => ({
+    address: Keypair.generate().publicKey,
+    amount: 250,
+  }));
+
+  it("Creates the metadata account before revoking the mint authority", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations, {
+      metaplexMetadata: {
+        name: "Airdrop Token",
+        symbol: "DROP",
+        uri: "https://example.com/drop.json",
+        keepUpdateAuthority: true,
+      },
+    });
+
+    const account = await provider.connection.getAccountInfo(getMetaplexMetadata(airdrop.mint));
+    const metadata = parseMetadata(account.data);
+    expect(metadata.mint.toBase58()).to.equal(airdrop.mint.toBase58());
+    expect(metadata.updateAuthority.toBase58()).to.equal(authority.publicKey.toBase58());
+    expect(metadata.name).to.equal("Airdrop Token");
+    expect(metadata.symbol).to.equal("DROP");
+    expect(metadata.uri).to.equal("https://example.com/drop.json");
+
+    const mint = await getMint(provider.connection, airdrop.mint);
+    expect(mint.mintAuthority).to.be.null;
+  });
+
+  it("Skips the metadata account when none is requested", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations);
+
+    const account = await provider.connection.getAccountInfo(getMetaplexMetadata(airdrop.mint));
+    expect(account).to.be.null;
+  });
+
+  it("Fails with a symbol longer than the maximum", async () => {
+    try {
+      await initializeAirdrop(program, authority, allocations, {
+        metaplexMetadata: {
+          name: "Airdrop Token",
+          symbol: "S".repeat(11),
+          uri: "https://example.com/drop.json",
+          keepUpdateAuthority: false,
+        },
+      });
+      expect.fail("Initialization should have failed");
+    } catch (error) {
+      expect(error.error.errorMessage).to.equal("Token metadata field too long");
+    }
+  });
+});
@@ -0,0 +1,96 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair } from "@solana/web3.js";
+import { getAccount, getMint, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { Airdrop, initializeAirdrop } from "./utils";
+
+describe("mint authority modes", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  const allocations = Array.from({ length: 4 }, () => ({
+    address: Keypair.generate().publicKey,
+    amount: 100,
+  }));
+
+  const mintAdditional = (airdrop: Airdrop, amount: number) =>
+    program.methods.mintAdditional(new anchor.BN(amount))
+      .accountsPartial({
+        airdropState: airdrop.airdropState,
+        mint: airdrop.mint,
+        vault: airdrop.vault,
+        updateAuthority: authority.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([authority])
+      .rpc();
+
+  it("Revoke burns the mint authority", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations, { authorityMode: { revoke: {} } });
+
+    const mintAccount = await getMint(provider.connection, airdrop.mint);
+    expect(mintAccount.mintAuthority).to.equal(null);
+
+    try {
+      await mintAdditional(airdrop, 100);
+      expect.fail("Minting should have failed without mint authority");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Mint authority is not held by the airdrop");
+    }
+  });
+
+  it("KeepWithCreator leaves the mint authority with the creator", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations, { authorityMode: { keepWithCreator: {} } });
+
+    const mintAccount = await getMint(provider.connection, airdrop.mint);
+    expect(mintAccount.mintAuthority.toString()).to.equal(authority.publicKey.toString());
+
+    try {
+      await mintAdditional(airdrop, 100);
+      expect.fail("Minting should have failed without mint authority");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Mint authority is not held by the airdrop");
+    }
+  });
+
+  it("TransferToPda lets the authority mint additional tokens into the vault", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations, { authorityMode: { transferToPda: {} } });
+
+    const mintAccount = await getMint(provider.connection, airdrop.mint);
+    expect(mintAccount.mintAuthority.toString()).to.equal(airdrop.airdropState.toString());
+
+    await mintAdditional(airdrop, 250);
+
+    const account = await program.account.airdropState.fetch(airdrop.airdropState);
+    expect(account.airdropAmount.toNumber()).to.equal(400 + 250);
+    expect(Number((await getAccount(provider.connection, airdrop.vault)).amount)).to.equal(400 + 250);
+  });
+
+  it("Fail to mint additional tokens with a non-authority signer", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations, { authorityMode: { transferToPda: {} } });
+    const nonAuthority = Keypair.generate();
+
+    try {
+      await program.methods.mintAdditional(new anchor.BN(100))
+        .accountsPartial({
+          airdropState: airdrop.airdropState,
+          mint: airdrop.mint,
+          vault: airdrop.vault,
+          updateAuthority: nonAuthority.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([nonAuthority])
+        .rpc();
+
+      expect.fail("Minting should have failed for a non-authority signer");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Signer does not hold the role this instruction needs");
+    }
+  });
+});
@@ -0,0 +1,65 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair } from "@solana/web3.js";
+import { getAccount, getMint } from "@solana/spl-token";
+import { claimAirdrop, createBitmapShard, fund, initializeAirdrop } from "./utils";
+
+describe("mint-on-claim", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  const claimants = [Keypair.generate(), Keypair.generate()];
+  const allocations = claimants.map((claimant) => ({ address: claimant.publicKey, amount: 100 }));
+
+  before(async () => {
+    for (const claimant of claimants) {
+      await fund(provider, claimant.publicKey);
+    }
+  });
+
+  it("Mints claims directly to the claimant up to the cap", async () => {
+    // The cap only covers the first claim
+    const airdrop = await initializeAirdrop(program, authority, allocations, {
+      authorityMode: { transferToPda: {} },
+      mintOnClaim: true,
+      amount: 150,
+    });
+    await createBitmapShard(program, airdrop.airdropState, 0);
+
+    expect(Number((await getAccount(provider.connection, airdrop.vault)).amount)).to.equal(0);
+    expect((await getMint(provider.connection, airdrop.mint)).mintAuthority.toString()).to.equal(airdrop.airdropState.toString());
+
+    const signerAta = await claimAirdrop(program, claimants[0], airdrop, 100, 0);
+    expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(100);
+    expect(Number((await getMint(provider.connection, airdrop.mint)).supply)).to.equal(100);
+
+    const account = await program.account.airdropState.fetch(airdrop.airdropState);
+    expect(account.mintOnClaim).to.equal(true);
+    expect(account.amountClaimed.toNumber()).to.equal(100);
+
+    try {
+      await claimAirdrop(program, claimants[1], airdrop, 100, 1);
+      expect.fail("Claim should have failed past the mint cap");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Claim would exceed the airdrop mint cap");
+    }
+  });
+
+  it("Fail to enable mint-on-claim without handing the mint authority to the airdrop", async () => {
+    try {
+      await initializeAirdrop(program, authority, allocations, {
+        authorityMode: { revoke: {} },
+        mintOnClaim: true,
+      });
+      expect.fail("Initialization should have failed with a revoked mint authority");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Mint-on-claim requires the mint authority to move to the airdrop");
+    }
+  });
+});
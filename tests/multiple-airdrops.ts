@@ -0,0 +1,56 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair } from "@solana/web3.js";
+import { createMint, getAccount, getOrCreateAssociatedTokenAccount, mintTo } from "@solana/spl-token";
+import { claimAirdrop, createBitmapShard, fund, getAirdropState, initializeWithExistingMint } from "./utils";
+
+describe("multiple airdrops", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  const claimant = Keypair.generate();
+
+  before(async () => {
+    await fund(provider, claimant.publicKey);
+  });
+
+  it("Runs two airdrops of the same mint side by side", async () => {
+    const mint = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+    const authorityAta = await getOrCreateAssociatedTokenAccount(provider.connection, authority, mint, authority.publicKey);
+    await mintTo(provider.connection, authority, mint, authorityAta.address, authority, 1_000);
+
+    const first = await initializeWithExistingMint(program, authority, mint, authorityAta.address, [
+      { address: claimant.publicKey, amount: 100 },
+      { address: Keypair.generate().publicKey, amount: 200 },
+    ], 300);
+    const second = await initializeWithExistingMint(program, authority, mint, authorityAta.address, [
+      { address: Keypair.generate().publicKey, amount: 150 },
+      { address: claimant.publicKey, amount: 250 },
+    ], 400, undefined, [], 0, 0, 0, 0, {}, 1);
+
+    // Airdrop 0 keeps the address derived from the mint alone
+    expect(first.airdropState.equals(getAirdropState(program.programId, mint))).to.equal(true);
+    expect(first.airdropState.equals(second.airdropState)).to.equal(false);
+    expect(first.vault.equals(second.vault)).to.equal(false);
+
+    const [firstState, secondState] = await Promise.all([first, second].map(({ airdropState }) => program.account.airdropState.fetch(airdropState)));
+    expect(firstState.airdropId.toNumber()).to.equal(0);
+    expect(secondState.airdropId.toNumber()).to.equal(1);
+    expect(firstState.merkleRoot).to.not.deep.equal(secondState.merkleRoot);
+
+    await createBitmapShard(program, first.airdropState, 0);
+    await createBitmapShard(program, second.airdropState, 0);
+    await claimAirdrop(program, claimant, first, 100, 0);
+    const signerAta = await claimAirdrop(program, claimant, second, 250, 1);
+
+    expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(350);
+    expect(Number((await getAccount(provider.connection, first.vault)).amount)).to.equal(200);
+    expect(Number((await getAccount(provider.connection, second.vault)).amount)).to.equal(150);
+  });
+});
@@ -0,0 +1,163 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair, PublicKey, SystemProgram, Transaction } from "@solana/web3.js";
+import { createMint, createTransferCheckedInstruction, getAccount, getAssociatedTokenAddress, getOrCreateAssociatedTokenAccount, mintTo, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
+import { airdropParams, buildTree, createBitmapShard, fund, getAirdropState, getBitmapShard, getProof } from "./utils";
+
+describe("nft airdrop", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  // Airdrops of NFTs share the same `mint`, the airdrop id tells them apart
+  const NFT_AIRDROP = new PublicKey(new Uint8Array(32).fill(1));
+
+  const claimants = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  let nftMints: PublicKey[];
+  let allocations: { address: PublicKey; amount: number; nftMint: PublicKey }[];
+  let tree: ReturnType<typeof buildTree>;
+  let airdropState: PublicKey;
+
+  const createNft = async () => {
+    const mint = await createMint(provider.connection, authority, authority.publicKey, null, 0);
+    const authorityAta = await getOrCreateAssociatedTokenAccount(provider.connection, authority, mint, authority.publicKey);
+    await mintTo(provider.connection, authority, mint, authorityAta.address, authority, 1);
+    return mint;
+  };
+
+  const initializeNftAirdrop = async (airdropId: number, tree: ReturnType<typeof buildTree>, numLeaves: number, endTime = 0) => {
+    const airdropState = getAirdropState(program.programId, NFT_AIRDROP, airdropId);
+    await program.methods.initializeNftAirdrop(airdropParams(tree.get_merkle_root(), numLeaves, { airdropId, endTime }))
+      .accountsPartial({ airdropState, authority: authority.publicKey, payer: authority.publicKey, systemProgram: SystemProgram.programId })
+      .rpc();
+    await createBitmapShard(program, airdropState, 0);
+    return airdropState;
+  };
+
+  const depositNft = async (airdropState: PublicKey, nftMint: PublicKey) =>
+    program.methods.depositNft()
+      .accountsPartial({
+        airdropState,
+        nftMint,
+        vault: await getAssociatedTokenAddress(nftMint, airdropState, true),
+        authorityAta: await getAssociatedTokenAddress(nftMint, authority.publicKey),
+        updateAuthority: authority.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .rpc();
+
+  const claimNft = async (index: number, nftMint = allocations[index].nftMint) => {
+    const claimant = claimants[index];
+    const signerAta = await getAssociatedTokenAddress(nftMint, claimant.publicKey);
+    await program.methods.claimNft(getProof(tree, index), new anchor.BN(index), new anchor.BN(0))
+      .accountsPartial({
+        airdropState,
+        nftMint,
+        vault: await getAssociatedTokenAddress(nftMint, airdropState, true),
+        signerAta,
+        claimBitmap: getBitmapShard(program.programId, airdropState, 0),
+        claimant: claimant.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .signers([claimant])
+      .rpc();
+    return signerAta;
+  };
+
+  const expectError = async (call: Promise<unknown>, code: string) => {
+    try {
+      await call;
+      expect.fail(`Call should have failed with ${code}`);
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal(code);
+    }
+  };
+
+  before(async () => {
+    await Promise.all(claimants.map((claimant) => fund(provider, claimant.publicKey)));
+    nftMints = [await createNft(), await createNft(), await createNft()];
+    allocations = claimants.map((claimant, i) => ({ address: claimant.publicKey, amount: 1, nftMint: nftMints[i] }));
+    tree = buildTree(allocations, { leafVersion: 6 });
+    airdropState = await initializeNftAirdrop(0, tree, allocations.length);
+    // The third NFT is listed but never deposited
+    await depositNft(airdropState, nftMints[0]);
+    await depositNft(airdropState, nftMints[1]);
+  });
+
+  it("Holds every deposited NFT in its own vault", async () => {
+    const vault = await getAccount(provider.connection, await getAssociatedTokenAddress(nftMints[0], airdropState, true));
+    expect(Number(vault.amount)).to.equal(1);
+
+    const state = await program.account.airdropState.fetch(airdropState);
+    expect(state.mint.toBase58()).to.equal(NFT_AIRDROP.toBase58());
+    expect(state.airdropAmount.toNumber()).to.equal(allocations.length);
+  });
+
+  it("Fail to deposit a mint that is not an NFT", async () => {
+    const mint = await createMint(provider.connection, authority, authority.publicKey, null, 0);
+    const authorityAta = await getOrCreateAssociatedTokenAccount(provider.connection, authority, mint, authority.publicKey);
+    await mintTo(provider.connection, authority, mint, authorityAta.address, authority, 2);
+
+    await expectError(depositNft(airdropState, mint), "NotAnNft");
+  });
+
+  it("Fail to claim the NFT of another leaf", async () => {
+    await expectError(claimNft(0, nftMints[1]), "InvalidProof");
+  });
+
+  it("Pays the NFT named by the leaf", async () => {
+    const signerAta = await claimNft(0);
+
+    expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(1);
+    const vault = await getAccount(provider.connection, await getAssociatedTokenAddress(nftMints[0], airdropState, true));
+    expect(Number(vault.amount)).to.equal(0);
+    expect((await program.account.airdropState.fetch(airdropState)).numClaims.toNumber()).to.equal(1);
+  });
+
+  it("Fail to claim an NFT twice even once it is back in the vault", async () => {
+    const signerAta = await getAssociatedTokenAddress(nftMints[0], claimants[0].publicKey);
+    const vault = await getAssociatedTokenAddress(nftMints[0], airdropState, true);
+    await provider.sendAndConfirm(
+      new Transaction().add(createTransferCheckedInstruction(signerAta, nftMints[0], vault, claimants[0].publicKey, 1, 0)),
+      [claimants[0]]
+    );
+
+    await expectError(claimNft(0), "AlreadyClaimed");
+  });
+
+  it("Fail to claim a listed NFT that was never deposited", async () => {
+    await expectError(claimNft(2), "NftNotDeposited");
+  });
+
+  it("Withdraws an unclaimed NFT after the deadline", async () => {
+    const nftMint = await createNft();
+    const endedTree = buildTree([{ address: claimants[0].publicKey, amount: 1, nftMint }], { leafVersion: 6 });
+    const ended = await initializeNftAirdrop(1, endedTree, 1, Math.floor(Date.now() / 1000) - 60);
+    await depositNft(ended, nftMint);
+
+    const destination = await getAssociatedTokenAddress(nftMint, authority.publicKey);
+    await program.methods.withdrawUnclaimedNft()
+      .accountsPartial({
+        airdropState: ended,
+        nftMint,
+        vault: await getAssociatedTokenAddress(nftMint, ended, true),
+        destination,
+        withdrawAuthority: authority.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    expect(Number((await getAccount(provider.connection, destination)).amount)).to.equal(1);
+    expect((await program.account.airdropState.fetch(ended)).amountWithdrawn.toNumber()).to.equal(1);
+  });
+});
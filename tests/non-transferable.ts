@@ -0,0 +1,104 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { getAccount, getAssociatedTokenAddress, getMint, getOrCreateAssociatedTokenAccount, getNonTransferable, TOKEN_2022_PROGRAM_ID, transferChecked } from "@solana/spl-token";
+import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
+import { Airdrop, airdropParams, buildTree, claimAirdrop, createBitmapShard, fund, getAirdropState, payoutParams } from "./utils";
+
+describe("non-transferable", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  const claimants = [Keypair.generate(), Keypair.generate()];
+  const allocations = claimants.map((claimant) => ({ address: claimant.publicKey, amount: 100 }));
+  const tree = buildTree(allocations);
+
+  let airdrop: Airdrop;
+  let signerAta: PublicKey;
+
+  before(async () => {
+    await Promise.all(claimants.map((claimant) => fund(provider, claimant.publicKey)));
+
+    const mint = Keypair.generate();
+    const airdropState = getAirdropState(program.programId, mint.publicKey);
+    const vault = await getAssociatedTokenAddress(mint.publicKey, airdropState, true, TOKEN_2022_PROGRAM_ID);
+    await program.methods.initializeNonTransferable(
+      airdropParams(tree.get_merkle_root(), allocations.length),
+      payoutParams(200),
+      0
+    )
+      .accountsPartial({
+        airdropState,
+        mint: mint.publicKey,
+        vault,
+        authority: authority.publicKey,
+        payer: authority.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .signers([authority, mint])
+      .rpc();
+
+    airdrop = { mint: mint.publicKey, airdropState, vault, tree, tokenProgram: TOKEN_2022_PROGRAM_ID };
+    await createBitmapShard(program, airdropState, 0);
+  });
+
+  it("Creates a non-transferable mint the airdrop mints on claim", async () => {
+    const mint = await getMint(provider.connection, airdrop.mint, undefined, TOKEN_2022_PROGRAM_ID);
+    expect(getNonTransferable(mint)).to.not.be.null;
+    expect(mint.mintAuthority.toBase58()).to.equal(airdrop.airdropState.toBase58());
+    expect(Number(mint.supply)).to.equal(0);
+
+    const state = await program.account.airdropState.fetch(airdrop.airdropState);
+    expect(state.mintOnClaim).to.equal(true);
+  });
+
+  it("Mints the claim into the claimant's ATA", async () => {
+    signerAta = await claimAirdrop(program, claimants[0], airdrop, 100, 0);
+
+    expect(Number((await getAccount(provider.connection, signerAta, undefined, TOKEN_2022_PROGRAM_ID)).amount)).to.equal(100);
+    const state = await program.account.airdropState.fetch(airdrop.airdropState);
+    expect(state.amountClaimed.toNumber()).to.equal(100);
+    expect(state.numClaims.toNumber()).to.equal(1);
+  });
+
+  it("Fail to transfer claimed tokens", async () => {
+    const destination = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      claimants[1],
+      airdrop.mint,
+      claimants[1].publicKey,
+      false,
+      undefined,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    try {
+      await transferChecked(
+        provider.connection,
+        claimants[0],
+        signerAta,
+        airdrop.mint,
+        destination.address,
+        claimants[0],
+        100,
+        0,
+        [],
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      );
+      expect.fail("Non-transferable tokens should not move");
+    } catch (error: any) {
+      // TokenError::NonTransferable
+      expect(String(error)).to.include("0x25");
+    }
+    expect(Number((await getAccount(provider.connection, signerAta, undefined, TOKEN_2022_PROGRAM_ID)).amount)).to.equal(100);
+  });
+});
@@ -0,0 +1,120 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { getAccount, getAssociatedTokenAddress } from "@solana/spl-token";
+import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
+import { Airdrop, claimAirdrop, createBitmapShard, findCpiEvent, fund, getBitmapShard, getProof, initializeAirdrop } from "./utils";
+
+describe("partial claim", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  const claimant = Keypair.generate();
+  const allocations = [
+    { address: claimant.publicKey, amount: 1_000 },
+    { address: Keypair.generate().publicKey, amount: 500 },
+  ];
+
+  let airdrop: Airdrop;
+
+  const getClaimProgress = (index: number) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("progress"), airdrop.airdropState.toBuffer(), new anchor.BN(index).toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+
+  const claimPartial = async (claimAmount: number) => {
+    const signerAta = await getAssociatedTokenAddress(airdrop.mint, claimant.publicKey, false, airdrop.tokenProgram);
+    const signature = await program.methods.claimPartial(new anchor.BN(1_000), new anchor.BN(claimAmount), getProof(airdrop.tree, 0), new anchor.BN(0), new anchor.BN(0))
+      .accountsPartial({
+        airdropState: airdrop.airdropState,
+        mint: airdrop.mint,
+        vault: airdrop.vault,
+        signerAta,
+        claimBitmap: getBitmapShard(program.programId, airdrop.airdropState, 0),
+        claimProgress: getClaimProgress(0),
+        claimant: claimant.publicKey,
+        payer: claimant.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: airdrop.tokenProgram,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .signers([claimant])
+      .rpc();
+    return { signature, signerAta };
+  };
+
+  const expectError = async (claim: Promise<unknown>, code: string) => {
+    try {
+      await claim;
+      expect.fail(`Claim should have failed with ${code}`);
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal(code);
+    }
+  };
+
+  before(async () => {
+    await fund(provider, claimant.publicKey);
+    airdrop = await initializeAirdrop(program, authority, allocations);
+    await createBitmapShard(program, airdrop.airdropState, 0);
+  });
+
+  it("Fail to claim in parts before the airdrop is in partial mode", async () => {
+    await expectError(claimPartial(100), "ClaimModeMismatch");
+  });
+
+  it("Fail to set an unknown claim mode", async () => {
+    await expectError(
+      program.methods.setClaimMode(7).accountsPartial({ airdropState: airdrop.airdropState, updateAuthority: authority.publicKey }).rpc(),
+      "UnsupportedClaimMode"
+    );
+  });
+
+  it("Claims an allocation in parts", async () => {
+    await program.methods.setClaimMode(1).accountsPartial({ airdropState: airdrop.airdropState, updateAuthority: authority.publicKey }).rpc();
+
+    const { signature, signerAta } = await claimPartial(300);
+    const event = await findCpiEvent(program, signature, "claimEvent");
+    expect(event.amount.toNumber()).to.equal(300);
+    expect(event.allocationClaimed.toNumber()).to.equal(300);
+
+    await claimPartial(600);
+    expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(900);
+    expect((await program.account.claimProgress.fetch(getClaimProgress(0))).claimed.toNumber()).to.equal(900);
+
+    const state = await program.account.airdropState.fetch(airdrop.airdropState);
+    expect(state.amountClaimed.toNumber()).to.equal(900);
+    expect(state.numClaims.toNumber()).to.equal(1);
+  });
+
+  it("Fail to claim past the leaf amount", async () => {
+    await expectError(claimPartial(101), "AllocationExhausted");
+  });
+
+  it("Fail to claim the whole leaf the exact way in partial mode", async () => {
+    await expectError(claimAirdrop(program, claimant, airdrop, 1_000, 0), "ClaimModeMismatch");
+  });
+
+  it("Marks the index claimed once the allocation is used up", async () => {
+    const { signature, signerAta } = await claimPartial(100);
+    const event = await findCpiEvent(program, signature, "claimEvent");
+    expect(event.amount.toNumber()).to.equal(100);
+    expect(event.allocationClaimed.toNumber()).to.equal(1_000);
+    expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(1_000);
+
+    await expectError(claimPartial(1), "AlreadyClaimed");
+  });
+
+  it("Fail to change the claim mode once claims started", async () => {
+    await expectError(
+      program.methods.setClaimMode(0).accountsPartial({ airdropState: airdrop.airdropState, updateAuthority: authority.publicKey }).rpc(),
+      "ClaimModeLocked"
+    );
+  });
+});
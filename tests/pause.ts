@@ -0,0 +1,62 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { getAccount } from "@solana/spl-token";
+import { buildTree, claimAirdrop, createBitmapShard, fund, initializeAirdrop } from "./utils";
+
+describe("pause", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  const claimants = [Keypair.generate(), Keypair.generate()];
+  const allocations = claimants.map((claimant) => ({ address: claimant.publicKey, amount: 100 }));
+
+  const setPaused = (airdropState: PublicKey, paused: boolean) =>
+    program.methods.setPaused(paused)
+      .accountsPartial({ airdropState, authority: authority.publicKey })
+      .rpc();
+
+  before(async () => {
+    for (const claimant of claimants) {
+      await fund(provider, claimant.publicKey);
+    }
+  });
+
+  it("Rejects claims while paused and accepts them again once unpaused", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations);
+    await createBitmapShard(program, airdrop.airdropState, 0);
+
+    await setPaused(airdrop.airdropState, true);
+    expect((await program.account.airdropState.fetch(airdrop.airdropState)).paused).to.equal(true);
+
+    try {
+      await claimAirdrop(program, claimants[0], airdrop, 100, 0);
+      expect.fail("Claim should have failed while paused");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Claims are paused");
+    }
+
+    await setPaused(airdrop.airdropState, false);
+    const signerAta = await claimAirdrop(program, claimants[0], airdrop, 100, 0);
+    expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(100);
+  });
+
+  it("Updates the root while paused", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations);
+    await setPaused(airdrop.airdropState, true);
+
+    const fixedTree = buildTree([...allocations].reverse());
+    await program.methods.updateTree(Array.from(fixedTree.get_merkle_root()), new anchor.BN(allocations.length))
+      .accountsPartial({ airdropState: airdrop.airdropState, updateAuthority: authority.publicKey })
+      .rpc();
+
+    const account = await program.account.airdropState.fetch(airdrop.airdropState);
+    expect(Buffer.from(account.merkleRoot)).to.deep.equal(Buffer.from(fixedTree.get_merkle_root()));
+  });
+});
@@ -0,0 +1,63 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { getAccount } from "@solana/spl-token";
+import { buildTree, claimAirdrop, createBitmapShard, fund, getProof, initializeAirdrop } from "./utils";
+
+describe("domain-separated trees", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  // Test vectors computed independently: SHA-256 leaves of `0x00 ‖ claimant ‖ amount`, nodes of
+  // `0x01 ‖ left ‖ right`, the odd third leaf paired with itself
+  const vectorAllocations = [1, 2, 3].map((byte) => ({ address: new PublicKey(Buffer.alloc(32, byte)), amount: 100 * byte }));
+  const vectorRoot = "c0ef2a01acb016588c7beb7d5ba3672e08cf681d8feabced355d1f50c3278a53";
+  const vectorProofs = [
+    "9e091d64e946a11b24ba07fc41cf12c8a66894a45598b74679c931f44cdf2478301624f70ec109895e067b8ed768bbacb6c215557136242de3663ba95068dfb4",
+    "3704030f95718ebf350aa5d54e466fa2134818d26c4267f65d70d1a7421cbcb7301624f70ec109895e067b8ed768bbacb6c215557136242de3663ba95068dfb4",
+    "ca08e03b71dc8792c1a5848bb06ddc7f3b0d8775ba95af7e52267df4137074c33479b96a17dceada4de7da3c06596957cbbc9bc17112f825513db67489a335c7",
+  ];
+  const prefixedSha256 = { hashingAlgorithm: 1, pairOrdering: 2 };
+
+  const claimants = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  const allocations = claimants.map((claimant, i) => ({ address: claimant.publicKey, amount: 100 * (i + 1) }));
+
+  before(async () => {
+    for (const claimant of claimants) {
+      await fund(provider, claimant.publicKey);
+    }
+  });
+
+  it("Builds the test vector tree", () => {
+    const tree = buildTree(vectorAllocations, prefixedSha256);
+    expect(Buffer.from(tree.get_merkle_root()).toString("hex")).to.equal(vectorRoot);
+    vectorProofs.forEach((proof, index) => expect(getProof(tree, index).toString("hex")).to.equal(proof));
+  });
+
+  it("Verifies the test vector proofs", async () => {
+    const airdrop = await initializeAirdrop(program, authority, vectorAllocations, prefixedSha256);
+
+    for (const [index, entry] of vectorAllocations.entries()) {
+      const result = await program.methods.verifyEligibility(entry.address, new anchor.BN(entry.amount), Buffer.from(vectorProofs[index], "hex"), new anchor.BN(index), new anchor.BN(0))
+        .accountsPartial({ airdropState: airdrop.airdropState, claimBitmap: null })
+        .view();
+      expect(result.eligible).to.equal(true);
+    }
+  });
+
+  it("Claims every leaf of a domain-separated keccak tree", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations, { pairOrdering: 2 });
+    await createBitmapShard(program, airdrop.airdropState, 0);
+
+    for (const [index, entry] of allocations.entries()) {
+      const signerAta = await claimAirdrop(program, claimants[index], airdrop, entry.amount, index);
+      expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(entry.amount);
+    }
+  });
+});
@@ -0,0 +1,73 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { Airdrop, getProof, initializeAirdrop } from "./utils";
+
+describe("proof mutations", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  // Seeded so a failing amount or flipped bit can be reproduced from the run that found it
+  let seed = 0x5eed;
+  const random = (bound: number) => {
+    seed = (seed * 16_807) % 2_147_483_647;
+    return seed % bound;
+  };
+
+  const allocations = Array.from({ length: 13 }, () => ({
+    address: Keypair.generate().publicKey,
+    amount: 1 + random(1_000_000),
+  }));
+
+  let airdrop: Airdrop;
+
+  const isEligible = async (claimant: PublicKey, amount: number, proof: Buffer, index: number) =>
+    (await program.methods.verifyEligibility(claimant, new anchor.BN(amount), proof, new anchor.BN(index), new anchor.BN(0))
+      .accountsPartial({ airdropState: airdrop.airdropState, claimBitmap: null })
+      .view()).eligible;
+
+  before(async () => {
+    airdrop = await initializeAirdrop(program, authority, allocations);
+  });
+
+  it("Verifies every leaf of the tree", async () => {
+    for (const [index, { address, amount }] of allocations.entries()) {
+      expect(await isEligible(address, amount, getProof(airdrop.tree, index), index)).to.equal(true);
+    }
+  });
+
+  it("Rejects every leaf claimed for another amount", async () => {
+    for (const [index, { address, amount }] of allocations.entries()) {
+      expect(await isEligible(address, amount + 1 + random(1_000), getProof(airdrop.tree, index), index)).to.equal(false);
+    }
+  });
+
+  it("Rejects every proof presented at another index", async () => {
+    for (const [index, { address, amount }] of allocations.entries()) {
+      const otherIndex = (index + 1 + random(allocations.length - 1)) % allocations.length;
+      expect(await isEligible(address, amount, getProof(airdrop.tree, index), otherIndex)).to.equal(false);
+    }
+  });
+
+  it("Rejects every truncated proof", async () => {
+    for (const [index, { address, amount }] of allocations.entries()) {
+      const proof = getProof(airdrop.tree, index);
+      expect(await isEligible(address, amount, proof.subarray(0, proof.length - 32), index)).to.equal(false);
+    }
+  });
+
+  it("Rejects every proof with a bit of a sibling flipped", async () => {
+    for (const [index, { address, amount }] of allocations.entries()) {
+      const proof = Buffer.from(getProof(airdrop.tree, index));
+      const bit = random(proof.length * 8);
+      proof[Math.floor(bit / 8)] ^= 1 << (bit % 8);
+      expect(await isEligible(address, amount, proof, index)).to.equal(false);
+    }
+  });
+});
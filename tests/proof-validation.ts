@@ -0,0 +1,88 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair } from "@solana/web3.js";
+import { getAssociatedTokenAddress, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
+import { Airdrop, createBitmapShard, fund, getBitmapShard, getProof, initializeAirdrop } from "./utils";
+
+describe("proof validation", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  const claimant = Keypair.generate();
+  const allocations = [
+    { address: claimant.publicKey, amount: 100 },
+    { address: Keypair.generate().publicKey, amount: 200 },
+  ];
+  let airdrop: Airdrop;
+
+  const claimWithHashes = async (hashes: Buffer) =>
+    program.methods.claimAirdrop(new anchor.BN(100), hashes, new anchor.BN(0), new anchor.BN(0), null, false)
+      .accountsPartial({
+        airdropState: airdrop.airdropState,
+        mint: airdrop.mint,
+        vault: airdrop.vault,
+        signerAta: await getAssociatedTokenAddress(airdrop.mint, claimant.publicKey),
+        claimBitmap: getBitmapShard(program.programId, airdrop.airdropState, 0),
+        claimant: claimant.publicKey,
+        payer: claimant.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .signers([claimant])
+      .rpc();
+
+  before(async () => {
+    await fund(provider, claimant.publicKey);
+    airdrop = await initializeAirdrop(program, authority, allocations);
+    await createBitmapShard(program, airdrop.airdropState, 0);
+  });
+
+  it("Fail to claim with a proof that is not a whole number of hashes", async () => {
+    try {
+      await claimWithHashes(getProof(airdrop.tree, 0).subarray(0, 31));
+      expect.fail("Claim should have failed for a truncated proof");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Proof must be a non-empty sequence of 32-byte hashes");
+    }
+  });
+
+  it("Fail to claim with an empty proof from a tree of several leaves", async () => {
+    try {
+      await claimWithHashes(Buffer.alloc(0));
+      expect.fail("Claim should have failed for an empty proof");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Proof must be a non-empty sequence of 32-byte hashes");
+    }
+  });
+
+  it("Expose the maximum proof depth in the IDL", async () => {
+    const constant = program.idl.constants.find((c) => c.name.replace(/_/g, "").toLowerCase() === "maxproofdepth");
+    expect(constant.value).to.equal("32");
+  });
+
+  it("Fail to claim with a proof deeper than the maximum depth", async () => {
+    // A 64-level proof does not fit in a single transaction, so it is either refused
+    // before it is sent or rejected by the program before any hashing happens
+    try {
+      await claimWithHashes(Buffer.alloc(64 * 32));
+      expect.fail("Claim should have failed for a 64-level proof");
+    } catch (error: any) {
+      expect(error.message).to.not.equal("Claim should have failed for a 64-level proof");
+      if (error.error?.errorCode) {
+        expect(error.error.errorCode.code).to.equal("ProofTooLong");
+      }
+    }
+  });
+
+  it("Claim once the proof is well formed", async () => {
+    await claimWithHashes(getProof(airdrop.tree, 0));
+    expect((await program.account.airdropState.fetch(airdrop.airdropState)).amountClaimed.toNumber()).to.equal(100);
+  });
+});
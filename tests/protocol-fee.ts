@@ -0,0 +1,98 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { getAccount, getAssociatedTokenAddress, getOrCreateAssociatedTokenAccount } from "@solana/spl-token";
+import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
+import { Airdrop, claimAirdrop, createBitmapShard, findCpiEvent, fund, getBitmapShard, getProof, initializeAirdrop } from "./utils";
+
+describe("protocol fee", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+  const feeRecipient = Keypair.generate().publicKey;
+
+  const claimants = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  const allocations = claimants.map((claimant) => ({ address: claimant.publicKey, amount: 1_000 }));
+
+  let airdrop: Airdrop;
+  let feeAta: PublicKey;
+
+  const claimWithFee = async (index: number) => {
+    const claimant = claimants[index];
+    const signerAta = await getAssociatedTokenAddress(airdrop.mint, claimant.publicKey);
+    const signature = await program.methods.claimAirdrop(new anchor.BN(allocations[index].amount), getProof(airdrop.tree, index), new anchor.BN(index), new anchor.BN(0), null, false)
+      .accountsPartial({
+        airdropState: airdrop.airdropState,
+        mint: airdrop.mint,
+        vault: airdrop.vault,
+        signerAta,
+        claimBitmap: getBitmapShard(program.programId, airdrop.airdropState, 0),
+        feeAta,
+        claimant: claimant.publicKey,
+        payer: claimant.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: airdrop.tokenProgram,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .signers([claimant])
+      .rpc();
+    return { event: await findCpiEvent(program, signature, "claimEvent"), signerAta };
+  };
+
+  const lowerFee = (feeBps: number) =>
+    program.methods.lowerFee(feeBps)
+      .accountsPartial({ airdropState: airdrop.airdropState, updateAuthority: authority.publicKey })
+      .rpc();
+
+  const expectError = async (call: Promise<unknown>, code: string) => {
+    try {
+      await call;
+      expect.fail(`Call should have failed with ${code}`);
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal(code);
+    }
+  };
+
+  before(async () => {
+    await Promise.all(claimants.map((claimant) => fund(provider, claimant.publicKey)));
+    airdrop = await initializeAirdrop(program, authority, allocations, { feeBps: 250, feeRecipient });
+    await createBitmapShard(program, airdrop.airdropState, 0);
+    feeAta = (await getOrCreateAssociatedTokenAccount(provider.connection, authority, airdrop.mint, feeRecipient)).address;
+  });
+
+  it("Fail to initialize with a fee above the whole payout", async () => {
+    await expectError(initializeAirdrop(program, authority, allocations, { feeBps: 10_001, feeRecipient }), "InvalidFee");
+  });
+
+  it("Splits a claim between the fee recipient and the claimant", async () => {
+    const { event, signerAta } = await claimWithFee(0);
+
+    expect(event.amount.toNumber()).to.equal(1_000);
+    expect(event.fee.toNumber()).to.equal(25);
+    expect(event.netAmount.toNumber()).to.equal(975);
+    expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(975);
+    expect(Number((await getAccount(provider.connection, feeAta)).amount)).to.equal(25);
+    expect((await program.account.airdropState.fetch(airdrop.airdropState)).amountClaimed.toNumber()).to.equal(1_000);
+  });
+
+  it("Fail to claim without the token account of the fee recipient", async () => {
+    await expectError(claimAirdrop(program, claimants[1], airdrop, 1_000, 1), "MissingFeeAccount");
+  });
+
+  it("Fail to raise the fee", async () => {
+    await expectError(lowerFee(251), "FeeNotLowered");
+  });
+
+  it("Claims without the fee accounts once the fee is lowered to nothing", async () => {
+    await lowerFee(0);
+
+    const signerAta = await claimAirdrop(program, claimants[2], airdrop, 1_000, 2);
+    expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(1_000);
+    expect(Number((await getAccount(provider.connection, feeAta)).amount)).to.equal(25);
+  });
+});
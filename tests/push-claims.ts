@@ -0,0 +1,132 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { createMint, freezeAccount, getAccount, getAssociatedTokenAddressSync, getOrCreateAssociatedTokenAccount, mintTo, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
+import { Airdrop, createBitmapShard, fund, getBitmapShard, getCpiEvents, getProof, initializeWithExistingMint } from "./utils";
+
+describe("push claims", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  const recipients = [Keypair.generate(), Keypair.generate(), Keypair.generate(), Keypair.generate()].map((keypair) => keypair.publicKey);
+  const allocations = recipients.map((address, i) => ({ address, amount: 100 * (i + 1) }));
+
+  let airdrop: Airdrop;
+
+  const recipientAta = (recipient: PublicKey) => getAssociatedTokenAddressSync(airdrop.mint, recipient);
+
+  const getRevokedLeaf = (index: number) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("revoked"), airdrop.airdropState.toBuffer(), new anchor.BN(index).toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+
+  // Revocation markers follow each recipient's accounts once the airdrop has revoked leaves
+  const push = async (entries: { index: number; amount?: number }[], withPayer = true, signer = authority, withMarkers = false) => {
+    const claims = entries.map(({ index, amount }) => ({
+      recipient: recipients[index],
+      amount: new anchor.BN(amount ?? allocations[index].amount),
+      index: new anchor.BN(index),
+      proofLen: getProof(airdrop.tree, index).length / 32,
+    }));
+    return program.methods.claimForMany(new anchor.BN(0), claims, Buffer.concat(entries.map(({ index }) => getProof(airdrop.tree, index))), new anchor.BN(0))
+      .accountsPartial({
+        airdropState: airdrop.airdropState,
+        mint: airdrop.mint,
+        vault: airdrop.vault,
+        claimBitmap: getBitmapShard(program.programId, airdrop.airdropState, 0),
+        updateAuthority: signer.publicKey,
+        payer: withPayer ? signer.publicKey : null,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .remainingAccounts(entries.flatMap(({ index }) => [
+        { pubkey: recipients[index], isSigner: false, isWritable: false },
+        { pubkey: recipientAta(recipients[index]), isSigner: false, isWritable: true },
+        ...(withMarkers ? [{ pubkey: getRevokedLeaf(index), isSigner: false, isWritable: false }] : []),
+      ]))
+      .signers([signer])
+      .rpc();
+  };
+
+  const skipped = async (signature: string) =>
+    (await getCpiEvents(program, signature))
+      .filter((event) => event.name === "claimSkipped")
+      .map((event) => ({ index: event.data.index.toNumber(), reason: Object.keys(event.data.reason)[0] }));
+
+  before(async () => {
+    // A mint with a freeze authority, so one recipient's account can be frozen
+    const mint = await createMint(provider.connection, authority, authority.publicKey, authority.publicKey, 6);
+    const authorityAta = await getOrCreateAssociatedTokenAccount(provider.connection, authority, mint, authority.publicKey);
+    await mintTo(provider.connection, authority, mint, authorityAta.address, authority, 1_000);
+    airdrop = await initializeWithExistingMint(program, authority, mint, authorityAta.address, allocations, 1_000);
+    await createBitmapShard(program, airdrop.airdropState, 0);
+
+    const frozen = await getOrCreateAssociatedTokenAccount(provider.connection, authority, mint, recipients[3]);
+    await freezeAccount(provider.connection, authority, frozen.address, mint, authority);
+  });
+
+  it("Fail to push claims as someone else than the authority", async () => {
+    const outsider = Keypair.generate();
+    await fund(provider, outsider.publicKey);
+    try {
+      await push([{ index: 0 }], true, outsider);
+      expect.fail("Push should have failed for a non-authority signer");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("MissingRole");
+    }
+  });
+
+  it("Skips recipients without a token account when no payer is given", async () => {
+    const signature = await push([{ index: 0 }], false);
+
+    expect(await skipped(signature)).to.deep.equal([{ index: 0, reason: "missingTokenAccount" }]);
+    expect(await provider.connection.getAccountInfo(recipientAta(recipients[0]))).to.equal(null);
+  });
+
+  it("Pushes valid claims and reports the ones it could not pay", async () => {
+    const signature = await push([{ index: 0 }, { index: 1, amount: 1_000 }, { index: 2 }, { index: 3 }]);
+
+    expect(await skipped(signature)).to.deep.equal([
+      { index: 1, reason: "invalidProof" },
+      { index: 3, reason: "frozenTokenAccount" },
+    ]);
+    expect(Number((await getAccount(provider.connection, recipientAta(recipients[0]))).amount)).to.equal(100);
+    expect(Number((await getAccount(provider.connection, recipientAta(recipients[2]))).amount)).to.equal(300);
+
+    const state = await program.account.airdropState.fetch(airdrop.airdropState);
+    expect(state.amountClaimed.toNumber()).to.equal(400);
+    expect(state.numClaims.toNumber()).to.equal(2);
+  });
+
+  it("Continues the crank in a later transaction, skipping what was already pushed", async () => {
+    const signature = await push([{ index: 0 }, { index: 1 }]);
+
+    expect(await skipped(signature)).to.deep.equal([{ index: 0, reason: "alreadyClaimed" }]);
+    expect(Number((await getAccount(provider.connection, recipientAta(recipients[1]))).amount)).to.equal(200);
+  });
+
+  it("Skips revoked leaves, taking the revocation markers once any leaf is revoked", async () => {
+    await program.methods.revokeLeaf(new anchor.BN(3))
+      .accountsPartial({ airdropState: airdrop.airdropState, revokedLeaf: getRevokedLeaf(3), updateAuthority: authority.publicKey })
+      .rpc();
+
+    try {
+      await push([{ index: 3 }]);
+      expect.fail("Push should have failed without the revocation markers");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("RecipientAccountsMismatch");
+    }
+
+    const signature = await push([{ index: 3 }], true, authority, true);
+    expect(await skipped(signature)).to.deep.equal([{ index: 3, reason: "revoked" }]);
+  });
+});
@@ -0,0 +1,100 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { getAssociatedTokenAddress } from "@solana/spl-token";
+import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
+import { Airdrop, createBitmapShard, findCpiEvent, fund, getBitmapShard, getProof, initializeAirdrop } from "./utils";
+
+describe("referral", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+  const referrer = Keypair.generate().publicKey;
+  const otherReferrer = Keypair.generate().publicKey;
+
+  const claimants = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  const allocations = claimants.map((claimant, i) => ({ address: claimant.publicKey, amount: 100 * (i + 1) }));
+
+  let airdrop: Airdrop;
+
+  const getReferralStats = (referrer: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("referral"), airdrop.airdropState.toBuffer(), referrer.toBuffer()],
+      program.programId
+    )[0];
+
+  const createReferralStats = (referrer: PublicKey) =>
+    program.methods.createReferralStats(referrer)
+      .accountsPartial({ airdropState: airdrop.airdropState, referralStats: getReferralStats(referrer), payer: authority.publicKey })
+      .rpc();
+
+  const claimReferred = async (index: number, referredBy: PublicKey, referralStats = getReferralStats(referredBy)) => {
+    const claimant = claimants[index];
+    const signature = await program.methods.claimAirdrop(new anchor.BN(allocations[index].amount), getProof(airdrop.tree, index), new anchor.BN(index), new anchor.BN(0), referredBy, false)
+      .accountsPartial({
+        airdropState: airdrop.airdropState,
+        mint: airdrop.mint,
+        vault: airdrop.vault,
+        signerAta: await getAssociatedTokenAddress(airdrop.mint, claimant.publicKey),
+        claimBitmap: getBitmapShard(program.programId, airdrop.airdropState, 0),
+        referralStats,
+        claimant: claimant.publicKey,
+        payer: claimant.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: airdrop.tokenProgram,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .signers([claimant])
+      .rpc();
+    return findCpiEvent(program, signature, "claimEvent");
+  };
+
+  const expectError = async (call: Promise<unknown>, code: string) => {
+    try {
+      await call;
+      expect.fail(`Call should have failed with ${code}`);
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal(code);
+    }
+  };
+
+  before(async () => {
+    await Promise.all(claimants.map((claimant) => fund(provider, claimant.publicKey)));
+    airdrop = await initializeAirdrop(program, authority, allocations);
+    await createBitmapShard(program, airdrop.airdropState, 0);
+    await createReferralStats(referrer);
+  });
+
+  it("Creates the referral stats idempotently", async () => {
+    await createReferralStats(referrer);
+
+    const stats = await program.account.referralStats.fetch(getReferralStats(referrer));
+    expect(stats.referrer.toBase58()).to.equal(referrer.toBase58());
+    expect(stats.claims.toNumber()).to.equal(0);
+  });
+
+  it("Fail to refer yourself", async () => {
+    await createReferralStats(claimants[0].publicKey);
+    await expectError(claimReferred(0, claimants[0].publicKey), "SelfReferral");
+  });
+
+  it("Fail to credit a referrer with the stats of another", async () => {
+    await createReferralStats(otherReferrer);
+    await expectError(claimReferred(0, referrer, getReferralStats(otherReferrer)), "MissingReferralStats");
+  });
+
+  it("Counts referred claims and records the referrer in the claim event", async () => {
+    const event = await claimReferred(0, referrer);
+    await claimReferred(1, referrer);
+
+    expect(event.referrer.toBase58()).to.equal(referrer.toBase58());
+    const stats = await program.account.referralStats.fetch(getReferralStats(referrer));
+    expect(stats.claims.toNumber()).to.equal(2);
+    expect(stats.volume.toNumber()).to.equal(300);
+  });
+});
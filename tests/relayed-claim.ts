@@ -0,0 +1,78 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair, SystemProgram } from "@solana/web3.js";
+import { getAccount, getAssociatedTokenAddress } from "@solana/spl-token";
+import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
+import { Airdrop, claimAirdrop, createBitmapShard, fund, getBitmapShard, getProof, initializeAirdrop } from "./utils";
+
+describe("relayed claim", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  // Only signs, the relayer pays the fee and the rent of its token account
+  const claimant = Keypair.generate();
+  const selfPaying = Keypair.generate();
+  const allocations = [
+    { address: claimant.publicKey, amount: 100 },
+    { address: selfPaying.publicKey, amount: 200 },
+  ];
+
+  let airdrop: Airdrop;
+
+  const relayClaim = async (signer: Keypair, amount: number, index: number) => {
+    const signerAta = await getAssociatedTokenAddress(airdrop.mint, signer.publicKey, false, airdrop.tokenProgram);
+    await program.methods.claimAirdrop(new anchor.BN(amount), getProof(airdrop.tree, index), new anchor.BN(index), new anchor.BN(0), null, false)
+      .accountsPartial({
+        airdropState: airdrop.airdropState,
+        mint: airdrop.mint,
+        vault: airdrop.vault,
+        signerAta,
+        claimBitmap: getBitmapShard(program.programId, airdrop.airdropState, 0),
+        claimant: signer.publicKey,
+        payer: wallet.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: airdrop.tokenProgram,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .signers([signer])
+      .rpc();
+    return signerAta;
+  };
+
+  before(async () => {
+    airdrop = await initializeAirdrop(program, authority, allocations);
+    await createBitmapShard(program, airdrop.airdropState, 0);
+    await fund(provider, selfPaying.publicKey);
+  });
+
+  it("Fail to relay a claim signed by someone else than the allocation's owner", async () => {
+    try {
+      await relayClaim(selfPaying, 100, 0);
+      expect.fail("Claim should have failed for a claimant the leaf does not commit to");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("InvalidProof");
+    }
+  });
+
+  it("Claims with a relayer paying for a claimant without SOL", async () => {
+    const signerAta = await relayClaim(claimant, 100, 0);
+
+    const account = await getAccount(provider.connection, signerAta, undefined, airdrop.tokenProgram);
+    expect(Number(account.amount)).to.equal(100);
+    expect(account.owner.toBase58()).to.equal(claimant.publicKey.toBase58());
+    expect(await provider.connection.getBalance(claimant.publicKey)).to.equal(0);
+  });
+
+  it("Claims with the claimant paying for itself", async () => {
+    const signerAta = await claimAirdrop(program, selfPaying, airdrop, 200, 1);
+
+    const account = await getAccount(provider.connection, signerAta, undefined, airdrop.tokenProgram);
+    expect(Number(account.amount)).to.equal(200);
+  });
+});
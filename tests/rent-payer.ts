@@ -0,0 +1,64 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { getAccount } from "@solana/spl-token";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { airdropParams, buildTree, fund, getAirdropState, initializeAirdrop } from "./utils";
+
+describe("rent payer", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+
+  const allocations = Array.from({ length: 2 }, () => ({ address: Keypair.generate().publicKey, amount: 100 }));
+
+  const balance = (key: Keypair) => provider.connection.getBalance(key.publicKey);
+
+  it("Has the authority pay the rent by default", async () => {
+    const authority = Keypair.generate();
+    await fund(provider, authority.publicKey);
+    const before = await balance(authority);
+
+    const airdrop = await initializeAirdrop(program, authority, allocations);
+
+    expect(await balance(authority)).to.be.below(before);
+    expect((await program.account.airdropState.fetch(airdrop.airdropState)).updateAuthority.toBase58()).to.equal(authority.publicKey.toBase58());
+  });
+
+  it("Has a separate payer cover the rent of an authority without SOL", async () => {
+    // The wallet pays the transaction fee, the authority only signs
+    const authority = Keypair.generate();
+    const payer = Keypair.generate();
+    await fund(provider, payer.publicKey);
+    const before = await balance(payer);
+
+    const airdrop = await initializeAirdrop(program, authority, allocations, { payer });
+
+    expect(await balance(authority)).to.equal(0);
+    expect(await balance(payer)).to.be.below(before);
+    const state = await program.account.airdropState.fetch(airdrop.airdropState);
+    expect(state.updateAuthority.toBase58()).to.equal(authority.publicKey.toBase58());
+    expect(state.withdrawAuthority.toBase58()).to.equal(authority.publicKey.toBase58());
+    expect(Number((await getAccount(provider.connection, airdrop.vault)).amount)).to.equal(200);
+  });
+
+  it("Has the authority of a SOL airdrop fund only the lamports paid out", async () => {
+    const authority = Keypair.generate();
+    const payer = Keypair.generate();
+    await Promise.all([authority, payer].map((key) => fund(provider, key.publicKey)));
+    const before = await balance(authority);
+
+    const airdropState = getAirdropState(program.programId, PublicKey.default, 7);
+    const solVault = PublicKey.findProgramAddressSync([Buffer.from("sol_vault"), airdropState.toBuffer()], program.programId)[0];
+    await program.methods.initializeSolAirdrop(airdropParams(buildTree(allocations).get_merkle_root(), allocations.length, { airdropId: 7 }), new anchor.BN(LAMPORTS_PER_SOL / 2), 0)
+      .accountsPartial({ airdropState, solVault, authority: authority.publicKey, payer: payer.publicKey, systemProgram: SystemProgram.programId })
+      .signers([authority, payer])
+      .rpc();
+
+    expect(before - (await balance(authority))).to.equal(LAMPORTS_PER_SOL / 2);
+    expect(await provider.connection.getBalance(solVault)).to.equal(LAMPORTS_PER_SOL / 2 + await provider.connection.getMinimumBalanceForRentExemption(0));
+  });
+});
@@ -0,0 +1,160 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { getAccount, getAssociatedTokenAddress } from "@solana/spl-token";
+import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
+import { Airdrop, claimAirdrop, createBitmapShard, findCpiEvent, fund, getBitmapShard, getProof, initializeAirdrop } from "./utils";
+
+describe("revocation", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  const claimants = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  const allocations = claimants.map((claimant) => ({ address: claimant.publicKey, amount: 100 }));
+
+  let airdrop: Airdrop;
+
+  const getRevokedLeaf = (index: number) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("revoked"), airdrop.airdropState.toBuffer(), new anchor.BN(index).toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+
+  const revokeLeaf = (index: number) =>
+    program.methods.revokeLeaf(new anchor.BN(index))
+      .accountsPartial({ airdropState: airdrop.airdropState, revokedLeaf: getRevokedLeaf(index), updateAuthority: authority.publicKey })
+      .rpc();
+
+  const unrevokeLeaf = (index: number) =>
+    program.methods.unrevokeLeaf(new anchor.BN(index))
+      .accountsPartial({ airdropState: airdrop.airdropState, revokedLeaf: getRevokedLeaf(index), updateAuthority: authority.publicKey })
+      .rpc();
+
+  const claimChecked = async (index: number) => {
+    const claimant = claimants[index];
+    const signerAta = await getAssociatedTokenAddress(airdrop.mint, claimant.publicKey);
+    await program.methods.claimAirdrop(new anchor.BN(allocations[index].amount), getProof(airdrop.tree, index), new anchor.BN(index), new anchor.BN(0), null, false)
+      .accountsPartial({
+        airdropState: airdrop.airdropState,
+        mint: airdrop.mint,
+        vault: airdrop.vault,
+        signerAta,
+        claimBitmap: getBitmapShard(program.programId, airdrop.airdropState, 0),
+        revokedLeaf: getRevokedLeaf(index),
+        claimant: claimant.publicKey,
+        payer: claimant.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: airdrop.tokenProgram,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .signers([claimant])
+      .rpc();
+    return signerAta;
+  };
+
+  const expectError = async (call: Promise<unknown>, code: string) => {
+    try {
+      await call;
+      expect.fail(`Call should have failed with ${code}`);
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal(code);
+    }
+  };
+
+  before(async () => {
+    await Promise.all(claimants.map((claimant) => fund(provider, claimant.publicKey)));
+    airdrop = await initializeAirdrop(program, authority, allocations);
+    await createBitmapShard(program, airdrop.airdropState, 0);
+  });
+
+  it("Revokes a leaf and records it in an event", async () => {
+    const signature = await revokeLeaf(1);
+    const event = await findCpiEvent(program, signature, "leafRevoked");
+
+    expect(event.airdropState.toBase58()).to.equal(airdrop.airdropState.toBase58());
+    expect(event.index.toNumber()).to.equal(1);
+    expect((await program.account.airdropState.fetch(airdrop.airdropState)).numRevoked.toNumber()).to.equal(1);
+  });
+
+  it("Fail to revoke an index outside the tree", async () => {
+    await expectError(revokeLeaf(allocations.length), "IndexOutOfRange");
+  });
+
+  it("Fail to claim a revoked leaf", async () => {
+    await expectError(claimChecked(1), "AllocationRevoked");
+  });
+
+  it("Fail to claim without the revocation account while leaves are revoked", async () => {
+    await expectError(claimAirdrop(program, claimants[0], airdrop, 100, 0), "MissingRevocationAccount");
+  });
+
+  it("Claims a leaf that is not revoked", async () => {
+    const signerAta = await claimChecked(0);
+    expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(100);
+  });
+
+  it("Claims a leaf again once it is unrevoked", async () => {
+    const signature = await unrevokeLeaf(1);
+    const event = await findCpiEvent(program, signature, "leafUnrevoked");
+    expect(event.index.toNumber()).to.equal(1);
+    expect(await provider.connection.getAccountInfo(getRevokedLeaf(1))).to.be.null;
+
+    const signerAta = await claimAirdrop(program, claimants[1], airdrop, 100, 1);
+    expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(100);
+  });
+
+  it("Fail to unrevoke a leaf once the tree is finalized", async () => {
+    await revokeLeaf(2);
+    await program.methods.finalizeTree()
+      .accountsPartial({ airdropState: airdrop.airdropState, updateAuthority: authority.publicKey })
+      .rpc();
+
+    await expectError(unrevokeLeaf(2), "TreeFinalized");
+  });
+
+  it("Batch claims the leaves next to a revoked one", async () => {
+    // One claimant listed three times, the middle leaf revoked
+    const claimant = Keypair.generate();
+    await fund(provider, claimant.publicKey);
+    const batchAllocations = [100, 200, 300].map((amount) => ({ address: claimant.publicKey, amount }));
+    airdrop = await initializeAirdrop(program, authority, batchAllocations);
+    await createBitmapShard(program, airdrop.airdropState, 0);
+    await revokeLeaf(1);
+
+    const signerAta = await getAssociatedTokenAddress(airdrop.mint, claimant.publicKey);
+    const claimMany = (indices: number[], withMarkers = true) =>
+      program.methods.claimMany(
+        new anchor.BN(0),
+        indices.map((index) => ({ amount: new anchor.BN(batchAllocations[index].amount), index: new anchor.BN(index), proofLen: getProof(airdrop.tree, index).length / 32 })),
+        Buffer.concat(indices.map((index) => getProof(airdrop.tree, index))),
+        new anchor.BN(0)
+      )
+        .accountsPartial({
+          airdropState: airdrop.airdropState,
+          mint: airdrop.mint,
+          vault: airdrop.vault,
+          signerAta,
+          claimBitmap: getBitmapShard(program.programId, airdrop.airdropState, 0),
+          claimant: claimant.publicKey,
+          payer: claimant.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: airdrop.tokenProgram,
+          associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+        })
+        .remainingAccounts(withMarkers ? indices.map((index) => ({ pubkey: getRevokedLeaf(index), isSigner: false, isWritable: false })) : [])
+        .signers([claimant])
+        .rpc();
+
+    await expectError(claimMany([0, 2], false), "MissingRevocationAccount");
+    await expectError(claimMany([0, 1, 2]), "AllocationRevoked");
+    await claimMany([0, 2]);
+
+    expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(400);
+  });
+});
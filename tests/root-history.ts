@@ -0,0 +1,70 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { getAccount } from "@solana/spl-token";
+import { buildTree, claimAirdrop, createBitmapShard, fund, initializeAirdrop } from "./utils";
+
+describe("root history", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  const claimants = [Keypair.generate(), Keypair.generate()];
+  const allocations = claimants.map((claimant) => ({ address: claimant.publicKey, amount: 100 }));
+
+  const setGracePeriod = (airdropState: PublicKey, graceSlots: number) =>
+    program.methods.setRootGracePeriod(new anchor.BN(graceSlots))
+      .accountsPartial({ airdropState, updateAuthority: authority.publicKey })
+      .rpc();
+
+  const updateTree = (airdropState: PublicKey, root: number[]) =>
+    program.methods.updateTree(root, new anchor.BN(allocations.length))
+      .accountsPartial({ airdropState, updateAuthority: authority.publicKey })
+      .rpc();
+
+  before(async () => {
+    for (const claimant of claimants) {
+      await fund(provider, claimant.publicKey);
+    }
+  });
+
+  it("Accepts a proof against the previous root within the grace period", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations);
+    await createBitmapShard(program, airdrop.airdropState, 0);
+    await setGracePeriod(airdrop.airdropState, 1_000);
+
+    // The proof was fetched for version 0, then the authority republished the tree
+    await updateTree(airdrop.airdropState, Array.from(buildTree([...allocations].reverse()).get_merkle_root()));
+
+    const account = await program.account.airdropState.fetch(airdrop.airdropState);
+    expect(account.rootVersion.toNumber()).to.equal(1);
+    expect(account.rootHistory[0].root).to.deep.equal(Array.from(airdrop.tree.get_merkle_root()));
+    expect(account.rootHistory[0].version.toNumber()).to.equal(0);
+
+    const signerAta = await claimAirdrop(program, claimants[0], airdrop, 100, 0, [], 0);
+    expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(100);
+  });
+
+  it("Fail to claim against a historical root once its grace period ran out", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations);
+    await createBitmapShard(program, airdrop.airdropState, 0);
+    await setGracePeriod(airdrop.airdropState, 1_000);
+    await updateTree(airdrop.airdropState, Array.from(buildTree([...allocations].reverse()).get_merkle_root()));
+
+    // Shrinking the grace period to a single slot expires the replaced root right away
+    await setGracePeriod(airdrop.airdropState, 1);
+    await new Promise((resolve) => setTimeout(resolve, 1_000));
+
+    try {
+      await claimAirdrop(program, claimants[0], airdrop, 100, 0, [], 0);
+      expect.fail("Claim should have failed against an expired root");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Proof was generated against an outdated merkle root");
+    }
+  });
+});
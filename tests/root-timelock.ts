@@ -0,0 +1,112 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { fund, initializeAirdrop } from "./utils";
+
+describe("root timelock", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+  const anyone = Keypair.generate();
+
+  const allocations = Array.from({ length: 2 }, () => ({
+    address: Keypair.generate().publicKey,
+    amount: 100,
+  }));
+  const newRoot = Array(32).fill(9);
+
+  const proposeRoot = (airdropState: PublicKey, root: number[], numLeaves = allocations.length) =>
+    program.methods.proposeRoot(root, new anchor.BN(numLeaves))
+      .accountsPartial({ airdropState, updateAuthority: authority.publicKey })
+      .rpc();
+
+  const activateRoot = (airdropState: PublicKey) =>
+    program.methods.activateRoot()
+      .accountsPartial({ airdropState })
+      .signers([anyone])
+      .rpc();
+
+  before(async () => {
+    await fund(provider, anyone.publicKey);
+  });
+
+  it("Lets anyone activate a proposed root once the delay elapsed", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations, { rootTimelock: 3 });
+    await proposeRoot(airdrop.airdropState, newRoot, 5);
+
+    let account = await program.account.airdropState.fetch(airdrop.airdropState);
+    expect(account.pendingRoot).to.deep.equal(newRoot);
+    expect(account.pendingNumLeaves.toNumber()).to.equal(5);
+    expect(account.numLeaves.toNumber()).to.equal(2);
+    expect(account.merkleRoot).to.deep.equal(Array.from(airdrop.tree.get_merkle_root()));
+
+    await new Promise((resolve) => setTimeout(resolve, 6_000));
+    await program.methods.activateRoot()
+      .accountsPartial({ airdropState: airdrop.airdropState })
+      .rpc();
+
+    account = await program.account.airdropState.fetch(airdrop.airdropState);
+    expect(account.merkleRoot).to.deep.equal(newRoot);
+    expect(account.pendingRoot).to.be.null;
+    expect(account.rootVersion.toNumber()).to.equal(1);
+    expect(account.numLeaves.toNumber()).to.equal(5);
+  });
+
+  it("Fail to activate a proposed root before the delay elapsed", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations, { rootTimelock: 86_400 });
+    await proposeRoot(airdrop.airdropState, newRoot);
+
+    try {
+      await activateRoot(airdrop.airdropState);
+      expect.fail("Activation should have failed before the timelock elapsed");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Root timelock has not elapsed");
+    }
+  });
+
+  it("Cancels a pending root before activation", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations, { rootTimelock: 86_400 });
+    await proposeRoot(airdrop.airdropState, newRoot);
+
+    await program.methods.cancelPendingRoot()
+      .accountsPartial({ airdropState: airdrop.airdropState, updateAuthority: authority.publicKey })
+      .rpc();
+    expect((await program.account.airdropState.fetch(airdrop.airdropState)).pendingRoot).to.be.null;
+
+    try {
+      await activateRoot(airdrop.airdropState);
+      expect.fail("Activation should have failed without a pending root");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("No root is pending activation");
+    }
+  });
+
+  it("Fail to propose a root for an airdrop without a timelock", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations);
+
+    try {
+      await proposeRoot(airdrop.airdropState, newRoot);
+      expect.fail("Proposal should have failed without a timelock");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("RootTimelocked");
+    }
+  });
+
+  it("Fail to update a timelocked root directly", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations, { rootTimelock: 86_400 });
+
+    try {
+      await program.methods.updateTree(newRoot, new anchor.BN(allocations.length))
+        .accountsPartial({ airdropState: airdrop.airdropState, updateAuthority: authority.publicKey })
+        .rpc();
+      expect.fail("Update should not bypass the timelock");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Root changes must be proposed and activated through the timelock");
+    }
+  });
+});
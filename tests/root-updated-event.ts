@@ -0,0 +1,58 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair } from "@solana/web3.js";
+import { claimAirdrop, createBitmapShard, findCpiEvent, fund, initializeAirdrop } from "./utils";
+
+describe("root updated event", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  const claimant = Keypair.generate();
+  const allocations = [
+    { address: claimant.publicKey, amount: 100 },
+    { address: Keypair.generate().publicKey, amount: 100 },
+  ];
+
+  before(async () => {
+    await fund(provider, claimant.publicKey);
+  });
+
+  it("Logs the root lineage across regular and forced updates", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations);
+    await createBitmapShard(program, airdrop.airdropState, 0);
+    const initialRoot = Array.from(airdrop.tree.get_merkle_root());
+    const firstRoot = Array(32).fill(1);
+    const secondRoot = Array(32).fill(2);
+
+    const signatures = [
+      await program.methods.updateTree(firstRoot, new anchor.BN(allocations.length))
+        .accountsPartial({ airdropState: airdrop.airdropState, updateAuthority: authority.publicKey })
+        .rpc(),
+      await program.methods.updateTree(initialRoot, new anchor.BN(allocations.length))
+        .accountsPartial({ airdropState: airdrop.airdropState, updateAuthority: authority.publicKey })
+        .rpc(),
+    ];
+    await claimAirdrop(program, claimant, airdrop, 100, 0);
+    signatures.push(
+      await program.methods.forceUpdateTree(secondRoot, new anchor.BN(allocations.length))
+        .accountsPartial({ airdropState: airdrop.airdropState, updateAuthority: authority.publicKey })
+        .rpc()
+    );
+    const events = await Promise.all(signatures.map((signature) => findCpiEvent(program, signature, "rootUpdated")));
+
+    expect(events.map((event) => event.oldRoot)).to.deep.equal([initialRoot, firstRoot, initialRoot]);
+    expect(events.map((event) => event.newRoot)).to.deep.equal([firstRoot, initialRoot, secondRoot]);
+    expect(events.map((event) => event.rootVersion.toNumber())).to.deep.equal([1, 2, 3]);
+    events.forEach((event) => {
+      expect(event.authority.toString()).to.equal(authority.publicKey.toString());
+      expect(event.slot.toNumber()).to.be.greaterThan(0);
+      expect(event.unixTimestamp.toNumber()).to.be.greaterThan(0);
+    });
+  });
+});
@@ -0,0 +1,58 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { getAccount } from "@solana/spl-token";
+import { buildTree, claimAirdrop, createBitmapShard, fund, initializeAirdrop } from "./utils";
+
+describe("root version", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  const claimants = [Keypair.generate(), Keypair.generate()];
+  const allocations = claimants.map((claimant) => ({ address: claimant.publicKey, amount: 100 }));
+
+  const updateTree = (airdropState: PublicKey, root: number[]) =>
+    program.methods.updateTree(root, new anchor.BN(allocations.length))
+      .accountsPartial({ airdropState, updateAuthority: authority.publicKey })
+      .rpc();
+
+  before(async () => {
+    for (const claimant of claimants) {
+      await fund(provider, claimant.publicKey);
+    }
+  });
+
+  it("Bumps the version on every root update", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations);
+    expect((await program.account.airdropState.fetch(airdrop.airdropState)).rootVersion.toNumber()).to.equal(0);
+
+    await updateTree(airdrop.airdropState, Array(32).fill(1));
+    await updateTree(airdrop.airdropState, Array.from(airdrop.tree.get_merkle_root()));
+    expect((await program.account.airdropState.fetch(airdrop.airdropState)).rootVersion.toNumber()).to.equal(2);
+  });
+
+  it("Rejects a proof for an outdated version as stale and accepts the current one", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations);
+    await createBitmapShard(program, airdrop.airdropState, 0);
+
+    // Republishing the tree in a different order invalidates proofs fetched for version 0
+    const reordered = buildTree([...allocations].reverse());
+    await updateTree(airdrop.airdropState, Array.from(reordered.get_merkle_root()));
+
+    try {
+      await claimAirdrop(program, claimants[0], airdrop, 100, 0, [], 0);
+      expect.fail("Claim should have failed with an outdated root version");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Proof was generated against an outdated merkle root");
+    }
+
+    const signerAta = await claimAirdrop(program, claimants[0], { ...airdrop, tree: reordered }, 100, 1, [], 1);
+    expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(100);
+  });
+});
@@ -0,0 +1,122 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { getAccount, getAssociatedTokenAddress } from "@solana/spl-token";
+import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
+import { Airdrop, buildTree, findCpiEvent, fund, getProof, initializeAirdrop } from "./utils";
+
+describe("rounds", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  // The claimant is in both rounds, at a different index and amount in each
+  const claimant = Keypair.generate();
+  const rounds = [
+    [{ address: claimant.publicKey, amount: 100 }, { address: Keypair.generate().publicKey, amount: 50 }],
+    [{ address: Keypair.generate().publicKey, amount: 70 }, { address: claimant.publicKey, amount: 200 }],
+  ];
+  const trees = rounds.map((allocations) => buildTree(allocations));
+
+  let airdrop: Airdrop;
+
+  const getRoundState = (round: number) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("round"), airdrop.airdropState.toBuffer(), new anchor.BN(round).toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+
+  const getRoundBitmapShard = (round: number, shardIndex: number) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("bitmap"), getRoundState(round).toBuffer(), new anchor.BN(shardIndex).toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+
+  const claimRound = async (round: number, index: number, proof = getProof(trees[round], index)) => {
+    const signerAta = await getAssociatedTokenAddress(airdrop.mint, claimant.publicKey, false, airdrop.tokenProgram);
+    const signature = await program.methods.claimRound(new anchor.BN(rounds[round][index].amount), proof, new anchor.BN(index))
+      .accountsPartial({
+        airdropState: airdrop.airdropState,
+        roundState: getRoundState(round),
+        mint: airdrop.mint,
+        vault: airdrop.vault,
+        signerAta,
+        claimBitmap: getRoundBitmapShard(round, 0),
+        claimant: claimant.publicKey,
+        payer: claimant.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: airdrop.tokenProgram,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .signers([claimant])
+      .rpc();
+    return { signature, signerAta };
+  };
+
+  const expectError = async (claim: Promise<unknown>, code: string) => {
+    try {
+      await claim;
+      expect.fail(`Claim should have failed with ${code}`);
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal(code);
+    }
+  };
+
+  before(async () => {
+    await fund(provider, claimant.publicKey);
+    airdrop = await initializeAirdrop(program, authority, rounds[0], { amount: 500 });
+
+    // Both rounds open right away, the second one closing later
+    const now = Math.floor(Date.now() / 1000);
+    for (const [round, tree] of trees.entries()) {
+      await program.methods.createRound(Array.from(tree.get_merkle_root()), new anchor.BN(rounds[round].length), new anchor.BN(0), new anchor.BN(round === 0 ? 0 : now + 3_600), new anchor.BN(250))
+        .accountsPartial({ airdropState: airdrop.airdropState, roundState: getRoundState(round), updateAuthority: authority.publicKey, systemProgram: SystemProgram.programId })
+        .rpc();
+      await program.methods.createRoundBitmapShard(new anchor.BN(0))
+        .accountsPartial({ roundState: getRoundState(round), claimBitmap: getRoundBitmapShard(round, 0), payer: authority.publicKey, systemProgram: SystemProgram.programId })
+        .rpc();
+    }
+  });
+
+  it("Fail to reserve more for rounds than the airdrop holds", async () => {
+    await expectError(
+      program.methods.createRound(Array(32).fill(0), new anchor.BN(1), new anchor.BN(0), new anchor.BN(0), new anchor.BN(1))
+        .accountsPartial({ airdropState: airdrop.airdropState, roundState: getRoundState(2), updateAuthority: authority.publicKey, systemProgram: SystemProgram.programId })
+        .rpc(),
+      "RoundAllocationExceeded"
+    );
+  });
+
+  it("Fail to claim with a proof of another round", async () => {
+    await expectError(claimRound(1, 1, getProof(trees[0], 0)), "InvalidProof");
+  });
+
+  it("Claims the same wallet in every round, tracked per round", async () => {
+    const first = await claimRound(0, 0);
+    const event = await findCpiEvent(program, first.signature, "claimEvent");
+    expect(event.round.toNumber()).to.equal(0);
+
+    const { signerAta } = await claimRound(1, 1);
+    expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(300);
+
+    const [roundZero, roundOne] = await Promise.all([0, 1].map((round) => program.account.roundState.fetch(getRoundState(round))));
+    expect(roundZero.amountClaimed.toNumber()).to.equal(100);
+    expect(roundOne.amountClaimed.toNumber()).to.equal(200);
+    expect(roundZero.numClaims.toNumber()).to.equal(1);
+    expect(roundOne.numClaims.toNumber()).to.equal(1);
+
+    const state = await program.account.airdropState.fetch(airdrop.airdropState);
+    expect(state.amountClaimed.toNumber()).to.equal(300);
+    expect(state.numClaims.toNumber()).to.equal(2);
+    expect(state.numRounds.toNumber()).to.equal(2);
+  });
+
+  it("Fail to claim twice in the same round", async () => {
+    await expectError(claimRound(0, 0), "AlreadyClaimed");
+  });
+});
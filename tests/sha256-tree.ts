@@ -0,0 +1,50 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair } from "@solana/web3.js";
+import { getAccount } from "@solana/spl-token";
+import { sha256 } from "@noble/hashes/sha256";
+import { claimAirdrop, createBitmapShard, encodeLeaf, fund, getProof, initializeAirdrop } from "./utils";
+
+describe("sha-256 trees", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  // Fixed claimants so the tree is the same on every run, with an odd leaf count
+  const claimants = [1, 2, 3].map((seed) => Keypair.fromSeed(new Uint8Array(32).fill(seed)));
+  const allocations = claimants.map((claimant, i) => ({ address: claimant.publicKey, amount: 1_000 * (i + 1) }));
+
+  before(async () => {
+    for (const claimant of claimants) {
+      await fund(provider, claimant.publicKey);
+    }
+  });
+
+  it("Hashes leaves as SHA-256 over pubkey and little-endian amount", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations, { hashingAlgorithm: 1 });
+
+    for (const [index, entry] of allocations.entries()) {
+      const result = await program.methods.verifyEligibility(entry.address, new anchor.BN(entry.amount), getProof(airdrop.tree, index), new anchor.BN(index), new anchor.BN(0))
+        .accountsPartial({ airdropState: airdrop.airdropState, claimBitmap: null })
+        .view();
+      expect(result.eligible).to.equal(true);
+      expect(result.leafHash).to.deep.equal(Array.from(sha256(encodeLeaf(entry.address, entry.amount))));
+    }
+  });
+
+  it("Claims every leaf of a SHA-256 tree", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations, { hashingAlgorithm: 1 });
+    await createBitmapShard(program, airdrop.airdropState, 0);
+
+    for (const [index, entry] of allocations.entries()) {
+      const signerAta = await claimAirdrop(program, claimants[index], airdrop, entry.amount, index);
+      expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(entry.amount);
+    }
+    expect(Number((await getAccount(provider.connection, airdrop.vault)).amount)).to.equal(0);
+  });
+});
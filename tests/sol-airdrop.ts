@@ -0,0 +1,131 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { airdropParams, buildTree, createBitmapShard, findCpiEvent, getAirdropState, getBitmapShard, getProof } from "./utils";
+
+describe("sol airdrop", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  // Airdrops of native SOL share the same `mint`, the airdrop id tells them apart
+  const NATIVE_SOL = PublicKey.default;
+
+  const claimants = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  // One allocation more than the airdrop is funded with
+  const allocations = claimants.map((claimant, i) => ({ address: claimant.publicKey, amount: (i + 1) * LAMPORTS_PER_SOL }));
+  const tree = buildTree(allocations);
+
+  const now = () => Math.floor(Date.now() / 1000);
+
+  const getSolVault = (airdropState: PublicKey) =>
+    PublicKey.findProgramAddressSync([Buffer.from("sol_vault"), airdropState.toBuffer()], program.programId)[0];
+
+  const initializeSolAirdrop = async (airdropId: number, amount: number, endTime = 0) => {
+    const airdropState = getAirdropState(program.programId, NATIVE_SOL, airdropId);
+    await program.methods.initializeSolAirdrop(
+      airdropParams(tree.get_merkle_root(), allocations.length, { airdropId, endTime }),
+      new anchor.BN(amount),
+      0
+    )
+      .accountsPartial({
+        airdropState,
+        solVault: getSolVault(airdropState),
+        authority: authority.publicKey,
+        payer: authority.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    await createBitmapShard(program, airdropState, 0);
+    return airdropState;
+  };
+
+  const claimSol = (airdropState: PublicKey, index: number) =>
+    program.methods.claimSol(new anchor.BN(allocations[index].amount), getProof(tree, index), new anchor.BN(index), new anchor.BN(0))
+      .accountsPartial({
+        airdropState,
+        solVault: getSolVault(airdropState),
+        claimBitmap: getBitmapShard(program.programId, airdropState, 0),
+        claimant: claimants[index].publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([claimants[index]])
+      .rpc();
+
+  const withdrawUnclaimedSol = (airdropState: PublicKey) =>
+    program.methods.withdrawUnclaimedSol()
+      .accountsPartial({
+        airdropState,
+        solVault: getSolVault(airdropState),
+        withdrawAuthority: authority.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+  const expectError = async (call: Promise<unknown>, code: string) => {
+    try {
+      await call;
+      expect.fail(`Call should have failed with ${code}`);
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal(code);
+    }
+  };
+
+  let airdropState: PublicKey;
+  let rentExemption: number;
+
+  before(async () => {
+    rentExemption = await provider.connection.getMinimumBalanceForRentExemption(0);
+    airdropState = await initializeSolAirdrop(1, 3 * LAMPORTS_PER_SOL);
+  });
+
+  it("Funds the SOL vault on top of its rent exemption", async () => {
+    expect(await provider.connection.getBalance(getSolVault(airdropState))).to.equal(3 * LAMPORTS_PER_SOL + rentExemption);
+
+    const state = await program.account.airdropState.fetch(airdropState);
+    expect(state.mint.toBase58()).to.equal(NATIVE_SOL.toBase58());
+    expect(state.airdropAmount.toNumber()).to.equal(3 * LAMPORTS_PER_SOL);
+  });
+
+  it("Pays a claim in lamports", async () => {
+    const signature = await claimSol(airdropState, 1);
+
+    expect(await provider.connection.getBalance(claimants[1].publicKey)).to.equal(2 * LAMPORTS_PER_SOL);
+    const state = await program.account.airdropState.fetch(airdropState);
+    expect(state.amountClaimed.toNumber()).to.equal(2 * LAMPORTS_PER_SOL);
+    expect(state.numClaims.toNumber()).to.equal(1);
+
+    const event = await findCpiEvent(program, signature, "claimEvent");
+    expect(event.claimer.toBase58()).to.equal(claimants[1].publicKey.toBase58());
+    expect(event.amount.toNumber()).to.equal(2 * LAMPORTS_PER_SOL);
+  });
+
+  it("Fail to claim the same allocation twice", async () => {
+    await expectError(claimSol(airdropState, 1), "AlreadyClaimed");
+  });
+
+  it("Fail to pay a claim out of the rent of the vault", async () => {
+    await claimSol(airdropState, 0);
+
+    await expectError(claimSol(airdropState, 2), "AllocationExceeded");
+    expect(await provider.connection.getBalance(getSolVault(airdropState))).to.equal(rentExemption);
+  });
+
+  it("Fail to withdraw while claims are still open", async () => {
+    await expectError(withdrawUnclaimedSol(airdropState), "ClaimsStillOpen");
+  });
+
+  it("Withdraws the unclaimed lamports after the deadline", async () => {
+    const ended = await initializeSolAirdrop(2, LAMPORTS_PER_SOL, now() - 60);
+
+    await withdrawUnclaimedSol(ended);
+
+    expect(await provider.connection.getBalance(getSolVault(ended))).to.equal(rentExemption);
+    expect((await program.account.airdropState.fetch(ended)).amountWithdrawn.toNumber()).to.equal(LAMPORTS_PER_SOL);
+  });
+});
@@ -0,0 +1,61 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair } from "@solana/web3.js";
+import { getAccount } from "@solana/spl-token";
+import { claimAirdrop, createBitmapShard, fund, initializeAirdrop } from "./utils";
+
+describe("sorted pairs", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  // An odd leaf count exercises the promotion of unpaired nodes
+  const claimants = Array.from({ length: 5 }, () => Keypair.generate());
+  const allocations = claimants.map((claimant, i) => ({ address: claimant.publicKey, amount: 100 * (i + 1) }));
+
+  before(async () => {
+    for (const claimant of claimants) {
+      await fund(provider, claimant.publicKey);
+    }
+  });
+
+  it("Claims every leaf of a sorted-pair tree", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations, { pairOrdering: 1 });
+    await createBitmapShard(program, airdrop.airdropState, 0);
+
+    for (const [index, entry] of allocations.entries()) {
+      const signerAta = await claimAirdrop(program, claimants[index], airdrop, entry.amount, index);
+      expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(entry.amount);
+    }
+  });
+
+  it("Still tracks claims by index", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations, { pairOrdering: 1, leafHashing: 1 });
+    await createBitmapShard(program, airdrop.airdropState, 0);
+    await claimAirdrop(program, claimants[2], airdrop, 300, 2);
+
+    try {
+      await claimAirdrop(program, claimants[2], airdrop, 300, 2);
+      expect.fail("Second claim should have failed for an already claimed index");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Already claimed");
+    }
+  });
+
+  it("Fail to claim a sorted-pair tree with the wrong amount", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations, { pairOrdering: 1 });
+    await createBitmapShard(program, airdrop.airdropState, 0);
+
+    try {
+      await claimAirdrop(program, claimants[0], airdrop, 500, 0);
+      expect.fail("Claim should have failed for the wrong amount");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Invalid Merkle proof");
+    }
+  });
+});
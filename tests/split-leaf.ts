@@ -0,0 +1,104 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair, SystemProgram } from "@solana/web3.js";
+import { getAccount, getAssociatedTokenAddress } from "@solana/spl-token";
+import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
+import { Airdrop, claimAirdrop, createBitmapShard, findCpiEvent, fund, getBitmapShard, getProof, getVestingAccount, getVestingConfig, initializeAirdrop } from "./utils";
+
+describe("split leaf", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  // `amount` is the unlocked part of each allocation
+  const claimants = [Keypair.generate(), Keypair.generate()];
+  const allocations = [
+    { address: claimants[0].publicKey, amount: 300, locked: 700 },
+    { address: claimants[1].publicKey, amount: 0, locked: 500 },
+  ];
+
+  let airdrop: Airdrop;
+
+  const claimSplit = async (index: number, unlocked = allocations[index].amount, locked = allocations[index].locked) => {
+    const claimant = claimants[index];
+    const signerAta = await getAssociatedTokenAddress(airdrop.mint, claimant.publicKey, false, airdrop.tokenProgram);
+    const signature = await program.methods.claimSplitLeaf(new anchor.BN(unlocked), getProof(airdrop.tree, index), new anchor.BN(index), new anchor.BN(0), new anchor.BN(locked))
+      .accountsPartial({
+        airdropState: airdrop.airdropState,
+        mint: airdrop.mint,
+        vault: airdrop.vault,
+        signerAta,
+        claimBitmap: getBitmapShard(program.programId, airdrop.airdropState, 0),
+        vestingConfig: getVestingConfig(program.programId, airdrop.airdropState),
+        vestingAccount: getVestingAccount(program.programId, airdrop.airdropState, index),
+        claimant: claimant.publicKey,
+        payer: claimant.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: airdrop.tokenProgram,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .signers([claimant])
+      .rpc();
+    return { signature, signerAta };
+  };
+
+  const expectError = async (claim: Promise<unknown>, code: string) => {
+    try {
+      await claim;
+      expect.fail(`Claim should have failed with ${code}`);
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal(code);
+    }
+  };
+
+  before(async () => {
+    await Promise.all(claimants.map((claimant) => fund(provider, claimant.publicKey)));
+    airdrop = await initializeAirdrop(program, authority, allocations, { amount: 1_500, leafVersion: 3 });
+    await createBitmapShard(program, airdrop.airdropState, 0);
+
+    // Nothing vests for an hour
+    await program.methods.configureVesting(new anchor.BN(Math.floor(Date.now() / 1000) + 3_600), new anchor.BN(3_600), new anchor.BN(0))
+      .accountsPartial({ airdropState: airdrop.airdropState, vestingConfig: getVestingConfig(program.programId, airdrop.airdropState), updateAuthority: authority.publicKey, systemProgram: SystemProgram.programId })
+      .rpc();
+  });
+
+  it("Fail to claim a split leaf with a single amount", async () => {
+    await expectError(claimAirdrop(program, claimants[0], airdrop, 1_000, 0), "SplitAmountsRequired");
+  });
+
+  it("Fail to claim with the parts of the leaf swapped", async () => {
+    await expectError(claimSplit(0, 700, 300), "InvalidProof");
+  });
+
+  it("Pays the unlocked part and escrows the locked part in one claim", async () => {
+    const { signature, signerAta } = await claimSplit(0);
+
+    const event = await findCpiEvent(program, signature, "claimEvent");
+    expect(event.amount.toNumber()).to.equal(300);
+    expect(event.amountLocked.toNumber()).to.equal(700);
+    expect(event.allocationClaimed.toNumber()).to.equal(1_000);
+    expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(300);
+
+    const vestingAccount = await program.account.vestingAccount.fetch(getVestingAccount(program.programId, airdrop.airdropState, 0));
+    expect(vestingAccount.total.toNumber()).to.equal(700);
+    expect(vestingAccount.released.toNumber()).to.equal(0);
+
+    // Escrowed tokens are only claimed once released out of the vault
+    const state = await program.account.airdropState.fetch(airdrop.airdropState);
+    expect(state.amountClaimed.toNumber()).to.equal(300);
+    expect(Number((await getAccount(provider.connection, airdrop.vault)).amount)).to.equal(1_200);
+  });
+
+  it("Escrows a leaf without an unlocked part", async () => {
+    const { signerAta } = await claimSplit(1);
+
+    expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(0);
+    expect((await program.account.vestingConfig.fetch(getVestingConfig(program.programId, airdrop.airdropState))).amountVesting.toNumber()).to.equal(1_200);
+    expect((await program.account.airdropState.fetch(airdrop.airdropState)).numClaims.toNumber()).to.equal(2);
+  });
+});
@@ -0,0 +1,87 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { getAccount, getAssociatedTokenAddress } from "@solana/spl-token";
+import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
+import { Airdrop, claimAirdrop, createBitmapShard, fund, getBitmapShard, getProof, initializeAirdrop } from "./utils";
+
+describe("sponsored claim", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  // Never funded, the sponsor covers every lamport the claim costs
+  const recipient = Keypair.generate();
+  const allocations = [
+    { address: recipient.publicKey, amount: 100 },
+    { address: Keypair.generate().publicKey, amount: 100 },
+  ];
+
+  let airdrop: Airdrop;
+
+  const claimFor = async (to: PublicKey, amount: number, index: number) => {
+    const recipientAta = await getAssociatedTokenAddress(airdrop.mint, to, false, airdrop.tokenProgram);
+    await program.methods.claimFor(new anchor.BN(amount), getProof(airdrop.tree, index), new anchor.BN(index), new anchor.BN(0))
+      .accountsPartial({
+        airdropState: airdrop.airdropState,
+        mint: airdrop.mint,
+        vault: airdrop.vault,
+        recipient: to,
+        recipientAta,
+        claimBitmap: getBitmapShard(program.programId, airdrop.airdropState, 0),
+        payer: wallet.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: airdrop.tokenProgram,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .rpc();
+    return recipientAta;
+  };
+
+  before(async () => {
+    airdrop = await initializeAirdrop(program, authority, allocations);
+    await createBitmapShard(program, airdrop.airdropState, 0);
+  });
+
+  it("Fail to redirect an allocation to another recipient", async () => {
+    try {
+      await claimFor(wallet.publicKey, 100, 0);
+      expect.fail("Claim should have failed for a recipient the leaf does not commit to");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("InvalidProof");
+    }
+  });
+
+  it("Claims on behalf of a recipient without SOL", async () => {
+    const recipientAta = await claimFor(recipient.publicKey, 100, 0);
+
+    const account = await getAccount(provider.connection, recipientAta, undefined, airdrop.tokenProgram);
+    expect(Number(account.amount)).to.equal(100);
+    expect(account.owner.toBase58()).to.equal(recipient.publicKey.toBase58());
+    expect(await provider.connection.getBalance(recipient.publicKey)).to.equal(0);
+  });
+
+  it("Fail to claim a sponsored allocation again", async () => {
+    try {
+      await claimFor(recipient.publicKey, 100, 0);
+      expect.fail("Sponsored claim should only go through once");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("AlreadyClaimed");
+    }
+  });
+
+  it("Fail for the recipient to claim a sponsored allocation themselves", async () => {
+    await fund(provider, recipient.publicKey);
+    try {
+      await claimAirdrop(program, recipient, airdrop, 100, 0);
+      expect.fail("Claim should have failed for an allocation already claimed on the recipient's behalf");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("AlreadyClaimed");
+    }
+  });
+});
@@ -0,0 +1,82 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { getAccount, getOrCreateAssociatedTokenAccount, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { Airdrop, buildTree, claimAirdrop, createBitmapShard, fund, getAirdropState } from "./utils";
+
+describe("state migration", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+
+  // Loaded by the validator from tests/fixtures, a mint-on-claim airdrop of 1_000 tokens
+  // written in the layout from before state versioning
+  const authority = Keypair.fromSeed(Buffer.alloc(32, 0x70));
+  const mint = new PublicKey("GH5vcstyxna4n8v4cVXRqBcaJx1vQC9YJiV7hR6aPZ3x");
+  const airdropState = getAirdropState(program.programId, mint);
+
+  const claimant = Keypair.generate();
+  const tree = buildTree([{ address: claimant.publicKey, amount: 300 }]);
+
+  let airdrop: Airdrop;
+
+  const migrate = () =>
+    program.methods.migrateState()
+      .accountsPartial({ airdropState, updateAuthority: authority.publicKey, payer: wallet.publicKey, systemProgram: SystemProgram.programId })
+      .signers([authority])
+      .rpc();
+
+  before(async () => {
+    await fund(provider, authority.publicKey);
+    await fund(provider, claimant.publicKey);
+    const vault = await getOrCreateAssociatedTokenAccount(provider.connection, wallet.payer, mint, airdropState, true);
+    airdrop = { mint, airdropState, vault: vault.address, tree, tokenProgram: TOKEN_PROGRAM_ID };
+  });
+
+  it("Fail to read the airdrop state before it is migrated", async () => {
+    expect(airdropState.toBase58()).to.equal("6rvq9yXUDj9nW1YwePRunY78uim6TU3ESiSZhp4vjNxm");
+    try {
+      await program.methods.setPaused(false)
+        .accountsPartial({ airdropState, authority: authority.publicKey })
+        .signers([authority])
+        .rpc();
+      expect.fail("Update should have failed on the old layout");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("AccountDidNotDeserialize");
+    }
+  });
+
+  it("Migrates the airdrop state and claims from it", async () => {
+    await migrate();
+
+    const state = await program.account.airdropState.fetch(airdropState);
+    expect(state.version).to.equal(3);
+    expect(state.feeBps).to.equal(0);
+    expect(state.updateAuthority.equals(authority.publicKey)).to.equal(true);
+    expect(state.withdrawAuthority.equals(authority.publicKey)).to.equal(true);
+    expect(state.airdropAmount.toNumber()).to.equal(1_000);
+    expect(state.mintOnClaim).to.equal(true);
+
+    await program.methods.updateTree(Array.from(tree.get_merkle_root()), new anchor.BN(1))
+      .accountsPartial({ airdropState, updateAuthority: authority.publicKey })
+      .signers([authority])
+      .rpc();
+    await createBitmapShard(program, airdropState, 0);
+    const signerAta = await claimAirdrop(program, claimant, airdrop, 300, 0);
+
+    expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(300);
+  });
+
+  it("Fail to migrate an airdrop state already in the current layout", async () => {
+    try {
+      await migrate();
+      expect.fail("Migration should have failed on the current layout");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("UnsupportedStateVersion");
+    }
+  });
+});
@@ -0,0 +1,110 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { createMint, getAccount, getAssociatedTokenAddress, getOrCreateAssociatedTokenAccount, mintTo, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
+import { Airdrop, claimAirdrop, createBitmapShard, fund, getBitmapShard, getProof, initializeAirdrop } from "./utils";
+
+describe("ticket gate", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  // One ticket per claim, the second claimant never got one
+  const claimants = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  const tickets = [1, 0, 1];
+  const allocations = claimants.map((claimant) => ({ address: claimant.publicKey, amount: 100 }));
+
+  let airdrop: Airdrop;
+  let ticketMint: PublicKey;
+  const ticketAtas: PublicKey[] = [];
+
+  const setTicketGate = (ticketCost: number) =>
+    program.methods.setTicketGate(ticketMint, new anchor.BN(ticketCost))
+      .accountsPartial({ airdropState: airdrop.airdropState, updateAuthority: authority.publicKey })
+      .rpc();
+
+  const claimWithTicket = async (index: number, amount = allocations[index].amount) => {
+    const claimant = claimants[index];
+    const signerAta = await getAssociatedTokenAddress(airdrop.mint, claimant.publicKey);
+    await program.methods.claimAirdrop(new anchor.BN(amount), getProof(airdrop.tree, index), new anchor.BN(index), new anchor.BN(0), null, false)
+      .accountsPartial({
+        airdropState: airdrop.airdropState,
+        mint: airdrop.mint,
+        vault: airdrop.vault,
+        signerAta,
+        claimBitmap: getBitmapShard(program.programId, airdrop.airdropState, 0),
+        ticketMint,
+        ticketAta: ticketAtas[index],
+        ticketTokenProgram: TOKEN_PROGRAM_ID,
+        claimant: claimant.publicKey,
+        payer: claimant.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: airdrop.tokenProgram,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .signers([claimant])
+      .rpc();
+    return signerAta;
+  };
+
+  const ticketBalance = async (index: number) => Number((await getAccount(provider.connection, ticketAtas[index])).amount);
+
+  const expectError = async (call: Promise<unknown>, code: string) => {
+    try {
+      await call;
+      expect.fail(`Call should have failed with ${code}`);
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal(code);
+    }
+  };
+
+  before(async () => {
+    await Promise.all(claimants.map((claimant) => fund(provider, claimant.publicKey)));
+    ticketMint = await createMint(provider.connection, authority, authority.publicKey, null, 0);
+    for (const [index, claimant] of claimants.entries()) {
+      const ata = await getOrCreateAssociatedTokenAccount(provider.connection, authority, ticketMint, claimant.publicKey);
+      if (tickets[index] > 0) {
+        await mintTo(provider.connection, authority, ticketMint, ata.address, authority, tickets[index]);
+      }
+      ticketAtas.push(ata.address);
+    }
+
+    airdrop = await initializeAirdrop(program, authority, allocations);
+    await createBitmapShard(program, airdrop.airdropState, 0);
+  });
+
+  it("Fail to gate on tickets without a ticket cost", async () => {
+    await expectError(setTicketGate(0), "InvalidTicketCost");
+  });
+
+  it("Fail to claim without the ticket accounts", async () => {
+    await setTicketGate(1);
+    await expectError(claimAirdrop(program, claimants[0], airdrop, 100, 0), "MissingTicketAccount");
+  });
+
+  it("Fail to claim without enough tickets", async () => {
+    await expectError(claimWithTicket(1), "InsufficientTickets");
+  });
+
+  it("Keeps the ticket of a claim that fails after the burn", async () => {
+    await expectError(claimWithTicket(2, 99), "InvalidProof");
+    expect(await ticketBalance(2)).to.equal(1);
+  });
+
+  it("Burns a ticket and pays the claim", async () => {
+    const signerAta = await claimWithTicket(0);
+
+    expect(await ticketBalance(0)).to.equal(0);
+    expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(100);
+  });
+
+  it("Fail to change the ticket gate once claims started", async () => {
+    await expectError(setTicketGate(2), "GateLocked");
+  });
+});
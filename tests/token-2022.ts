@@ -0,0 +1,36 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair } from "@solana/web3.js";
+import { getAccount, getMint, TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
+import { claimAirdrop, createBitmapShard, fund, initializeAirdrop } from "./utils";
+
+describe("token-2022", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  it("Initialize and claim an airdrop of a Token-2022 mint", async () => {
+    const claimant = Keypair.generate();
+    await fund(provider, claimant.publicKey);
+
+    const allocations = [
+      { address: claimant.publicKey, amount: 420 },
+      { address: Keypair.generate().publicKey, amount: 80 },
+    ];
+    const airdrop = await initializeAirdrop(program, authority, allocations, { tokenProgram: TOKEN_2022_PROGRAM_ID });
+    await createBitmapShard(program, airdrop.airdropState, 0);
+
+    const mintAccount = await getMint(provider.connection, airdrop.mint, undefined, TOKEN_2022_PROGRAM_ID);
+    expect(mintAccount.mintAuthority).to.equal(null);
+    expect(Number((await getAccount(provider.connection, airdrop.vault, undefined, TOKEN_2022_PROGRAM_ID)).amount)).to.equal(500);
+
+    const signerAta = await claimAirdrop(program, claimant, airdrop, 420, 0);
+    expect(Number((await getAccount(provider.connection, signerAta, undefined, TOKEN_2022_PROGRAM_ID)).amount)).to.equal(420);
+    expect(Number((await getAccount(provider.connection, airdrop.vault, undefined, TOKEN_2022_PROGRAM_ID)).amount)).to.equal(80);
+  });
+});
@@ -0,0 +1,96 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { getAccount, getAssociatedTokenAddress, getTokenMetadata, TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
+import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
+import { airdropParams, buildTree, getAirdropState, payoutParams } from "./utils";
+
+describe("token-2022 metadata", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  const allocations = Array.from({ length: 4 }, () => ({
+    address: Keypair.generate().publicKey,
+    amount: 250,
+  }));
+  const tree = buildTree(allocations);
+
+  const initializeWithMetadata = async (mint: Keypair, metadata: { name: string; symbol: string; uri: string; keepUpdateAuthority: boolean }) => {
+    const airdropState = getAirdropState(program.programId, mint.publicKey);
+    const vault = await getAssociatedTokenAddress(mint.publicKey, airdropState, true, TOKEN_2022_PROGRAM_ID);
+
+    await program.methods.initializeWithMetadata(
+      airdropParams(tree.get_merkle_root(), allocations.length),
+      payoutParams(1_000),
+      6,
+      { revoke: {} },
+      false,
+      metadata
+    )
+      .accountsPartial({
+        airdropState,
+        mint: mint.publicKey,
+        vault,
+        authority: authority.publicKey,
+        payer: authority.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .signers([authority, mint])
+      .rpc();
+
+    return vault;
+  };
+
+  it("Creates the mint with name, symbol and uri before funding the vault", async () => {
+    const mint = Keypair.generate();
+    const vault = await initializeWithMetadata(mint, {
+      name: "Airdrop Token",
+      symbol: "DROP",
+      uri: "https://example.com/drop.json",
+      keepUpdateAuthority: true,
+    });
+
+    const metadata = await getTokenMetadata(provider.connection, mint.publicKey);
+    expect(metadata.name).to.equal("Airdrop Token");
+    expect(metadata.symbol).to.equal("DROP");
+    expect(metadata.uri).to.equal("https://example.com/drop.json");
+    expect(metadata.updateAuthority.toString()).to.equal(authority.publicKey.toString());
+
+    expect(Number((await getAccount(provider.connection, vault, undefined, TOKEN_2022_PROGRAM_ID)).amount)).to.equal(1_000);
+  });
+
+  it("Freezes the metadata without an update authority", async () => {
+    const mint = Keypair.generate();
+    await initializeWithMetadata(mint, {
+      name: "Frozen Token",
+      symbol: "FRZN",
+      uri: "https://example.com/frozen.json",
+      keepUpdateAuthority: false,
+    });
+
+    const metadata = await getTokenMetadata(provider.connection, mint.publicKey);
+    expect(metadata.updateAuthority).to.equal(undefined);
+  });
+
+  it("Fail to initialize with a name over the length limit", async () => {
+    try {
+      await initializeWithMetadata(Keypair.generate(), {
+        name: "x".repeat(33),
+        symbol: "DROP",
+        uri: "https://example.com/drop.json",
+        keepUpdateAuthority: true,
+      });
+      expect.fail("Initialization should have failed with a 33 character name");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Token metadata field too long");
+    }
+  });
+});
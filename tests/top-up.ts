@@ -0,0 +1,99 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { createMint, getAccount, getOrCreateAssociatedTokenAccount, mintTo } from "@solana/spl-token";
+import { Airdrop, initializeAirdrop, initializeWithExistingMint } from "./utils";
+
+describe("top up", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  const allocations = Array.from({ length: 4 }, () => ({
+    address: Keypair.generate().publicKey,
+    amount: 100,
+  }));
+
+  const topUp = (airdrop: Airdrop, authorityAta: PublicKey, amount: number) =>
+    program.methods.topUp(new anchor.BN(amount))
+      .accountsPartial({
+        airdropState: airdrop.airdropState,
+        mint: airdrop.mint,
+        vault: airdrop.vault,
+        authorityAta,
+        updateAuthority: authority.publicKey,
+        tokenProgram: airdrop.tokenProgram,
+      })
+      .rpc();
+
+  it("Tops up a program-created mint from the creator's tokens", async () => {
+    // Only 300 of the 400 allocated tokens are minted into the vault up front
+    const airdrop = await initializeAirdrop(program, authority, allocations, {
+      authorityMode: { keepWithCreator: {} },
+      amount: 300,
+    });
+    const authorityAta = await getOrCreateAssociatedTokenAccount(provider.connection, authority, airdrop.mint, authority.publicKey);
+    await mintTo(provider.connection, authority, airdrop.mint, authorityAta.address, authority, 100);
+
+    let event: any;
+    const listener = program.addEventListener("toppedUp", (emitted) => {
+      event = emitted;
+    });
+    await topUp(airdrop, authorityAta.address, 100);
+    await new Promise((resolve) => setTimeout(resolve, 1_000));
+    await program.removeEventListener(listener);
+
+    expect((await program.account.airdropState.fetch(airdrop.airdropState)).airdropAmount.toNumber()).to.equal(400);
+    expect(Number((await getAccount(provider.connection, airdrop.vault)).amount)).to.equal(400);
+    expect(event.amount.toNumber()).to.equal(100);
+    expect(event.vaultBalance.toNumber()).to.equal(400);
+  });
+
+  it("Tops up an airdrop of an existing mint", async () => {
+    const mint = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+    const authorityAta = await getOrCreateAssociatedTokenAccount(provider.connection, authority, mint, authority.publicKey);
+    await mintTo(provider.connection, authority, mint, authorityAta.address, authority, 1_000);
+
+    const airdrop = await initializeWithExistingMint(program, authority, mint, authorityAta.address, allocations, 300);
+    await topUp(airdrop, authorityAta.address, 100);
+
+    expect((await program.account.airdropState.fetch(airdrop.airdropState)).airdropAmount.toNumber()).to.equal(400);
+    expect(Number((await getAccount(provider.connection, airdrop.vault)).amount)).to.equal(400);
+    expect(Number((await getAccount(provider.connection, authorityAta.address)).amount)).to.equal(600);
+  });
+
+  it("Fail to top up more than the authority holds", async () => {
+    const mint = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+    const authorityAta = await getOrCreateAssociatedTokenAccount(provider.connection, authority, mint, authority.publicKey);
+    await mintTo(provider.connection, authority, mint, authorityAta.address, authority, 500);
+
+    const airdrop = await initializeWithExistingMint(program, authority, mint, authorityAta.address, allocations, 400);
+
+    try {
+      await topUp(airdrop, authorityAta.address, 200);
+      expect.fail("Top up should have failed without enough tokens");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Insufficient funds to fund the airdrop");
+    }
+  });
+
+  it("Fail to top up nothing", async () => {
+    const mint = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+    const authorityAta = await getOrCreateAssociatedTokenAccount(provider.connection, authority, mint, authority.publicKey);
+    await mintTo(provider.connection, authority, mint, authorityAta.address, authority, 400);
+
+    const airdrop = await initializeWithExistingMint(program, authority, mint, authorityAta.address, allocations, 400);
+
+    try {
+      await topUp(airdrop, authorityAta.address, 0);
+      expect.fail("Top up should have failed without an amount");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("InvalidAmount");
+    }
+  });
+});
@@ -0,0 +1,99 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair, PublicKey, SystemProgram, Transaction, sendAndConfirmTransaction } from "@solana/web3.js";
+import {
+  createInitializeMintInstruction,
+  createInitializeTransferFeeConfigInstruction,
+  ExtensionType,
+  getAccount,
+  getMintLen,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+  TOKEN_2022_PROGRAM_ID,
+} from "@solana/spl-token";
+import { claimAirdrop, createBitmapShard, fund, initializeWithExistingMint } from "./utils";
+
+describe("transfer-fee mints", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  const createTransferFeeMint = async (feeBasisPoints: number, maxFee: bigint) => {
+    const mint = Keypair.generate();
+    const mintLen = getMintLen([ExtensionType.TransferFeeConfig]);
+    const lamports = await provider.connection.getMinimumBalanceForRentExemption(mintLen);
+
+    await sendAndConfirmTransaction(
+      provider.connection,
+      new Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: authority.publicKey,
+          newAccountPubkey: mint.publicKey,
+          space: mintLen,
+          lamports,
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeTransferFeeConfigInstruction(mint.publicKey, authority.publicKey, authority.publicKey, feeBasisPoints, maxFee, TOKEN_2022_PROGRAM_ID),
+        createInitializeMintInstruction(mint.publicKey, 6, authority.publicKey, null, TOKEN_2022_PROGRAM_ID)
+      ),
+      [authority, mint]
+    );
+
+    const authorityAta = await getOrCreateAssociatedTokenAccount(provider.connection, authority, mint.publicKey, authority.publicKey, false, undefined, undefined, TOKEN_2022_PROGRAM_ID);
+    await mintTo(provider.connection, authority, mint.publicKey, authorityAta.address, authority, 10_000_000, [], undefined, TOKEN_2022_PROGRAM_ID);
+
+    return { mint: mint.publicKey, authorityAta: authorityAta.address };
+  };
+
+  it("Claimant nets the leaf amount from a transfer-fee mint", async () => {
+    const claimant = Keypair.generate();
+    await fund(provider, claimant.publicKey);
+
+    // 2.5% fee capped at 5_000 raw units
+    const { mint, authorityAta } = await createTransferFeeMint(250, BigInt(5_000));
+    const allocations = [
+      { address: claimant.publicKey, amount: 100_000 },
+      { address: Keypair.generate().publicKey, amount: 40_000 },
+    ];
+    const airdrop = await initializeWithExistingMint(program, authority, mint, authorityAta, allocations, 1_000_000, TOKEN_2022_PROGRAM_ID);
+    await createBitmapShard(program, airdrop.airdropState, 0);
+
+    const vaultBefore = Number((await getAccount(provider.connection, airdrop.vault, undefined, TOKEN_2022_PROGRAM_ID)).amount);
+    const signerAta = await claimAirdrop(program, claimant, airdrop, 100_000, 0);
+
+    const signerAccount = await getAccount(provider.connection, signerAta, undefined, TOKEN_2022_PROGRAM_ID);
+    expect(Number(signerAccount.amount)).to.equal(100_000);
+
+    // The vault paid the gross amount and it is what the airdrop accounts as claimed
+    const vaultAfter = Number((await getAccount(provider.connection, airdrop.vault, undefined, TOKEN_2022_PROGRAM_ID)).amount);
+    const account = await program.account.airdropState.fetch(airdrop.airdropState);
+    expect(vaultBefore - vaultAfter).to.be.greaterThan(100_000);
+    expect(account.amountClaimed.toNumber()).to.equal(vaultBefore - vaultAfter);
+  });
+
+  it("Fail to claim when the fee makes the net amount unreachable", async () => {
+    const claimant = Keypair.generate();
+    await fund(provider, claimant.publicKey);
+
+    // A 100% uncapped fee never lets the recipient net anything
+    const { mint, authorityAta } = await createTransferFeeMint(10_000, BigInt("18446744073709551615"));
+    const allocations = [
+      { address: claimant.publicKey, amount: 100 },
+      { address: Keypair.generate().publicKey, amount: 100 },
+    ];
+    const airdrop = await initializeWithExistingMint(program, authority, mint, authorityAta, allocations, 1_000, TOKEN_2022_PROGRAM_ID);
+    await createBitmapShard(program, airdrop.airdropState, 0);
+
+    try {
+      await claimAirdrop(program, claimant, airdrop, 100, 0);
+      expect.fail("Claim should have failed with an unreachable net amount");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Transfer fee makes the exact claim amount unreachable");
+    }
+  });
+});
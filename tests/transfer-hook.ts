@@ -0,0 +1,120 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { TransferHookCounter } from "../target/types/transfer_hook_counter";
+import { expect } from "chai";
+import { Keypair, PublicKey, SystemProgram, Transaction, sendAndConfirmTransaction } from "@solana/web3.js";
+import {
+  createInitializeMintInstruction,
+  createInitializeTransferHookInstruction,
+  ExtensionType,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  getMintLen,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+  TOKEN_2022_PROGRAM_ID,
+} from "@solana/spl-token";
+import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
+import { Airdrop, claimAirdrop, createBitmapShard, fund, getBitmapShard, getProof, initializeWithExistingMint } from "./utils";
+
+describe("transfer-hook mints", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const hookProgram = anchor.workspace.TransferHookCounter as Program<TransferHookCounter>;
+  const authority = wallet.payer;
+
+  let airdrop: Airdrop;
+  let allocations: { address: PublicKey; amount: number }[];
+
+  it("Claim forwards the hook's extra accounts to the transfer", async () => {
+    const mint = Keypair.generate();
+    const mintLen = getMintLen([ExtensionType.TransferHook]);
+    const lamports = await provider.connection.getMinimumBalanceForRentExemption(mintLen);
+
+    await sendAndConfirmTransaction(
+      provider.connection,
+      new Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: authority.publicKey,
+          newAccountPubkey: mint.publicKey,
+          space: mintLen,
+          lamports,
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeTransferHookInstruction(mint.publicKey, authority.publicKey, hookProgram.programId, TOKEN_2022_PROGRAM_ID),
+        createInitializeMintInstruction(mint.publicKey, 6, authority.publicKey, null, TOKEN_2022_PROGRAM_ID)
+      ),
+      [authority, mint]
+    );
+
+    const extraAccountMetaList = PublicKey.findProgramAddressSync([Buffer.from("extra-account-metas"), mint.publicKey.toBuffer()], hookProgram.programId)[0];
+    const counter = PublicKey.findProgramAddressSync([Buffer.from("counter"), mint.publicKey.toBuffer()], hookProgram.programId)[0];
+
+    await hookProgram.methods.initializeExtraAccountMetaList()
+      .accountsPartial({
+        extraAccountMetaList,
+        mint: mint.publicKey,
+        counter,
+        payer: authority.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([authority])
+      .rpc();
+
+    const authorityAta = await getOrCreateAssociatedTokenAccount(provider.connection, authority, mint.publicKey, authority.publicKey, false, undefined, undefined, TOKEN_2022_PROGRAM_ID);
+    await mintTo(provider.connection, authority, mint.publicKey, authorityAta.address, authority, 1_000, [], undefined, TOKEN_2022_PROGRAM_ID);
+
+    const hookAccounts = [
+      { pubkey: extraAccountMetaList, isSigner: false, isWritable: false },
+      { pubkey: counter, isSigner: false, isWritable: true },
+      { pubkey: hookProgram.programId, isSigner: false, isWritable: false },
+    ];
+
+    const claimant = Keypair.generate();
+    await fund(provider, claimant.publicKey);
+    allocations = [
+      { address: claimant.publicKey, amount: 600 },
+      { address: Keypair.generate().publicKey, amount: 400 },
+    ];
+
+    // Funding the vault is a hooked transfer as well
+    airdrop = await initializeWithExistingMint(program, authority, mint.publicKey, authorityAta.address, allocations, 1_000, TOKEN_2022_PROGRAM_ID, hookAccounts);
+    expect((await hookProgram.account.counter.fetch(counter)).count.toNumber()).to.equal(1);
+
+    await createBitmapShard(program, airdrop.airdropState, 0);
+    const signerAta = await claimAirdrop(program, claimant, airdrop, 600, 0, hookAccounts);
+
+    expect(Number((await getAccount(provider.connection, signerAta, undefined, TOKEN_2022_PROGRAM_ID)).amount)).to.equal(600);
+    expect((await hookProgram.account.counter.fetch(counter)).count.toNumber()).to.equal(2);
+  });
+
+  it("Fail to push claims of a hooked mint before paying any of them", async () => {
+    const recipient = allocations[1].address;
+    try {
+      await program.methods.claimForMany(new anchor.BN(0), [{ recipient, amount: new anchor.BN(400), index: new anchor.BN(1), proofLen: getProof(airdrop.tree, 1).length / 32 }], getProof(airdrop.tree, 1), new anchor.BN(0))
+        .accountsPartial({
+          airdropState: airdrop.airdropState,
+          mint: airdrop.mint,
+          vault: airdrop.vault,
+          claimBitmap: getBitmapShard(program.programId, airdrop.airdropState, 0),
+          updateAuthority: authority.publicKey,
+          payer: authority.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+        })
+        .remainingAccounts([
+          { pubkey: recipient, isSigner: false, isWritable: false },
+          { pubkey: getAssociatedTokenAddressSync(airdrop.mint, recipient, false, TOKEN_2022_PROGRAM_ID), isSigner: false, isWritable: true },
+        ])
+        .rpc();
+      expect.fail("Push should have failed for a mint with a transfer hook");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("TransferHookUnsupported");
+    }
+  });
+});
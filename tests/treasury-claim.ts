@@ -0,0 +1,79 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { TreasuryClaimer } from "../target/types/treasury_claimer";
+import { expect } from "chai";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { getAccount, getAssociatedTokenAddress, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { Airdrop, claimAirdrop, createBitmapShard, fund, getBitmapShard, getProof, initializeAirdrop } from "./utils";
+
+describe("treasury claim", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const treasuryClaimer = anchor.workspace.TreasuryClaimer as Program<TreasuryClaimer>;
+  const authority = wallet.payer;
+
+  // Off curve, so it can only ever sign through its program
+  const treasury = PublicKey.findProgramAddressSync([Buffer.from("treasury")], treasuryClaimer.programId)[0];
+  const member = Keypair.generate();
+  const allocations = [
+    { address: treasury, amount: 1_000 },
+    { address: member.publicKey, amount: 100 },
+  ];
+
+  let airdrop: Airdrop;
+
+  const claimThroughTreasury = async (amount: number, index: number) => {
+    const treasuryAta = await getAssociatedTokenAddress(airdrop.mint, treasury, true);
+    await treasuryClaimer.methods.claim(new anchor.BN(amount), getProof(airdrop.tree, index), new anchor.BN(index), new anchor.BN(0))
+      .accountsPartial({
+        airdropState: airdrop.airdropState,
+        mint: airdrop.mint,
+        vault: airdrop.vault,
+        treasury,
+        treasuryAta,
+        claimBitmap: getBitmapShard(program.programId, airdrop.airdropState, 0),
+        payer: wallet.publicKey,
+        eventAuthority: PublicKey.findProgramAddressSync([Buffer.from("__event_authority")], program.programId)[0],
+        claimerProgram: program.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    return treasuryAta;
+  };
+
+  before(async () => {
+    airdrop = await initializeAirdrop(program, authority, allocations);
+    await createBitmapShard(program, airdrop.airdropState, 0);
+    await fund(provider, member.publicKey);
+  });
+
+  it("Fail for the treasury program to claim an allocation of someone else", async () => {
+    try {
+      await claimThroughTreasury(100, 1);
+      expect.fail("Claim should have failed for an allocation not committed to the treasury");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("InvalidProof");
+    }
+  });
+
+  it("Fail to take the treasury's allocation with another signer", async () => {
+    try {
+      await claimAirdrop(program, member, airdrop, 1_000, 0);
+      expect.fail("Claim should have failed for a signer other than the treasury");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("InvalidProof");
+    }
+  });
+
+  it("Claims the treasury's allocation through its program", async () => {
+    const treasuryAta = await claimThroughTreasury(1_000, 0);
+
+    const account = await getAccount(provider.connection, treasuryAta);
+    expect(Number(account.amount)).to.equal(1_000);
+    expect(account.owner.toBase58()).to.equal(treasury.toBase58());
+  });
+});
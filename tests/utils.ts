@@ -0,0 +1,558 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { AccountMeta, Keypair, PublicKey, SystemProgram, LAMPORTS_PER_SOL, SYSVAR_RENT_PUBKEY, Transaction } from "@solana/web3.js";
+import { getAssociatedTokenAddress, getOrCreateAssociatedTokenAccount, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { HashingAlgorithm, MerkleTree } from "svm-merkle-tree";
+import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
+import { keccak_256 } from "@noble/hashes/sha3";
+import { sha256 } from "@noble/hashes/sha256";
+
+export const LEAVES_PER_SHARD = 8 * 1024;
+
+export interface Allocation {
+  address: PublicKey;
+  amount: number;
+  // Hash of the extra allocation data committed to with LEAF_VERSION_COMMITTED
+  dataHash?: Buffer;
+  // Locked part of a LEAF_VERSION_SPLIT allocation, `amount` being the unlocked part
+  locked?: number;
+  // Unix timestamp a LEAF_VERSION_DEADLINE allocation expires after
+  deadline?: number;
+  // Amount of each LEAF_VERSION_BASKET mint in the order of the basket, `amount` being the airdrop mint's
+  basket?: number[];
+  // Mint a LEAF_VERSION_NFT allocation pays the one token of
+  nftMint?: PublicKey;
+}
+
+// Tree configuration, the program's HASHING_ALGORITHM_*, LEAF_HASHING_*, PAIR_ORDERING_* and LEAF_VERSION_* discriminants
+export interface TreeOptions {
+  hashingAlgorithm?: number;
+  leafHashing?: number;
+  pairOrdering?: number;
+  leafVersion?: number;
+}
+
+// Integers are little-endian, LEAF_VERSION_INDEX_CLAIMANT_AMOUNT prefixes the leaf index
+export const encodeLeaf = (address: PublicKey, amount: number, leafVersion = 0, index = 0) =>
+  Buffer.concat([
+    ...(leafVersion === 1 ? [new anchor.BN(index).toArrayLike(Buffer, "le", 8)] : []),
+    address.toBuffer(),
+    Buffer.from(new Uint8Array(new anchor.BN(amount).toArray("le", 8))),
+  ]);
+
+// Indexed by the program's HASHING_ALGORITHM_* discriminants
+export const HASHING_ALGORITHMS = [HashingAlgorithm.Keccak, HashingAlgorithm.Sha256];
+const LEAF_HASHES = [keccak_256, sha256];
+
+// LEAF_VERSION_COMMITTED leaf, hash(hash(claimant ‖ amount) ‖ dataHash)
+export const committedLeaf = (address: PublicKey, amount: number, dataHash: Buffer, hashingAlgorithm = 0) => {
+  const hash = LEAF_HASHES[hashingAlgorithm];
+  return Buffer.from(hash(Buffer.concat([hash(encodeLeaf(address, amount)), dataHash])));
+};
+
+// LEAF_VERSION_SPLIT leaf, claimant ‖ unlocked ‖ locked
+export const splitLeaf = (address: PublicKey, unlocked: number, locked: number) =>
+  Buffer.concat([
+    address.toBuffer(),
+    new anchor.BN(unlocked).toArrayLike(Buffer, "le", 8),
+    new anchor.BN(locked).toArrayLike(Buffer, "le", 8),
+  ]);
+
+// LEAF_VERSION_DEADLINE leaf, claimant ‖ amount ‖ deadline
+export const deadlineLeaf = (address: PublicKey, amount: number, deadline: number) =>
+  Buffer.concat([
+    address.toBuffer(),
+    new anchor.BN(amount).toArrayLike(Buffer, "le", 8),
+    new anchor.BN(deadline).toTwos(64).toArrayLike(Buffer, "le", 8),
+  ]);
+
+// LEAF_VERSION_BASKET leaf, claimant ‖ amount ‖ basket amounts
+export const basketLeaf = (address: PublicKey, amount: number, basket: number[]) =>
+  Buffer.concat([
+    address.toBuffer(),
+    ...[amount, ...basket].map((value) => new anchor.BN(value).toArrayLike(Buffer, "le", 8)),
+  ]);
+
+// LEAF_VERSION_NFT leaf, claimant ‖ nft mint
+export const nftLeaf = (address: PublicKey, nftMint: PublicKey) => Buffer.concat([address.toBuffer(), nftMint.toBuffer()]);
+
+// Leaf as it is fed into the tree, hashed once more with LEAF_HASHING_DOUBLE
+export const prepareLeaf = (leaf: Buffer, hashingAlgorithm = 0, leafHashing = 0) =>
+  leafHashing === 1 ? Buffer.from(LEAF_HASHES[hashingAlgorithm](leaf)) : leaf;
+
+// Unknown discriminants fall back to Keccak so tests can reach the program's own validation
+export const buildTree = (allocations: Allocation[], options: TreeOptions = {}) => {
+  const hashingAlgorithm = options.hashingAlgorithm ?? 0;
+  const leaves = allocations.map((entry, index) =>
+    prepareLeaf(
+      options.leafVersion === 2
+        ? committedLeaf(entry.address, entry.amount, entry.dataHash, hashingAlgorithm)
+        : options.leafVersion === 3
+          ? splitLeaf(entry.address, entry.amount, entry.locked ?? 0)
+          : options.leafVersion === 4
+            ? deadlineLeaf(entry.address, entry.amount, entry.deadline ?? 0)
+            : options.leafVersion === 5
+              ? basketLeaf(entry.address, entry.amount, entry.basket ?? [])
+              : options.leafVersion === 6
+                ? nftLeaf(entry.address, entry.nftMint)
+                : encodeLeaf(entry.address, entry.amount, options.leafVersion ?? 0, index),
+      hashingAlgorithm,
+      options.leafHashing ?? 0
+    )
+  );
+  if (options.pairOrdering === 1) {
+    return buildSortedPairTree(leaves);
+  }
+  if (options.pairOrdering === 2) {
+    return buildPrefixedTree(leaves, LEAF_HASHES[hashingAlgorithm]);
+  }
+
+  const tree = new MerkleTree(HASHING_ALGORITHMS[hashingAlgorithm] ?? HashingAlgorithm.Keccak, 32);
+  leaves.forEach((leaf) => tree.add_leaf(leaf));
+  tree.merklize();
+  return tree;
+};
+
+// Keccak tree with sorted sibling pairs and odd nodes promoted, like merkletreejs with `sortPairs`
+const buildSortedPairTree = (leaves: Buffer[]) => {
+  const hashPair = (a: Buffer, b: Buffer) =>
+    Buffer.from(keccak_256(Buffer.compare(a, b) <= 0 ? Buffer.concat([a, b]) : Buffer.concat([b, a])));
+  const layers = [leaves.map((leaf) => Buffer.from(keccak_256(leaf)))];
+  while (layers[layers.length - 1].length > 1) {
+    const level = layers[layers.length - 1];
+    layers.push(Array.from({ length: Math.ceil(level.length / 2) }, (_, i) =>
+      2 * i + 1 < level.length ? hashPair(level[2 * i], level[2 * i + 1]) : level[2 * i]
+    ));
+  }
+
+  // Same surface as MerkleTree, so the claim helpers work with either
+  return {
+    get_merkle_root: () => layers[layers.length - 1][0],
+    merkle_proof_index: (index: number) => ({
+      get_pairing_hashes: () => Buffer.concat(layers.slice(0, -1).flatMap((level, depth) => {
+        const sibling = (index >> depth) ^ 1;
+        return sibling < level.length ? [level[sibling]] : [];
+      })),
+    }),
+  } as unknown as MerkleTree;
+};
+
+// Index-ordered tree hashing leaves as hash(0x00 ‖ leaf) and nodes as hash(0x01 ‖ left ‖ right),
+// an odd node being paired with itself
+const buildPrefixedTree = (leaves: Buffer[], hash: (data: Uint8Array) => Uint8Array) => {
+  const layers = [leaves.map((leaf) => Buffer.from(hash(Buffer.concat([Buffer.from([0x00]), leaf]))))];
+  while (layers[layers.length - 1].length > 1) {
+    const level = layers[layers.length - 1];
+    layers.push(Array.from({ length: Math.ceil(level.length / 2) }, (_, i) =>
+      Buffer.from(hash(Buffer.concat([Buffer.from([0x01]), level[2 * i], level[2 * i + 1] ?? level[2 * i]])))
+    ));
+  }
+
+  return {
+    get_merkle_root: () => layers[layers.length - 1][0],
+    merkle_proof_index: (index: number) => ({
+      get_pairing_hashes: () => Buffer.concat(layers.slice(0, -1).map((level, depth) =>
+        level[((index >> depth) ^ 1)] ?? level[index >> depth]
+      )),
+    }),
+  } as unknown as MerkleTree;
+};
+
+// Keccak tree with index-ordered pairs of `depth` levels of which only the path of `leaf` at
+// `index` is known, the siblings along it being random, to measure proofs of any depth
+export const buildPathTree = (leaf: Buffer, depth: number, index = 0) => {
+  const siblings = Array.from({ length: depth }, () => Keypair.generate().publicKey.toBuffer());
+  const root = siblings.reduce((node, sibling, level) =>
+    Buffer.from(keccak_256((index >> level) & 1 ? Buffer.concat([sibling, node]) : Buffer.concat([node, sibling]))),
+    Buffer.from(keccak_256(leaf))
+  );
+
+  return {
+    get_merkle_root: () => root,
+    merkle_proof_index: () => ({ get_pairing_hashes: () => Buffer.concat(siblings) }),
+  } as unknown as MerkleTree;
+};
+
+export const getProof = (tree: MerkleTree, index: number) =>
+  Buffer.from(tree.merkle_proof_index(index).get_pairing_hashes());
+
+// Airdrop 0 of a mint keeps the address derived from the mint alone
+export const getAirdropState = (programId: PublicKey, mint: PublicKey, airdropId = 0) =>
+  PublicKey.findProgramAddressSync(
+    [Buffer.from("merkle_tree"), mint.toBuffer(), ...(airdropId === 0 ? [] : [new anchor.BN(airdropId).toArrayLike(Buffer, "le", 8)])],
+    programId
+  )[0];
+
+export const getBitmapShard = (programId: PublicKey, airdropState: PublicKey, shardIndex: number) =>
+  PublicKey.findProgramAddressSync(
+    [Buffer.from("bitmap"), airdropState.toBuffer(), new anchor.BN(shardIndex).toArrayLike(Buffer, "le", 8)],
+    programId
+  )[0];
+
+export const getVestingConfig = (programId: PublicKey, airdropState: PublicKey) =>
+  PublicKey.findProgramAddressSync([Buffer.from("vesting_config"), airdropState.toBuffer()], programId)[0];
+
+export const getVestingAccount = (programId: PublicKey, airdropState: PublicKey, index: number) =>
+  PublicKey.findProgramAddressSync(
+    [Buffer.from("vesting"), airdropState.toBuffer(), new anchor.BN(index).toArrayLike(Buffer, "le", 8)],
+    programId
+  )[0];
+
+export const TOKEN_METADATA_PROGRAM_ID = new PublicKey("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+export const getMetaplexMetadata = (mint: PublicKey) =>
+  PublicKey.findProgramAddressSync(
+    [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer()],
+    TOKEN_METADATA_PROGRAM_ID
+  )[0];
+
+// Decodes the events of this program that `signature` emitted through self-CPI
+export const getCpiEvents = async (program: Program<MerkleTreeTokenClaimer>, signature: string) => {
+  const connection = program.provider.connection;
+  await connection.confirmTransaction(signature, "confirmed");
+  const tx = await connection.getTransaction(signature, { commitment: "confirmed", maxSupportedTransactionVersion: 0 });
+  const accountKeys = tx.transaction.message.getAccountKeys();
+
+  return tx.meta.innerInstructions
+    .flatMap((inner) => inner.instructions)
+    .filter((ix) => accountKeys.get(ix.programIdIndex).equals(program.programId))
+    .map((ix) => program.coder.events.decode(anchor.utils.bytes.base64.encode(Buffer.from(anchor.utils.bytes.bs58.decode(ix.data).subarray(8)))))
+    .filter((event) => event !== null);
+};
+
+// Compute units this program consumed according to the logs of a transaction or simulation,
+// its last report being that of the top-level instruction with everything it invoked
+export const getComputeUnits = (program: { programId: PublicKey }, logs: string[]) => {
+  const consumed = logs
+    .map((log) => log.match(/^Program (\w+) consumed (\d+) of \d+ compute units$/))
+    .filter((match) => match && match[1] === program.programId.toBase58());
+  return Number(consumed[consumed.length - 1][2]);
+};
+
+export const findCpiEvent = async (program: Program<MerkleTreeTokenClaimer>, signature: string, name: string) =>
+  (await getCpiEvents(program, signature)).find((event) => event.name === name)?.data as any;
+
+export const fund = async (provider: anchor.AnchorProvider, to: PublicKey, lamports = LAMPORTS_PER_SOL) => {
+  await provider.sendAndConfirm(
+    new Transaction().add(
+      SystemProgram.transfer({
+        fromPubkey: provider.publicKey,
+        toPubkey: to,
+        lamports,
+      })
+    ),
+    []
+  );
+};
+
+export interface InitializeOptions extends TreeOptions {
+  decimals?: number;
+  authorityMode?: any;
+  mintOnClaim?: boolean;
+  amount?: number;
+  // Defaults to the number of allocations
+  numLeaves?: number;
+  tokenProgram?: PublicKey;
+  startTime?: number;
+  endTime?: number;
+  rootTimelock?: number;
+  expectedRecipients?: number;
+  airdropId?: number;
+  label?: string;
+  metadataUri?: string;
+  metaplexMetadata?: { name: string; symbol: string; uri: string; keepUpdateAuthority: boolean };
+  feeBps?: number;
+  feeRecipient?: PublicKey;
+  claimFeeLamports?: number;
+  feeTreasury?: PublicKey;
+  feeMint?: PublicKey;
+  feeAmount?: number;
+  cosigner?: PublicKey;
+  // Tree to publish instead of the one built from the allocations
+  tree?: MerkleTree;
+  // Pays the rent of the new accounts instead of the authority
+  payer?: Keypair;
+}
+
+// The program's `AirdropParams`, fields missing from `options` defaulted
+export const airdropParams = (merkleRoot: ArrayLike<number>, numLeaves: number, options: InitializeOptions = {}) => ({
+  airdropId: new anchor.BN(options.airdropId ?? 0),
+  label: options.label ?? "",
+  metadataUri: options.metadataUri ?? "",
+  merkleRoot: Array.from(merkleRoot),
+  numLeaves: new anchor.BN(numLeaves),
+  startTime: new anchor.BN(options.startTime ?? 0),
+  endTime: new anchor.BN(options.endTime ?? 0),
+  rootTimelock: new anchor.BN(options.rootTimelock ?? 0),
+  expectedRecipients: new anchor.BN(options.expectedRecipients ?? 0),
+  hashingAlgorithm: options.hashingAlgorithm ?? 0,
+  leafHashing: options.leafHashing ?? 0,
+  pairOrdering: options.pairOrdering ?? 0,
+});
+
+// The program's `PayoutParams`, fields missing from `options` defaulted
+export const payoutParams = (amount: number, options: InitializeOptions = {}) => ({
+  amount: new anchor.BN(amount),
+  leafVersion: options.leafVersion ?? 0,
+  feeBps: options.feeBps ?? 0,
+  feeRecipient: options.feeRecipient ?? PublicKey.default,
+  claimFeeLamports: new anchor.BN(options.claimFeeLamports ?? 0),
+  feeTreasury: options.feeTreasury ?? PublicKey.default,
+  feeMint: options.feeMint ?? null,
+  feeAmount: new anchor.BN(options.feeAmount ?? 0),
+  cosigner: options.cosigner ?? null,
+});
+
+export interface Airdrop {
+  mint: PublicKey;
+  airdropState: PublicKey;
+  vault: PublicKey;
+  tree: MerkleTree;
+  tokenProgram: PublicKey;
+  // Initialization transaction, for tests reading its events
+  signature?: string;
+}
+
+// Creates a fresh mint and airdrop for `allocations`, returning the addresses tests need
+export const initializeAirdrop = async (
+  program: Program<MerkleTreeTokenClaimer>,
+  authority: Keypair,
+  allocations: Allocation[],
+  options: InitializeOptions = {}
+): Promise<Airdrop> => {
+  const mint = Keypair.generate();
+  const tokenProgram = options.tokenProgram ?? TOKEN_PROGRAM_ID;
+  const airdropState = getAirdropState(program.programId, mint.publicKey, options.airdropId);
+  const vault = await getAssociatedTokenAddress(mint.publicKey, airdropState, true, tokenProgram);
+  const tree = options.tree ?? buildTree(allocations, options);
+  const amount = options.amount ?? allocations.reduce((sum, entry) => sum + entry.amount, 0);
+
+  const signature = await program.methods.initializeAirdropData(
+    airdropParams(tree.get_merkle_root(), options.numLeaves ?? allocations.length, options),
+    payoutParams(amount, options),
+    options.decimals ?? 6,
+    options.authorityMode ?? { revoke: {} },
+    options.mintOnClaim ?? false,
+    options.metaplexMetadata ?? null
+  )
+    .accountsPartial({
+      airdropState,
+      mint: mint.publicKey,
+      vault,
+      authority: authority.publicKey,
+      payer: (options.payer ?? authority).publicKey,
+      systemProgram: SystemProgram.programId,
+      tokenProgram,
+      associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      metadata: options.metaplexMetadata ? getMetaplexMetadata(mint.publicKey) : null,
+      tokenMetadataProgram: options.metaplexMetadata ? TOKEN_METADATA_PROGRAM_ID : null,
+      rent: options.metaplexMetadata ? SYSVAR_RENT_PUBKEY : null,
+    })
+    .signers([authority, mint, ...(options.payer ? [options.payer] : [])])
+    .rpc();
+
+  return { mint: mint.publicKey, airdropState, vault, tree, tokenProgram, signature };
+};
+
+// Starts an airdrop funded from `authorityAta`, a token account of an already existing mint,
+// or in lamports for wrapped SOL without one
+export const initializeWithExistingMint = async (
+  program: Program<MerkleTreeTokenClaimer>,
+  authority: Keypair,
+  mint: PublicKey,
+  authorityAta: PublicKey | null,
+  allocations: Allocation[],
+  amount: number,
+  tokenProgram = TOKEN_PROGRAM_ID,
+  remainingAccounts: AccountMeta[] = [],
+  startTime = 0,
+  endTime = 0,
+  rootTimelock = 0,
+  expectedRecipients = 0,
+  treeOptions: TreeOptions = {},
+  airdropId = 0,
+  label = "",
+  metadataUri = "",
+  feeBps = 0,
+  feeRecipient = PublicKey.default,
+  claimFeeLamports = 0,
+  feeTreasury = PublicKey.default,
+  feeMint: PublicKey | null = null,
+  feeAmount = 0,
+  cosigner: PublicKey | null = null
+): Promise<Airdrop> => {
+  const airdropState = getAirdropState(program.programId, mint, airdropId);
+  const vault = await getAssociatedTokenAddress(mint, airdropState, true, tokenProgram);
+  const tree = buildTree(allocations, treeOptions);
+
+  const options = { ...treeOptions, airdropId, label, metadataUri, startTime, endTime, rootTimelock, expectedRecipients, feeBps, feeRecipient, claimFeeLamports, feeTreasury, feeMint, feeAmount, cosigner };
+  const signature = await program.methods.initializeWithExistingMint(
+    airdropParams(tree.get_merkle_root(), allocations.length, options),
+    payoutParams(amount, options)
+  )
+    .accountsPartial({
+      airdropState,
+      mint,
+      vault,
+      authorityAta,
+      authority: authority.publicKey,
+      payer: authority.publicKey,
+      systemProgram: SystemProgram.programId,
+      tokenProgram,
+      associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+    })
+    .remainingAccounts(remainingAccounts)
+    .signers([authority])
+    .rpc();
+
+  return { mint, airdropState, vault, tree, tokenProgram, signature };
+};
+
+export const createBitmapShard = async (
+  program: Program<MerkleTreeTokenClaimer>,
+  airdropState: PublicKey,
+  shardIndex: number
+) => {
+  const claimBitmap = getBitmapShard(program.programId, airdropState, shardIndex);
+  await program.methods.createBitmapShard(new anchor.BN(shardIndex))
+    .accountsPartial({
+      airdropState,
+      claimBitmap,
+      payer: program.provider.publicKey,
+      systemProgram: SystemProgram.programId,
+    })
+    .rpc();
+  return claimBitmap;
+};
+
+export const claimAirdrop = async (
+  program: Program<MerkleTreeTokenClaimer>,
+  claimant: Keypair,
+  airdrop: Airdrop,
+  amount: number,
+  index: number,
+  remainingAccounts: AccountMeta[] = [],
+  rootVersion?: number
+) => {
+  const signerAta = await getAssociatedTokenAddress(airdrop.mint, claimant.publicKey, false, airdrop.tokenProgram);
+  // Proofs are built against the tree as published, which defaults to the current root version
+  const expectedRootVersion = rootVersion === undefined
+    ? (await program.account.airdropState.fetch(airdrop.airdropState)).rootVersion
+    : new anchor.BN(rootVersion);
+  await program.methods.claimAirdrop(new anchor.BN(amount), getProof(airdrop.tree, index), new anchor.BN(index), expectedRootVersion, null, false)
+    .accountsPartial({
+      airdropState: airdrop.airdropState,
+      mint: airdrop.mint,
+      vault: airdrop.vault,
+      signerAta,
+      claimBitmap: getBitmapShard(program.programId, airdrop.airdropState, Math.floor(index / LEAVES_PER_SHARD)),
+      claimProgress: null,
+      vestingConfig: null,
+      vestingAccount: null,
+      claimReceipt: null,
+      decayConfig: null,
+      bonusConfig: null,
+      feeAta: null,
+      feeTreasury: null,
+      feeMint: null,
+      payerFeeAta: null,
+      treasuryFeeAta: null,
+      feeTokenProgram: null,
+      ticketMint: null,
+      ticketAta: null,
+      ticketTokenProgram: null,
+      gateNftAccount: null,
+      gateNftMetadata: null,
+      cosigner: null,
+      instructionsSysvar: null,
+      revokedLeaf: null,
+      referralStats: null,
+      hookProgram: null,
+      hookAuthority: null,
+      stakeConfig: null,
+      stakeProgram: null,
+      unwrapDestination: null,
+      basket: null,
+      canopy: null,
+      claimant: claimant.publicKey,
+      payer: claimant.publicKey,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: airdrop.tokenProgram,
+      associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+    })
+    .remainingAccounts(remainingAccounts)
+    .signers([claimant])
+    .rpc();
+  return signerAta;
+};
+
+// Moves the remaining vault balance to the authority's associated token account
+export const withdrawUnclaimed = async (
+  program: Program<MerkleTreeTokenClaimer>,
+  authority: Keypair,
+  airdrop: Airdrop,
+  remainingAccounts: AccountMeta[] = [],
+  vestingConfig: PublicKey | null = null
+) => {
+  const destination = await getOrCreateAssociatedTokenAccount(
+    program.provider.connection,
+    authority,
+    airdrop.mint,
+    authority.publicKey,
+    false,
+    undefined,
+    undefined,
+    airdrop.tokenProgram
+  );
+  await program.methods.withdrawUnclaimed()
+    .accountsPartial({
+      airdropState: airdrop.airdropState,
+      mint: airdrop.mint,
+      vault: airdrop.vault,
+      destination: destination.address,
+      vestingConfig,
+      withdrawAuthority: authority.publicKey,
+      tokenProgram: airdrop.tokenProgram,
+    })
+    .remainingAccounts(remainingAccounts)
+    .signers([authority])
+    .rpc();
+  return destination.address;
+};
+
+export const closeAirdrop = async (
+  program: Program<MerkleTreeTokenClaimer>,
+  authority: Keypair,
+  airdrop: Airdrop
+) => {
+  await program.methods.closeAirdrop()
+    .accountsPartial({
+      airdropState: airdrop.airdropState,
+      mint: airdrop.mint,
+      vault: airdrop.vault,
+      withdrawAuthority: authority.publicKey,
+      tokenProgram: airdrop.tokenProgram,
+    })
+    .signers([authority])
+    .rpc();
+};
+
+export const burnUnclaimed = async (
+  program: Program<MerkleTreeTokenClaimer>,
+  authority: Keypair,
+  airdrop: Airdrop,
+  vestingConfig: PublicKey | null = null
+) => {
+  await program.methods.burnUnclaimed()
+    .accountsPartial({
+      airdropState: airdrop.airdropState,
+      mint: airdrop.mint,
+      vault: airdrop.vault,
+      vestingConfig,
+      withdrawAuthority: authority.publicKey,
+      tokenProgram: airdrop.tokenProgram,
+    })
+    .signers([authority])
+    .rpc();
+};
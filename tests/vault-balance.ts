@@ -0,0 +1,45 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair } from "@solana/web3.js";
+import { claimAirdrop, createBitmapShard, fund, initializeAirdrop, withdrawUnclaimed } from "./utils";
+
+describe("vault balance", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  const claimant = Keypair.generate();
+  const allocations = [
+    { address: claimant.publicKey, amount: 100 },
+    { address: Keypair.generate().publicKey, amount: 100 },
+  ];
+
+  const now = () => Math.floor(Date.now() / 1000);
+
+  before(async () => {
+    await fund(provider, claimant.publicKey);
+  });
+
+  it("Fail to claim from a drained vault with a dedicated error", async () => {
+    // Drain the vault once the deadline passed, then reopen claims
+    const airdrop = await initializeAirdrop(program, authority, allocations, { endTime: now() - 60 });
+    await createBitmapShard(program, airdrop.airdropState, 0);
+    await withdrawUnclaimed(program, authority, airdrop);
+    await program.methods.extendDeadline(new anchor.BN(now() + 3_600))
+      .accountsPartial({ airdropState: airdrop.airdropState, updateAuthority: authority.publicKey })
+      .rpc();
+
+    try {
+      await claimAirdrop(program, claimant, airdrop, 100, 0);
+      expect.fail("Claim should have failed against an empty vault");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("VaultInsufficientFunds");
+      expect(error.logs.some((log: string) => log.includes("Vault is short by 100 tokens"))).to.equal(true);
+    }
+  });
+});
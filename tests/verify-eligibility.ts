@@ -0,0 +1,53 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { keccak_256 } from "@noble/hashes/sha3";
+import { Airdrop, claimAirdrop, createBitmapShard, encodeLeaf, fund, getProof, initializeAirdrop } from "./utils";
+
+describe("verify eligibility", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  const claimants = [Keypair.generate(), Keypair.generate()];
+  const allocations = claimants.map((claimant) => ({ address: claimant.publicKey, amount: 100 }));
+
+  const verifyEligibility = (airdrop: Airdrop, claimant: PublicKey, amount: number, index: number, claimBitmap: PublicKey | null) =>
+    program.methods.verifyEligibility(claimant, new anchor.BN(amount), getProof(airdrop.tree, index), new anchor.BN(index), new anchor.BN(0))
+      .accountsPartial({ airdropState: airdrop.airdropState, claimBitmap })
+      .view();
+
+  before(async () => {
+    for (const claimant of claimants) {
+      await fund(provider, claimant.publicKey);
+    }
+  });
+
+  it("Reports a valid proof with its leaf hash and claim status", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations);
+    const claimBitmap = await createBitmapShard(program, airdrop.airdropState, 0);
+
+    let result = await verifyEligibility(airdrop, claimants[0].publicKey, 100, 0, claimBitmap);
+    expect(result.eligible).to.equal(true);
+    expect(result.alreadyClaimed).to.equal(false);
+    expect(result.leafHash).to.deep.equal(Array.from(keccak_256(encodeLeaf(claimants[0].publicKey, 100))));
+
+    await claimAirdrop(program, claimants[0], airdrop, 100, 0);
+    result = await verifyEligibility(airdrop, claimants[0].publicKey, 100, 0, claimBitmap);
+    expect(result.eligible).to.equal(true);
+    expect(result.alreadyClaimed).to.equal(true);
+  });
+
+  it("Reports a wrong amount as ineligible without the bitmap shard", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations);
+
+    const result = await verifyEligibility(airdrop, claimants[1].publicKey, 1_000, 1, null);
+    expect(result.eligible).to.equal(false);
+    expect(result.alreadyClaimed).to.equal(false);
+  });
+});
@@ -0,0 +1,196 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair, SystemProgram } from "@solana/web3.js";
+import { getAccount, getAssociatedTokenAddress } from "@solana/spl-token";
+import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
+import { Airdrop, burnUnclaimed, claimAirdrop, createBitmapShard, fund, getBitmapShard, getProof, getVestingAccount, getVestingConfig, initializeAirdrop, withdrawUnclaimed } from "./utils";
+
+describe("vesting", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  const claimants = [Keypair.generate(), Keypair.generate()];
+  const allocations = claimants.map((claimant) => ({ address: claimant.publicKey, amount: 1_000 }));
+
+  const configureVesting = (airdrop: Airdrop, vestingStart: number, vestingDuration: number, cliffSeconds = 0) =>
+    program.methods.configureVesting(new anchor.BN(vestingStart), new anchor.BN(vestingDuration), new anchor.BN(cliffSeconds))
+      .accountsPartial({ airdropState: airdrop.airdropState, vestingConfig: getVestingConfig(program.programId, airdrop.airdropState), updateAuthority: authority.publicKey, systemProgram: SystemProgram.programId })
+      .rpc();
+
+  const claimVesting = async (airdrop: Airdrop, index: number) => {
+    const claimant = claimants[index];
+    await program.methods.claimAirdrop(new anchor.BN(allocations[index].amount), getProof(airdrop.tree, index), new anchor.BN(index), new anchor.BN(0), null, false)
+      .accountsPartial({
+        airdropState: airdrop.airdropState,
+        mint: airdrop.mint,
+        vault: airdrop.vault,
+        signerAta: await getAssociatedTokenAddress(airdrop.mint, claimant.publicKey, false, airdrop.tokenProgram),
+        claimBitmap: getBitmapShard(program.programId, airdrop.airdropState, 0),
+        vestingConfig: getVestingConfig(program.programId, airdrop.airdropState),
+        vestingAccount: getVestingAccount(program.programId, airdrop.airdropState, index),
+        claimant: claimant.publicKey,
+        payer: claimant.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: airdrop.tokenProgram,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .signers([claimant])
+      .rpc();
+  };
+
+  // Anyone can crank a release, here the authority pays for it
+  const release = async (airdrop: Airdrop, index: number) => {
+    const beneficiaryAta = await getAssociatedTokenAddress(airdrop.mint, claimants[index].publicKey, false, airdrop.tokenProgram);
+    await program.methods.release()
+      .accountsPartial({
+        airdropState: airdrop.airdropState,
+        vestingConfig: getVestingConfig(program.programId, airdrop.airdropState),
+        vestingAccount: getVestingAccount(program.programId, airdrop.airdropState, index),
+        mint: airdrop.mint,
+        vault: airdrop.vault,
+        beneficiaryAta,
+        beneficiary: claimants[index].publicKey,
+        payer: authority.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: airdrop.tokenProgram,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .rpc();
+    return Number((await getAccount(provider.connection, beneficiaryAta)).amount);
+  };
+
+  const expectError = async (call: Promise<unknown>, code: string) => {
+    try {
+      await call;
+      expect.fail(`Call should have failed with ${code}`);
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal(code);
+    }
+  };
+
+  const setUp = async (vestingStart: number, vestingDuration: number, cliffSeconds = 0) => {
+    const airdrop = await initializeAirdrop(program, authority, allocations, { amount: 2_000 });
+    await createBitmapShard(program, airdrop.airdropState, 0);
+    await configureVesting(airdrop, vestingStart, vestingDuration, cliffSeconds);
+    return airdrop;
+  };
+
+  before(async () => {
+    await Promise.all(claimants.map((claimant) => fund(provider, claimant.publicKey)));
+  });
+
+  it("Fail to configure a vesting schedule without a duration", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations);
+    await expectError(configureVesting(airdrop, 0, 0), "InvalidVestingSchedule");
+  });
+
+  it("Fail to configure a cliff past the end of the schedule", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations);
+    await expectError(configureVesting(airdrop, 0, 3_600, 3_601), "InvalidVestingSchedule");
+  });
+
+  it("Escrows a claim and releases what vested so far", async () => {
+    // Halfway through a two hour schedule
+    const now = Math.floor(Date.now() / 1000);
+    const airdrop = await setUp(now - 3_600, 7_200);
+
+    await claimVesting(airdrop, 0);
+    const vestingAccount = await program.account.vestingAccount.fetch(getVestingAccount(program.programId, airdrop.airdropState, 0));
+    expect(vestingAccount.beneficiary.toBase58()).to.equal(claimants[0].publicKey.toBase58());
+    expect(vestingAccount.total.toNumber()).to.equal(1_000);
+    expect(vestingAccount.released.toNumber()).to.equal(0);
+    expect(Number((await getAccount(provider.connection, airdrop.vault)).amount)).to.equal(2_000);
+
+    const released = await release(airdrop, 0);
+    expect(released).to.be.within(450, 550);
+    expect((await program.account.vestingAccount.fetch(getVestingAccount(program.programId, airdrop.airdropState, 0))).released.toNumber()).to.equal(released);
+    const state = await program.account.airdropState.fetch(airdrop.airdropState);
+    expect(state.amountClaimed.toNumber()).to.equal(released);
+    expect(state.numClaims.toNumber()).to.equal(1);
+  });
+
+  it("Fail to claim the exact way or configure vesting once claims started", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    const airdrop = await setUp(now, 7_200);
+    await claimVesting(airdrop, 0);
+
+    await expectError(claimAirdrop(program, claimants[1], airdrop, 1_000, 1), "MissingVestingAccount");
+    await expectError(configureVesting(airdrop, now, 3_600), "ClaimModeLocked");
+  });
+
+  it("Sweeps the whole escrow once the schedule is over, then releases nothing more", async () => {
+    const airdrop = await setUp(1, 1);
+    await claimVesting(airdrop, 1);
+
+    expect(await release(airdrop, 1)).to.equal(1_000);
+    expect(await release(airdrop, 1)).to.equal(1_000);
+    expect((await program.account.vestingAccount.fetch(getVestingAccount(program.programId, airdrop.airdropState, 1))).released.toNumber()).to.equal(1_000);
+    expect((await program.account.airdropState.fetch(airdrop.airdropState)).amountClaimed.toNumber()).to.equal(1_000);
+  });
+
+  it("Withdraws around what the escrows still owe once claims are over", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations, { amount: 2_000, endTime: Math.floor(Date.now() / 1000) + 5 });
+    await createBitmapShard(program, airdrop.airdropState, 0);
+    await configureVesting(airdrop, 1, 1);
+    await claimVesting(airdrop, 0);
+
+    // Let the short claim window run out
+    await new Promise((resolve) => setTimeout(resolve, 8_000));
+
+    await expectError(withdrawUnclaimed(program, authority, airdrop), "MissingVestingAccount");
+    await withdrawUnclaimed(program, authority, airdrop, [], getVestingConfig(program.programId, airdrop.airdropState));
+    expect(Number((await getAccount(provider.connection, airdrop.vault)).amount)).to.equal(1_000);
+    expect((await program.account.airdropState.fetch(airdrop.airdropState)).amountWithdrawn.toNumber()).to.equal(1_000);
+
+    expect(await release(airdrop, 0)).to.equal(1_000);
+  });
+
+  it("Burns around what the escrows still owe once claims are over", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations, { amount: 2_000, endTime: Math.floor(Date.now() / 1000) + 5 });
+    await createBitmapShard(program, airdrop.airdropState, 0);
+    await configureVesting(airdrop, 1, 1);
+    await claimVesting(airdrop, 1);
+
+    // Let the short claim window run out
+    await new Promise((resolve) => setTimeout(resolve, 8_000));
+
+    await expectError(burnUnclaimed(program, authority, airdrop), "MissingVestingAccount");
+    await burnUnclaimed(program, authority, airdrop, getVestingConfig(program.programId, airdrop.airdropState));
+    expect(Number((await getAccount(provider.connection, airdrop.vault)).amount)).to.equal(1_000);
+    expect((await program.account.airdropState.fetch(airdrop.airdropState)).amountBurned.toNumber()).to.equal(1_000);
+
+    expect(await release(airdrop, 1)).to.equal(1_000);
+  });
+
+  it("Releases nothing before the cliff", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    const airdrop = await setUp(now - 3_600, 14_400, 7_200);
+    await claimVesting(airdrop, 0);
+
+    expect(await release(airdrop, 0)).to.equal(0);
+    expect((await program.account.vestingAccount.fetch(getVestingAccount(program.programId, airdrop.airdropState, 0))).released.toNumber()).to.equal(0);
+  });
+
+  it("Counts the time before the cliff once it passed", async () => {
+    // A quarter through the schedule, past a cliff at one eighth
+    const now = Math.floor(Date.now() / 1000);
+    const airdrop = await setUp(now - 3_600, 14_400, 1_800);
+    await claimVesting(airdrop, 0);
+
+    expect(await release(airdrop, 0)).to.be.within(200, 300);
+  });
+
+  it("Releases everything at once past a cliff as long as the schedule", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    const airdrop = await setUp(now - 3_600, 3_600, 3_600);
+    await claimVesting(airdrop, 1);
+
+    expect(await release(airdrop, 1)).to.equal(1_000);
+  });
+});
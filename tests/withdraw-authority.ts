@@ -0,0 +1,115 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { getAccount } from "@solana/spl-token";
+import { Keypair } from "@solana/web3.js";
+import { Airdrop, closeAirdrop, fund, initializeAirdrop, withdrawUnclaimed } from "./utils";
+
+describe("withdraw authority", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  // The initializer stays the ops key managing the root, the treasury takes over the funds
+  const ops = wallet.payer;
+  const treasury = Keypair.generate();
+
+  const allocations = Array.from({ length: 2 }, () => ({ address: Keypair.generate().publicKey, amount: 100 }));
+
+  let airdrop: Airdrop;
+
+  const expectError = async (call: Promise<unknown>, code: string) => {
+    try {
+      await call;
+      expect.fail(`Call should have failed with ${code}`);
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal(code);
+    }
+  };
+
+  before(async () => {
+    await fund(provider, treasury.publicKey);
+    airdrop = await initializeAirdrop(program, ops, allocations, { endTime: Math.floor(Date.now() / 1000) - 60 });
+  });
+
+  it("Starts with the initializer as both authorities", async () => {
+    const state = await program.account.airdropState.fetch(airdrop.airdropState);
+    expect(state.updateAuthority.toBase58()).to.equal(ops.publicKey.toBase58());
+    expect(state.withdrawAuthority.toBase58()).to.equal(ops.publicKey.toBase58());
+  });
+
+  it("Keeps the withdraw authority until the nominee accepts", async () => {
+    await program.methods.nominateWithdrawAuthority(treasury.publicKey)
+      .accountsPartial({ airdropState: airdrop.airdropState, withdrawAuthority: ops.publicKey })
+      .rpc();
+
+    const state = await program.account.airdropState.fetch(airdrop.airdropState);
+    expect(state.withdrawAuthority.toBase58()).to.equal(ops.publicKey.toBase58());
+    expect(state.pendingWithdrawAuthority.toBase58()).to.equal(treasury.publicKey.toBase58());
+  });
+
+  it("Fail to accept the withdraw authority without being nominated", async () => {
+    const stranger = Keypair.generate();
+    await expectError(
+      program.methods.acceptWithdrawAuthority()
+        .accountsPartial({ airdropState: airdrop.airdropState, pendingWithdrawAuthority: stranger.publicKey })
+        .signers([stranger])
+        .rpc(),
+      "NotPendingAuthority"
+    );
+  });
+
+  it("Hands the withdraw authority to the treasury once it accepts", async () => {
+    await program.methods.acceptWithdrawAuthority()
+      .accountsPartial({ airdropState: airdrop.airdropState, pendingWithdrawAuthority: treasury.publicKey })
+      .signers([treasury])
+      .rpc();
+
+    const state = await program.account.airdropState.fetch(airdrop.airdropState);
+    expect(state.withdrawAuthority.toBase58()).to.equal(treasury.publicKey.toBase58());
+    expect(state.pendingWithdrawAuthority).to.be.null;
+    expect(state.updateAuthority.toBase58()).to.equal(ops.publicKey.toBase58());
+  });
+
+  it("Fail to withdraw or close with the ops key", async () => {
+    await expectError(withdrawUnclaimed(program, ops, airdrop), "MissingRole");
+    await expectError(closeAirdrop(program, ops, airdrop), "MissingRole");
+  });
+
+  it("Fail to update the root with the treasury key", async () => {
+    await expectError(
+      program.methods.updateTree(Array(32).fill(1), new anchor.BN(allocations.length))
+        .accountsPartial({ airdropState: airdrop.airdropState, updateAuthority: treasury.publicKey })
+        .signers([treasury])
+        .rpc(),
+      "MissingRole"
+    );
+  });
+
+  it("Pauses with either key", async () => {
+    for (const [signer, paused] of [[treasury, true], [ops, false]] as const) {
+      await program.methods.setPaused(paused)
+        .accountsPartial({ airdropState: airdrop.airdropState, authority: signer.publicKey })
+        .signers([signer])
+        .rpc();
+      expect((await program.account.airdropState.fetch(airdrop.airdropState)).paused).to.equal(paused);
+    }
+
+    const stranger = Keypair.generate();
+    await expectError(
+      program.methods.setPaused(true)
+        .accountsPartial({ airdropState: airdrop.airdropState, authority: stranger.publicKey })
+        .signers([stranger])
+        .rpc(),
+      "MissingRole"
+    );
+  });
+
+  it("Withdraws the unclaimed balance with the treasury key", async () => {
+    const destination = await withdrawUnclaimed(program, treasury, airdrop);
+
+    expect(Number((await getAccount(provider.connection, destination)).amount)).to.equal(200);
+  });
+});
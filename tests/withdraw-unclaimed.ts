@@ -0,0 +1,65 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair } from "@solana/web3.js";
+import { getAccount } from "@solana/spl-token";
+import { initializeAirdrop, withdrawUnclaimed } from "./utils";
+
+describe("withdraw unclaimed", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  const allocations = Array.from({ length: 4 }, () => ({
+    address: Keypair.generate().publicKey,
+    amount: 250,
+  }));
+
+  const now = () => Math.floor(Date.now() / 1000);
+
+  it("Withdraws the remaining vault balance after the deadline", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations, { endTime: now() - 60 });
+
+    let event: any;
+    const listener = program.addEventListener("unclaimedWithdrawn", (emitted) => {
+      event = emitted;
+    });
+    const destination = await withdrawUnclaimed(program, authority, airdrop);
+    await new Promise((resolve) => setTimeout(resolve, 1_000));
+    await program.removeEventListener(listener);
+
+    expect(Number((await getAccount(provider.connection, airdrop.vault)).amount)).to.equal(0);
+    expect(Number((await getAccount(provider.connection, destination)).amount)).to.equal(1_000);
+    expect((await program.account.airdropState.fetch(airdrop.airdropState)).amountWithdrawn.toNumber()).to.equal(1_000);
+
+    expect(event.airdropState.toString()).to.equal(airdrop.airdropState.toString());
+    expect(event.destination.toString()).to.equal(destination.toString());
+    expect(event.amount.toNumber()).to.equal(1_000);
+  });
+
+  it("Fail to withdraw while claims are still open", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations, { endTime: now() + 3_600 });
+
+    try {
+      await withdrawUnclaimed(program, authority, airdrop);
+      expect.fail("Withdrawal should have failed before the deadline");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Claims are still open");
+    }
+  });
+
+  it("Fail to withdraw from an airdrop without a deadline", async () => {
+    const airdrop = await initializeAirdrop(program, authority, allocations);
+
+    try {
+      await withdrawUnclaimed(program, authority, airdrop);
+      expect.fail("Withdrawal should have failed without a deadline");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Claims are still open");
+    }
+  });
+});
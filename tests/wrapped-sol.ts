@@ -0,0 +1,84 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair, LAMPORTS_PER_SOL, SystemProgram } from "@solana/web3.js";
+import { getAccount, getAssociatedTokenAddress, NATIVE_MINT } from "@solana/spl-token";
+import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
+import { Airdrop, claimAirdrop, createBitmapShard, fund, getBitmapShard, getProof, initializeAirdrop, initializeWithExistingMint } from "./utils";
+
+describe("wrapped sol", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  const claimants = [Keypair.generate(), Keypair.generate()];
+  const allocations = claimants.map((claimant) => ({ address: claimant.publicKey, amount: LAMPORTS_PER_SOL / 2 }));
+
+  let airdrop: Airdrop;
+
+  const claimUnwrapped = async (airdrop: Airdrop, index: number) => {
+    const claimant = claimants[index];
+    const signerAta = await getAssociatedTokenAddress(airdrop.mint, claimant.publicKey);
+    await program.methods.claimAirdrop(new anchor.BN(allocations[index].amount), getProof(airdrop.tree, index), new anchor.BN(index), new anchor.BN(0), null, true)
+      .accountsPartial({
+        airdropState: airdrop.airdropState,
+        mint: airdrop.mint,
+        vault: airdrop.vault,
+        signerAta,
+        claimBitmap: getBitmapShard(program.programId, airdrop.airdropState, 0),
+        unwrapDestination: claimant.publicKey,
+        claimant: claimant.publicKey,
+        payer: claimant.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: airdrop.tokenProgram,
+        associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      })
+      .signers([claimant])
+      .rpc();
+    return signerAta;
+  };
+
+  before(async () => {
+    await Promise.all(claimants.map((claimant) => fund(provider, claimant.publicKey)));
+    airdrop = await initializeWithExistingMint(program, authority, NATIVE_MINT, null, allocations, LAMPORTS_PER_SOL);
+    await createBitmapShard(program, airdrop.airdropState, 0);
+  });
+
+  it("Funds a wrapped SOL vault in lamports", async () => {
+    const vault = await getAccount(provider.connection, airdrop.vault);
+    expect(vault.isNative).to.equal(true);
+    expect(Number(vault.amount)).to.equal(LAMPORTS_PER_SOL);
+  });
+
+  it("Pays an unwrapped claim in SOL", async () => {
+    const before = await provider.connection.getBalance(claimants[0].publicKey);
+    const signerAta = await claimUnwrapped(airdrop, 0);
+
+    // The claimant paid the rent of the ATA and has it back with the claim once it is closed
+    expect(await provider.connection.getBalance(claimants[0].publicKey)).to.equal(before + LAMPORTS_PER_SOL / 2);
+    expect(await provider.connection.getAccountInfo(signerAta)).to.be.null;
+    expect((await program.account.airdropState.fetch(airdrop.airdropState)).amountClaimed.toNumber()).to.equal(LAMPORTS_PER_SOL / 2);
+  });
+
+  it("Pays a claim in wrapped SOL without unwrapping", async () => {
+    const signerAta = await claimAirdrop(program, claimants[1], airdrop, LAMPORTS_PER_SOL / 2, 1);
+
+    expect(Number((await getAccount(provider.connection, signerAta)).amount)).to.equal(LAMPORTS_PER_SOL / 2);
+  });
+
+  it("Fail to unwrap a claim of another mint", async () => {
+    const other = await initializeAirdrop(program, authority, allocations);
+    await createBitmapShard(program, other.airdropState, 0);
+
+    try {
+      await claimUnwrapped(other, 0);
+      expect.fail("Only wrapped SOL should unwrap");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("NotNativeMint");
+    }
+  });
+});
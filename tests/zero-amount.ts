@@ -0,0 +1,46 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { MerkleTreeTokenClaimer } from "../target/types/merkle_tree_token_claimer";
+import { expect } from "chai";
+import { Keypair } from "@solana/web3.js";
+import { claimAirdrop, createBitmapShard, fund, initializeAirdrop } from "./utils";
+
+describe("zero amounts", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const wallet = anchor.Wallet.local();
+
+  const program = anchor.workspace.MerkleTreeTokenClaimer as Program<MerkleTreeTokenClaimer>;
+  const authority = wallet.payer;
+
+  const claimant = Keypair.generate();
+
+  before(async () => {
+    await fund(provider, claimant.publicKey);
+  });
+
+  it("Fail to claim a zero-amount leaf", async () => {
+    const allocations = [
+      { address: claimant.publicKey, amount: 0 },
+      { address: Keypair.generate().publicKey, amount: 100 },
+    ];
+    const airdrop = await initializeAirdrop(program, authority, allocations);
+    await createBitmapShard(program, airdrop.airdropState, 0);
+
+    try {
+      await claimAirdrop(program, claimant, airdrop, 0, 0);
+      expect.fail("Claim should have failed for a zero amount");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Amount must be greater than zero");
+    }
+  });
+
+  it("Fail to initialize an airdrop funded with zero tokens", async () => {
+    try {
+      await initializeAirdrop(program, authority, [{ address: claimant.publicKey, amount: 100 }], { amount: 0 });
+      expect.fail("Initialization should have failed for a zero amount");
+    } catch (error: any) {
+      expect(error.error.errorMessage).to.equal("Amount must be greater than zero");
+    }
+  });
+});